@@ -32,7 +32,7 @@ async fn create_new_association_pair(
     let mut closed_rx1 = closed_tx.subscribe();
 
     // Setup client
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let client = Association::client(sctp::association::Config {
             net_conn: ca,
             max_receive_buffer_size: 0,
@@ -48,7 +48,7 @@ async fn create_new_association_pair(
     });
 
     // Setup server
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let server = Association::server(sctp::association::Config {
             net_conn: cb,
             max_receive_buffer_size: 0,
@@ -112,7 +112,7 @@ async fn close_association_pair(
     let mut closed_rx1 = closed_tx.subscribe();
 
     // Close client
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         client.close().await?;
         let _ = handshake0ch_tx.send(()).await;
         let _ = closed_rx0.recv().await;
@@ -121,7 +121,7 @@ async fn close_association_pair(
     });
 
     // Close server
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         server.close().await?;
         let _ = handshake1ch_tx.send(()).await;
         let _ = closed_rx1.recv().await;
@@ -256,7 +256,7 @@ async fn pr_ordered_unordered_test(channel_type: ChannelType, is_ordered: bool)
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_reliable_ordered() -> Result<()> {
     let mut sbuf = vec![0u8; 1000];
     let mut rbuf = vec![0u8; 1500];
@@ -319,7 +319,7 @@ async fn test_data_channel_channel_type_reliable_ordered() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_reliable_unordered() -> Result<()> {
     let mut sbuf = vec![0u8; 1000];
     let mut rbuf = vec![0u8; 1500];
@@ -392,32 +392,32 @@ async fn test_data_channel_channel_type_reliable_unordered() -> Result<()> {
 }
 
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_partial_reliable_rexmit() -> Result<()> {
     pr_ordered_unordered_test(ChannelType::PartialReliableRexmit, true).await
 }
 
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_partial_reliable_rexmit_unordered() -> Result<()> {
     pr_ordered_unordered_test(ChannelType::PartialReliableRexmitUnordered, false).await
 }
 
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_partial_reliable_timed() -> Result<()> {
     pr_ordered_unordered_test(ChannelType::PartialReliableTimed, true).await
 }
 
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_channel_type_partial_reliable_timed_unordered() -> Result<()> {
     pr_ordered_unordered_test(ChannelType::PartialReliableTimedUnordered, false).await
 }
 
 //TODO: remove this conditional test
 #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_buffered_amount() -> Result<()> {
     let sbuf = vec![0u8; 1000];
     let mut rbuf = vec![0u8; 1000];
@@ -489,7 +489,7 @@ async fn test_data_channel_buffered_amount() -> Result<()> {
     }
 
     let dc1_cloned = Arc::clone(&dc1);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         while let Ok(n) = dc1_cloned.read(&mut rbuf[..]).await {
             if n == 0 {
                 break;
@@ -523,7 +523,7 @@ async fn test_data_channel_buffered_amount() -> Result<()> {
 
 //TODO: remove this conditional test
 #[cfg(not(any(target_os = "macos", target_os = "windows")))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stats() -> Result<()> {
     let sbuf = vec![0u8; 1000];
     let mut rbuf = vec![0u8; 1500];
@@ -617,7 +617,7 @@ async fn test_stats() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_poll_data_channel() -> Result<()> {
     let mut sbuf = vec![0u8; 1000];
     let mut rbuf = vec![0u8; 1500];
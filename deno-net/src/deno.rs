@@ -0,0 +1,432 @@
+//! Backend built on the Deno runtime APIs.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{DatagramSocket, Instant, Net, NetworkInterface, ResolveError, Runtime, SrvRecord, StreamListener, StreamSocket, SystemTime, Ticker};
+
+#[wasm_bindgen]
+extern "C" {
+	#[wasm_bindgen(catch, js_namespace = Deno)]
+	async fn resolveDns(query: &str, record_type: &str) -> Result<JsValue, JsValue>;
+
+	pub type Listener;
+	#[wasm_bindgen(method, catch, js_name = accept)]
+	async fn js_accept(this: &Listener) -> Result<JsValue, JsValue>; // Promise<Conn>
+	#[wasm_bindgen(method, getter, js_name = addr)]
+	fn js_addr(this: &Listener) -> Addr;
+	#[wasm_bindgen(method, catch, js_name = close)]
+	fn js_close(this: &Listener) -> Result<(), JsValue>;
+
+	type JsDatagramConn;
+	#[wasm_bindgen(method, getter, js_name = addr)]
+	fn js_addr(this: &JsDatagramConn) -> Addr;
+	#[wasm_bindgen(method, catch, js_name = receive)]
+	async fn js_receive(this: &JsDatagramConn, p: &Uint8Array) -> Result<JsValue, JsValue>; // Promise<[Uint8Array, Addr]>
+	#[wasm_bindgen(method, catch, js_name = send)]
+	async fn js_send(this: &JsDatagramConn, p: &Uint8Array, addr: &JsValue) -> Result<JsValue, JsValue>; // Promise<number>
+	#[wasm_bindgen(method, catch, js_name = joinMulticastV4)]
+	async fn js_join_multicast_v4(this: &JsDatagramConn, address: &str, network_interface: &str) -> Result<JsValue, JsValue>; // Promise<MulticastV4Membership>
+	#[wasm_bindgen(method, catch, js_name = close)]
+	fn js_close(this: &JsDatagramConn) -> Result<(), JsValue>;
+
+	pub type Conn;
+	#[wasm_bindgen(method, getter, js_name = localAddr)]
+	fn js_local_addr(this: &Conn) -> Addr;
+	#[wasm_bindgen(method, getter, js_name = remoteAddr)]
+	fn js_remote_addr(this: &Conn) -> Addr;
+	#[wasm_bindgen(method, catch, js_name = read)]
+	async fn js_read(this: &Conn, p: &Uint8Array) -> Result<JsValue, JsValue>; // Promise<number | null>
+	#[wasm_bindgen(method, catch, js_name = write)]
+	async fn js_write(this: &Conn, p: &Uint8Array) -> Result<JsValue, JsValue>; // Promise<number>
+	#[wasm_bindgen(method, catch, js_name = close)]
+	fn js_close(this: &Conn) -> Result<(), JsValue>;
+
+	type Addr;
+	#[wasm_bindgen(method, getter)]
+	fn hostname(this: &Addr) -> String;
+	#[wasm_bindgen(method, getter)]
+	fn port(this: &Addr) -> u16;
+
+	#[wasm_bindgen(catch, js_namespace = Deno)]
+	fn listen(options: &JsValue) -> Result<Listener, JsValue>;
+	#[wasm_bindgen(catch, js_namespace = Deno)]
+	async fn connect(options: &JsValue) -> Result<JsValue, JsValue>; // Promise<Conn>
+	#[wasm_bindgen(catch, js_namespace = Deno)]
+	async fn startTls(conn: &JsValue, options: &JsValue) -> Result<JsValue, JsValue>; // Promise<TlsConn>
+	#[wasm_bindgen(catch, js_namespace = Deno)]
+	fn listenDatagram(options: &JsValue) -> Result<JsDatagramConn, JsValue>;
+
+	#[wasm_bindgen(catch, js_namespace = Deno, js_name = networkInterfaces)]
+	fn network_interfaces() -> Result<Array, JsValue>; // NetworkInterfaceInfo[]
+
+	fn setTimeout(cb: Function, millis: u32);
+	fn setInterval(cb: &Function, millis: u32) -> JsValue;
+	fn clearInterval(id: &JsValue);
+
+	#[wasm_bindgen(js_namespace = performance, js_name = now)]
+	fn performance_now() -> f64;
+}
+
+/// The class name and message of a thrown Deno error.
+fn js_error_parts(e: &JsValue) -> (String, String) {
+	let name = Reflect::get(e, &JsValue::from_str("name")).ok().and_then(|v| v.as_string()).unwrap_or_default();
+	let message = Reflect::get(e, &JsValue::from_str("message")).ok().and_then(|v| v.as_string())
+		.or_else(|| e.as_string())
+		.unwrap_or_else(|| "unknown Deno error".to_owned());
+	(name, message)
+}
+
+/// Converts a rejected Deno promise into an `io::Error`, keeping the error class where Deno
+/// reports one.
+pub(crate) fn js_to_io(e: JsValue) -> io::Error {
+	let (name, message) = js_error_parts(&e);
+	let kind = match name.as_str() {
+		"NotFound" => io::ErrorKind::NotFound,
+		"PermissionDenied" => io::ErrorKind::PermissionDenied,
+		"ConnectionRefused" => io::ErrorKind::ConnectionRefused,
+		"ConnectionReset" => io::ErrorKind::ConnectionReset,
+		"ConnectionAborted" => io::ErrorKind::ConnectionAborted,
+		"NotConnected" => io::ErrorKind::NotConnected,
+		"AddrInUse" => io::ErrorKind::AddrInUse,
+		"AddrNotAvailable" => io::ErrorKind::AddrNotAvailable,
+		"BrokenPipe" => io::ErrorKind::BrokenPipe,
+		"InvalidData" => io::ErrorKind::InvalidData,
+		"TimedOut" => io::ErrorKind::TimedOut,
+		"Interrupted" | "BadResource" => io::ErrorKind::NotConnected,
+		"WriteZero" => io::ErrorKind::WriteZero,
+		"UnexpectedEof" => io::ErrorKind::UnexpectedEof,
+		_ => io::ErrorKind::Other,
+	};
+	io::Error::new(kind, message)
+}
+
+/// Classifies a `Deno.resolveDns` rejection for `query`.
+fn dns_error(e: &JsValue, query: &str) -> ResolveError {
+	let (name, message) = js_error_parts(e);
+	match name.as_str() {
+		"NotFound" => ResolveError::NotFound(query.to_owned()),
+		"TimedOut" => ResolveError::TimedOut(query.to_owned()),
+		"PermissionDenied" | "NotCapable" => ResolveError::PermissionDenied(query.to_owned()),
+		_ => ResolveError::Other(format!("{}: {}", query, message)),
+	}
+}
+
+fn addr_options(addr: SocketAddr, transport: &str) -> JsValue {
+	let options = Object::new();
+	let _ = Reflect::set(&options, &JsValue::from_str("transport"), &JsValue::from_str(transport));
+	let _ = Reflect::set(&options, &JsValue::from_str("hostname"), &JsValue::from_str(&addr.ip().to_string()));
+	let _ = Reflect::set(&options, &JsValue::from_str("port"), &JsValue::from(addr.port()));
+	options.into()
+}
+
+fn get_string(obj: &JsValue, key: &str) -> Option<String> {
+	Reflect::get(obj, &JsValue::from_str(key)).ok()?.as_string()
+}
+
+fn in_subnet(addr: IpAddr, netmask: IpAddr, other: IpAddr) -> bool {
+	match (addr, netmask, other) {
+		(IpAddr::V4(a), IpAddr::V4(m), IpAddr::V4(o)) => u32::from(a) & u32::from(m) == u32::from(o) & u32::from(m),
+		(IpAddr::V6(a), IpAddr::V6(m), IpAddr::V6(o)) => u128::from(a) & u128::from(m) == u128::from(o) & u128::from(m),
+		_ => false,
+	}
+}
+
+fn to_socket_addr(addr: &Addr) -> io::Result<SocketAddr> {
+	// Deno reports IPv6 hostnames without brackets.
+	let ip = addr.hostname().parse::<IpAddr>()
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Failed to parse hostname"))?;
+	Ok(SocketAddr::new(ip, addr.port()))
+}
+
+/// The Deno runtime. Requires `--unstable` for datagram sockets.
+pub struct Deno;
+
+impl Runtime for Deno {
+	type Interval = Interval;
+
+	fn spawn<F: Future<Output = ()> + 'static>(future: F) {
+		wasm_bindgen_futures::spawn_local(future)
+	}
+	fn sleep(dur: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+		let promise = Promise::new(&mut move |res, _rej| {
+			setTimeout(res, dur.as_millis() as u32);
+		});
+		Box::pin(async move {
+			let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+		})
+	}
+	fn interval(period: Duration) -> Interval {
+		let state = Rc::new(RefCell::new(IntervalState::default()));
+		let callback = {
+			let state = Rc::clone(&state);
+			Closure::<dyn FnMut()>::new(move || {
+				let mut state = state.borrow_mut();
+				state.fired = true;
+				if let Some(waker) = state.waker.take() {
+					waker.wake();
+				}
+			})
+		};
+		let id = setInterval(callback.as_ref().unchecked_ref(), period.as_millis() as u32);
+		Interval { id, _callback: callback, state, started: false }
+	}
+	fn now() -> SystemTime {
+		SystemTime::from_unix_epoch(Duration::from_secs_f64(js_sys::Date::now() / 1000.0))
+	}
+	fn monotonic_now() -> Instant {
+		// Both are milliseconds with a fraction down to a few microseconds. The time origin is
+		// fixed for the life of the process, so the sum only moves with the monotonic part.
+		thread_local! {
+			static TIME_ORIGIN: f64 = Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+				.and_then(|performance| Reflect::get(&performance, &JsValue::from_str("timeOrigin")))
+				.ok()
+				.and_then(|origin| origin.as_f64())
+				.unwrap_or_default();
+		}
+		let millis = TIME_ORIGIN.with(|origin| *origin) + performance_now();
+		Instant::from_unix_epoch(Duration::from_secs_f64(millis.max(0.0) / 1000.0))
+	}
+}
+
+#[derive(Default)]
+struct IntervalState {
+	fired: bool,
+	waker: Option<Waker>,
+}
+
+/// A `setInterval` timer, cleared on drop.
+pub struct Interval {
+	id: JsValue,
+	_callback: Closure<dyn FnMut()>,
+	state: Rc<RefCell<IntervalState>>,
+	started: bool,
+}
+
+#[async_trait(?Send)]
+impl Ticker for Interval {
+	async fn tick(&mut self) -> Instant {
+		if !std::mem::replace(&mut self.started, true) {
+			return Instant::now();
+		}
+		// The callback only raises a flag, so periods that fire while nobody waits collapse into one.
+		std::future::poll_fn(|cx| {
+			let mut state = self.state.borrow_mut();
+			if std::mem::take(&mut state.fired) {
+				Poll::Ready(())
+			} else {
+				state.waker = Some(cx.waker().clone());
+				Poll::Pending
+			}
+		}).await;
+		Instant::now()
+	}
+}
+
+impl Drop for Interval {
+	fn drop(&mut self) {
+		clearInterval(&self.id);
+	}
+}
+
+#[async_trait(?Send)]
+impl Net for Deno {
+	type DatagramConn = DatagramConn;
+	type Conn = Conn;
+	type Listener = Listener;
+
+	async fn bind_udp(addr: SocketAddr) -> io::Result<DatagramConn> {
+		listenDatagram(&addr_options(addr, "udp")).map(DatagramConn::new).map_err(js_to_io)
+	}
+	async fn bind_multicast(addr: SocketAddr, reuse_port: bool) -> io::Result<DatagramConn> {
+		let options = addr_options(addr, "udp");
+		// Deno's reuseAddress sets SO_REUSEPORT along with SO_REUSEADDR, there is no way to
+		// share the address alone. Without either, a second mDNS responder couldn't bind.
+		let _ = reuse_port;
+		let _ = Reflect::set(&options, &JsValue::from_str("reuseAddress"), &JsValue::TRUE);
+		let _ = Reflect::set(&options, &JsValue::from_str("loopback"), &JsValue::TRUE);
+		listenDatagram(&options).map(DatagramConn::new).map_err(js_to_io)
+	}
+	async fn listen_tcp(addr: SocketAddr) -> io::Result<Listener> {
+		listen(&addr_options(addr, "tcp")).map_err(js_to_io)
+	}
+	async fn connect_tcp(addr: SocketAddr) -> io::Result<Conn> {
+		let conn = connect(&addr_options(addr, "tcp")).await.map_err(js_to_io)?;
+		Ok(conn.unchecked_into())
+	}
+	async fn connect_tls(addr: SocketAddr, hostname: &str) -> io::Result<Conn> {
+		let conn = connect(&addr_options(addr, "tcp")).await.map_err(js_to_io)?;
+		let options = Object::new();
+		let _ = Reflect::set(&options, &JsValue::from_str("hostname"), &JsValue::from_str(hostname));
+		// A TlsConn is a Conn that encrypts what goes through it.
+		let conn = startTls(&conn, &options).await.map_err(js_to_io)?;
+		Ok(conn.unchecked_into())
+	}
+	async fn resolve_host(host: &str) -> io::Result<Vec<IpAddr>> {
+		let (v4, v6) = tokio::join!(resolveDns(host, "A"), resolveDns(host, "AAAA"));
+		// A name may only have records of one family, so only fail when both lookups do.
+		let (v4, v6) = match (v4, v6) {
+			(Err(e), Err(_)) => return Err(dns_error(&e, host).into()),
+			(v4, v6) => (v4.unwrap_or_else(|_| Array::new().into()), v6.unwrap_or_else(|_| Array::new().into())),
+		};
+		let v4 = v4.unchecked_into::<Array>();
+		let v6 = v6.unchecked_into::<Array>();
+		Ok(v4.iter().chain(v6.iter()).flat_map(|v| v.as_string()?.parse().ok()).collect())
+	}
+	async fn resolve_srv(name: &str) -> io::Result<Vec<SrvRecord>> {
+		let records = resolveDns(name, "SRV").await.map_err(|e| dns_error(&e, name))?;
+		Ok(records.unchecked_into::<Array>().iter().filter_map(|r| {
+			let field = |k: &str| Reflect::get(&r, &JsValue::from_str(k)).ok()?.as_f64().map(|v| v as u16);
+			Some(SrvRecord {
+				priority: field("priority")?,
+				weight: field("weight")?,
+				port: field("port")?,
+				target: get_string(&r, "target")?.trim_end_matches('.').to_owned(),
+			})
+		}).collect())
+	}
+	async fn resolve_txt(name: &str) -> io::Result<Vec<String>> {
+		// Each record comes back as its list of character-strings.
+		let records = resolveDns(name, "TXT").await.map_err(|e| dns_error(&e, name))?;
+		Ok(records.unchecked_into::<Array>().iter().map(|r| {
+			r.unchecked_into::<Array>().iter().filter_map(|c| c.as_string()).collect()
+		}).collect())
+	}
+	async fn local_ip_for(remote: IpAddr) -> io::Result<IpAddr> {
+		// Deno can't connect a datagram socket to ask the kernel for a route, so pick the
+		// interface whose subnet holds `remote`, falling back to any usable one of the same family.
+		let mut fallback = None;
+		for iface in Self::network_interfaces()? {
+			if iface.addr.is_ipv4() != remote.is_ipv4() {
+				continue;
+			}
+			if matches!(iface.netmask, Some(m) if in_subnet(iface.addr, m, remote)) {
+				return Ok(iface.addr);
+			}
+			if fallback.is_none() && !iface.addr.is_loopback() {
+				fallback = Some(iface.addr);
+			}
+		}
+		fallback.ok_or_else(|| io::Error::new(io::ErrorKind::AddrNotAvailable, "no interface can reach the remote address"))
+	}
+	fn network_interfaces() -> io::Result<Vec<NetworkInterface>> {
+		// Requires `--allow-sys`. Entries we can't parse are skipped rather than failing the lot.
+		Ok(network_interfaces().map_err(js_to_io)?.iter().filter_map(|iface| {
+			Some(NetworkInterface {
+				name: get_string(&iface, "name")?,
+				addr: get_string(&iface, "address")?.parse().ok()?,
+				netmask: get_string(&iface, "netmask").and_then(|m| m.parse().ok()),
+			})
+		}).collect())
+	}
+}
+
+/// A `Deno.DatagramConn`. Deno's sockets can't be connected, so the peer is kept on this side.
+pub struct DatagramConn {
+	socket: JsDatagramConn,
+	peer: Cell<Option<SocketAddr>>,
+}
+
+impl DatagramConn {
+	fn new(socket: JsDatagramConn) -> Self {
+		DatagramConn {
+			socket,
+			peer: Cell::new(None),
+		}
+	}
+}
+
+#[async_trait(?Send)]
+impl DatagramSocket for DatagramConn {
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		to_socket_addr(&self.socket.js_addr())
+	}
+	async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+		let p = Uint8Array::new_with_length(buf.len() as u32);
+		let ret = self.socket.js_receive(&p).await.map_err(js_to_io)?;
+		let ret = ret.unchecked_into::<Array>();
+		let u8a = ret.get(0).unchecked_into::<Uint8Array>();
+		let addr = ret.get(1).unchecked_into::<Addr>();
+		let n = (u8a.length() as usize).min(buf.len());
+		u8a.subarray(0, n as u32).copy_to(&mut buf[..n]);
+		Ok((n, to_socket_addr(&addr)?))
+	}
+	async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+		let p = Uint8Array::from(buf);
+		let ret = self.socket.js_send(&p, &addr_options(target, "udp")).await.map_err(js_to_io)?;
+		Ok(ret.as_f64().unwrap_or_default() as usize)
+	}
+	async fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+		self.peer.set(Some(addr));
+		Ok(())
+	}
+	fn peer_addr(&self) -> Option<SocketAddr> {
+		self.peer.get()
+	}
+	async fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+		// The membership handle is dropped, the group is left when the socket closes.
+		self.socket.js_join_multicast_v4(&multiaddr.to_string(), &interface.to_string()).await.map_err(js_to_io)?;
+		Ok(())
+	}
+	fn close(&self) {
+		let _ = self.socket.js_close();
+	}
+}
+
+#[async_trait(?Send)]
+impl StreamSocket for Conn {
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		to_socket_addr(&self.js_local_addr())
+	}
+	fn remote_addr(&self) -> io::Result<SocketAddr> {
+		to_socket_addr(&self.js_remote_addr())
+	}
+	async fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+		let p = Uint8Array::new_with_length(buf.len() as u32);
+		let ret = self.js_read(&p).await.map_err(js_to_io)?;
+		// Deno signals EOF with null.
+		let Some(n) = ret.as_f64() else {
+			return Ok(0);
+		};
+		let n = (n as usize).min(buf.len());
+		p.subarray(0, n as u32).copy_to(&mut buf[..n]);
+		Ok(n)
+	}
+	async fn write(&self, buf: &[u8]) -> io::Result<usize> {
+		let p = Uint8Array::from(buf);
+		let ret = self.js_write(&p).await.map_err(js_to_io)?;
+		Ok(ret.as_f64().unwrap_or_default() as usize)
+	}
+	fn close(&self) {
+		let _ = self.js_close();
+	}
+}
+
+#[async_trait(?Send)]
+impl StreamListener for Listener {
+	type Conn = Conn;
+
+	async fn accept(&self) -> io::Result<(Conn, SocketAddr)> {
+		let conn = self.js_accept().await.map_err(js_to_io)?.unchecked_into::<Conn>();
+		let raddr = StreamSocket::remote_addr(&conn)?;
+		Ok((conn, raddr))
+	}
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		to_socket_addr(&self.js_addr())
+	}
+	fn close(&self) {
+		let _ = self.js_close();
+	}
+}
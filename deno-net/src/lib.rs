@@ -1,4 +1,14 @@
+//! Platform services used by the WebRTC stack.
+//!
+//! Timers, task spawning, name resolution and sockets are described by the [`Runtime`] and
+//! [`Net`] traits. The backend is chosen at build time: the `deno` feature (on by default) binds
+//! to the Deno APIs through wasm-bindgen, while the `native` feature runs on tokio so the stack
+//! can be exercised on a regular host. If both are enabled, `native` wins.
+
 use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::time::Duration;
 use std::net::{
 	SocketAddr,
@@ -9,105 +19,373 @@ use std::net::{
 	Ipv6Addr,
 };
 
-use wasm_bindgen::prelude::*;
-use js_sys::{Function, Promise, Array, Object, Reflect};
-use wasm_bindgen::JsCast;
+use async_trait::async_trait;
+
+#[cfg(test)]
+mod lib_test;
+
+#[cfg(feature = "deno")]
+pub mod deno;
+#[cfg(feature = "native")]
+pub mod native;
+
+#[cfg(feature = "native")]
+pub use native::{Tokio as Backend, DatagramConn, Conn, Listener, Interval};
+#[cfg(all(feature = "deno", not(feature = "native")))]
+pub use deno::{Deno as Backend, DatagramConn, Conn, Listener, Interval};
+#[cfg(not(any(feature = "deno", feature = "native")))]
+compile_error!("deno-net needs a backend: enable either the `deno` or the `native` feature.");
+
+/// Timers, clocks and task spawning.
+pub trait Runtime {
+	type Interval: Ticker;
+
+	/// Runs `future` to completion in the background on the current thread.
+	fn spawn<F: Future<Output = ()> + 'static>(future: F);
+	/// Completes once `dur` has elapsed.
+	fn sleep(dur: Duration) -> Pin<Box<dyn Future<Output = ()>>>;
+	/// Fires every `period`, starting right away.
+	fn interval(period: Duration) -> Self::Interval;
+	/// Current wall-clock time.
+	fn now() -> SystemTime;
+	/// Current monotonic time.
+	fn monotonic_now() -> Instant;
+}
+
+/// A timer that fires periodically.
+#[async_trait(?Send)]
+pub trait Ticker {
+	/// Completes at the next period and returns when it fired. The first tick completes
+	/// immediately. Periods missed while nobody was waiting are skipped rather than fired in a burst.
+	async fn tick(&mut self) -> Instant;
+}
+
+/// Sockets and name resolution.
+#[async_trait(?Send)]
+pub trait Net {
+	type DatagramConn: DatagramSocket;
+	type Conn: StreamSocket;
+	type Listener: StreamListener<Conn = Self::Conn>;
+
+	async fn bind_udp(addr: SocketAddr) -> io::Result<Self::DatagramConn>;
+	/// Binds a UDP socket that shares its address with other processes, for multicast protocols
+	/// such as mDNS, and its port too with `reuse_port` (SO_REUSEPORT). Multicast loopback is
+	/// enabled so local peers see each other.
+	async fn bind_multicast(addr: SocketAddr, reuse_port: bool) -> io::Result<Self::DatagramConn>;
+	async fn listen_tcp(addr: SocketAddr) -> io::Result<Self::Listener>;
+	async fn connect_tcp(addr: SocketAddr) -> io::Result<Self::Conn>;
+	/// Opens a TLS connection to `addr`, verifying the server certificate against `hostname`.
+	/// Only the deno backend has a TLS stack, the native one fails with `Unsupported`.
+	async fn connect_tls(addr: SocketAddr, hostname: &str) -> io::Result<Self::Conn>;
+	/// Resolves `host` to all of its A and AAAA records. Failures carry a [`ResolveError`].
+	async fn resolve_host(host: &str) -> io::Result<Vec<IpAddr>>;
+	/// Looks up the SRV records of `name`, e.g. `_stun._udp.example.org`.
+	async fn resolve_srv(name: &str) -> io::Result<Vec<SrvRecord>>;
+	/// Looks up the TXT records of `name`, one string per record.
+	async fn resolve_txt(name: &str) -> io::Result<Vec<String>>;
+	/// The local address the host would use to reach `remote`.
+	async fn local_ip_for(remote: IpAddr) -> io::Result<IpAddr>;
+	/// Lists the addresses assigned to the host's network interfaces, one entry per address.
+	fn network_interfaces() -> io::Result<Vec<NetworkInterface>>;
+}
+
+/// A DNS SRV record, see RFC 2782.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+	pub priority: u16,
+	pub weight: u16,
+	pub port: u16,
+	pub target: String,
+}
+
+/// Why a name lookup failed. The `io::Error`s returned by [`lookup_host`], [`resolve_srv`] and
+/// [`resolve_txt`] carry one of these, see [`ResolveError::from_io`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+	/// The name does not exist (NXDOMAIN) or has no records of the requested type.
+	NotFound(String),
+	/// The resolver did not answer in time.
+	TimedOut(String),
+	/// The runtime may not resolve names, e.g. Deno without `--allow-net`.
+	PermissionDenied(String),
+	/// The address is not of the form `host:port`.
+	InvalidAddress(String),
+	Other(String),
+}
+impl ResolveError {
+	pub fn kind(&self) -> io::ErrorKind {
+		match self {
+			Self::NotFound(_) => io::ErrorKind::NotFound,
+			Self::TimedOut(_) => io::ErrorKind::TimedOut,
+			Self::PermissionDenied(_) => io::ErrorKind::PermissionDenied,
+			Self::InvalidAddress(_) => io::ErrorKind::InvalidInput,
+			Self::Other(_) => io::ErrorKind::Other,
+		}
+	}
+	/// The lookup failure behind `err`, if it came from a lookup.
+	pub fn from_io(err: &io::Error) -> Option<&ResolveError> {
+		err.get_ref()?.downcast_ref()
+	}
+}
+impl std::fmt::Display for ResolveError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::NotFound(name) => write!(f, "no DNS records found for {}", name),
+			Self::TimedOut(name) => write!(f, "DNS lookup of {} timed out", name),
+			Self::PermissionDenied(name) => write!(f, "not permitted to resolve {}", name),
+			Self::InvalidAddress(reason) => write!(f, "invalid address: {}", reason),
+			Self::Other(reason) => write!(f, "DNS lookup failed: {}", reason),
+		}
+	}
+}
+impl std::error::Error for ResolveError {}
+impl From<ResolveError> for io::Error {
+	fn from(err: ResolveError) -> Self {
+		io::Error::new(err.kind(), err)
+	}
+}
+
+/// An address assigned to a network interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkInterface {
+	pub name: String,
+	pub addr: IpAddr,
+	pub netmask: Option<IpAddr>,
+}
+
+/// A bound UDP socket.
+#[async_trait(?Send)]
+pub trait DatagramSocket {
+	fn local_addr(&self) -> io::Result<SocketAddr>;
+	async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)>;
+	async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize>;
+	/// Makes `addr` the peer of this socket, see [`peer_addr`](Self::peer_addr). Where the platform
+	/// supports it the socket is connected as well, which also picks the local address.
+	async fn connect(&self, addr: SocketAddr) -> io::Result<()>;
+	/// The peer set with [`connect`](Self::connect), if any.
+	fn peer_addr(&self) -> Option<SocketAddr>;
+	/// Joins the IPv4 multicast group `multiaddr` on the interface owning `interface`, or on the
+	/// default interface when `interface` is unspecified.
+	async fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()>;
+	/// Closes the socket, pending receives fail.
+	fn close(&self);
+}
+
+/// A connected TCP stream.
+#[async_trait(?Send)]
+pub trait StreamSocket {
+	fn local_addr(&self) -> io::Result<SocketAddr>;
+	fn remote_addr(&self) -> io::Result<SocketAddr>;
+	/// Reads some bytes into `buf`, returning 0 once the peer has closed the stream.
+	async fn read(&self, buf: &mut [u8]) -> io::Result<usize>;
+	async fn write(&self, buf: &[u8]) -> io::Result<usize>;
+	async fn write_all(&self, mut buf: &[u8]) -> io::Result<()> {
+		while !buf.is_empty() {
+			match self.write(buf).await? {
+				0 => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer")),
+				n => buf = &buf[n..],
+			}
+		}
+		Ok(())
+	}
+	/// Closes the stream, pending reads and writes fail.
+	fn close(&self);
+}
+
+/// A listening TCP socket.
+#[async_trait(?Send)]
+pub trait StreamListener {
+	type Conn: StreamSocket;
 
+	async fn accept(&self) -> io::Result<(Self::Conn, SocketAddr)>;
+	fn local_addr(&self) -> io::Result<SocketAddr>;
+	/// Closes the listener, pending accepts fail.
+	fn close(&self);
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct SystemTimeError;
+impl std::fmt::Display for SystemTimeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("second time provided was later than self")
+	}
+}
+impl std::error::Error for SystemTimeError {}
+/// Wall-clock time, kept as the duration since the unix epoch.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct SystemTime(u64);
+pub struct SystemTime(Duration);
 impl SystemTime {
-	pub const UNIX_EPOCH: Self = Self(0);
+	pub const UNIX_EPOCH: Self = Self(Duration::ZERO);
 	pub fn now() -> Self {
-		Self(js_sys::Date::now() as u64)
+		Backend::now()
+	}
+	pub(crate) fn from_unix_epoch(since_epoch: Duration) -> Self {
+		Self(since_epoch)
 	}
 	pub fn duration_since(&self, earlier: Self) -> Result<Duration, SystemTimeError> {
-		if earlier.0 > self.0 {
-			Err(SystemTimeError)
-		} else {
-			Ok(Duration::from_millis(self.0 - earlier.0))
-		}
+		self.0.checked_sub(earlier.0).ok_or(SystemTimeError)
 	}
 	pub fn checked_add(&self, dur: Duration) -> Option<Self> {
-		self.0.checked_add(dur.as_millis() as u64).map(|v| Self(v))
+		self.0.checked_add(dur).map(Self)
+	}
+	pub fn checked_sub(&self, dur: Duration) -> Option<Self> {
+		self.0.checked_sub(dur).map(Self)
 	}
 	pub fn elapsed(&self) -> Result<Duration, SystemTimeError> {
-		SystemTime::now().duration_since(self.clone())
+		SystemTime::now().duration_since(*self)
 	}
 }
 impl std::ops::Add<Duration> for SystemTime {
 	type Output = Self;
 	fn add(self, rhs: Duration) -> Self::Output {
-		SystemTime(self.0 + rhs.as_millis() as u64)
+		SystemTime(self.0 + rhs)
 	}
 }
 impl std::ops::AddAssign<Duration> for SystemTime {
 	fn add_assign(&mut self, rhs: Duration) {
-		self.0 += rhs.as_millis() as u64;
+		self.0 += rhs;
+	}
+}
+impl std::ops::Sub<Duration> for SystemTime {
+	type Output = Self;
+	fn sub(self, rhs: Duration) -> Self::Output {
+		SystemTime(self.0 - rhs)
+	}
+}
+impl std::ops::SubAssign<Duration> for SystemTime {
+	fn sub_assign(&mut self, rhs: Duration) {
+		self.0 -= rhs;
 	}
 }
 pub const UNIX_EPOCH: SystemTime = SystemTime::UNIX_EPOCH;
 
-#[wasm_bindgen]
-extern "C" {
-	#[wasm_bindgen(catch, js_namespace = Deno)]
-	async fn resolveDns(query: &str, record_type: &str) -> Result<JsValue, JsValue>;
-
-	pub type Listener;
-	#[wasm_bindgen(method)]
-	pub async fn accept(this: &Listener) -> JsValue; // Returns a Conn
-
-	pub type DatagramConn;
-	#[wasm_bindgen(method, getter)]
-	pub fn addr(this: &DatagramConn) -> Addr;
-	#[wasm_bindgen(method, getter)]
-	pub fn remoteAddr(this: &DatagramConn) -> Option<Addr>;
-	#[wasm_bindgen(method, catch)]
-	pub async fn receive(this: &DatagramConn, p: Option<&mut [u8]>) -> Result<JsValue, JsValue>; // Promise<[Uint8Array, Addr]>
-	#[wasm_bindgen(method, catch)]
-	pub async fn send(this: &DatagramConn, p: &[u8], addr: Option<Addr>) -> Result<JsValue, JsValue>; // Promise<number>
-	#[wasm_bindgen(method)]
-	pub fn close(this: &DatagramConn);
-
-	pub type Conn;
-	#[wasm_bindgen(method, getter)]
-	pub fn localAddr(this: &Conn) -> Addr;
-	#[wasm_bindgen(method, getter)]
-	pub fn remoteAddr(this: &Conn) -> Addr;
-	#[wasm_bindgen(method, catch)]
-	pub async fn read(this: &Conn, p: &mut [u8]) -> Result<JsValue, JsValue>; // Promise<number | null>
-	#[wasm_bindgen(method, catch)]
-	pub async fn write(this: &Conn, p: &[u8]) -> Result<JsValue, JsValue>; // Promise<number>
-	#[wasm_bindgen(method)]
-	pub fn close(this: &Conn);
-
-	pub type Addr;
-	#[wasm_bindgen(method, getter)]
-	pub fn transport(this: &Addr) -> String;
-	#[wasm_bindgen(method, getter)]
-	pub fn hostname(this: &Addr) -> String;
-	#[wasm_bindgen(method, getter)]
-	pub fn port(this: &Addr) -> u16;
-
-	#[wasm_bindgen(js_namespace = Deno)]
-	pub fn listen(options: JsValue) -> Listener;
-
-	#[wasm_bindgen(js_namespace = Deno)]
-	pub fn listenDatagram(options: JsValue) -> DatagramConn;
-	
-	fn setTimeout(cb: Function, millis: u32);
-
-	// #[wasm_bindgen(catch, js_namespace = Deno)]
-	// async fn resolveDns(query: &str, record_type: &str) -> Result<JsValue, JsValue>;
-}
-pub async fn sleep(dur: Duration) {
-	let _ = wasm_bindgen_futures::JsFuture::from(Promise::new(&mut move |res, _rej| {
-		setTimeout(res, dur.as_millis() as u32);
-	})).await;
+/// A monotonic clock reading, for measuring elapsed time and scheduling timers. Unlike
+/// [`SystemTime`] it never jumps when the wall clock is adjusted.
+///
+/// The clock starts at the wall-clock time the process started and then only moves forward, so
+/// instants from well before startup can still be represented.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Instant(Duration);
+impl Instant {
+	pub fn now() -> Self {
+		Backend::monotonic_now()
+	}
+	/// The instant that corresponds to `since_epoch` after the unix epoch on the clock's
+	/// startup timeline. Mostly useful for tests and mock clocks.
+	pub fn from_unix_epoch(since_epoch: Duration) -> Self {
+		Self(since_epoch)
+	}
+	/// Offset of this instant from the unix epoch on the clock's startup timeline. It drifts away
+	/// from the wall clock over time, absolute timestamps like NTP should come from [`SystemTime`].
+	pub fn since_unix_epoch(&self) -> Duration {
+		self.0
+	}
+	/// Time elapsed from `earlier` to `self`, zero if `earlier` is later.
+	pub fn duration_since(&self, earlier: Self) -> Duration {
+		self.saturating_duration_since(earlier)
+	}
+	pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+		self.0.checked_sub(earlier.0)
+	}
+	pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
+		self.0.saturating_sub(earlier.0)
+	}
+	pub fn elapsed(&self) -> Duration {
+		Instant::now().duration_since(*self)
+	}
+	pub fn checked_add(&self, dur: Duration) -> Option<Self> {
+		self.0.checked_add(dur).map(Self)
+	}
+	pub fn checked_sub(&self, dur: Duration) -> Option<Self> {
+		self.0.checked_sub(dur).map(Self)
+	}
+}
+impl std::ops::Add<Duration> for Instant {
+	type Output = Self;
+	fn add(self, rhs: Duration) -> Self::Output {
+		Instant(self.0 + rhs)
+	}
+}
+impl std::ops::AddAssign<Duration> for Instant {
+	fn add_assign(&mut self, rhs: Duration) {
+		self.0 += rhs;
+	}
+}
+impl std::ops::Sub<Duration> for Instant {
+	type Output = Self;
+	fn sub(self, rhs: Duration) -> Self::Output {
+		Instant(self.0 - rhs)
+	}
+}
+impl std::ops::SubAssign<Duration> for Instant {
+	fn sub_assign(&mut self, rhs: Duration) {
+		self.0 -= rhs;
+	}
+}
+impl std::ops::Sub<Instant> for Instant {
+	type Output = Duration;
+	fn sub(self, rhs: Instant) -> Self::Output {
+		self.duration_since(rhs)
+	}
+}
+
+/// Runs `future` in the background. Like a detached tokio task, its output is dropped.
+pub fn spawn<F: Future + 'static>(future: F) {
+	Backend::spawn(async move {
+		let _ = future.await;
+	})
+}
+/// Future returned by [`sleep`] and [`sleep_until`].
+pub struct Sleep {
+	deadline: Instant,
+	inner: Pin<Box<dyn Future<Output = ()>>>,
+}
+impl Sleep {
+	pub fn deadline(&self) -> Instant {
+		self.deadline
+	}
+	/// Moves the deadline, whether or not the previous one has passed.
+	pub fn reset(&mut self, deadline: Instant) {
+		*self = sleep_until(deadline);
+	}
+}
+impl Future for Sleep {
+	type Output = ();
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		self.inner.as_mut().poll(cx)
+	}
+}
+/// Completes once `dur` has elapsed.
+pub fn sleep(dur: Duration) -> Sleep {
+	Sleep {
+		deadline: Instant::now() + dur,
+		inner: Backend::sleep(dur),
+	}
+}
+/// Completes once `deadline` is reached.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+	Sleep {
+		deadline,
+		inner: Backend::sleep(deadline.saturating_duration_since(Instant::now())),
+	}
+}
+/// Fires every `period`, see [`Ticker`].
+pub fn interval(period: Duration) -> Interval {
+	Backend::interval(period)
 }
+/// Lists the addresses assigned to the host's network interfaces, one entry per address.
+pub fn network_interfaces() -> io::Result<Vec<NetworkInterface>> {
+	Backend::network_interfaces()
+}
+/// Error returned by [`timeout`] when the deadline passes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Elapsed;
+impl std::fmt::Display for Elapsed {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("deadline has elapsed")
+	}
+}
+impl std::error::Error for Elapsed {}
 pub async fn timeout<F: Future>(d: Duration, f: F) -> Result<F::Output, Elapsed> {
 	tokio::select! {
 		_ = sleep(d) => Err(Elapsed),
@@ -116,102 +394,186 @@ pub async fn timeout<F: Future>(d: Duration, f: F) -> Result<F::Output, Elapsed>
 }
 
 impl DatagramConn {
-	pub async fn bind<A: ToSocketAddrs>(addr: A) -> std::io::Result<Self> {
-		let sa = lookup_host(addr).await?.next().ok_or(std::io::Error::new(std::io::ErrorKind::Other, "No address found during bind"))?;
-		let options = Object::new();
-		let _ = Reflect::set(&options, &JsValue::from_str("hostname"), &JsValue::from_str(&sa.ip().to_string()));
-		let _ = Reflect::set(&options, &JsValue::from_str("port"), &JsValue::from(sa.port()));
-		Ok(listenDatagram(JsValue::from(options)))
+	pub async fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+		let sa = lookup_host(addr).await?.next().ok_or(io::Error::other("No address found during bind"))?;
+		Backend::bind_udp(sa).await
+	}
+	pub async fn bind_multicast(addr: SocketAddr, reuse_port: bool) -> io::Result<Self> {
+		Backend::bind_multicast(addr, reuse_port).await
+	}
+}
+impl Listener {
+	pub async fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+		let sa = lookup_host(addr).await?.next().ok_or(io::Error::other("No address found during listen"))?;
+		Backend::listen_tcp(sa).await
 	}
 }
+impl Conn {
+	pub async fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+		let mut last_err = io::Error::other("No address found during connect");
+		for sa in lookup_host(addr).await? {
+			match Backend::connect_tcp(sa).await {
+				Ok(conn) => return Ok(conn),
+				Err(e) => last_err = e,
+			}
+		}
+		Err(last_err)
+	}
+	/// Connects over TLS to `addr`, verifying the server certificate against `hostname`.
+	///
+	/// Natively this fails with [`io::ErrorKind::Unsupported`], there is no TLS stack with a
+	/// root store on that backend.
+	pub async fn connect_tls(addr: SocketAddr, hostname: &str) -> io::Result<Self> {
+		Backend::connect_tls(addr, hostname).await
+	}
+}
+
+/// What a [`ToSocketAddrs`] value stands for: addresses, or a host name still to be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Addrs {
+	Resolved(Vec<SocketAddr>),
+	Lookup(String, u16),
+}
 
 pub trait ToSocketAddrs {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)>;
+	fn to_addrs(&self) -> Result<Addrs, ResolveError>;
 }
 impl<T: ?Sized + ToSocketAddrs> ToSocketAddrs for &T {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
 		T::to_addrs(self)
 	}
 }
 impl ToSocketAddrs for SocketAddr {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![*self])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![*self]))
 	}
 }
 impl ToSocketAddrs for SocketAddrV4 {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![SocketAddr::V4(*self)])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![SocketAddr::V4(*self)]))
 	}
 }
 impl ToSocketAddrs for SocketAddrV6 {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![SocketAddr::V6(*self)])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![SocketAddr::V6(*self)]))
 	}
 }
 impl ToSocketAddrs for (IpAddr, u16) {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![SocketAddr::new(self.0, self.1)])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![SocketAddr::new(self.0, self.1)]))
 	}
 }
 impl ToSocketAddrs for (Ipv4Addr, u16) {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![SocketAddr::new(IpAddr::V4(self.0), self.1)])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![SocketAddr::new(IpAddr::V4(self.0), self.1)]))
 	}
 }
 impl ToSocketAddrs for (Ipv6Addr, u16) {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(vec![SocketAddr::new(IpAddr::V6(self.0), self.1)])
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(vec![SocketAddr::new(IpAddr::V6(self.0), self.1)]))
 	}
 }
 impl ToSocketAddrs for &[SocketAddr] {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Ok(self.into_iter().cloned().collect())
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		Ok(Addrs::Resolved(self.to_vec()))
 	}
 }
 impl ToSocketAddrs for str {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		let (ip, port) = self.rsplit_once(':').unwrap();
-		let ip = ip.to_owned();
-		let port = port.parse().unwrap();
-		Err((ip, port))
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		if let Ok(sa) = self.parse::<SocketAddr>() {
+			return Ok(Addrs::Resolved(vec![sa]));
+		}
+		// A bare IPv6 address is all colons, its last group is not a port.
+		if self.parse::<IpAddr>().is_ok() {
+			return Err(ResolveError::InvalidAddress(format!("{}: missing port", self)));
+		}
+		let (host, port) = self.rsplit_once(':')
+			.ok_or_else(|| ResolveError::InvalidAddress(format!("{}: missing port", self)))?;
+		let port = port.parse()
+			.map_err(|_| ResolveError::InvalidAddress(format!("{}: invalid port", self)))?;
+		let host = match host.strip_prefix('[') {
+			Some(bracketed) => bracketed.strip_suffix(']')
+				.ok_or_else(|| ResolveError::InvalidAddress(format!("{}: unbalanced brackets", self)))?,
+			None if host.contains(':') => {
+				return Err(ResolveError::InvalidAddress(format!("{}: IPv6 hosts need brackets", self)));
+			}
+			None => host,
+		};
+		host_port(host, port)
 	}
 }
 impl ToSocketAddrs for (&str, u16) {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Err((self.0.to_owned(), self.1))
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		host_port(self.0, self.1)
 	}
 }
 impl ToSocketAddrs for (String, u16) {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
-		Err(self.clone())
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
+		host_port(&self.0, self.1)
 	}
 }
 impl ToSocketAddrs for String {
-	fn to_addrs(&self) -> Result<Vec<SocketAddr>, (String, u16)> {
+	fn to_addrs(&self) -> Result<Addrs, ResolveError> {
 		self.as_str().to_addrs()
 	}
 }
+fn host_port(host: &str, port: u16) -> Result<Addrs, ResolveError> {
+	if host.is_empty() {
+		return Err(ResolveError::InvalidAddress("missing host".to_owned()));
+	}
+	Ok(match host.parse::<IpAddr>() {
+		Ok(ip) => Addrs::Resolved(vec![SocketAddr::new(ip, port)]),
+		Err(_) => Addrs::Lookup(host.to_owned(), port),
+	})
+}
 
-pub async fn lookup_host<T>(host: T) -> std::io::Result<impl Iterator<Item = SocketAddr>>
+pub async fn lookup_host<T>(host: T) -> io::Result<impl Iterator<Item = SocketAddr>>
 where
 	T: ToSocketAddrs,
 {
-	match host.to_addrs() {
-		Ok(v) => Ok(v.into_iter()),
-		Err((query, port)) => {
-			match tokio::try_join!(
-				resolveDns(&query, "A"),
-				resolveDns(&query, "AAAA")
-			) {
-				Err(_e) => {
-					Err(std::io::Error::new(std::io::ErrorKind::Other, "Deno's resolveDns failed."))
-				},
-				Ok((v4, v6)) => {
-					let v4 = v4.unchecked_into::<Array>();
-					let v6 = v6.unchecked_into::<Array>();
-					Ok(v4.iter().chain(v6.iter()).flat_map(|v| -> Option<IpAddr> { v.as_string().unwrap().parse().ok() }).map(|ip| SocketAddr::new(ip, port)).collect::<Vec<_>>().into_iter())
-				}
+	match host.to_addrs()? {
+		Addrs::Resolved(v) => Ok(v.into_iter()),
+		Addrs::Lookup(query, port) => {
+			let ips = Backend::resolve_host(&query).await?;
+			if ips.is_empty() {
+				return Err(ResolveError::NotFound(query).into());
 			}
+			Ok(ips.into_iter().map(|ip| SocketAddr::new(ip, port)).collect::<Vec<_>>().into_iter())
+		}
+	}
+}
+/// Looks up the SRV records of `name` in the order to try them: lowest priority first and, within
+/// a priority, the weighted random order of RFC 2782.
+pub async fn resolve_srv(name: &str) -> io::Result<Vec<SrvRecord>> {
+	let records = Backend::resolve_srv(name).await?;
+	Ok(order_srv(records, &mut rand::thread_rng()))
+}
+/// Sorts `records` by priority and shuffles each priority by weight: every pick takes a record
+/// with probability proportional to its weight, zero weights only getting a small chance.
+fn order_srv<R: rand::Rng + ?Sized>(mut records: Vec<SrvRecord>, rng: &mut R) -> Vec<SrvRecord> {
+	// Zero weights go first so a running sum of 0 can select them, as RFC 2782 suggests.
+	records.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.weight.cmp(&b.weight)));
+	let mut ordered = Vec::with_capacity(records.len());
+	while !records.is_empty() {
+		let priority = records[0].priority;
+		let len = records.iter().take_while(|r| r.priority == priority).count();
+		let mut group: Vec<SrvRecord> = records.drain(..len).collect();
+		while !group.is_empty() {
+			let total: u32 = group.iter().map(|r| u32::from(r.weight)).sum();
+			let pick = rng.gen_range(0..=total);
+			let mut sum = 0;
+			let index = group.iter()
+				.position(|r| {
+					sum += u32::from(r.weight);
+					sum >= pick
+				})
+				.unwrap_or(0);
+			ordered.push(group.remove(index));
 		}
 	}
+	ordered
+}
+/// Looks up the TXT records of `name`, one string per record.
+pub async fn resolve_txt(name: &str) -> io::Result<Vec<String>> {
+	Backend::resolve_txt(name).await
 }
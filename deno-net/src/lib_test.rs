@@ -0,0 +1,94 @@
+use super::*;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn srv(priority: u16, weight: u16, target: &str) -> SrvRecord {
+	SrvRecord {
+		priority,
+		weight,
+		port: 3478,
+		target: target.to_owned(),
+	}
+}
+
+#[test]
+fn test_str_to_addrs() {
+	let v4: SocketAddr = "127.0.0.1:3478".parse().unwrap();
+	assert_eq!("127.0.0.1:3478".to_addrs(), Ok(Addrs::Resolved(vec![v4])));
+	let v6: SocketAddr = "[::1]:3478".parse().unwrap();
+	assert_eq!("[::1]:3478".to_addrs(), Ok(Addrs::Resolved(vec![v6])));
+	assert_eq!("example.com:3478".to_addrs(), Ok(Addrs::Lookup("example.com".to_owned(), 3478)));
+	assert_eq!("[example.com]:3478".to_addrs(), Ok(Addrs::Lookup("example.com".to_owned(), 3478)));
+}
+
+#[test]
+fn test_str_to_addrs_invalid() {
+	let tests = [
+		// A bare IPv6 address must not lose its last group to the port.
+		"::1",
+		"fe80::1",
+		"2001:db8::1:3478",
+		"127.0.0.1",
+		"example.com",
+		"example.com:port",
+		":3478",
+		"[::1:3478",
+	];
+	for addr in tests {
+		match addr.to_addrs() {
+			Err(ResolveError::InvalidAddress(_)) => {}
+			other => panic!("{}: expected an invalid address, got {:?}", addr, other),
+		}
+	}
+}
+
+#[test]
+fn test_order_srv_by_priority() {
+	let records = vec![srv(20, 0, "c"), srv(10, 5, "a"), srv(30, 100, "d"), srv(20, 0, "b")];
+	let ordered = order_srv(records, &mut StdRng::seed_from_u64(0));
+	let priorities: Vec<u16> = ordered.iter().map(|r| r.priority).collect();
+	assert_eq!(priorities, vec![10, 20, 20, 30]);
+}
+
+#[test]
+fn test_order_srv_by_weight() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let mut heavy_first = 0;
+	for _ in 0..1000 {
+		let ordered = order_srv(vec![srv(10, 10, "light"), srv(10, 90, "heavy")], &mut rng);
+		assert_eq!(ordered.len(), 2);
+		if ordered[0].target == "heavy" {
+			heavy_first += 1;
+		}
+	}
+	// 90% expected, a strict heavier-first sort would give 100% and a plain shuffle 50%.
+	assert!((850..=950).contains(&heavy_first), "heavy first {} times out of 1000", heavy_first);
+}
+
+#[test]
+fn test_order_srv_zero_weight() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let mut zero_first = 0;
+	for _ in 0..1000 {
+		let ordered = order_srv(vec![srv(10, 0, "zero"), srv(10, 100, "weighted")], &mut rng);
+		if ordered[0].target == "zero" {
+			zero_first += 1;
+		}
+	}
+	// Zero weights are only picked when the draw is exactly 0.
+	assert!(zero_first > 0 && zero_first < 50, "zero weight first {} times out of 1000", zero_first);
+}
+
+#[cfg(feature = "native")]
+#[tokio::test(flavor = "local")]
+async fn test_lookup_host() -> io::Result<()> {
+	let addrs: Vec<SocketAddr> = lookup_host("localhost:3478").await?.collect();
+	assert!(!addrs.is_empty());
+	assert!(addrs.iter().all(|a| a.ip().is_loopback() && a.port() == 3478), "{:?}", addrs);
+
+	let err = lookup_host("::1").await.err().expect("a bare IPv6 address has no port");
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	assert!(matches!(ResolveError::from_io(&err), Some(ResolveError::InvalidAddress(_))));
+	Ok(())
+}
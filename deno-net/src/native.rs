@@ -0,0 +1,372 @@
+//! Backend built on tokio, for running the stack on a regular host.
+//!
+//! The stack is single threaded (`?Send` futures), so tasks are spawned with
+//! `tokio::task::spawn_local` and everything has to be driven from inside a `tokio::task::LocalSet`
+//! or a `tokio::runtime::LocalRuntime` (`#[tokio::test(flavor = "local")]`).
+
+use std::cell::Cell;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+
+use crate::{DatagramSocket, Instant, Net, NetworkInterface, ResolveError, Runtime, SrvRecord, StreamListener, StreamSocket, SystemTime, Ticker};
+
+/// Lets `&self` methods close a socket that tokio would only close on drop.
+#[derive(Default)]
+struct Closer {
+	closed: AtomicBool,
+	notify: Notify,
+}
+impl Closer {
+	fn close(&self) {
+		self.closed.store(true, Ordering::SeqCst);
+		self.notify.notify_waiters();
+	}
+	fn is_closed(&self) -> bool {
+		self.closed.load(Ordering::SeqCst)
+	}
+	async fn closed(&self) {
+		// Created before checking the flag so a concurrent close can't be missed.
+		let notified = self.notify.notified();
+		if self.is_closed() {
+			return;
+		}
+		notified.await
+	}
+}
+
+fn closed_error() -> io::Error {
+	io::Error::new(io::ErrorKind::NotConnected, "socket closed")
+}
+
+/// Resolves `host` with getaddrinfo. std folds its EAI_* code into a message, so it is called
+/// directly to tell a missing name from a failing resolver.
+#[cfg(unix)]
+fn getaddrinfo(host: &str) -> Result<Vec<IpAddr>, ResolveError> {
+	use nix::libc;
+	use std::ffi::{CStr, CString};
+
+	let node = CString::new(host).map_err(|_| ResolveError::InvalidAddress(format!("{:?}: contains a nul byte", host)))?;
+	// SAFETY: an all zero addrinfo is the "no hints" value.
+	let mut hints: libc::addrinfo = unsafe { std::mem::zeroed() };
+	hints.ai_socktype = libc::SOCK_STREAM;
+	let mut res = std::ptr::null_mut();
+	// SAFETY: node is nul terminated and res is only read when the call succeeded.
+	let code = unsafe { libc::getaddrinfo(node.as_ptr(), std::ptr::null(), &hints, &mut res) };
+	match code {
+		0 => {}
+		libc::EAI_NONAME | libc::EAI_NODATA => return Err(ResolveError::NotFound(host.to_owned())),
+		libc::EAI_AGAIN => return Err(ResolveError::TimedOut(host.to_owned())),
+		libc::EAI_SYSTEM => return Err(ResolveError::Other(format!("{}: {}", host, io::Error::last_os_error()))),
+		_ => {
+			// SAFETY: gai_strerror returns a static string.
+			let reason = unsafe { CStr::from_ptr(libc::gai_strerror(code)) };
+			return Err(ResolveError::Other(format!("{}: {}", host, reason.to_string_lossy())));
+		}
+	}
+	let mut ips = vec![];
+	let mut cur = res;
+	while !cur.is_null() {
+		// SAFETY: cur walks the list getaddrinfo returned, ai_addr matches ai_family.
+		unsafe {
+			let ai = &*cur;
+			match ai.ai_family {
+				libc::AF_INET => {
+					let sin = &*(ai.ai_addr as *const libc::sockaddr_in);
+					ips.push(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)).into());
+				}
+				libc::AF_INET6 => {
+					let sin6 = &*(ai.ai_addr as *const libc::sockaddr_in6);
+					ips.push(Ipv6Addr::from(sin6.sin6_addr.s6_addr).into());
+				}
+				_ => {}
+			}
+			cur = ai.ai_next;
+		}
+	}
+	// SAFETY: res came from a successful getaddrinfo and is freed once.
+	unsafe { libc::freeaddrinfo(res) };
+	Ok(ips)
+}
+
+/// The tokio runtime.
+pub struct Tokio;
+
+impl Runtime for Tokio {
+	type Interval = Interval;
+
+	fn spawn<F: Future<Output = ()> + 'static>(future: F) {
+		tokio::task::spawn_local(future);
+	}
+	fn sleep(dur: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+		Box::pin(tokio::time::sleep(dur))
+	}
+	fn interval(period: Duration) -> Interval {
+		let mut interval = tokio::time::interval(period);
+		interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+		Interval { interval }
+	}
+	fn now() -> SystemTime {
+		let since_epoch = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.unwrap_or_default();
+		SystemTime::from_unix_epoch(since_epoch)
+	}
+	fn monotonic_now() -> Instant {
+		// Follows tokio's clock, so tests that pause and advance time see it move.
+		static ORIGIN: OnceLock<(Duration, tokio::time::Instant)> = OnceLock::new();
+		let (origin_unix, origin) = *ORIGIN.get_or_init(|| {
+			let since_epoch = std::time::SystemTime::now()
+				.duration_since(std::time::UNIX_EPOCH)
+				.unwrap_or_default();
+			(since_epoch, tokio::time::Instant::now())
+		});
+		Instant::from_unix_epoch(origin_unix + tokio::time::Instant::now().saturating_duration_since(origin))
+	}
+}
+
+pub struct Interval {
+	interval: tokio::time::Interval,
+}
+
+#[async_trait(?Send)]
+impl Ticker for Interval {
+	async fn tick(&mut self) -> Instant {
+		self.interval.tick().await;
+		Instant::now()
+	}
+}
+
+#[async_trait(?Send)]
+impl Net for Tokio {
+	type DatagramConn = DatagramConn;
+	type Conn = Conn;
+	type Listener = Listener;
+
+	async fn bind_udp(addr: SocketAddr) -> io::Result<DatagramConn> {
+		Ok(DatagramConn {
+			socket: tokio::net::UdpSocket::bind(addr).await?,
+			closer: Closer::default(),
+			peer: Cell::new(None),
+		})
+	}
+	async fn bind_multicast(addr: SocketAddr, reuse_port: bool) -> io::Result<DatagramConn> {
+		let socket = socket2::Socket::new(
+			socket2::Domain::for_address(addr),
+			socket2::Type::DGRAM,
+			Some(socket2::Protocol::UDP),
+		)?;
+		#[cfg(target_family = "unix")]
+		socket.set_reuse_port(reuse_port)?;
+		#[cfg(not(target_family = "unix"))]
+		let _ = reuse_port;
+		socket.set_reuse_address(true)?;
+		socket.set_broadcast(true)?;
+		if addr.is_ipv4() {
+			socket.set_multicast_loop_v4(true)?;
+		}
+		socket.set_nonblocking(true)?;
+		socket.bind(&addr.into())?;
+		Ok(DatagramConn {
+			socket: tokio::net::UdpSocket::from_std(socket.into())?,
+			closer: Closer::default(),
+			peer: Cell::new(None),
+		})
+	}
+	async fn listen_tcp(addr: SocketAddr) -> io::Result<Listener> {
+		Ok(Listener {
+			listener: tokio::net::TcpListener::bind(addr).await?,
+			closer: Closer::default(),
+		})
+	}
+	async fn connect_tcp(addr: SocketAddr) -> io::Result<Conn> {
+		Ok(Conn::new(tokio::net::TcpStream::connect(addr).await?))
+	}
+	async fn connect_tls(_addr: SocketAddr, _hostname: &str) -> io::Result<Conn> {
+		// There is no TLS stack with a root store on this backend.
+		Err(io::Error::new(io::ErrorKind::Unsupported, "TLS connections need the deno backend"))
+	}
+	#[cfg(unix)]
+	async fn resolve_host(host: &str) -> io::Result<Vec<IpAddr>> {
+		let host = host.to_owned();
+		match tokio::task::spawn_blocking(move || getaddrinfo(&host)).await {
+			Ok(result) => Ok(result?),
+			Err(e) => Err(io::Error::other(e)),
+		}
+	}
+	#[cfg(not(unix))]
+	async fn resolve_host(host: &str) -> io::Result<Vec<IpAddr>> {
+		match tokio::net::lookup_host((host, 0)).await {
+			Ok(addrs) => Ok(addrs.map(|sa| sa.ip()).collect()),
+			Err(e) => Err(ResolveError::Other(format!("{}: {}", host, e)).into()),
+		}
+	}
+	async fn resolve_srv(_name: &str) -> io::Result<Vec<SrvRecord>> {
+		// getaddrinfo only knows address records.
+		Err(io::Error::new(io::ErrorKind::Unsupported, "SRV lookups need the deno backend"))
+	}
+	async fn resolve_txt(_name: &str) -> io::Result<Vec<String>> {
+		Err(io::Error::new(io::ErrorKind::Unsupported, "TXT lookups need the deno backend"))
+	}
+	async fn local_ip_for(remote: IpAddr) -> io::Result<IpAddr> {
+		// Connecting a datagram socket sends nothing, it only makes the kernel pick a route.
+		let unspecified: IpAddr = if remote.is_ipv4() { Ipv4Addr::UNSPECIFIED.into() } else { Ipv6Addr::UNSPECIFIED.into() };
+		let socket = std::net::UdpSocket::bind((unspecified, 0))?;
+		socket.connect((remote, 9))?;
+		Ok(socket.local_addr()?.ip())
+	}
+	#[cfg(unix)]
+	fn network_interfaces() -> io::Result<Vec<NetworkInterface>> {
+		fn to_ip(addr: &nix::sys::socket::SockaddrStorage) -> Option<IpAddr> {
+			if let Some(sin) = addr.as_sockaddr_in() {
+				Some(Ipv4Addr::from(sin.ip()).into())
+			} else {
+				addr.as_sockaddr_in6().map(|sin6| sin6.ip().into())
+			}
+		}
+		let ifaddrs = nix::ifaddrs::getifaddrs().map_err(io::Error::from)?;
+		// Interfaces without an address (or with a link layer one) have nothing to offer.
+		Ok(ifaddrs.filter_map(|ifaddr| {
+			Some(NetworkInterface {
+				addr: to_ip(ifaddr.address.as_ref()?)?,
+				netmask: ifaddr.netmask.as_ref().and_then(to_ip),
+				name: ifaddr.interface_name,
+			})
+		}).collect())
+	}
+	#[cfg(not(unix))]
+	fn network_interfaces() -> io::Result<Vec<NetworkInterface>> {
+		Err(io::Error::new(io::ErrorKind::Unsupported, "interface enumeration is only implemented for unix hosts"))
+	}
+}
+
+pub struct DatagramConn {
+	socket: tokio::net::UdpSocket,
+	closer: Closer,
+	peer: Cell<Option<SocketAddr>>,
+}
+
+impl DatagramConn {
+	/// The underlying tokio socket, for options the [`DatagramSocket`] trait doesn't cover.
+	pub fn socket(&self) -> &tokio::net::UdpSocket {
+		&self.socket
+	}
+}
+
+#[async_trait(?Send)]
+impl DatagramSocket for DatagramConn {
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.socket.local_addr()
+	}
+	async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+		tokio::select! {
+			_ = self.closer.closed() => Err(closed_error()),
+			r = self.socket.recv_from(buf) => r,
+		}
+	}
+	async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+		if self.closer.is_closed() {
+			return Err(closed_error());
+		}
+		self.socket.send_to(buf, target).await
+	}
+	async fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+		self.socket.connect(addr).await?;
+		// The kernel resolves an unspecified address to loopback, keep what it settled on.
+		self.peer.set(Some(self.socket.peer_addr()?));
+		Ok(())
+	}
+	fn peer_addr(&self) -> Option<SocketAddr> {
+		self.peer.get()
+	}
+	async fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+		self.socket.join_multicast_v4(multiaddr, interface)
+	}
+	fn close(&self) {
+		self.closer.close();
+	}
+}
+
+pub struct Conn {
+	stream: tokio::net::TcpStream,
+	closer: Closer,
+}
+
+impl Conn {
+	fn new(stream: tokio::net::TcpStream) -> Self {
+		// Framed packets are small, don't hold them back waiting for more data.
+		let _ = stream.set_nodelay(true);
+		Conn {
+			stream,
+			closer: Closer::default(),
+		}
+	}
+}
+
+#[async_trait(?Send)]
+impl StreamSocket for Conn {
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.stream.local_addr()
+	}
+	fn remote_addr(&self) -> io::Result<SocketAddr> {
+		self.stream.peer_addr()
+	}
+	async fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+		loop {
+			tokio::select! {
+				_ = self.closer.closed() => return Err(closed_error()),
+				r = self.stream.readable() => r?,
+			}
+			match self.stream.try_read(buf) {
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+				r => return r,
+			}
+		}
+	}
+	async fn write(&self, buf: &[u8]) -> io::Result<usize> {
+		loop {
+			tokio::select! {
+				_ = self.closer.closed() => return Err(closed_error()),
+				r = self.stream.writable() => r?,
+			}
+			match self.stream.try_write(buf) {
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+				r => return r,
+			}
+		}
+	}
+	fn close(&self) {
+		self.closer.close();
+		let _ = socket2::SockRef::from(&self.stream).shutdown(Shutdown::Both);
+	}
+}
+
+pub struct Listener {
+	listener: tokio::net::TcpListener,
+	closer: Closer,
+}
+
+#[async_trait(?Send)]
+impl StreamListener for Listener {
+	type Conn = Conn;
+
+	async fn accept(&self) -> io::Result<(Conn, SocketAddr)> {
+		tokio::select! {
+			_ = self.closer.closed() => Err(closed_error()),
+			r = self.listener.accept() => r.map(|(stream, raddr)| (Conn::new(stream), raddr)),
+		}
+	}
+	fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.listener.local_addr()
+	}
+	fn close(&self) {
+		self.closer.close();
+	}
+}
@@ -9,7 +9,7 @@ use webrtc_dtls::{config::*, conn::DTLSConn};
 
 // cargo run --example dial_psk -- --server 127.0.0.1:4444
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -56,7 +56,11 @@ async fn main() -> Result<(), Error> {
     let server = matches.value_of("server").unwrap();
 
     let conn = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
-    conn.connect(server).await?;
+    let server_addr = deno_net::lookup_host(server)
+        .await?
+        .next()
+        .ok_or_else(|| Error::Other(format!("{} did not resolve", server)))?;
+    conn.connect(server_addr).await?;
     println!("connecting {}..", server);
 
     let config = Config {
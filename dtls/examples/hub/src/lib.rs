@@ -6,7 +6,7 @@ pub mod utilities;
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use util::Conn;
 
 use dtls::Error;
@@ -37,7 +37,7 @@ impl Hub {
         }
 
         let conns = Arc::clone(&self.conns);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let _ = Hub::read_loop(conns, conn).await;
         });
     }
@@ -91,17 +91,14 @@ impl Hub {
 
     /// Chat starts the stdin readloop to dispatch messages to the hub
     pub async fn chat(&self) {
-        let input = std::io::stdin();
-        let mut reader = BufReader::new(input.lock());
-        loop {
-            let mut msg = String::new();
-            match reader.read_line(&mut msg) {
-                Ok(0) => return,
+        let mut lines = stdin_lines();
+        while let Some(msg) = lines.recv().await {
+            let msg = match msg {
+                Ok(msg) => msg,
                 Err(err) => {
                     println!("stdin read err: {}", err);
                     return;
                 }
-                _ => {}
             };
             if msg.trim() == "exit" {
                 return;
@@ -110,3 +107,29 @@ impl Hub {
         }
     }
 }
+
+/// stdin_lines reads stdin line by line on a thread of its own, a blocking read would stall
+/// the single-threaded runtime the examples run on.
+pub(crate) fn stdin_lines() -> mpsc::UnboundedReceiver<std::io::Result<String>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let input = std::io::stdin();
+        let mut reader = BufReader::new(input.lock());
+        loop {
+            let mut msg = String::new();
+            match reader.read_line(&mut msg) {
+                Ok(0) => return,
+                Ok(_) => {
+                    if tx.send(Ok(msg)).is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    let _ = tx.send(Err(err));
+                    return;
+                }
+            }
+        }
+    });
+    rx
+}
@@ -1,8 +1,6 @@
 use super::*;
 
 use dtls::crypto::{Certificate, CryptoPrivateKey};
-use rcgen::KeyPair;
-use rustls::internal::pemfile::certs;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -40,7 +38,7 @@ impl From<io::Error> for Error {
 /// chat simulates a simple text chat session over the connection
 pub async fn chat(conn: Arc<dyn Conn>) -> Result<(), Error> {
     let conn_rx = Arc::clone(&conn);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut b = vec![0u8; BUF_SIZE];
 
         while let Ok(n) = conn_rx.recv(&mut b).await {
@@ -51,17 +49,14 @@ pub async fn chat(conn: Arc<dyn Conn>) -> Result<(), Error> {
         Result::<(), Error>::Ok(())
     });
 
-    let input = std::io::stdin();
-    let mut reader = BufReader::new(input.lock());
-    loop {
-        let mut msg = String::new();
-        match reader.read_line(&mut msg) {
-            Ok(0) => return Ok(()),
+    let mut lines = stdin_lines();
+    while let Some(msg) = lines.recv().await {
+        let msg = match msg {
+            Ok(msg) => msg,
             Err(err) => {
                 println!("stdin read err: {}", err);
                 return Ok(());
             }
-            _ => {}
         };
         if msg.trim() == "exit" {
             return Ok(());
@@ -69,6 +64,8 @@ pub async fn chat(conn: Arc<dyn Conn>) -> Result<(), Error> {
 
         let _ = conn.send(msg.as_bytes()).await;
     }
+
+    Ok(())
 }
 
 /// load_key_and_certificate reads certificates or key from file
@@ -93,20 +90,34 @@ pub fn load_key(path: PathBuf) -> Result<CryptoPrivateKey, Error> {
     let mut buf = vec![];
     reader.read_to_end(&mut buf)?;
 
-    let s = String::from_utf8(buf).expect("utf8 of file");
-
-    let key_pair = KeyPair::from_pem(s.as_str()).expect("key pair in file");
+    let block = pem::parse(buf).map_err(|_| Error::ErrNoPrivateKeyFound)?;
+    if block.tag != "PRIVATE KEY" {
+        return Err(Error::ErrBlockIsNotPrivateKey);
+    }
 
-    Ok(CryptoPrivateKey::from_key_pair(&key_pair).expect("crypto key pair"))
+    CryptoPrivateKey::from_pkcs8(block.contents).map_err(|_| Error::ErrUnknownKeyTime)
 }
 
 /// load_certificate Load/read certificate(s) from file
-pub fn load_certificate(path: PathBuf) -> Result<Vec<rustls::Certificate>, Error> {
+pub fn load_certificate(path: PathBuf) -> Result<Vec<Vec<u8>>, Error> {
     let f = File::open(&path)?;
 
     let mut reader = BufReader::new(f);
-    match certs(&mut reader) {
-        Ok(ders) => Ok(ders),
-        Err(_) => Err(Error::ErrNoCertificateFound),
+    let mut buf = vec![];
+    reader.read_to_end(&mut buf)?;
+
+    let blocks = pem::parse_many(buf).map_err(|_| Error::ErrNoCertificateFound)?;
+    if blocks.is_empty() {
+        return Err(Error::ErrNoCertificateFound);
     }
+
+    let mut certificates = vec![];
+    for block in blocks {
+        if block.tag != "CERTIFICATE" {
+            return Err(Error::ErrBlockIsNotCertificate);
+        }
+        certificates.push(block.contents);
+    }
+
+    Ok(certificates)
 }
@@ -8,7 +8,7 @@ use webrtc_dtls::{config::Config, crypto::Certificate, listener::listen};
 
 // cargo run --example listen_selfsign -- --host 127.0.0.1:4444
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -72,7 +72,7 @@ async fn main() -> Result<(), Error> {
 
     let listener2 = Arc::clone(&listener);
     let h2 = Arc::clone(&h);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         while let Ok((dtls_conn, _remote_addr)) = listener2.accept().await {
             // Register the connection with the chat hub
             h2.register(dtls_conn).await;
@@ -9,7 +9,7 @@ use webrtc_dtls::{config::Config, listener::listen};
 
 // cargo run --example listen_verify -- --host 127.0.0.1:4444
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -87,7 +87,7 @@ async fn main() -> Result<(), Error> {
 
     let listener2 = Arc::clone(&listener);
     let h2 = Arc::clone(&h);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         loop {
             tokio::select! {
                 _ = done_rx.recv() => {
@@ -60,12 +60,14 @@ impl CipherSuite for CipherSuiteAes128Ccm {
 
     fn init(
         &mut self,
+        provider: &Arc<dyn CryptoProvider>,
         master_secret: &[u8],
         client_random: &[u8],
         server_random: &[u8],
         is_client: bool,
     ) -> Result<()> {
         let keys = prf_encryption_keys(
+            &**provider,
             master_secret,
             client_random,
             server_random,
@@ -77,20 +79,22 @@ impl CipherSuite for CipherSuiteAes128Ccm {
 
         if is_client {
             self.ccm = Some(CryptoCcm::new(
+                &**provider,
                 &self.crypto_ccm_tag_len,
                 &keys.client_write_key,
                 &keys.client_write_iv,
                 &keys.server_write_key,
                 &keys.server_write_iv,
-            ));
+            )?);
         } else {
             self.ccm = Some(CryptoCcm::new(
+                &**provider,
                 &self.crypto_ccm_tag_len,
                 &keys.server_write_key,
                 &keys.server_write_iv,
                 &keys.client_write_key,
                 &keys.client_write_iv,
-            ));
+            )?);
         }
 
         Ok(())
@@ -106,9 +110,9 @@ impl CipherSuite for CipherSuiteAes128Ccm {
         }
     }
 
-    fn decrypt(&self, input: &[u8]) -> Result<Vec<u8>> {
+    fn decrypt(&self, h: &RecordLayerHeader, input: &[u8]) -> Result<Vec<u8>> {
         if let Some(ccm) = &self.ccm {
-            ccm.decrypt(input)
+            ccm.decrypt(h, input)
         } else {
             Err(Error::Other(
                 "CipherSuite has not been initialized, unable to decrypt".to_owned(),
@@ -57,12 +57,14 @@ impl CipherSuite for CipherSuiteAes128GcmSha256 {
 
     fn init(
         &mut self,
+        provider: &Arc<dyn CryptoProvider>,
         master_secret: &[u8],
         client_random: &[u8],
         server_random: &[u8],
         is_client: bool,
     ) -> Result<()> {
         let keys = prf_encryption_keys(
+            &**provider,
             master_secret,
             client_random,
             server_random,
@@ -74,18 +76,20 @@ impl CipherSuite for CipherSuiteAes128GcmSha256 {
 
         if is_client {
             self.gcm = Some(CryptoGcm::new(
+                &**provider,
                 &keys.client_write_key,
                 &keys.client_write_iv,
                 &keys.server_write_key,
                 &keys.server_write_iv,
-            ));
+            )?);
         } else {
             self.gcm = Some(CryptoGcm::new(
+                &**provider,
                 &keys.server_write_key,
                 &keys.server_write_iv,
                 &keys.client_write_key,
                 &keys.client_write_iv,
-            ));
+            )?);
         }
 
         Ok(())
@@ -101,9 +105,9 @@ impl CipherSuite for CipherSuiteAes128GcmSha256 {
         }
     }
 
-    fn decrypt(&self, input: &[u8]) -> Result<Vec<u8>> {
+    fn decrypt(&self, h: &RecordLayerHeader, input: &[u8]) -> Result<Vec<u8>> {
         if let Some(cg) = &self.gcm {
-            cg.decrypt(input)
+            cg.decrypt(h, input)
         } else {
             Err(Error::Other(
                 "CipherSuite has not been initialized, unable to decrypt".to_owned(),
@@ -57,12 +57,14 @@ impl CipherSuite for CipherSuiteAes256CbcSha {
 
     fn init(
         &mut self,
+        provider: &Arc<dyn CryptoProvider>,
         master_secret: &[u8],
         client_random: &[u8],
         server_random: &[u8],
         is_client: bool,
     ) -> Result<()> {
         let keys = prf_encryption_keys(
+            &**provider,
             master_secret,
             client_random,
             server_random,
@@ -74,6 +76,7 @@ impl CipherSuite for CipherSuiteAes256CbcSha {
 
         if is_client {
             self.cbc = Some(CryptoCbc::new(
+                provider,
                 &keys.client_write_key,
                 &keys.client_mac_key,
                 &keys.server_write_key,
@@ -81,6 +84,7 @@ impl CipherSuite for CipherSuiteAes256CbcSha {
             )?);
         } else {
             self.cbc = Some(CryptoCbc::new(
+                provider,
                 &keys.server_write_key,
                 &keys.server_mac_key,
                 &keys.client_write_key,
@@ -101,9 +105,9 @@ impl CipherSuite for CipherSuiteAes256CbcSha {
         }
     }
 
-    fn decrypt(&self, input: &[u8]) -> Result<Vec<u8>> {
+    fn decrypt(&self, h: &RecordLayerHeader, input: &[u8]) -> Result<Vec<u8>> {
         if let Some(cg) = &self.cbc {
-            cg.decrypt(input)
+            cg.decrypt(h, input)
         } else {
             Err(Error::Other(
                 "CipherSuite has not been initialized, unable to decrypt".to_owned(),
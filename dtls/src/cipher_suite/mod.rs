@@ -1,6 +1,8 @@
 pub mod cipher_suite_aes_128_ccm;
 pub mod cipher_suite_aes_128_gcm_sha256;
 pub mod cipher_suite_aes_256_cbc_sha;
+pub mod cipher_suite_aes_256_gcm_sha384;
+pub mod cipher_suite_chacha20_poly1305_sha256;
 pub mod cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm;
 pub mod cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm8;
 pub mod cipher_suite_tls_psk_with_aes_128_ccm;
@@ -8,13 +10,18 @@ pub mod cipher_suite_tls_psk_with_aes_128_ccm8;
 pub mod cipher_suite_tls_psk_with_aes_128_gcm_sha256;
 
 use std::fmt;
+use std::sync::Arc;
 
 use super::client_certificate_type::*;
+use super::crypto::provider::CryptoProvider;
 use super::error::*;
 use super::record_layer::record_layer_header::*;
+use super::signature_hash_algorithm::HashAlgorithm;
 
 use cipher_suite_aes_128_gcm_sha256::*;
 use cipher_suite_aes_256_cbc_sha::*;
+use cipher_suite_aes_256_gcm_sha384::*;
+use cipher_suite_chacha20_poly1305_sha256::*;
 use cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm::*;
 use cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm8::*;
 use cipher_suite_tls_psk_with_aes_128_ccm::*;
@@ -34,6 +41,14 @@ pub enum CipherSuiteId {
     Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256 = 0xc02b,
     Tls_Ecdhe_Rsa_With_Aes_128_Gcm_Sha256 = 0xc02f,
 
+    // AES-256-GCM-SHA384
+    Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384 = 0xc02c,
+    Tls_Ecdhe_Rsa_With_Aes_256_Gcm_Sha384 = 0xc030,
+
+    // CHACHA20-POLY1305-SHA256
+    Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256 = 0xcca9,
+    Tls_Ecdhe_Rsa_With_Chacha20_Poly1305_Sha256 = 0xcca8,
+
     // AES-256-CBC-SHA
     Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha = 0xc00a,
     Tls_Ecdhe_Rsa_With_Aes_256_Cbc_Sha = 0xc014,
@@ -60,6 +75,18 @@ impl fmt::Display for CipherSuiteId {
             CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_128_Gcm_Sha256 => {
                 write!(f, "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256")
             }
+            CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384 => {
+                write!(f, "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384")
+            }
+            CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Gcm_Sha384 => {
+                write!(f, "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384")
+            }
+            CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256 => {
+                write!(f, "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256")
+            }
+            CipherSuiteId::Tls_Ecdhe_Rsa_With_Chacha20_Poly1305_Sha256 => {
+                write!(f, "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256")
+            }
             CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha => {
                 write!(f, "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA")
             }
@@ -87,6 +114,14 @@ impl From<u16> for CipherSuiteId {
             0xc02b => CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
             0xc02f => CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_128_Gcm_Sha256,
 
+            // AES-256-GCM-SHA384
+            0xc02c => CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384,
+            0xc030 => CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Gcm_Sha384,
+
+            // CHACHA20-POLY1305-SHA256
+            0xcca9 => CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256,
+            0xcca8 => CipherSuiteId::Tls_Ecdhe_Rsa_With_Chacha20_Poly1305_Sha256,
+
             // AES-256-CBC-SHA
             0xc00a => CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha,
             0xc014 => CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Cbc_Sha,
@@ -103,12 +138,21 @@ impl From<u16> for CipherSuiteId {
 #[derive(Copy, Clone, Debug)]
 pub enum CipherSuiteHash {
     Sha256,
+    Sha384,
 }
 
 impl CipherSuiteHash {
     pub(crate) fn size(&self) -> usize {
         match *self {
             CipherSuiteHash::Sha256 => 32,
+            CipherSuiteHash::Sha384 => 48,
+        }
+    }
+
+    pub(crate) fn algorithm(&self) -> HashAlgorithm {
+        match *self {
+            CipherSuiteHash::Sha256 => HashAlgorithm::Sha256,
+            CipherSuiteHash::Sha384 => HashAlgorithm::Sha384,
         }
     }
 }
@@ -124,6 +168,7 @@ pub trait CipherSuite {
     // Generate the internal encryption state
     fn init(
         &mut self,
+        provider: &Arc<dyn CryptoProvider>,
         master_secret: &[u8],
         client_random: &[u8],
         server_random: &[u8],
@@ -131,7 +176,7 @@ pub trait CipherSuite {
     ) -> Result<()>;
 
     fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>>;
-    fn decrypt(&self, input: &[u8]) -> Result<Vec<u8>>;
+    fn decrypt(&self, h: &RecordLayerHeader, input: &[u8]) -> Result<Vec<u8>>;
 }
 
 // Taken from https://www.iana.org/assignments/tls-parameters/tls-parameters.xml
@@ -151,6 +196,18 @@ pub fn cipher_suite_for_id(id: CipherSuiteId) -> Result<Box<dyn CipherSuite>> {
         CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_128_Gcm_Sha256 => {
             Ok(Box::new(CipherSuiteAes128GcmSha256::new(true)))
         }
+        CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384 => {
+            Ok(Box::new(CipherSuiteAes256GcmSha384::new(false)))
+        }
+        CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Gcm_Sha384 => {
+            Ok(Box::new(CipherSuiteAes256GcmSha384::new(true)))
+        }
+        CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256 => {
+            Ok(Box::new(CipherSuiteChaCha20Poly1305Sha256::new(false)))
+        }
+        CipherSuiteId::Tls_Ecdhe_Rsa_With_Chacha20_Poly1305_Sha256 => {
+            Ok(Box::new(CipherSuiteChaCha20Poly1305Sha256::new(true)))
+        }
         CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Cbc_Sha => {
             Ok(Box::new(CipherSuiteAes256CbcSha::new(true)))
         }
@@ -174,8 +231,12 @@ pub fn cipher_suite_for_id(id: CipherSuiteId) -> Result<Box<dyn CipherSuite>> {
 pub(crate) fn default_cipher_suites() -> Vec<Box<dyn CipherSuite>> {
     vec![
         Box::new(CipherSuiteAes128GcmSha256::new(false)),
+        Box::new(CipherSuiteAes256GcmSha384::new(false)),
+        Box::new(CipherSuiteChaCha20Poly1305Sha256::new(false)),
         Box::new(CipherSuiteAes256CbcSha::new(false)),
         Box::new(CipherSuiteAes128GcmSha256::new(true)),
+        Box::new(CipherSuiteAes256GcmSha384::new(true)),
+        Box::new(CipherSuiteChaCha20Poly1305Sha256::new(true)),
         Box::new(CipherSuiteAes256CbcSha::new(true)),
     ]
 }
@@ -186,6 +247,10 @@ fn all_cipher_suites() -> Vec<Box<dyn CipherSuite>> {
         Box::new(new_cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm8()),
         Box::new(CipherSuiteAes128GcmSha256::new(false)),
         Box::new(CipherSuiteAes128GcmSha256::new(true)),
+        Box::new(CipherSuiteAes256GcmSha384::new(false)),
+        Box::new(CipherSuiteAes256GcmSha384::new(true)),
+        Box::new(CipherSuiteChaCha20Poly1305Sha256::new(false)),
+        Box::new(CipherSuiteChaCha20Poly1305Sha256::new(true)),
         Box::new(CipherSuiteAes256CbcSha::new(false)),
         Box::new(CipherSuiteAes256CbcSha::new(true)),
         Box::new(new_cipher_suite_tls_psk_with_aes_128_ccm()),
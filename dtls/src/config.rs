@@ -1,10 +1,14 @@
 use crate::cipher_suite::*;
+use crate::crypto::provider::CryptoProvider;
 use crate::crypto::*;
 use crate::error::*;
 use crate::extension::extension_use_srtp::SrtpProtectionProfile;
 use crate::handshaker::VerifyPeerCertificateFn;
+use crate::session::SessionStore;
 use crate::signature_hash_algorithm::SignatureScheme;
+use crate::verifier::{ClientCertVerifier, ServerCertVerifier};
 
+use rand::Rng;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -75,14 +79,30 @@ pub struct Config {
     /// that one peer uses when verifying the other peer's certificates.
     /// If RootCAs is nil, TLS uses the host's root CA set.
     /// Used by Client to verify server's certificate
+    #[cfg(not(target_arch = "wasm32"))]
     pub roots_cas: rustls::RootCertStore,
 
     /// client_cas defines the set of root certificate authorities
     /// that servers use if required to verify a client certificate
     /// by the policy in client_auth.
     /// Used by Server to verify client's certificate
+    #[cfg(not(target_arch = "wasm32"))]
     pub client_cas: rustls::RootCertStore,
 
+    /// server_cert_verifier is used by a client to decide whether it trusts the
+    /// server's certificate chain, unless insecure_skip_verify is set.
+    /// If server_cert_verifier is nil, a WebPkiVerifier over roots_cas is used,
+    /// which also checks the chain is valid for server_name. There is no roots_cas on wasm,
+    /// where a NoRootsVerifier rejecting every chain is used instead; pass a
+    /// WebPkiVerifier::from_root_certificates to validate chains there.
+    pub server_cert_verifier: Option<Arc<dyn ServerCertVerifier>>,
+
+    /// client_cert_verifier is used by a server to decide whether it trusts the
+    /// client's certificate chain when client_auth asks for it to be verified.
+    /// If client_cert_verifier is nil, a WebPkiVerifier over client_cas is used,
+    /// or a NoRootsVerifier on wasm, as for server_cert_verifier.
+    pub client_cert_verifier: Option<Arc<dyn ClientCertVerifier>>,
+
     /// server_name is used to verify the hostname on the returned
     /// certificates unless insecure_skip_verify is given.
     pub server_name: String,
@@ -96,6 +116,27 @@ pub struct Config {
     /// Packet with sequence number older than this value compared to the latest
     /// accepted packet will be discarded. (default is 64)
     pub replay_protection_window: usize,
+
+    /// crypto_provider supplies the hashing, AEAD, key exchange and signature primitives.
+    /// If crypto_provider is nil, the pure Rust provider is used.
+    pub crypto_provider: Option<Arc<dyn CryptoProvider>>,
+
+    /// session_store saves negotiated sessions so later connections can resume them
+    /// with an abbreviated handshake, skipping key exchange and certificates.
+    /// If session_store is nil, session resumption is disabled.
+    pub session_store: Option<Arc<dyn SessionStore>>,
+
+    /// connection_id_generator picks the connection id (RFC 9146) the peer must put in the
+    /// records it sends us, which keeps the association alive when our address changes.
+    /// Generating an empty connection id lets the peer ask for one without us asking too.
+    /// If connection_id_generator is nil, connection ids are not negotiated.
+    pub connection_id_generator: Option<ConnectionIdGenerator>,
+
+    /// key_log receives a line in the NSS key log format (the format of SSLKEYLOGFILE)
+    /// for every master secret, so captured traffic can be decrypted in Wireshark.
+    /// Logging keys compromises the security of the connection; use it for debugging only.
+    /// If key_log is nil, keys are not logged.
+    pub key_log: Option<KeyLogCallback>,
 }
 
 impl Default for Config {
@@ -113,11 +154,19 @@ impl Default for Config {
             insecure_skip_verify: false,
             insecure_hashes: false,
             verify_peer_certificate: None,
+            #[cfg(not(target_arch = "wasm32"))]
             roots_cas: rustls::RootCertStore::empty(),
+            #[cfg(not(target_arch = "wasm32"))]
             client_cas: rustls::RootCertStore::empty(),
+            server_cert_verifier: None,
+            client_cert_verifier: None,
             server_name: String::default(),
             mtu: 0,
             replay_protection_window: 0,
+            crypto_provider: None,
+            session_store: None,
+            connection_id_generator: None,
+            key_log: None,
         }
     }
 }
@@ -128,6 +177,28 @@ pub(crate) const DEFAULT_MTU: usize = 1200; // bytes
 // If the remote provided none it will be nil
 pub(crate) type PskCallback = Arc<dyn (Fn(&[u8]) -> Result<Vec<u8>>)>;
 
+// ConnectionIdGenerator is called once per handshake to pick the connection id
+// the peer should use when sending records to us.
+pub type ConnectionIdGenerator = Arc<dyn Fn() -> Vec<u8>>;
+
+// KeyLogCallback is called with each line to append to a key log file.
+pub type KeyLogCallback = Arc<dyn Fn(&str)>;
+
+/// random_cid_generator returns a ConnectionIdGenerator of random `size` byte connection ids.
+pub fn random_cid_generator(size: usize) -> ConnectionIdGenerator {
+    Arc::new(move || {
+        let mut cid = vec![0u8; size];
+        rand::thread_rng().fill(cid.as_mut_slice());
+        cid
+    })
+}
+
+/// only_send_cid_generator returns a ConnectionIdGenerator that accepts the peer's
+/// connection id without asking it to use one.
+pub fn only_send_cid_generator() -> ConnectionIdGenerator {
+    Arc::new(Vec::new)
+}
+
 // ClientAuthType declares the policy the server will follow for
 // TLS Client Authentication.
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -179,8 +250,8 @@ pub(crate) fn validate_config(is_client: bool, config: &Config) -> Result<()> {
 
     for cert in &config.certificates {
         match cert.private_key.kind {
-            CryptoPrivateKeyKind::Ed25519(_) => {}
-            CryptoPrivateKeyKind::Ecdsa256(_) => {}
+            CryptoPrivateKeyKind::Ed25519 => {}
+            CryptoPrivateKeyKind::Ecdsa256 => {}
             _ => return Err(Error::ErrInvalidPrivateKey),
         }
     }
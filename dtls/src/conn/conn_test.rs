@@ -16,7 +16,9 @@ use crate::handshake::handshake_message_server_hello::*;
 use crate::handshake::handshake_message_server_hello_done::*;
 use crate::handshake::handshake_message_server_key_exchange::*;
 use crate::handshake::handshake_random::*;
+use crate::session::*;
 use crate::signature_hash_algorithm::*;
+use crate::verifier::*;
 
 use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
 use rand::Rng;
@@ -43,7 +45,7 @@ async fn pipe_conn(
     let (c_tx, mut c_rx) = mpsc::channel(1);
 
     // Setup client
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let client = create_test_client(
             ca,
             Config {
@@ -124,7 +126,7 @@ async fn create_test_server(
     DTLSConn::new(cb, cfg, false, None).await
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_routine_leak_on_close() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -165,7 +167,7 @@ async fn test_routine_leak_on_close() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_sequence_number_overflow_on_application_data() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -229,7 +231,7 @@ async fn test_sequence_number_overflow_on_application_data() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_sequence_number_overflow_on_handshake() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -263,6 +265,7 @@ async fn test_sequence_number_overflow_on_handshake() -> Result<()> {
                     HandshakeMessageClientHello {
                         version: PROTOCOL_VERSION1_2,
                         random: HandshakeRandom::default(),
+                        session_id: vec![],
                         cookie: vec![0; 64],
 
                         cipher_suites: vec![CipherSuiteId::Tls_Psk_With_Aes_128_Gcm_Sha256],
@@ -297,7 +300,7 @@ async fn test_sequence_number_overflow_on_handshake() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handshake_with_alert() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -353,7 +356,7 @@ async fn test_handshake_with_alert() -> Result<()> {
         let (client_err_tx, mut client_err_rx) = mpsc::channel(1);
 
         let (ca, cb) = pipe();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let result = create_test_client(Arc::new(ca), config_client, true).await;
             let _ = client_err_tx.send(result).await;
         });
@@ -400,7 +403,7 @@ async fn test_handshake_with_alert() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_export_keying_material() -> Result<()> {
     let export_label = "EXTRACTOR-dtls_srtp";
     let expected_server_key = vec![0x61, 0x09, 0x9d, 0x7d, 0xcb, 0x08, 0x52, 0x2c, 0xe7, 0x7b];
@@ -447,6 +450,7 @@ async fn test_export_keying_material() -> Result<()> {
         packet_tx: Arc::new(packet_tx),
         handle_queue_tx,
         handshake_done_tx: None,
+        last_flight: Arc::new(Mutex::new(vec![])),
 
         reader_close_tx: Mutex::new(None),
     };
@@ -514,7 +518,7 @@ async fn test_export_keying_material() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_psk() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -544,7 +548,7 @@ async fn test_psk() -> Result<()> {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
 
         let (ca, cb) = pipe();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let conf = Config {
                 psk: Some(Arc::new(psk_callback_client)),
                 psk_identity_hint: Some(client_identity.to_vec()),
@@ -590,7 +594,7 @@ async fn test_psk() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_psk_hint_fail() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -610,7 +614,7 @@ async fn test_psk_hint_fail() -> Result<()> {
     let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
 
     let (ca, cb) = pipe();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let conf = Config {
             psk: Some(Arc::new(psk_callback_hint_fail)),
             psk_identity_hint: Some(vec![]),
@@ -658,7 +662,7 @@ async fn test_psk_hint_fail() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_timeout() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -678,7 +682,7 @@ async fn test_client_timeout() -> Result<()> {
     let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
 
     let (ca, _cb) = pipe();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let conf = Config::default();
         let result = deno_net::timeout(
             Duration::from_millis(100),
@@ -705,9 +709,98 @@ async fn test_client_timeout() -> Result<()> {
     Ok(())
 }
 
+// LastFlightLossConn loses the first datagram sent through it that starts with a
+// ChangeCipherSpec record, which is how the server's last flight goes out.
+struct LastFlightLossConn {
+    conn: Arc<dyn util::Conn>,
+    dropped: AtomicBool,
+}
+
+#[async_trait(?Send)]
+impl util::Conn for LastFlightLossConn {
+    async fn connect(&self, addr: SocketAddr) -> std::result::Result<(), util::Error> {
+        self.conn.connect(addr).await
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> std::result::Result<usize, util::Error> {
+        self.conn.recv(buf).await
+    }
+
+    async fn recv_from(
+        &self,
+        buf: &mut [u8],
+    ) -> std::result::Result<(usize, SocketAddr), util::Error> {
+        self.conn.recv_from(buf).await
+    }
+
+    async fn send(&self, buf: &[u8]) -> std::result::Result<usize, util::Error> {
+        if buf.first() == Some(&(ContentType::ChangeCipherSpec as u8))
+            && !self.dropped.swap(true, Ordering::SeqCst)
+        {
+            return Ok(buf.len());
+        }
+        self.conn.send(buf).await
+    }
+
+    async fn send_to(
+        &self,
+        buf: &[u8],
+        _target: SocketAddr,
+    ) -> std::result::Result<usize, util::Error> {
+        self.send(buf).await
+    }
+
+    fn local_addr(&self) -> std::result::Result<SocketAddr, util::Error> {
+        self.conn.local_addr()
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.conn.remote_addr()
+    }
+
+    async fn close(&self) -> std::result::Result<(), util::Error> {
+        self.conn.close().await
+    }
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_resend_last_flight() -> Result<()> {
+    // The server is done once its last flight is out. When that flight is lost, the client
+    // retransmits its own last flight, which the server has to answer with its last flight
+    // again.
+    let (ca, cb) = pipe();
+    let cb = Arc::new(LastFlightLossConn {
+        conn: Arc::new(cb),
+        dropped: AtomicBool::new(false),
+    });
+
+    let config = Config {
+        flight_interval: Duration::from_millis(100),
+        ..Default::default()
+    };
+    let (client, server) = deno_net::timeout(Duration::from_secs(5), async {
+        tokio::join!(
+            create_test_client(Arc::new(ca), config.clone(), true),
+            create_test_server(Arc::clone(&cb) as Arc<dyn util::Conn>, config, true),
+        )
+    })
+    .await
+    .map_err(|_| Error::ErrDeadlineExceeded)?;
+
+    assert!(
+        cb.dropped.load(Ordering::SeqCst),
+        "the server's last flight was not lost"
+    );
+    let (client, server) = (client?, server?);
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_srtp_configuration() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -792,6 +885,20 @@ async fn test_srtp_configuration() -> Result<()> {
             None,
             None,
         ),
+        (
+            "256-bit Suites",
+            vec![
+                SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm,
+                SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80,
+            ],
+            vec![
+                SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80,
+                SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm,
+            ],
+            SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm,
+            None,
+            None,
+        ),
     ];
 
     for (name, client_srtp, server_srtp, expected_profile, want_client_err, want_server_err) in
@@ -799,7 +906,7 @@ async fn test_srtp_configuration() -> Result<()> {
     {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let conf = Config {
                 srtp_protection_profiles: client_srtp,
                 ..Default::default()
@@ -872,7 +979,7 @@ async fn test_srtp_configuration() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_certificate() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -894,13 +1001,13 @@ async fn test_client_certificate() -> Result<()> {
     let srv_cert = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
     let mut srv_ca_pool = rustls::RootCertStore::empty();
     srv_ca_pool
-        .add(&srv_cert.certificate[0])
+        .add(&rustls::Certificate(srv_cert.certificate[0].clone()))
         .map_err(|_err| Error::Other("add srv_cert error".to_owned()))?;
 
     let cert = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
     let mut ca_pool = rustls::RootCertStore::empty();
     ca_pool
-        .add(&cert.certificate[0])
+        .add(&rustls::Certificate(cert.certificate[0].clone()))
         .map_err(|_err| Error::Other("add cert error".to_owned()))?;
 
     let tests = vec![
@@ -1059,7 +1166,7 @@ async fn test_client_certificate() -> Result<()> {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
         let client_cfg_clone = client_cfg.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let result = DTLSConn::new(Arc::new(ca), client_cfg_clone, true, None).await;
             let _ = client_res_tx.send(result).await;
         });
@@ -1105,7 +1212,7 @@ async fn test_client_certificate() -> Result<()> {
             //if actual_client_cert.len() != len(tt.clientCfg.Certificates[0].Certificate) || !bytes.Equal(tt.clientCfg.Certificates[0].Certificate[0], actual_client_cert[0]) {
             assert_eq!(
                 actual_client_cert[0],
-                client_cfg.certificates[0].certificate[0].as_ref(),
+                client_cfg.certificates[0].certificate[0],
                 "{} Client certificate was not communicated correctly",
                 name,
             );
@@ -1133,13 +1240,13 @@ async fn test_client_certificate() -> Result<()> {
         )*/
         assert_eq!(
             actual_server_cert[0].len(),
-            server_cfg.certificates[0].certificate[0].as_ref().len(),
+            server_cfg.certificates[0].certificate[0].len(),
             "{} Server certificate was not communicated correctly",
             name,
         );
         assert_eq!(
             actual_server_cert[0],
-            server_cfg.certificates[0].certificate[0].as_ref(),
+            server_cfg.certificates[0].certificate[0],
             "{} Server certificate was not communicated correctly",
             name,
         );
@@ -1148,7 +1255,7 @@ async fn test_client_certificate() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_extended_master_secret() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1289,7 +1396,7 @@ async fn test_extended_master_secret() -> Result<()> {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
         let client_cfg_clone = client_cfg.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let result = create_test_client(Arc::new(ca), client_cfg_clone, true).await;
             let _ = client_res_tx.send(result).await;
         });
@@ -1335,25 +1442,43 @@ async fn test_extended_master_secret() -> Result<()> {
     Ok(())
 }
 
-fn fn_not_expected_chain(_cert: &[Vec<u8>], chain: &[rustls::Certificate]) -> Result<()> {
+fn fn_not_expected_chain(_cert: &[Vec<u8>], chain: &[Vec<u8>]) -> Result<()> {
     if !chain.is_empty() {
         return Err(Error::Other(ERR_NOT_EXPECTED_CHAIN.to_owned()));
     }
     Ok(())
 }
 
-fn fn_expected_chain(_cert: &[Vec<u8>], chain: &[rustls::Certificate]) -> Result<()> {
+fn fn_expected_chain(_cert: &[Vec<u8>], chain: &[Vec<u8>]) -> Result<()> {
     if chain.is_empty() {
         return Err(Error::Other(ERR_EXPECTED_CHAIN.to_owned()));
     }
     Ok(())
 }
 
-fn fn_wrong_cert(_cert: &[Vec<u8>], _chain: &[rustls::Certificate]) -> Result<()> {
+fn fn_wrong_cert(_cert: &[Vec<u8>], _chain: &[Vec<u8>]) -> Result<()> {
     Err(Error::Other(ERR_WRONG_CERT.to_owned()))
 }
 
-#[tokio::test]
+// ServerNameVerifier trusts any certificate chain presented for its server name.
+struct ServerNameVerifier(String);
+
+impl ServerCertVerifier for ServerNameVerifier {
+    fn verify_server_cert(
+        &self,
+        chain: &CertificateChain<'_>,
+        server_name: &str,
+        _ocsp_response: Option<&[u8]>,
+    ) -> Result<()> {
+        if server_name == self.0 && chain.parsed.len() == chain.raw.len() {
+            Ok(())
+        } else {
+            Err(Error::Other(ERR_WRONG_CERT.to_owned()))
+        }
+    }
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_server_certificate() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1374,7 +1499,7 @@ async fn test_server_certificate() -> Result<()> {
     let cert = Certificate::generate_self_signed(vec![server_name.clone()])?;
     let mut ca_pool = rustls::RootCertStore::empty();
     ca_pool
-        .add(&cert.certificate[0])
+        .add(&rustls::Certificate(cert.certificate[0].clone()))
         .map_err(|_err| Error::Other("add cert error".to_owned()))?;
 
     let tests = vec![
@@ -1495,13 +1620,42 @@ async fn test_server_certificate() -> Result<()> {
             },
             true,
         ),
+        (
+            "custom_server_cert_verifier",
+            Config {
+                server_name: server_name.clone(),
+                server_cert_verifier: Some(Arc::new(ServerNameVerifier(server_name.clone()))),
+                ..Default::default()
+            },
+            Config {
+                certificates: vec![cert.clone()],
+                client_auth: ClientAuthType::NoClientCert,
+                ..Default::default()
+            },
+            false,
+        ),
+        (
+            "custom_server_cert_verifier_error",
+            Config {
+                roots_cas: ca_pool.clone(),
+                server_name: server_name.clone(),
+                server_cert_verifier: Some(Arc::new(ServerNameVerifier("barfoo".to_owned()))),
+                ..Default::default()
+            },
+            Config {
+                certificates: vec![cert.clone()],
+                client_auth: ClientAuthType::NoClientCert,
+                ..Default::default()
+            },
+            true,
+        ),
     ];
 
     for (name, client_cfg, server_cfg, want_err) in tests {
         let (res_tx, mut res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let result = DTLSConn::new(Arc::new(cb), server_cfg, false, None).await;
             let _ = res_tx.send(result).await;
         });
@@ -1525,7 +1679,7 @@ async fn test_server_certificate() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_cipher_suite_configuration() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1591,6 +1745,22 @@ async fn test_cipher_suite_configuration() -> Result<()> {
             None,
             Some(CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Ccm_8),
         ),
+        (
+            "Valid CipherSuites AES-256-GCM specified",
+            vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384],
+            vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384],
+            None,
+            None,
+            Some(CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Gcm_Sha384),
+        ),
+        (
+            "Valid CipherSuites ChaCha20-Poly1305 specified",
+            vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256],
+            vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256],
+            None,
+            None,
+            Some(CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Chacha20_Poly1305_Sha256),
+        ),
         (
             "Server supports subset of client suites",
             vec![
@@ -1615,7 +1785,7 @@ async fn test_cipher_suite_configuration() -> Result<()> {
     {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let conf = Config {
                 cipher_suites: client_cipher_suites,
                 ..Default::default()
@@ -1692,7 +1862,7 @@ fn psk_callback(_b: &[u8]) -> Result<Vec<u8>> {
     Ok(vec![0x00, 0x01, 0x02])
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_psk_configuration() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1770,7 +1940,7 @@ async fn test_psk_configuration() -> Result<()> {
     {
         let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
         let (ca, cb) = pipe();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let conf = Config {
                 psk: if client_psk {
                     Some(Arc::new(psk_callback))
@@ -1839,7 +2009,7 @@ async fn test_psk_configuration() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_server_timeout() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1918,6 +2088,7 @@ async fn test_server_timeout() -> Result<()> {
                 version: PROTOCOL_VERSION1_2,
                 cookie,
                 random,
+                session_id: vec![],
                 cipher_suites,
                 compression_methods: default_compression_methods(),
                 extensions,
@@ -1940,7 +2111,7 @@ async fn test_server_timeout() -> Result<()> {
     let ca_rx = Arc::new(ca);
     let ca_tx = Arc::clone(&ca_rx);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut data = vec![0; 8192];
         loop {
             if let Ok(n) = ca_rx.recv(&mut data).await {
@@ -1955,7 +2126,7 @@ async fn test_server_timeout() -> Result<()> {
     });
 
     // Start sending ClientHello packets until server responds with first packet
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         loop {
             let timer = deno_net::sleep(Duration::from_millis(10));
             tokio::pin!(timer);
@@ -2005,7 +2176,7 @@ async fn test_server_timeout() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_protocol_version_validation() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2052,6 +2223,7 @@ async fn test_protocol_version_validation() -> Result<()> {
                             }, // try to downgrade
                             cookie: cookie.clone(),
                             random: random.clone(),
+                            session_id: vec![],
                             cipher_suites: vec![
                                 CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
                             ],
@@ -2072,6 +2244,7 @@ async fn test_protocol_version_validation() -> Result<()> {
                                 version: PROTOCOL_VERSION1_2,
                                 cookie: cookie.clone(),
                                 random: random.clone(),
+                                session_id: vec![],
                                 cipher_suites: vec![
                                     CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
                                 ],
@@ -2089,6 +2262,7 @@ async fn test_protocol_version_validation() -> Result<()> {
                                 }, // try to downgrade
                                 cookie: cookie.clone(),
                                 random: random.clone(),
+                                session_id: vec![],
                                 cipher_suites: vec![
                                     CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
                                 ],
@@ -2111,7 +2285,7 @@ async fn test_protocol_version_validation() -> Result<()> {
         for (name, records) in server_cases {
             let (ca, cb) = pipe();
 
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let config = Config {
                     cipher_suites: vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256],
                     flight_interval: Duration::from_millis(100),
@@ -2191,6 +2365,7 @@ async fn test_protocol_version_validation() -> Result<()> {
                                 minor: 0xff,
                             }, // try to downgrade
                             random: random.clone(),
+                            session_id: vec![],
                             cipher_suite: CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
                             compression_method: default_compression_methods().ids[0],
                             extensions: vec![],
@@ -2251,7 +2426,7 @@ async fn test_protocol_version_validation() -> Result<()> {
         for (name, records) in client_cases {
             let (ca, cb) = pipe();
 
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let config = Config {
                     cipher_suites: vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256],
                     flight_interval: Duration::from_millis(100),
@@ -2313,7 +2488,7 @@ async fn test_protocol_version_validation() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_multiple_hello_verify_request() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2363,7 +2538,7 @@ async fn test_multiple_hello_verify_request() -> Result<()> {
 
     let (ca, cb) = pipe();
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let conf = Config::default();
         let _ = deno_net::timeout(
             Duration::from_millis(100),
@@ -2421,6 +2596,7 @@ async fn send_client_hello(
     let mut h = Handshake::new(HandshakeMessage::ClientHello(HandshakeMessageClientHello {
         version: PROTOCOL_VERSION1_2,
         random: HandshakeRandom::default(),
+        session_id: vec![],
         cookie,
 
         cipher_suites: vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256],
@@ -2446,7 +2622,7 @@ async fn send_client_hello(
 // Assert that a DTLS Server always responds with RenegotiationInfo if
 // a ClientHello contained that extension or not
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_renegotation_info() -> Result<()> {
     let mut resp = vec![0u8; 1024];
 
@@ -2458,7 +2634,7 @@ async fn test_renegotation_info() -> Result<()> {
     for (name, send_renegotiation_info) in tests {
         let (ca, cb) = pipe();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let conf = Config::default();
             let _ = deno_net::timeout(
                 Duration::from_millis(100),
@@ -2498,7 +2674,7 @@ async fn test_renegotation_info() -> Result<()> {
         )
         .await?;
         let n = ca.recv(&mut resp).await?;
-        let messages = unpack_datagram(&resp[..n])?;
+        let messages = unpack_datagram(&resp[..n], 0)?;
 
         let mut reader = BufReader::new(&messages[0][..]);
         let record = RecordLayer::unmarshal(&mut reader)?;
@@ -2538,3 +2714,356 @@ async fn test_renegotation_info() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "local")]
+async fn test_session_resume() -> Result<()> {
+    let client_store = Arc::new(MemorySessionStore::new(8));
+    let server_store = Arc::new(MemorySessionStore::new(8));
+    let server_cert = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+
+    let mut session_ids = vec![];
+    for (name, resumed) in [("full handshake", false), ("abbreviated handshake", true)] {
+        let (ca, cb) = pipe();
+        let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
+
+        let session_store: Arc<dyn SessionStore> = client_store.clone();
+        deno_net::spawn(async move {
+            let conf = Config {
+                server_name: "localhost".to_owned(),
+                session_store: Some(session_store),
+                ..Default::default()
+            };
+
+            let result = create_test_client(Arc::new(ca), conf, false).await;
+            let _ = client_res_tx.send(result).await;
+        });
+
+        let config = Config {
+            certificates: vec![server_cert.clone()],
+            session_store: Some(server_store.clone()),
+            ..Default::default()
+        };
+        let server = create_test_server(Arc::new(cb), config, false).await?;
+        let client = client_res_rx.recv().await.unwrap()?;
+
+        let expected_flights = if resumed {
+            ("Flight 5b", "Flight 4b")
+        } else {
+            ("Flight 5", "Flight 6")
+        };
+        assert_eq!(
+            (
+                client.current_flight.to_string(),
+                server.current_flight.to_string()
+            ),
+            (expected_flights.0.to_owned(), expected_flights.1.to_owned()),
+            "{}: unexpected final flights",
+            name
+        );
+
+        assert!(!client.state.session_id.is_empty(), "{}", name);
+        assert_eq!(client.state.session_id, server.state.session_id, "{}", name);
+        session_ids.push(client.state.session_id.clone());
+
+        // The resumed session still reports the server's certificate.
+        assert_eq!(
+            client.connection_state().await.peer_certificates,
+            vec![server_cert.certificate[0].clone()],
+            "{}",
+            name
+        );
+
+        let client_keys = client
+            .connection_state()
+            .await
+            .export_keying_material("EXTRACTOR-dtls_srtp", &[], 16)
+            .await?;
+        let server_keys = server
+            .connection_state()
+            .await
+            .export_keying_material("EXTRACTOR-dtls_srtp", &[], 16)
+            .await?;
+        assert_eq!(client_keys, server_keys, "{}", name);
+
+        client.write(b"ping", None).await?;
+        let mut buf = vec![0u8; 16];
+        let n = server.read(&mut buf, None).await?;
+        assert_eq!(&buf[..n], b"ping", "{}", name);
+
+        client.close().await?;
+        server.close().await?;
+    }
+
+    assert_eq!(session_ids[0], session_ids[1]);
+    assert!(server_store.get(&session_ids[0]).await?.is_some());
+
+    Ok(())
+}
+
+// session_handshake runs a handshake over a pipe and returns the client and server results.
+async fn session_handshake(
+    client_config: Config,
+    server_config: Config,
+) -> (Result<DTLSConn>, Result<DTLSConn>) {
+    let (ca, cb) = pipe();
+    let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
+    deno_net::spawn(async move {
+        let result = create_test_client(Arc::new(ca), client_config, false).await;
+        let _ = client_res_tx.send(result).await;
+    });
+
+    let server = create_test_server(Arc::new(cb), server_config, false).await;
+    let client = client_res_rx.recv().await.unwrap();
+    (client, server)
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_session_resume_mismatch() -> Result<()> {
+    let server_cert = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+    let negotiated = CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256;
+    let other = CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha;
+
+    for name in [
+        "client stopped offering the cipher suite",
+        "client dropped extended master secret",
+        "server resumed with another cipher suite",
+    ] {
+        let client_store = Arc::new(MemorySessionStore::new(8));
+        let server_store = Arc::new(MemorySessionStore::new(8));
+        let client_config = || Config {
+            server_name: "localhost".to_owned(),
+            cipher_suites: vec![negotiated, other],
+            session_store: Some(client_store.clone() as Arc<dyn SessionStore>),
+            ..Default::default()
+        };
+        let server_config = || Config {
+            certificates: vec![server_cert.clone()],
+            session_store: Some(server_store.clone() as Arc<dyn SessionStore>),
+            ..Default::default()
+        };
+
+        let (client, server) = session_handshake(client_config(), server_config()).await;
+        let (client, server) = (client?, server?);
+        let session_id = client.state.session_id.clone();
+        assert_eq!(client.state.cipher_suite_id().await, negotiated, "{}", name);
+        client.close().await?;
+        server.close().await?;
+
+        let mut config = client_config();
+        match name {
+            "client stopped offering the cipher suite" => config.cipher_suites = vec![other],
+            "client dropped extended master secret" => {
+                config.extended_master_secret = ExtendedMasterSecretType::Disable
+            }
+            _ => {
+                // The server resumes with the suite it saved, not the one the client remembers.
+                let key = b"localhost_";
+                let mut session = client_store
+                    .get(key)
+                    .await?
+                    .expect("client saved no session");
+                session.cipher_suite = other;
+                client_store.set(key, session).await?;
+            }
+        }
+        let (client, server) = session_handshake(config, server_config()).await;
+
+        match name {
+            "client stopped offering the cipher suite" => {
+                // The server falls back to a full handshake with a new session.
+                let (client, server) = (client?, server?);
+                assert_eq!(
+                    (
+                        client.current_flight.to_string(),
+                        server.current_flight.to_string()
+                    ),
+                    ("Flight 5".to_owned(), "Flight 6".to_owned()),
+                    "{}",
+                    name
+                );
+                assert_eq!(client.state.cipher_suite_id().await, other, "{}", name);
+                assert_ne!(client.state.session_id, session_id, "{}", name);
+                client.close().await?;
+                server.close().await?;
+            }
+            "client dropped extended master secret" => {
+                assert!(
+                    matches!(server, Err(Error::ErrSessionMismatch)),
+                    "{}: {:?}",
+                    name,
+                    server.err()
+                );
+                assert!(client.is_err(), "{}", name);
+            }
+            _ => {
+                assert!(
+                    matches!(client, Err(Error::ErrSessionMismatch)),
+                    "{}: {:?}",
+                    name,
+                    client.err()
+                );
+                assert!(server.is_err(), "{}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_connection_id() -> Result<()> {
+    let tests = vec![
+        (
+            "both sides receive connection ids",
+            Some(random_cid_generator(8)),
+            Some(random_cid_generator(4)),
+            Some(8),
+            Some(4),
+        ),
+        (
+            "only client receives connection ids",
+            Some(random_cid_generator(8)),
+            Some(only_send_cid_generator()),
+            Some(8),
+            Some(0),
+        ),
+        (
+            "server doesn't support connection ids",
+            Some(random_cid_generator(8)),
+            None,
+            None,
+            None,
+        ),
+        (
+            "client doesn't support connection ids",
+            None,
+            Some(random_cid_generator(4)),
+            None,
+            None,
+        ),
+    ];
+
+    let server_cert = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+
+    for (name, client_generator, server_generator, client_cid_len, server_cid_len) in tests {
+        let (ca, cb) = pipe();
+        let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
+
+        deno_net::spawn(async move {
+            let conf = Config {
+                insecure_skip_verify: true,
+                connection_id_generator: client_generator,
+                ..Default::default()
+            };
+
+            let result = create_test_client(Arc::new(ca), conf, false).await;
+            let _ = client_res_tx.send(result).await;
+        });
+
+        let config = Config {
+            certificates: vec![server_cert.clone()],
+            connection_id_generator: server_generator,
+            ..Default::default()
+        };
+        let server = create_test_server(Arc::new(cb), config, false).await?;
+        let client = client_res_rx.recv().await.unwrap()?;
+
+        let client_cid = client.state.local_connection_id.lock().await.clone();
+        let server_cid = server.state.local_connection_id.lock().await.clone();
+        assert_eq!(
+            client_cid.as_ref().map(|cid| cid.len()),
+            client_cid_len,
+            "{}: unexpected client connection id",
+            name
+        );
+        assert_eq!(
+            server_cid.as_ref().map(|cid| cid.len()),
+            server_cid_len,
+            "{}: unexpected server connection id",
+            name
+        );
+        assert_eq!(
+            *server.state.remote_connection_id.lock().await,
+            client_cid,
+            "{}",
+            name
+        );
+        assert_eq!(
+            *client.state.remote_connection_id.lock().await,
+            server_cid,
+            "{}",
+            name
+        );
+
+        let mut buf = vec![0u8; 16];
+        client.write(b"ping", None).await?;
+        let n = server.read(&mut buf, Some(Duration::from_secs(5))).await?;
+        assert_eq!(&buf[..n], b"ping", "{}", name);
+
+        server.write(b"pong", None).await?;
+        let n = client.read(&mut buf, Some(Duration::from_secs(5))).await?;
+        assert_eq!(&buf[..n], b"pong", "{}", name);
+
+        client.close().await?;
+        server.close().await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_key_log() -> Result<()> {
+    let (ca, cb) = pipe();
+    let (client_res_tx, mut client_res_rx) = mpsc::channel(1);
+
+    let client_lines = Arc::new(std::sync::Mutex::new(vec![]));
+    let server_lines = Arc::new(std::sync::Mutex::new(vec![]));
+
+    let client_key_log: KeyLogCallback = {
+        let lines = Arc::clone(&client_lines);
+        Arc::new(move |line: &str| lines.lock().unwrap().push(line.to_owned()))
+    };
+    deno_net::spawn(async move {
+        let conf = Config {
+            insecure_skip_verify: true,
+            key_log: Some(client_key_log),
+            ..Default::default()
+        };
+
+        let result = create_test_client(Arc::new(ca), conf, true).await;
+        let _ = client_res_tx.send(result).await;
+    });
+
+    let server_key_log: KeyLogCallback = {
+        let lines = Arc::clone(&server_lines);
+        Arc::new(move |line: &str| lines.lock().unwrap().push(line.to_owned()))
+    };
+    let config = Config {
+        key_log: Some(server_key_log),
+        ..Default::default()
+    };
+    let server = create_test_server(Arc::new(cb), config, true).await?;
+    let client = client_res_rx.recv().await.unwrap()?;
+
+    let client_lines = client_lines.lock().unwrap().clone();
+    let server_lines = server_lines.lock().unwrap().clone();
+    assert_eq!(client_lines.len(), 1, "client should log one master secret");
+    assert_eq!(client_lines, server_lines, "both sides should log the same");
+
+    let fields: Vec<&str> = client_lines[0].split(' ').collect();
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields[0], "CLIENT_RANDOM");
+    assert_eq!(fields[1].len(), 2 * HANDSHAKE_RANDOM_LENGTH);
+    let master_secret: String = client
+        .state
+        .master_secret
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(fields[2], master_secret);
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use crate::application_data::*;
 use crate::cipher_suite::*;
 use crate::config::*;
 use crate::content::*;
+use crate::crypto::provider::default_provider;
 use crate::curve::named_curve::NamedCurve;
 use crate::error::*;
 use crate::extension::extension_use_srtp::*;
@@ -23,6 +24,10 @@ use crate::record_layer::record_layer_header::*;
 use crate::record_layer::*;
 use crate::signature_hash_algorithm::parse_signature_schemes;
 use crate::state::*;
+#[cfg(target_arch = "wasm32")]
+use crate::verifier::NoRootsVerifier;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::verifier::WebPkiVerifier;
 
 use util::{replay_detector::*, Conn};
 
@@ -60,10 +65,17 @@ struct ConnReaderContext {
     fragment_buffer: FragmentBuffer,
     cache: HandshakeCache,
     cipher_suite: Arc<Mutex<Option<Box<dyn CipherSuite>>>>,
+    local_connection_id: Arc<Mutex<Option<Vec<u8>>>>,
+    // newest_record is the epoch and sequence number of the newest record that authenticated.
+    newest_record: Option<(u16, u64)>,
+    // peer_may_have_moved is set when that record carried our connection id, so the address
+    // it came from is the peer's address now (RFC 9146 Section 6).
+    peer_may_have_moved: bool,
     remote_epoch: Arc<AtomicU16>,
     handshake_tx: mpsc::Sender<mpsc::Sender<()>>,
     handshake_done_rx: mpsc::Receiver<()>,
     packet_tx: Arc<mpsc::Sender<PacketSendRequest>>,
+    last_flight: Arc<Mutex<Vec<Packet>>>,
 }
 
 // Conn represents a DTLS connection
@@ -98,6 +110,9 @@ pub struct DTLSConn {
     pub(crate) packet_tx: Arc<mpsc::Sender<PacketSendRequest>>,
     pub(crate) handle_queue_tx: mpsc::Sender<mpsc::Sender<()>>,
     pub(crate) handshake_done_tx: Option<mpsc::Sender<()>>,
+    // last_flight is our final handshake flight, kept after the handshake so it can be
+    // sent again if the peer retransmits its own last flight because ours was lost.
+    pub(crate) last_flight: Arc<Mutex<Vec<Packet>>>,
 
     reader_close_tx: Mutex<Option<mpsc::Sender<()>>>,
 }
@@ -198,6 +213,11 @@ impl DTLSConn {
             }
         }
 
+        let crypto_provider = config
+            .crypto_provider
+            .clone()
+            .unwrap_or_else(default_provider);
+
         let cfg = HandshakeConfig {
             local_psk_callback: config.psk.take(),
             local_psk_identity_hint: config.psk_identity_hint.take(),
@@ -210,21 +230,39 @@ impl DTLSConn {
             local_certificates: config.certificates.clone(),
             insecure_skip_verify: config.insecure_skip_verify,
             verify_peer_certificate: config.verify_peer_certificate.take(),
-            roots_cas: config.roots_cas,
+            server_cert_verifier: match config.server_cert_verifier.take() {
+                Some(verifier) => verifier,
+                #[cfg(not(target_arch = "wasm32"))]
+                None => Arc::new(WebPkiVerifier::new(config.roots_cas)),
+                #[cfg(target_arch = "wasm32")]
+                None => Arc::new(NoRootsVerifier),
+            },
             client_cert_verifier: if config.client_auth as u8
                 >= ClientAuthType::VerifyClientCertIfGiven as u8
             {
-                Some(rustls::AllowAnyAuthenticatedClient::new(config.client_cas))
+                match config.client_cert_verifier.take() {
+                    Some(verifier) => Some(verifier),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    None => Some(Arc::new(WebPkiVerifier::new(config.client_cas))),
+                    #[cfg(target_arch = "wasm32")]
+                    None => Some(Arc::new(NoRootsVerifier)),
+                }
             } else {
                 None
             },
             retransmit_interval,
             //log: logger,
             initial_epoch: 0,
+            session_store: config.session_store.clone(),
+            remote_addr: conn.remote_addr(),
+            connection_id_generator: config.connection_id_generator.clone(),
+            key_log: config.key_log.clone(),
             ..Default::default()
         };
 
-        let (state, flight, initial_fsm_state) = if let Some(state) = initial_state {
+        let (state, flight, initial_fsm_state) = if let Some(mut state) = initial_state {
+            state.crypto_provider = crypto_provider;
+
             let flight = if is_client {
                 Box::new(Flight5 {}) as Box<dyn Flight>
             } else {
@@ -242,6 +280,7 @@ impl DTLSConn {
             (
                 State {
                     is_client,
+                    crypto_provider,
                     ..Default::default()
                 },
                 flight,
@@ -283,13 +322,15 @@ impl DTLSConn {
             packet_tx,
             handle_queue_tx,
             handshake_done_tx: Some(handshake_done_tx),
+            last_flight: Arc::new(Mutex::new(vec![])),
             reader_close_tx: Mutex::new(Some(reader_close_tx)),
         };
 
         let cipher_suite1 = Arc::clone(&c.state.cipher_suite);
         let sequence_number = Arc::clone(&c.state.local_sequence_number);
+        let remote_connection_id = Arc::clone(&c.state.remote_connection_id);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             loop {
                 let rx = packet_rx.recv().await;
                 if let Some(r) = rx {
@@ -302,6 +343,7 @@ impl DTLSConn {
                         is_client,
                         &sequence_number,
                         &cipher_suite1,
+                        &remote_connection_id,
                         maximum_transmission_unit,
                     )
                     .await;
@@ -319,8 +361,10 @@ impl DTLSConn {
         let local_epoch = Arc::clone(&c.state.local_epoch);
         let remote_epoch = Arc::clone(&c.state.remote_epoch);
         let cipher_suite2 = Arc::clone(&c.state.cipher_suite);
+        let local_connection_id = Arc::clone(&c.state.local_connection_id);
+        let last_flight = Arc::clone(&c.last_flight);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; INBOUND_BUFFER_SIZE];
             let mut ctx = ConnReaderContext {
                 is_client,
@@ -331,10 +375,14 @@ impl DTLSConn {
                 fragment_buffer: FragmentBuffer::new(),
                 cache: cache2,
                 cipher_suite: cipher_suite2,
+                local_connection_id,
+                newest_record: None,
+                peer_may_have_moved: false,
                 remote_epoch,
                 handshake_tx,
                 handshake_done_rx,
                 packet_tx: packet_tx2,
+                last_flight,
             };
 
             //trace!("before enter read_and_buffer: {}] ", srv_cli_str(is_client));
@@ -518,6 +566,7 @@ impl DTLSConn {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_outgoing_packets(
         next_conn: &Arc<dyn util::Conn>,
         mut pkts: Vec<Packet>,
@@ -525,8 +574,15 @@ impl DTLSConn {
         is_client: bool,
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite>>>>,
+        remote_connection_id: &Arc<Mutex<Option<Vec<u8>>>>,
         maximum_transmission_unit: usize,
     ) -> Result<()> {
+        let connection_id = remote_connection_id
+            .lock()
+            .await
+            .clone()
+            .unwrap_or_default();
+
         let mut raw_packets = vec![];
         for p in &mut pkts {
             if let Content::Handshake(h) = &p.record.content {
@@ -555,6 +611,7 @@ impl DTLSConn {
                 let raw_handshake_packets = DTLSConn::process_handshake_packet(
                     local_sequence_number,
                     cipher_suite,
+                    &connection_id,
                     maximum_transmission_unit,
                     p,
                     h,
@@ -568,8 +625,13 @@ impl DTLSConn {
                     }
                 }*/
 
-                let raw_packet =
-                    DTLSConn::process_packet(local_sequence_number, cipher_suite, p).await?;
+                let raw_packet = DTLSConn::process_packet(
+                    local_sequence_number,
+                    cipher_suite,
+                    &connection_id,
+                    p,
+                )
+                .await?;
                 raw_packets.push(raw_packet);
             }
         }
@@ -589,6 +651,7 @@ impl DTLSConn {
     async fn process_packet(
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite>>>>,
+        connection_id: &[u8],
         p: &mut Packet,
     ) -> Result<Vec<u8>> {
         let epoch = p.record.record_layer_header.epoch as usize;
@@ -620,7 +683,12 @@ impl DTLSConn {
         if p.should_encrypt {
             let cipher_suite = cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
-                raw_packet = cipher_suite.encrypt(&p.record.record_layer_header, &raw_packet)?;
+                raw_packet = DTLSConn::encrypt_packet(
+                    &**cipher_suite,
+                    &p.record.record_layer_header,
+                    &raw_packet,
+                    connection_id,
+                )?;
             }
         }
 
@@ -630,6 +698,7 @@ impl DTLSConn {
     async fn process_handshake_packet(
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite>>>>,
+        connection_id: &[u8],
         maximum_transmission_unit: usize,
         p: &Packet,
         h: &Handshake,
@@ -661,6 +730,7 @@ impl DTLSConn {
                 content_len: handshake_fragment.len() as u16,
                 epoch: p.record.record_layer_header.epoch,
                 sequence_number: seq,
+                connection_id: vec![],
             };
 
             let mut record_layer_header_bytes = vec![];
@@ -677,7 +747,12 @@ impl DTLSConn {
             if p.should_encrypt {
                 let cipher_suite = cipher_suite.lock().await;
                 if let Some(cipher_suite) = &*cipher_suite {
-                    raw_packet = cipher_suite.encrypt(&record_layer_header, &raw_packet)?;
+                    raw_packet = DTLSConn::encrypt_packet(
+                        &**cipher_suite,
+                        &record_layer_header,
+                        &raw_packet,
+                        connection_id,
+                    )?;
                 }
             }
 
@@ -687,6 +762,23 @@ impl DTLSConn {
         Ok(raw_packets)
     }
 
+    // Once the peer asked for a connection id, encrypted records are sent in the
+    // tls12_cid format so the peer can find them whatever address they arrive from.
+    // https://www.rfc-editor.org/rfc/rfc9146#section-4
+    fn encrypt_packet(
+        cipher_suite: &dyn CipherSuite,
+        h: &RecordLayerHeader,
+        raw: &[u8],
+        connection_id: &[u8],
+    ) -> Result<Vec<u8>> {
+        if connection_id.is_empty() {
+            cipher_suite.encrypt(h, raw)
+        } else {
+            let (cid_header, cid_raw) = pack_connection_id(h, raw, connection_id)?;
+            cipher_suite.encrypt(&cid_header, &cid_raw)
+        }
+    }
+
     fn fragment_handshake(maximum_transmission_unit: usize, h: &Handshake) -> Result<Vec<Vec<u8>>> {
         let mut content = vec![];
         {
@@ -749,11 +841,27 @@ impl DTLSConn {
         local_epoch: &Arc<AtomicU16>,
         handshake_completed_successfully: &Arc<AtomicBool>,
     ) -> Result<()> {
-        let n = next_conn.recv(buf).await?;
-        let pkts = unpack_datagram(&buf[..n])?;
+        let cid_len = ctx
+            .local_connection_id
+            .lock()
+            .await
+            .as_ref()
+            .map_or(0, |cid| cid.len());
+        // Records carrying our connection id may come from a new address of the peer.
+        let (n, raddr) = if cid_len > 0 {
+            let (n, raddr) = next_conn.recv_from(buf).await?;
+            (n, Some(raddr))
+        } else {
+            (next_conn.recv(buf).await?, None)
+        };
+        let pkts = unpack_datagram(&buf[..n], cid_len)?;
         let mut has_handshake = false;
         for pkt in pkts {
+            ctx.peer_may_have_moved = false;
             let (hs, alert, mut err) = DTLSConn::handle_incoming_packet(ctx, pkt, true).await;
+            if let Some(raddr) = raddr.filter(|_| ctx.peer_may_have_moved) {
+                DTLSConn::follow_peer(ctx, next_conn, raddr).await;
+            }
             if let Some(alert) = alert {
                 let alert_err = ctx
                     .packet_tx
@@ -796,7 +904,14 @@ impl DTLSConn {
             }
         }
 
-        if has_handshake {
+        if has_handshake && handshake_completed_successfully.load(Ordering::SeqCst) {
+            // The handshaker is gone, so this is the peer retransmitting its last flight
+            // because it never saw ours. Send ours again, or the peer never finishes.
+            let pkts = ctx.last_flight.lock().await.clone();
+            if !pkts.is_empty() {
+                ctx.packet_tx.send((pkts, None)).await?;
+            }
+        } else if has_handshake {
             let (done_tx, mut done_rx) = mpsc::channel(1);
 
             tokio::select! {
@@ -827,6 +942,27 @@ impl DTLSConn {
         Ok(())
     }
 
+    // follow_peer sends to raddr from now on if the peer isn't there already.
+    async fn follow_peer(
+        ctx: &ConnReaderContext,
+        next_conn: &Arc<dyn util::Conn>,
+        raddr: SocketAddr,
+    ) {
+        if !matches!(next_conn.remote_addr(), Some(addr) if addr != raddr) {
+            return;
+        }
+
+        debug!("{}: peer moved to {}", srv_cli_str(ctx.is_client), raddr);
+        if let Err(err) = next_conn.connect(raddr).await {
+            debug!(
+                "{}: failed to follow peer to {}: {}",
+                srv_cli_str(ctx.is_client),
+                raddr,
+                err
+            );
+        }
+    }
+
     async fn handle_queued_packets(
         ctx: &mut ConnReaderContext,
         local_epoch: &Arc<AtomicU16>,
@@ -880,8 +1016,15 @@ impl DTLSConn {
         mut pkt: Vec<u8>,
         enqueue: bool,
     ) -> (bool, Option<Alert>, Option<Error>) {
+        let local_connection_id = ctx
+            .local_connection_id
+            .lock()
+            .await
+            .clone()
+            .unwrap_or_default();
+
         let mut reader = BufReader::new(pkt.as_slice());
-        let h = match RecordLayerHeader::unmarshal(&mut reader) {
+        let h = match RecordLayerHeader::unmarshal_cid(&mut reader, local_connection_id.len()) {
             Ok(h) => h,
             Err(err) => {
                 // Decode error must be silently discarded
@@ -895,6 +1038,21 @@ impl DTLSConn {
             }
         };
 
+        // tls12_cid records are always encrypted and must carry the connection id we asked for
+        if h.content_type == ContentType::ConnectionId
+            && (h.epoch == 0
+                || local_connection_id.is_empty()
+                || h.connection_id != local_connection_id)
+        {
+            debug!(
+                "{}: discarded packet with unknown connection id (epoch: {}, seq: {})",
+                srv_cli_str(ctx.is_client),
+                h.epoch,
+                h.sequence_number,
+            );
+            return (false, None, None);
+        }
+
         // Validate epoch
         let epoch = ctx.remote_epoch.load(Ordering::SeqCst);
         if h.epoch > epoch {
@@ -962,7 +1120,7 @@ impl DTLSConn {
 
             let cipher_suite = ctx.cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
-                pkt = match cipher_suite.decrypt(&pkt) {
+                pkt = match cipher_suite.decrypt(&h, &pkt) {
                     Ok(pkt) => pkt,
                     Err(err) => {
                         debug!("{}: decrypt failed: {}", srv_cli_str(ctx.is_client), err);
@@ -970,6 +1128,26 @@ impl DTLSConn {
                     }
                 };
             }
+
+            if h.content_type == ContentType::ConnectionId {
+                pkt = match unpack_connection_id(&h, &pkt) {
+                    Ok(pkt) => pkt,
+                    Err(err) => {
+                        debug!(
+                            "{}: discarded broken connection id packet: {}",
+                            srv_cli_str(ctx.is_client),
+                            err
+                        );
+                        return (false, None, None);
+                    }
+                };
+            }
+
+            let record = (h.epoch, h.sequence_number);
+            if ctx.newest_record.map_or(true, |newest| record > newest) {
+                ctx.newest_record = Some(record);
+                ctx.peer_may_have_moved = h.content_type == ContentType::ConnectionId;
+            }
         }
 
         let is_handshake = match ctx.fragment_buffer.push(&pkt) {
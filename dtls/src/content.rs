@@ -13,6 +13,7 @@ pub enum ContentType {
     Alert = 21,
     Handshake = 22,
     ApplicationData = 23,
+    ConnectionId = 25, // tls12_cid, https://www.rfc-editor.org/rfc/rfc9146#section-4
     Invalid,
 }
 
@@ -23,6 +24,7 @@ impl From<u8> for ContentType {
             21 => ContentType::Alert,
             22 => ContentType::Handshake,
             23 => ContentType::ApplicationData,
+            25 => ContentType::ConnectionId,
             _ => ContentType::Invalid,
         }
     }
@@ -8,18 +8,21 @@
 
 // https://github.com/RustCrypto/block-ciphers
 
-use std::io::Cursor;
 use std::ops::Not;
 
+use super::generate_aead_additional_data;
+use super::provider::CryptoProvider;
 use crate::content::*;
 use crate::error::*;
 use crate::prf::*;
 use crate::record_layer::record_layer_header::*;
+use crate::signature_hash_algorithm::HashAlgorithm;
 
 use aes::Aes256;
 use block_modes::BlockModeError;
 use block_modes::{BlockMode, Cbc};
 use rand::Rng;
+use std::sync::Arc;
 use subtle::ConstantTimeEq;
 
 use super::padding::DtlsPadding;
@@ -28,6 +31,7 @@ type Aes256Cbc = Cbc<Aes256, DtlsPadding>;
 // State needed to handle encrypted input/output
 #[derive(Clone)]
 pub struct CryptoCbc {
+    provider: Arc<dyn CryptoProvider>,
     local_key: Vec<u8>,
     remote_key: Vec<u8>,
     write_mac: Vec<u8>,
@@ -39,12 +43,14 @@ impl CryptoCbc {
     const MAC_SIZE: usize = 20;
 
     pub fn new(
+        provider: &Arc<dyn CryptoProvider>,
         local_key: &[u8],
         local_mac: &[u8],
         remote_key: &[u8],
         remote_mac: &[u8],
     ) -> Result<Self> {
         Ok(CryptoCbc {
+            provider: Arc::clone(provider),
             local_key: local_key.to_vec(),
             write_mac: local_mac.to_vec(),
 
@@ -53,21 +59,36 @@ impl CryptoCbc {
         })
     }
 
+    // Records with a connection id are MACed over the same pseudo header that AEAD
+    // ciphers authenticate.
+    // https://www.rfc-editor.org/rfc/rfc9146#section-5.1
+    fn mac(&self, h: &RecordLayerHeader, payload: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+        if h.content_type == ContentType::ConnectionId {
+            let mut msg = generate_aead_additional_data(h, payload.len());
+            msg.extend_from_slice(payload);
+            self.provider.hmac(HashAlgorithm::Sha1, key, &msg)
+        } else {
+            prf_mac(
+                &*self.provider,
+                h.epoch,
+                h.sequence_number,
+                h.content_type,
+                h.protocol_version,
+                payload,
+                key,
+            )
+        }
+    }
+
     pub fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
-        let mut payload = raw[RECORD_LAYER_HEADER_SIZE..].to_vec();
-        let raw = &raw[..RECORD_LAYER_HEADER_SIZE];
+        let header_size = pkt_rlh.size();
+        let mut payload = raw[header_size..].to_vec();
+        let raw = &raw[..header_size];
 
         // Generate + Append MAC
         let h = pkt_rlh;
 
-        let mac = prf_mac(
-            h.epoch,
-            h.sequence_number,
-            h.content_type,
-            h.protocol_version,
-            &payload,
-            &self.write_mac,
-        )?;
+        let mac = self.mac(h, &payload, &self.write_mac)?;
         payload.extend_from_slice(&mac);
 
         let mut iv: Vec<u8> = vec![0; Self::BLOCK_SIZE];
@@ -82,22 +103,20 @@ impl CryptoCbc {
         r.extend_from_slice(&iv);
         r.extend_from_slice(&encrypted);
 
-        let r_len = (r.len() - RECORD_LAYER_HEADER_SIZE) as u16;
-        r[RECORD_LAYER_HEADER_SIZE - 2..RECORD_LAYER_HEADER_SIZE]
-            .copy_from_slice(&r_len.to_be_bytes());
+        let r_len = (r.len() - header_size) as u16;
+        r[header_size - 2..header_size].copy_from_slice(&r_len.to_be_bytes());
 
         Ok(r)
     }
 
-    pub fn decrypt(&self, r: &[u8]) -> Result<Vec<u8>> {
-        let mut reader = Cursor::new(r);
-        let h = RecordLayerHeader::unmarshal(&mut reader)?;
+    pub fn decrypt(&self, h: &RecordLayerHeader, r: &[u8]) -> Result<Vec<u8>> {
+        let header_size = h.size();
         if h.content_type == ContentType::ChangeCipherSpec {
             // Nothing to encrypt with ChangeCipherSpec
             return Ok(r.to_vec());
         }
 
-        let body = &r[RECORD_LAYER_HEADER_SIZE..];
+        let body = &r[header_size..];
         let iv = &body[0..Self::BLOCK_SIZE];
         let body = &body[Self::BLOCK_SIZE..];
         //TODO: add body.len() check
@@ -108,21 +127,14 @@ impl CryptoCbc {
 
         let recv_mac = &decrypted[decrypted.len() - Self::MAC_SIZE..];
         let decrypted = &decrypted[0..decrypted.len() - Self::MAC_SIZE];
-        let mac = prf_mac(
-            h.epoch,
-            h.sequence_number,
-            h.content_type,
-            h.protocol_version,
-            decrypted,
-            &self.read_mac,
-        )?;
+        let mac = self.mac(h, decrypted, &self.read_mac)?;
 
         if recv_mac.ct_eq(&mac).not().into() {
             return Err(BlockModeError.into());
         }
 
-        let mut d = Vec::with_capacity(RECORD_LAYER_HEADER_SIZE + decrypted.len());
-        d.extend_from_slice(&r[..RECORD_LAYER_HEADER_SIZE]);
+        let mut d = Vec::with_capacity(header_size + decrypted.len());
+        d.extend_from_slice(&r[..header_size]);
         d.extend_from_slice(decrypted);
 
         Ok(d)
@@ -10,124 +10,81 @@
 
 use rand::Rng;
 
-use std::io::Cursor;
-
+use super::provider::{AeadAlgorithm, AeadKey, CryptoProvider};
 use super::*;
 use crate::content::*;
 use crate::error::*;
 use crate::record_layer::record_layer_header::*;
 
-use aes::Aes128;
-use ccm::aead::{generic_array::GenericArray, AeadInPlace, NewAead};
-use ccm::{
-    consts::{U12, U16, U8},
-    Ccm,
-};
-
 const CRYPTO_CCM_8_TAG_LENGTH: usize = 8;
 const CRYPTO_CCM_TAG_LENGTH: usize = 16;
 const CRYPTO_CCM_NONCE_LENGTH: usize = 12;
 
-type AesCcm8 = Ccm<Aes128, U8, U12>;
-type AesCcm = Ccm<Aes128, U16, U12>;
-
 #[derive(Clone)]
 pub enum CryptoCcmTagLen {
     CryptoCcm8TagLength,
     CryptoCcmTagLength,
 }
 
-enum CryptoCcmType {
-    CryptoCcm8(AesCcm8),
-    CryptoCcm(AesCcm),
+impl CryptoCcmTagLen {
+    fn algorithm(&self) -> AeadAlgorithm {
+        match self {
+            CryptoCcmTagLen::CryptoCcmTagLength => AeadAlgorithm::Aes128Ccm,
+            CryptoCcmTagLen::CryptoCcm8TagLength => AeadAlgorithm::Aes128Ccm8,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            CryptoCcmTagLen::CryptoCcmTagLength => CRYPTO_CCM_TAG_LENGTH,
+            CryptoCcmTagLen::CryptoCcm8TagLength => CRYPTO_CCM_8_TAG_LENGTH,
+        }
+    }
 }
 
 // State needed to handle encrypted input/output
+#[derive(Clone)]
 pub struct CryptoCcm {
-    local_ccm: CryptoCcmType,
-    remote_ccm: CryptoCcmType,
+    tag_len: CryptoCcmTagLen,
+    local_ccm: Arc<dyn AeadKey>,
+    remote_ccm: Arc<dyn AeadKey>,
     local_write_iv: Vec<u8>,
     remote_write_iv: Vec<u8>,
-    // used by clone()
-    local_write_key: Vec<u8>,
-    remote_write_key: Vec<u8>,
-}
-
-impl Clone for CryptoCcm {
-    fn clone(&self) -> Self {
-        match self.local_ccm {
-            CryptoCcmType::CryptoCcm(_) => Self::new(
-                &CryptoCcmTagLen::CryptoCcmTagLength,
-                &self.local_write_key,
-                &self.local_write_iv,
-                &self.remote_write_key,
-                &self.remote_write_iv,
-            ),
-            CryptoCcmType::CryptoCcm8(_) => Self::new(
-                &CryptoCcmTagLen::CryptoCcm8TagLength,
-                &self.local_write_key,
-                &self.local_write_iv,
-                &self.remote_write_key,
-                &self.remote_write_iv,
-            ),
-        }
-    }
 }
 
 impl CryptoCcm {
     pub fn new(
+        provider: &dyn CryptoProvider,
         tag_len: &CryptoCcmTagLen,
         local_key: &[u8],
         local_write_iv: &[u8],
         remote_key: &[u8],
         remote_write_iv: &[u8],
-    ) -> Self {
-        let key = GenericArray::from_slice(local_key);
-        let local_ccm = match tag_len {
-            CryptoCcmTagLen::CryptoCcmTagLength => CryptoCcmType::CryptoCcm(AesCcm::new(key)),
-            CryptoCcmTagLen::CryptoCcm8TagLength => CryptoCcmType::CryptoCcm8(AesCcm8::new(key)),
-        };
-
-        let key = GenericArray::from_slice(remote_key);
-        let remote_ccm = match tag_len {
-            CryptoCcmTagLen::CryptoCcmTagLength => CryptoCcmType::CryptoCcm(AesCcm::new(key)),
-            CryptoCcmTagLen::CryptoCcm8TagLength => CryptoCcmType::CryptoCcm8(AesCcm8::new(key)),
-        };
-
-        CryptoCcm {
+    ) -> Result<Self> {
+        let local_ccm = provider.aead(tag_len.algorithm(), local_key)?;
+        let remote_ccm = provider.aead(tag_len.algorithm(), remote_key)?;
+
+        Ok(CryptoCcm {
+            tag_len: tag_len.clone(),
             local_ccm,
-            local_write_key: local_key.to_vec(),
             local_write_iv: local_write_iv.to_vec(),
             remote_ccm,
-            remote_write_key: remote_key.to_vec(),
             remote_write_iv: remote_write_iv.to_vec(),
-        }
+        })
     }
 
     pub fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
-        let payload = &raw[RECORD_LAYER_HEADER_SIZE..];
-        let raw = &raw[..RECORD_LAYER_HEADER_SIZE];
+        let header_size = pkt_rlh.size();
+        let payload = &raw[header_size..];
+        let raw = &raw[..header_size];
 
         let mut nonce = vec![0u8; CRYPTO_CCM_NONCE_LENGTH];
         nonce[..4].copy_from_slice(&self.local_write_iv[..4]);
         rand::thread_rng().fill(&mut nonce[4..]);
-        let nonce = GenericArray::from_slice(&nonce);
 
         let additional_data = generate_aead_additional_data(pkt_rlh, payload.len());
 
-        let mut buffer: Vec<u8> = Vec::new();
-        buffer.extend_from_slice(payload);
-
-        match &self.local_ccm {
-            CryptoCcmType::CryptoCcm(ccm) => {
-                ccm.encrypt_in_place(nonce, &additional_data, &mut buffer)
-                    .map_err(|e| Error::Other(e.to_string()))?;
-            }
-            CryptoCcmType::CryptoCcm8(ccm8) => {
-                ccm8.encrypt_in_place(nonce, &additional_data, &mut buffer)
-                    .map_err(|e| Error::Other(e.to_string()))?;
-            }
-        }
+        let buffer = self.local_ccm.seal(&nonce, &additional_data, payload)?;
 
         let mut r = Vec::with_capacity(raw.len() + nonce.len() + buffer.len());
 
@@ -136,52 +93,35 @@ impl CryptoCcm {
         r.extend_from_slice(&buffer);
 
         // Update recordLayer size to include explicit nonce
-        let r_len = (r.len() - RECORD_LAYER_HEADER_SIZE) as u16;
-        r[RECORD_LAYER_HEADER_SIZE - 2..RECORD_LAYER_HEADER_SIZE]
-            .copy_from_slice(&r_len.to_be_bytes());
+        let r_len = (r.len() - header_size) as u16;
+        r[header_size - 2..header_size].copy_from_slice(&r_len.to_be_bytes());
 
         Ok(r)
     }
 
-    pub fn decrypt(&self, r: &[u8]) -> Result<Vec<u8>> {
-        let mut reader = Cursor::new(r);
-        let h = RecordLayerHeader::unmarshal(&mut reader)?;
+    pub fn decrypt(&self, h: &RecordLayerHeader, r: &[u8]) -> Result<Vec<u8>> {
+        let header_size = h.size();
         if h.content_type == ContentType::ChangeCipherSpec {
             // Nothing to encrypt with ChangeCipherSpec
             return Ok(r.to_vec());
         }
 
-        if r.len() <= (RECORD_LAYER_HEADER_SIZE + 8) {
+        if r.len() <= (header_size + 8) {
             return Err(Error::ErrNotEnoughRoomForNonce);
         }
 
         let mut nonce = vec![];
         nonce.extend_from_slice(&self.remote_write_iv[..4]);
-        nonce.extend_from_slice(&r[RECORD_LAYER_HEADER_SIZE..RECORD_LAYER_HEADER_SIZE + 8]);
-        let nonce = GenericArray::from_slice(&nonce);
-
-        let out = &r[RECORD_LAYER_HEADER_SIZE + 8..];
-
-        let mut buffer: Vec<u8> = Vec::new();
-        buffer.extend_from_slice(out);
-
-        match &self.remote_ccm {
-            CryptoCcmType::CryptoCcm(ccm) => {
-                let additional_data =
-                    generate_aead_additional_data(&h, out.len() - CRYPTO_CCM_TAG_LENGTH);
-                ccm.decrypt_in_place(nonce, &additional_data, &mut buffer)
-                    .map_err(|e| Error::Other(e.to_string()))?;
-            }
-            CryptoCcmType::CryptoCcm8(ccm8) => {
-                let additional_data =
-                    generate_aead_additional_data(&h, out.len() - CRYPTO_CCM_8_TAG_LENGTH);
-                ccm8.decrypt_in_place(nonce, &additional_data, &mut buffer)
-                    .map_err(|e| Error::Other(e.to_string()))?;
-            }
-        }
+        nonce.extend_from_slice(&r[header_size..header_size + 8]);
+
+        let out = &r[header_size + 8..];
+
+        let additional_data = generate_aead_additional_data(h, out.len() - self.tag_len.len());
+
+        let buffer = self.remote_ccm.open(&nonce, &additional_data, out)?;
 
-        let mut d = Vec::with_capacity(RECORD_LAYER_HEADER_SIZE + buffer.len());
-        d.extend_from_slice(&r[..RECORD_LAYER_HEADER_SIZE]);
+        let mut d = Vec::with_capacity(header_size + buffer.len());
+        d.extend_from_slice(&r[..header_size]);
         d.extend_from_slice(&buffer);
 
         Ok(d)
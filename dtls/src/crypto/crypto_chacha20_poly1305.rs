@@ -0,0 +1,107 @@
+// ChaCha20-Poly1305
+// A stream cipher with a one-time authenticator, much faster than AES without hardware support.
+// RFC 7905 year 2016 https://tools.ietf.org/html/rfc7905
+// Unlike the AES modes there is no explicit nonce on the wire, the per record nonce is the
+// write IV XORed with the epoch and sequence number.
+
+// https://github.com/RustCrypto/AEADs
+// https://docs.rs/chacha20poly1305/0.7.1/chacha20poly1305/
+
+use super::provider::{AeadAlgorithm, AeadKey, CryptoProvider};
+use super::*;
+use crate::content::*;
+use crate::error::*;
+use crate::record_layer::record_layer_header::*;
+
+const CRYPTO_CHACHA20_POLY1305_TAG_LENGTH: usize = 16;
+const CRYPTO_CHACHA20_POLY1305_NONCE_LENGTH: usize = 12;
+
+// State needed to handle encrypted input/output
+#[derive(Clone)]
+pub struct CryptoChaCha20Poly1305 {
+    local_aead: Arc<dyn AeadKey>,
+    remote_aead: Arc<dyn AeadKey>,
+    local_write_iv: Vec<u8>,
+    remote_write_iv: Vec<u8>,
+}
+
+impl CryptoChaCha20Poly1305 {
+    pub fn new(
+        provider: &dyn CryptoProvider,
+        local_key: &[u8],
+        local_write_iv: &[u8],
+        remote_key: &[u8],
+        remote_write_iv: &[u8],
+    ) -> Result<Self> {
+        let local_aead = provider.aead(AeadAlgorithm::ChaCha20Poly1305, local_key)?;
+        let remote_aead = provider.aead(AeadAlgorithm::ChaCha20Poly1305, remote_key)?;
+
+        Ok(CryptoChaCha20Poly1305 {
+            local_aead,
+            local_write_iv: local_write_iv.to_vec(),
+            remote_aead,
+            remote_write_iv: remote_write_iv.to_vec(),
+        })
+    }
+
+    // https://tools.ietf.org/html/rfc7905#section-2
+    fn nonce(write_iv: &[u8], h: &RecordLayerHeader) -> Vec<u8> {
+        let mut nonce = write_iv[..CRYPTO_CHACHA20_POLY1305_NONCE_LENGTH].to_vec();
+        let mut seq = [0u8; 8];
+        seq[..2].copy_from_slice(&h.epoch.to_be_bytes());
+        seq[2..].copy_from_slice(&h.sequence_number.to_be_bytes()[2..]);
+        for (n, s) in nonce[4..].iter_mut().zip(seq.iter()) {
+            *n ^= s;
+        }
+        nonce
+    }
+
+    pub fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
+        let header_size = pkt_rlh.size();
+        let payload = &raw[header_size..];
+        let raw = &raw[..header_size];
+
+        let nonce = CryptoChaCha20Poly1305::nonce(&self.local_write_iv, pkt_rlh);
+
+        let additional_data = generate_aead_additional_data(pkt_rlh, payload.len());
+
+        let buffer = self.local_aead.seal(&nonce, &additional_data, payload)?;
+
+        let mut r = Vec::with_capacity(raw.len() + buffer.len());
+        r.extend_from_slice(raw);
+        r.extend_from_slice(&buffer);
+
+        // Update recordLayer size to include the tag
+        let r_len = (r.len() - header_size) as u16;
+        r[header_size - 2..header_size].copy_from_slice(&r_len.to_be_bytes());
+
+        Ok(r)
+    }
+
+    pub fn decrypt(&self, h: &RecordLayerHeader, r: &[u8]) -> Result<Vec<u8>> {
+        let header_size = h.size();
+        if h.content_type == ContentType::ChangeCipherSpec {
+            // Nothing to encrypt with ChangeCipherSpec
+            return Ok(r.to_vec());
+        }
+
+        if r.len() < (header_size + CRYPTO_CHACHA20_POLY1305_TAG_LENGTH) {
+            return Err(Error::ErrInvalidPacketLength);
+        }
+
+        let nonce = CryptoChaCha20Poly1305::nonce(&self.remote_write_iv, h);
+
+        let out = &r[header_size..];
+
+        let additional_data =
+            generate_aead_additional_data(h, out.len() - CRYPTO_CHACHA20_POLY1305_TAG_LENGTH);
+
+        let buffer = self.remote_aead.open(&nonce, &additional_data, out)?;
+
+        let mut d = Vec::with_capacity(header_size + buffer.len());
+        d.extend_from_slice(&r[..header_size]);
+        d.extend_from_slice(&buffer);
+
+        Ok(d)
+    }
+}
@@ -1,11 +1,16 @@
 use super::crypto_ccm::*;
+use super::crypto_chacha20_poly1305::*;
+use super::provider::default_provider;
 use super::*;
 
 use crate::content::ContentType;
 use crate::record_layer::record_layer_header::{ProtocolVersion, RECORD_LAYER_HEADER_SIZE};
 
 use std::io::Cursor;
+use std::time::Duration;
 
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::EncodePrivateKey;
 use x509_parser::pem::Pem;
 
 const RAW_PRIVATE_KEY: &str = "
@@ -38,8 +43,8 @@ JPhfPySIPG4UmwE4gW8t79vfOKxnUu2fDD1ZXUYopan6EckACNH/
 -----END RSA PRIVATE KEY-----
 ";
 
-#[test]
-fn test_generate_key_signature() -> Result<()> {
+#[tokio::test(flavor = "local")]
+async fn test_generate_key_signature() -> Result<()> {
     let reader = Cursor::new(RAW_PRIVATE_KEY.as_bytes());
     let pem = match Pem::read(reader) {
         Ok((pem, _)) => pem,
@@ -83,18 +88,20 @@ fn test_generate_key_signature() -> Result<()> {
         0x86,
     ];
 
+    let private_key = rsa::RsaPrivateKey::from_pkcs1_der(&pem.contents)
+        .and_then(|key| key.to_pkcs8_der().map_err(Into::into))
+        .map_err(|e| Error::Other(e.to_string()))?;
+
     let signature = generate_key_signature(
+        &*default_provider(),
         &client_random,
         &server_random,
         &public_key,
         NamedCurve::X25519,
-        &CryptoPrivateKey {
-            kind: CryptoPrivateKeyKind::Rsa256(
-                RsaKeyPair::from_der(&pem.contents).map_err(|e| Error::Other(e.to_string()))?,
-            ),
-            serialized_der: pem.contents.clone(),
-        }, //hashAlgorithmSHA256,
-    )?;
+        &CryptoPrivateKey::from_pkcs8(private_key.as_bytes().to_vec())?,
+        HashAlgorithm::Sha256,
+    )
+    .await?;
 
     assert_eq!(
         expected_signature, signature,
@@ -113,7 +120,14 @@ fn test_ccm_encryption_and_decryption() -> Result<()> {
     ];
     let iv = vec![0x0e, 0xb2, 0x09, 0x06];
 
-    let ccm = CryptoCcm::new(&CryptoCcmTagLen::CryptoCcmTagLength, &key, &iv, &key, &iv);
+    let ccm = CryptoCcm::new(
+        &*default_provider(),
+        &CryptoCcmTagLen::CryptoCcmTagLength,
+        &key,
+        &iv,
+        &key,
+        &iv,
+    )?;
 
     let rlh = RecordLayerHeader {
         content_type: ContentType::ApplicationData,
@@ -123,6 +137,7 @@ fn test_ccm_encryption_and_decryption() -> Result<()> {
         },
         epoch: 0,
         sequence_number: 18,
+        connection_id: vec![],
         content_len: 3,
     };
 
@@ -141,7 +156,7 @@ fn test_ccm_encryption_and_decryption() -> Result<()> {
         &cipher_text[RECORD_LAYER_HEADER_SIZE - 2..RECORD_LAYER_HEADER_SIZE]
     );
 
-    let plain_text = ccm.decrypt(&cipher_text)?;
+    let plain_text = ccm.decrypt(&rlh, &cipher_text)?;
 
     assert_eq!(
         raw[RECORD_LAYER_HEADER_SIZE..],
@@ -155,7 +170,64 @@ fn test_ccm_encryption_and_decryption() -> Result<()> {
 }
 
 #[test]
-fn test_certificate_verify() -> Result<()> {
+fn test_chacha20_poly1305_encryption_and_decryption() -> Result<()> {
+    let key = vec![0x42u8; 32];
+    let iv = vec![
+        0x0e, 0xb2, 0x09, 0x06, 0x5c, 0x23, 0x6a, 0x11, 0x7f, 0x90, 0x3d, 0xe4,
+    ];
+
+    let chacha = CryptoChaCha20Poly1305::new(&*default_provider(), &key, &iv, &key, &iv)?;
+
+    let rlh = RecordLayerHeader {
+        content_type: ContentType::ApplicationData,
+        protocol_version: ProtocolVersion {
+            major: 0xfe,
+            minor: 0xff,
+        },
+        epoch: 1,
+        sequence_number: 18,
+        connection_id: vec![],
+        content_len: 3,
+    };
+
+    let raw = vec![
+        0x17, 0xfe, 0xff, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x03, 0xff, 0xaa,
+        0xbb,
+    ];
+
+    let cipher_text = chacha.encrypt(&rlh, &raw)?;
+
+    assert_eq!(
+        [0, 19],
+        &cipher_text[RECORD_LAYER_HEADER_SIZE - 2..RECORD_LAYER_HEADER_SIZE],
+        "RecordLayer size updating failed \nexp: {:?} \nactual {:?} ",
+        [0, 19],
+        &cipher_text[RECORD_LAYER_HEADER_SIZE - 2..RECORD_LAYER_HEADER_SIZE]
+    );
+
+    let plain_text = chacha.decrypt(&rlh, &cipher_text)?;
+
+    assert_eq!(
+        raw[RECORD_LAYER_HEADER_SIZE..],
+        plain_text[RECORD_LAYER_HEADER_SIZE..],
+        "Decryption failed \nexp: {:?} \nactual {:?} ",
+        &raw[RECORD_LAYER_HEADER_SIZE..],
+        &plain_text[RECORD_LAYER_HEADER_SIZE..]
+    );
+
+    // The nonce is derived from the sequence number, so it must not open as another record.
+    let other = RecordLayerHeader {
+        sequence_number: 19,
+        ..rlh
+    };
+    assert!(chacha.decrypt(&other, &cipher_text).is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_certificate_verify() -> Result<()> {
+    let provider = default_provider();
     let plain_text: Vec<u8> = vec![
         0x6f, 0x47, 0x97, 0x85, 0xcc, 0x76, 0x50, 0x93, 0xbd, 0xe2, 0x6a, 0x69, 0x0b, 0xc3, 0x03,
         0xd1, 0xb7, 0xe4, 0xab, 0x88, 0x7b, 0xa6, 0x52, 0x80, 0xdf, 0xaa, 0x25, 0x7a, 0xdb, 0x29,
@@ -179,9 +251,15 @@ fn test_certificate_verify() -> Result<()> {
 
     //test ECDSA256
     let certificate_ecdsa256 = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
-    let cert_verify_ecdsa256 =
-        generate_certificate_verify(&plain_text, &certificate_ecdsa256.private_key)?;
+    let cert_verify_ecdsa256 = generate_certificate_verify(
+        &*provider,
+        &plain_text,
+        &certificate_ecdsa256.private_key,
+        HashAlgorithm::Sha256,
+    )
+    .await?;
     verify_certificate_verify(
+        &*provider,
         &plain_text,
         &SignatureHashAlgorithm {
             hash: HashAlgorithm::Sha256,
@@ -189,20 +267,59 @@ fn test_certificate_verify() -> Result<()> {
         },
         &cert_verify_ecdsa256,
         &certificate_ecdsa256
-            .certificate
-            .iter()
-            .map(|x| x.0.clone())
-            .collect::<Vec<Vec<u8>>>(),
-    )?;
+            .certificate,
+    )
+    .await?;
+
+    //test ECDSA256 with SHA-384, TLS 1.2 doesn't tie the hash to the curve
+    let cert_verify_ecdsa256_sha384 = generate_certificate_verify(
+        &*provider,
+        &plain_text,
+        &certificate_ecdsa256.private_key,
+        HashAlgorithm::Sha384,
+    )
+    .await?;
+    verify_certificate_verify(
+        &*provider,
+        &plain_text,
+        &SignatureHashAlgorithm {
+            hash: HashAlgorithm::Sha384,
+            signature: SignatureAlgorithm::Ecdsa,
+        },
+        &cert_verify_ecdsa256_sha384,
+        &certificate_ecdsa256.certificate,
+    )
+    .await?;
+    assert!(
+        verify_certificate_verify(
+            &*provider,
+            &plain_text,
+            &SignatureHashAlgorithm {
+                hash: HashAlgorithm::Sha256,
+                signature: SignatureAlgorithm::Ecdsa,
+            },
+            &cert_verify_ecdsa256_sha384,
+            &certificate_ecdsa256.certificate,
+        )
+        .await
+        .is_err(),
+        "a SHA-384 signature must not verify as SHA-256"
+    );
 
     //test ED25519
     let certificate_ed25519 = Certificate::generate_self_signed_with_alg(
         vec!["localhost".to_owned()],
-        &rcgen::PKCS_ED25519,
+        SignatureAlgorithm::Ed25519,
     )?;
-    let cert_verify_ed25519 =
-        generate_certificate_verify(&plain_text, &certificate_ed25519.private_key)?;
+    let cert_verify_ed25519 = generate_certificate_verify(
+        &*provider,
+        &plain_text,
+        &certificate_ed25519.private_key,
+        HashAlgorithm::Sha256,
+    )
+    .await?;
     verify_certificate_verify(
+        &*provider,
         &plain_text,
         &SignatureHashAlgorithm {
             hash: HashAlgorithm::Sha256,
@@ -210,11 +327,56 @@ fn test_certificate_verify() -> Result<()> {
         },
         &cert_verify_ed25519,
         &certificate_ed25519
-            .certificate
-            .iter()
-            .map(|x| x.0.clone())
-            .collect::<Vec<Vec<u8>>>(),
-    )?;
+            .certificate,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_generate_self_signed_with_provider() -> Result<()> {
+    let provider = default_provider();
+    let plain_text = b"handshake bodies".to_vec();
+    let not_after = SystemTime::UNIX_EPOCH + Duration::from_secs(4_102_444_800); // 2100-01-01
+
+    for (signature, hash) in [
+        (SignatureAlgorithm::Ecdsa, HashAlgorithm::Sha256),
+        (SignatureAlgorithm::Ed25519, HashAlgorithm::Ed25519),
+        (SignatureAlgorithm::Rsa, HashAlgorithm::Sha256),
+    ] {
+        let certificate = Certificate::generate_self_signed_with_provider(
+            &*provider,
+            vec!["localhost".to_owned()],
+            signature,
+            not_after,
+        )
+        .await?;
+
+        let (_, x509) = x509_parser::parse_x509_certificate(&certificate.certificate[0])
+            .map_err(|e| Error::Other(e.to_string()))?;
+        assert_eq!(x509.tbs_certificate.validity.not_after.timestamp(), 4_102_444_800);
+        provider
+            .verify(
+                &SignatureHashAlgorithm { hash, signature },
+                x509.tbs_certificate.subject_pki.raw,
+                x509.tbs_certificate.as_ref(),
+                x509.signature_value.data,
+            )
+            .await?;
+
+        let cert_verify =
+            generate_certificate_verify(&*provider, &plain_text, &certificate.private_key, hash)
+                .await?;
+        verify_certificate_verify(
+            &*provider,
+            &plain_text,
+            &SignatureHashAlgorithm { hash, signature },
+            &cert_verify,
+            &[certificate.certificate[0].clone()],
+        )
+        .await?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,203 @@
+// Minimal DER encoding, enough to assemble keys and self-signed certificates without pulling in
+// an ASN.1 library for writing. Parsing still goes through der-parser/x509-parser.
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_NULL: u8 = 0x05;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTF8_STRING: u8 = 0x0c;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+
+// 1.3.101.112
+pub(crate) const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+// 1.3.101.110
+pub(crate) const OID_X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+// 1.2.840.10045.2.1
+pub(crate) const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+// 1.2.840.10045.3.1.7
+pub(crate) const OID_PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+// 1.3.132.0.34
+pub(crate) const OID_SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+// 1.2.840.113549.1.1.1
+pub(crate) const OID_RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+// 1.2.840.10045.4.3.2
+pub(crate) const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+// 1.2.840.113549.1.1.11
+pub(crate) const OID_SHA256_WITH_RSA_ENCRYPTION: &[u8] =
+    &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+// 2.5.4.3
+pub(crate) const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+// 2.5.29.17
+pub(crate) const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+/// tlv encodes a single element with the given tag.
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+pub(crate) fn sequence(elements: &[&[u8]]) -> Vec<u8> {
+    tlv(TAG_SEQUENCE, &elements.concat())
+}
+
+/// integer encodes a non-negative big-endian integer, adding the leading zero DER requires when
+/// the high bit is set.
+pub(crate) fn integer(value: &[u8]) -> Vec<u8> {
+    let skip = value
+        .iter()
+        .take_while(|b| **b == 0)
+        .count()
+        .min(value.len().saturating_sub(1));
+    let value = &value[skip..];
+    if value.is_empty() {
+        return tlv(TAG_INTEGER, &[0]);
+    }
+    if value[0] & 0x80 != 0 {
+        let mut padded = vec![0];
+        padded.extend_from_slice(value);
+        tlv(TAG_INTEGER, &padded)
+    } else {
+        tlv(TAG_INTEGER, value)
+    }
+}
+
+pub(crate) fn oid(encoded: &[u8]) -> Vec<u8> {
+    tlv(TAG_OID, encoded)
+}
+
+pub(crate) fn octet_string(content: &[u8]) -> Vec<u8> {
+    tlv(TAG_OCTET_STRING, content)
+}
+
+/// bit_string encodes `content` as a BIT STRING with no unused bits.
+pub(crate) fn bit_string(content: &[u8]) -> Vec<u8> {
+    let mut bits = vec![0];
+    bits.extend_from_slice(content);
+    tlv(TAG_BIT_STRING, &bits)
+}
+
+/// context wraps `content` in a constructed, context-specific tag `[n]`.
+pub(crate) fn context(n: u8, content: &[u8]) -> Vec<u8> {
+    tlv(0xa0 | n, content)
+}
+
+/// algorithm encodes an AlgorithmIdentifier, with `parameters` already DER-encoded.
+pub(crate) fn algorithm(algorithm: &[u8], parameters: Option<&[u8]>) -> Vec<u8> {
+    match parameters {
+        Some(parameters) => sequence(&[&oid(algorithm), parameters]),
+        None => sequence(&[&oid(algorithm)]),
+    }
+}
+
+/// time encodes seconds since the unix epoch as UTCTime through 2049 and GeneralizedTime after,
+/// as RFC 5280 section 4.1.2.5 requires.
+pub(crate) fn time(unix_secs: u64) -> Vec<u8> {
+    let days = (unix_secs / 86400) as i64;
+    let secs = unix_secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    let hms = format!(
+        "{:02}{:02}{:02}Z",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    );
+    if year < 2050 {
+        let s = format!("{:02}{:02}{:02}{}", year % 100, month, day, hms);
+        tlv(TAG_UTC_TIME, s.as_bytes())
+    } else {
+        let s = format!("{:04}{:02}{:02}{}", year, month, day, hms);
+        tlv(TAG_GENERALIZED_TIME, s.as_bytes())
+    }
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian date.
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// read_tlv splits the first element off `input`, returning its tag, its content and the rest.
+pub(crate) fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, input) = input.split_first()?;
+    let (len, input) = if first < 0x80 {
+        (first as usize, input)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > std::mem::size_of::<usize>() || input.len() < n {
+            return None;
+        }
+        let len = input[..n]
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        (len, &input[n..])
+    };
+    if input.len() < len {
+        return None;
+    }
+    Some((tag, &input[..len], &input[len..]))
+}
+
+fn expect(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    match read_tlv(input)? {
+        (t, content, rest) if t == tag => Some((content, rest)),
+        _ => None,
+    }
+}
+
+/// AlgorithmIdentifier with its algorithm OID and, when present, a named-curve parameter OID.
+pub(crate) struct Algorithm<'a> {
+    pub(crate) oid: &'a [u8],
+    pub(crate) parameters: Option<&'a [u8]>,
+}
+
+fn read_algorithm(input: &[u8]) -> Option<(Algorithm<'_>, &[u8])> {
+    let (algorithm, rest) = expect(input, TAG_SEQUENCE)?;
+    let (oid, parameters) = expect(algorithm, TAG_OID)?;
+    let parameters = expect(parameters, TAG_OID).map(|(params, _)| params);
+    Some((Algorithm { oid, parameters }, rest))
+}
+
+/// private_key_info reads a PKCS#8 PrivateKeyInfo, returning the algorithm and the contents
+/// of the privateKey OCTET STRING. Anything after privateKey (attributes, or the v2 public key
+/// which ring encodes as a constructed `[1]`) is ignored.
+pub(crate) fn private_key_info(input: &[u8]) -> Option<(Algorithm<'_>, &[u8])> {
+    let (info, _) = expect(input, TAG_SEQUENCE)?;
+    let (_version, info) = expect(info, TAG_INTEGER)?;
+    let (algorithm, info) = read_algorithm(info)?;
+    let (private_key, _) = expect(info, TAG_OCTET_STRING)?;
+    Some((algorithm, private_key))
+}
+
+/// public_key_info reads a SubjectPublicKeyInfo, returning the algorithm and the public key bits.
+pub(crate) fn public_key_info(input: &[u8]) -> Option<(Algorithm<'_>, &[u8])> {
+    let (info, _) = expect(input, TAG_SEQUENCE)?;
+    let (algorithm, info) = read_algorithm(info)?;
+    match expect(info, TAG_BIT_STRING)? {
+        ([0, public_key @ ..], _) => Some((algorithm, public_key)),
+        _ => None,
+    }
+}
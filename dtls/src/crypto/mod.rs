@@ -3,59 +3,112 @@ mod crypto_test;
 
 pub mod crypto_cbc;
 pub mod crypto_ccm;
+pub mod crypto_chacha20_poly1305;
 pub mod crypto_gcm;
+pub(crate) mod der;
 pub mod padding;
+pub mod provider;
 
+use crate::content::ContentType;
 use crate::curve::named_curve::*;
 use crate::error::*;
 use crate::record_layer::record_layer_header::*;
 use crate::signature_hash_algorithm::{HashAlgorithm, SignatureAlgorithm, SignatureHashAlgorithm};
+use crate::verifier::*;
 
+use provider::{rust_crypto, CryptoProvider};
+
+use deno_net::{SystemTime, UNIX_EPOCH};
 use der_parser::{oid, oid::Oid};
+use rand_core::{OsRng, RngCore};
+#[cfg(not(target_arch = "wasm32"))]
 use rcgen::KeyPair;
-use ring::rand::SystemRandom;
-use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, RsaKeyPair};
+#[cfg(not(target_arch = "wasm32"))]
 use std::convert::TryFrom;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// A X.509 certificate(s) used to authenticate a DTLS connection.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Certificate {
     /// DER-encoded certificates.
-    pub certificate: Vec<rustls::Certificate>,
+    pub certificate: Vec<Vec<u8>>,
     /// Private key.
     pub private_key: CryptoPrivateKey,
 }
 
+const SELF_SIGNED_COMMON_NAME: &str = "webrtc-rs self signed cert";
+
+// The validity rcgen gives its self-signed certificates, which these used to be generated with.
+const SELF_SIGNED_NOT_BEFORE: Duration = Duration::from_secs(157_766_400); // 1975-01-01
+const SELF_SIGNED_NOT_AFTER: Duration = Duration::from_secs(67_090_118_400); // 4096-01-01
+
 impl Certificate {
-    /// Generate a self-signed certificate.
-    ///
-    /// See [`rcgen::generate_simple_self_signed`].
+    /// Generate a self-signed ECDSA P-256 certificate.
     pub fn generate_self_signed(subject_alt_names: impl Into<Vec<String>>) -> Result<Self> {
-        let cert = rcgen::generate_simple_self_signed(subject_alt_names)?;
-        let key_pair = cert.get_key_pair();
+        Self::generate_self_signed_with_alg(subject_alt_names, SignatureAlgorithm::Ecdsa)
+    }
+
+    /// Generate a self-signed certificate with a key for the given algorithm.
+    ///
+    /// The key and the signature come from [`RustCrypto`](provider::RustCrypto), see
+    /// [`Certificate::generate_self_signed_with_provider`] for other providers.
+    pub fn generate_self_signed_with_alg(
+        subject_alt_names: impl Into<Vec<String>>,
+        signature: SignatureAlgorithm,
+    ) -> Result<Self> {
+        Self::generate_self_signed_expiring(
+            subject_alt_names,
+            signature,
+            UNIX_EPOCH + SELF_SIGNED_NOT_AFTER,
+        )
+    }
+
+    /// Generate a self-signed certificate with a key for the given algorithm, valid until
+    /// `not_after`.
+    pub fn generate_self_signed_expiring(
+        subject_alt_names: impl Into<Vec<String>>,
+        signature: SignatureAlgorithm,
+        not_after: SystemTime,
+    ) -> Result<Self> {
+        let (private_key, public_key_info) = rust_crypto::generate_private_key(signature)?;
+        let tbs_certificate = self_signed_tbs_certificate(
+            &private_key,
+            &public_key_info,
+            subject_alt_names.into(),
+            not_after,
+        );
+        let signature = rust_crypto::sign(&private_key, HashAlgorithm::Sha256, &tbs_certificate)?;
 
         Ok(Certificate {
-            certificate: vec![rustls::Certificate(cert.serialize_der()?)],
-            private_key: CryptoPrivateKey::try_from(key_pair)?,
+            certificate: vec![signed_certificate(&private_key, &tbs_certificate, &signature)],
+            private_key,
         })
     }
 
-    /// Generate a self-signed certificate with the given algorithm.
+    /// Generate a self-signed certificate using `provider` for the key and the signature.
     ///
-    /// See [`rcgen::Certificate::from_params`].
-    pub fn generate_self_signed_with_alg(
+    /// This works with providers whose keys never leave the platform's crypto implementation.
+    pub async fn generate_self_signed_with_provider(
+        provider: &dyn CryptoProvider,
         subject_alt_names: impl Into<Vec<String>>,
-        alg: &'static rcgen::SignatureAlgorithm,
+        signature: SignatureAlgorithm,
+        not_after: SystemTime,
     ) -> Result<Self> {
-        let mut params = rcgen::CertificateParams::new(subject_alt_names);
-        params.alg = alg;
-        let cert = rcgen::Certificate::from_params(params)?;
-        let key_pair = cert.get_key_pair();
+        let (private_key, public_key_info) = provider.generate_private_key(signature).await?;
+        let tbs_certificate = self_signed_tbs_certificate(
+            &private_key,
+            &public_key_info,
+            subject_alt_names.into(),
+            not_after,
+        );
+        let signature = provider
+            .sign(&private_key, HashAlgorithm::Sha256, &tbs_certificate)
+            .await?;
 
         Ok(Certificate {
-            certificate: vec![rustls::Certificate(cert.serialize_der()?)],
-            private_key: CryptoPrivateKey::try_from(key_pair)?,
+            certificate: vec![signed_certificate(&private_key, &tbs_certificate, &signature)],
+            private_key,
         })
     }
 
@@ -76,10 +129,10 @@ impl Certificate {
             )));
         }
 
-        let keypair = rcgen::KeyPair::from_der(&pems[0].contents)
+        let private_key = CryptoPrivateKey::from_pkcs8(pems[0].contents.clone())
             .map_err(|e| Error::InvalidPEM(format!("can't decode keypair: {}", e)))?;
 
-        let mut rustls_certs = Vec::new();
+        let mut certs = Vec::new();
         for p in pems.drain(1..) {
             if p.tag != "CERTIFICATE" {
                 return Err(Error::InvalidPEM(format!(
@@ -87,12 +140,12 @@ impl Certificate {
                     p.tag
                 )));
             }
-            rustls_certs.push(rustls::Certificate(p.contents));
+            certs.push(p.contents);
         }
 
         Ok(Certificate {
-            certificate: rustls_certs,
-            private_key: CryptoPrivateKey::try_from(&keypair)?,
+            certificate: certs,
+            private_key,
         })
     }
 
@@ -103,16 +156,96 @@ impl Certificate {
             tag: "PRIVATE_KEY".to_string(),
             contents: self.private_key.serialized_der.clone(),
         }];
-        for rustls_cert in &self.certificate {
+        for cert in &self.certificate {
             data.push(pem::Pem {
                 tag: "CERTIFICATE".to_string(),
-                contents: rustls_cert.0.clone(),
+                contents: cert.clone(),
             });
         }
         pem::encode_many(&data)
     }
 }
 
+fn self_signed_algorithm(private_key: &CryptoPrivateKey) -> Vec<u8> {
+    match private_key.kind {
+        CryptoPrivateKeyKind::Ed25519 => der::algorithm(der::OID_ED25519, None),
+        CryptoPrivateKeyKind::Ecdsa256 => der::algorithm(der::OID_ECDSA_WITH_SHA256, None),
+        CryptoPrivateKeyKind::Rsa256 => der::algorithm(
+            der::OID_SHA256_WITH_RSA_ENCRYPTION,
+            Some(&[der::TAG_NULL, 0]),
+        ),
+    }
+}
+
+/// self_signed_tbs_certificate assembles the TBSCertificate of a self-signed certificate for
+/// the key, to be signed with SHA-256 (or Ed25519).
+fn self_signed_tbs_certificate(
+    private_key: &CryptoPrivateKey,
+    public_key_info: &[u8],
+    subject_alt_names: Vec<String>,
+    not_after: SystemTime,
+) -> Vec<u8> {
+    let mut serial = [0u8; 16];
+    OsRng.fill_bytes(&mut serial);
+    // Keep the serial number positive and its first octet non-zero.
+    serial[0] = (serial[0] & 0x7f) | 0x40;
+
+    let name = der::sequence(&[&der::tlv(
+        der::TAG_SET,
+        &der::sequence(&[
+            &der::oid(der::OID_COMMON_NAME),
+            &der::tlv(der::TAG_UTF8_STRING, SELF_SIGNED_COMMON_NAME.as_bytes()),
+        ]),
+    )]);
+
+    let seconds = |t: SystemTime| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let validity = der::sequence(&[
+        &der::time(SELF_SIGNED_NOT_BEFORE.as_secs()),
+        &der::time(seconds(not_after)),
+    ]);
+
+    let extensions = if subject_alt_names.is_empty() {
+        vec![]
+    } else {
+        // GeneralName ::= dNSName [2] IA5String, implicitly tagged
+        let names: Vec<Vec<u8>> = subject_alt_names
+            .iter()
+            .map(|name| der::tlv(0x82, name.as_bytes()))
+            .collect();
+        let names: Vec<&[u8]> = names.iter().map(|n| n.as_slice()).collect();
+        der::context(
+            3,
+            &der::sequence(&[&der::sequence(&[
+                &der::oid(der::OID_SUBJECT_ALT_NAME),
+                &der::octet_string(&der::sequence(&names)),
+            ])]),
+        )
+    };
+
+    der::sequence(&[
+        &der::context(0, &der::integer(&[2])), // v3
+        &der::integer(&serial),
+        &self_signed_algorithm(private_key),
+        &name,
+        &validity,
+        &name,
+        public_key_info,
+        &extensions,
+    ])
+}
+
+fn signed_certificate(
+    private_key: &CryptoPrivateKey,
+    tbs_certificate: &[u8],
+    signature: &[u8],
+) -> Vec<u8> {
+    der::sequence(&[
+        tbs_certificate,
+        &self_signed_algorithm(private_key),
+        &der::bit_string(signature),
+    ])
+}
+
 pub(crate) fn value_key_message(
     client_random: &[u8],
     server_random: &[u8],
@@ -134,15 +267,15 @@ pub(crate) fn value_key_message(
 }
 
 /// Either ED25519, ECDSA or RSA keypair.
-#[derive(Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum CryptoPrivateKeyKind {
-    Ed25519(Ed25519KeyPair),
-    Ecdsa256(EcdsaKeyPair),
-    Rsa256(RsaKeyPair),
+    Ed25519,
+    Ecdsa256,
+    Rsa256,
 }
 
 /// Private key.
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct CryptoPrivateKey {
     /// Keypair.
     pub kind: CryptoPrivateKeyKind,
@@ -150,128 +283,38 @@ pub struct CryptoPrivateKey {
     pub serialized_der: Vec<u8>,
 }
 
-impl PartialEq for CryptoPrivateKey {
-    fn eq(&self, other: &Self) -> bool {
-        if self.serialized_der != other.serialized_der {
-            return false;
-        }
-
-        matches!(
-            (&self.kind, &other.kind),
-            (
-                CryptoPrivateKeyKind::Rsa256(_),
-                CryptoPrivateKeyKind::Rsa256(_)
-            ) | (
-                CryptoPrivateKeyKind::Ecdsa256(_),
-                CryptoPrivateKeyKind::Ecdsa256(_)
-            ) | (
-                CryptoPrivateKeyKind::Ed25519(_),
-                CryptoPrivateKeyKind::Ed25519(_)
-            )
-        )
-    }
-}
-
-impl Clone for CryptoPrivateKey {
-    fn clone(&self) -> Self {
-        match self.kind {
-            CryptoPrivateKeyKind::Ed25519(_) => CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ed25519(
-                    Ed25519KeyPair::from_pkcs8(&self.serialized_der).unwrap(),
-                ),
-                serialized_der: self.serialized_der.clone(),
-            },
-            CryptoPrivateKeyKind::Ecdsa256(_) => CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ecdsa256(
-                    EcdsaKeyPair::from_pkcs8(
-                        &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
-                        &self.serialized_der,
-                    )
-                    .unwrap(),
-                ),
-                serialized_der: self.serialized_der.clone(),
-            },
-            CryptoPrivateKeyKind::Rsa256(_) => CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Rsa256(
-                    RsaKeyPair::from_pkcs8(&self.serialized_der).unwrap(),
-                ),
-                serialized_der: self.serialized_der.clone(),
-            },
-        }
-    }
-}
-
+#[cfg(not(target_arch = "wasm32"))]
 impl TryFrom<&KeyPair> for CryptoPrivateKey {
     type Error = Error;
 
     fn try_from(key_pair: &KeyPair) -> Result<Self> {
-        let serialized_der = key_pair.serialize_der();
-        if key_pair.is_compatible(&rcgen::PKCS_ED25519) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ed25519(
-                    Ed25519KeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else if key_pair.is_compatible(&rcgen::PKCS_ECDSA_P256_SHA256) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ecdsa256(
-                    EcdsaKeyPair::from_pkcs8(
-                        &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
-                        &serialized_der,
-                    )
-                    .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else if key_pair.is_compatible(&rcgen::PKCS_RSA_SHA256) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Rsa256(
-                    RsaKeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else {
-            Err(Error::Other("Unsupported key_pair".to_owned()))
-        }
+        Self::from_key_pair(key_pair)
     }
 }
 
 impl CryptoPrivateKey {
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_key_pair(key_pair: &KeyPair) -> Result<Self> {
-        let serialized_der = key_pair.serialize_der();
-        if key_pair.is_compatible(&rcgen::PKCS_ED25519) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ed25519(
-                    Ed25519KeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else if key_pair.is_compatible(&rcgen::PKCS_ECDSA_P256_SHA256) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ecdsa256(
-                    EcdsaKeyPair::from_pkcs8(
-                        &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
-                        &serialized_der,
-                    )
-                    .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else if key_pair.is_compatible(&rcgen::PKCS_RSA_SHA256) {
-            Ok(CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Rsa256(
-                    RsaKeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::Other(e.to_string()))?,
-                ),
-                serialized_der,
-            })
-        } else {
-            Err(Error::Other("Unsupported key_pair".to_owned()))
-        }
+        Self::from_pkcs8(key_pair.serialize_der())
+    }
+
+    /// Wraps a PKCS#8 DER-encoded private key, detecting its kind from the algorithm identifier.
+    /// ECDSA keys must be on P-256.
+    pub fn from_pkcs8(serialized_der: Vec<u8>) -> Result<Self> {
+        let (algorithm, _) =
+            der::private_key_info(&serialized_der).ok_or(Error::ErrInvalidPrivateKey)?;
+
+        let kind = match (algorithm.oid, algorithm.parameters) {
+            (der::OID_ED25519, _) => CryptoPrivateKeyKind::Ed25519,
+            (der::OID_RSA_ENCRYPTION, _) => CryptoPrivateKeyKind::Rsa256,
+            (der::OID_EC_PUBLIC_KEY, Some(der::OID_PRIME256V1)) => CryptoPrivateKeyKind::Ecdsa256,
+            _ => return Err(Error::Other("Unsupported key_pair".to_owned())),
+        };
+
+        Ok(CryptoPrivateKey {
+            kind,
+            serialized_der,
+        })
     }
 }
 
@@ -280,46 +323,25 @@ impl CryptoPrivateKey {
 // hash/signature algorithm pair that appears in that extension
 //
 // https://tools.ietf.org/html/rfc5246#section-7.4.2
-pub(crate) fn generate_key_signature(
+pub(crate) async fn generate_key_signature(
+    provider: &dyn CryptoProvider,
     client_random: &[u8],
     server_random: &[u8],
     public_key: &[u8],
     named_curve: NamedCurve,
-    private_key: &CryptoPrivateKey, /*, hash_algorithm: HashAlgorithm*/
+    private_key: &CryptoPrivateKey,
+    hash_algorithm: HashAlgorithm,
 ) -> Result<Vec<u8>> {
     let msg = value_key_message(client_random, server_random, public_key, named_curve);
-    let signature = match &private_key.kind {
-        CryptoPrivateKeyKind::Ed25519(kp) => kp.sign(&msg).as_ref().to_vec(),
-        CryptoPrivateKeyKind::Ecdsa256(kp) => {
-            let system_random = SystemRandom::new();
-            kp.sign(&system_random, &msg)
-                .map_err(|e| Error::Other(e.to_string()))?
-                .as_ref()
-                .to_vec()
-        }
-        CryptoPrivateKeyKind::Rsa256(kp) => {
-            let system_random = SystemRandom::new();
-            let mut signature = vec![0; kp.public_modulus_len()];
-            kp.sign(
-                &ring::signature::RSA_PKCS1_SHA256,
-                &system_random,
-                &msg,
-                &mut signature,
-            )
-            .map_err(|e| Error::Other(e.to_string()))?;
-
-            signature
-        }
-    };
-
-    Ok(signature)
+    provider.sign(private_key, hash_algorithm, &msg).await
 }
 
 // add OID_ED25519 which is not defined in x509_parser
 pub const OID_ED25519: Oid<'static> = oid!(1.3.101 .112);
 pub const OID_ECDSA: Oid<'static> = oid!(1.2.840 .10045 .2 .1);
 
-fn verify_signature(
+async fn verify_signature(
+    provider: &dyn CryptoProvider,
     message: &[u8],
     hash_algorithm: &SignatureHashAlgorithm,
     remote_key_signature: &[u8],
@@ -332,59 +354,33 @@ fn verify_signature(
     let (_, certificate) = x509_parser::parse_x509_certificate(&raw_certificates[0])
         .map_err(|e| Error::Other(e.to_string()))?;
 
-    let verify_alg: &dyn ring::signature::VerificationAlgorithm = match hash_algorithm.signature {
-        SignatureAlgorithm::Ed25519 => &ring::signature::ED25519,
-        SignatureAlgorithm::Ecdsa if hash_algorithm.hash == HashAlgorithm::Sha256 => {
-            &ring::signature::ECDSA_P256_SHA256_ASN1
-        }
-        SignatureAlgorithm::Ecdsa if hash_algorithm.hash == HashAlgorithm::Sha384 => {
-            &ring::signature::ECDSA_P384_SHA384_ASN1
-        }
-        SignatureAlgorithm::Rsa if hash_algorithm.hash == HashAlgorithm::Sha1 => {
-            &ring::signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY
-        }
-        SignatureAlgorithm::Rsa if hash_algorithm.hash == HashAlgorithm::Sha256 => {
-            &ring::signature::RSA_PKCS1_2048_8192_SHA256
-        }
-        SignatureAlgorithm::Rsa if hash_algorithm.hash == HashAlgorithm::Sha384 => {
-            &ring::signature::RSA_PKCS1_2048_8192_SHA384
-        }
-        SignatureAlgorithm::Rsa if hash_algorithm.hash == HashAlgorithm::Sha512 => {
-            &ring::signature::RSA_PKCS1_2048_8192_SHA512
-        }
-        _ => return Err(Error::ErrKeySignatureVerifyUnimplemented),
-    };
-
-    log::trace!("Picked an algorithm {:?}", verify_alg);
-
-    let public_key = ring::signature::UnparsedPublicKey::new(
-        verify_alg,
-        certificate
-            .tbs_certificate
-            .subject_pki
-            .subject_public_key
-            .data,
-    );
-
-    public_key
-        .verify(message, remote_key_signature)
-        .map_err(|e| Error::Other(e.to_string()))?;
+    log::trace!("Verifying a {:?} signature", hash_algorithm);
 
-    Ok(())
+    provider
+        .verify(
+            hash_algorithm,
+            certificate.tbs_certificate.subject_pki.raw,
+            message,
+            remote_key_signature,
+        )
+        .await
 }
 
-pub(crate) fn verify_key_signature(
+pub(crate) async fn verify_key_signature(
+    provider: &dyn CryptoProvider,
     message: &[u8],
     hash_algorithm: &SignatureHashAlgorithm,
     remote_key_signature: &[u8],
     raw_certificates: &[Vec<u8>],
 ) -> Result<()> {
     verify_signature(
+        provider,
         message,
         hash_algorithm,
         remote_key_signature,
         raw_certificates,
     )
+    .await
 }
 
 // If the server has sent a CertificateRequest message, the client MUST send the Certificate
@@ -395,100 +391,69 @@ pub(crate) fn verify_key_signature(
 // CertificateVerify message is sent to explicitly verify possession of
 // the private key in the certificate.
 // https://tools.ietf.org/html/rfc5246#section-7.3
-pub(crate) fn generate_certificate_verify(
+pub(crate) async fn generate_certificate_verify(
+    provider: &dyn CryptoProvider,
     handshake_bodies: &[u8],
-    private_key: &CryptoPrivateKey, /*, hashAlgorithm hashAlgorithm*/
+    private_key: &CryptoPrivateKey,
+    hash_algorithm: HashAlgorithm,
 ) -> Result<Vec<u8>> {
-    let signature = match &private_key.kind {
-        CryptoPrivateKeyKind::Ed25519(kp) => kp.sign(handshake_bodies).as_ref().to_vec(),
-        CryptoPrivateKeyKind::Ecdsa256(kp) => {
-            let system_random = SystemRandom::new();
-            kp.sign(&system_random, handshake_bodies)
-                .map_err(|e| Error::Other(e.to_string()))?
-                .as_ref()
-                .to_vec()
-        }
-        CryptoPrivateKeyKind::Rsa256(kp) => {
-            let system_random = SystemRandom::new();
-            let mut signature = vec![0; kp.public_modulus_len()];
-            kp.sign(
-                &ring::signature::RSA_PKCS1_SHA256,
-                &system_random,
-                handshake_bodies,
-                &mut signature,
-            )
-            .map_err(|e| Error::Other(e.to_string()))?;
-
-            signature
-        }
-    };
-
-    Ok(signature)
+    provider
+        .sign(private_key, hash_algorithm, handshake_bodies)
+        .await
 }
 
-pub(crate) fn verify_certificate_verify(
+pub(crate) async fn verify_certificate_verify(
+    provider: &dyn CryptoProvider,
     handshake_bodies: &[u8],
     hash_algorithm: &SignatureHashAlgorithm,
     remote_key_signature: &[u8],
     raw_certificates: &[Vec<u8>],
 ) -> Result<()> {
     verify_signature(
+        provider,
         handshake_bodies,
         hash_algorithm,
         remote_key_signature,
         raw_certificates,
     )
+    .await
 }
 
-pub(crate) fn load_certs(raw_certificates: &[Vec<u8>]) -> Result<Vec<rustls::Certificate>> {
+pub(crate) fn load_certs(raw_certificates: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
     if raw_certificates.is_empty() {
         return Err(Error::ErrLengthMismatch);
     }
 
-    let mut certs = vec![];
-    for raw_cert in raw_certificates {
-        let cert = rustls::Certificate(raw_cert.to_vec());
-        certs.push(cert);
-    }
-
-    Ok(certs)
+    Ok(raw_certificates.to_vec())
 }
 
 pub(crate) fn verify_client_cert(
     raw_certificates: &[Vec<u8>],
-    cert_verifier: &Arc<dyn rustls::ClientCertVerifier>,
-) -> Result<Vec<rustls::Certificate>> {
-    let chains = load_certs(raw_certificates)?;
+    cert_verifier: &Arc<dyn ClientCertVerifier>,
+    server_name: Option<&str>,
+) -> Result<Vec<Vec<u8>>> {
+    let chain = CertificateChain::parse(raw_certificates)?;
+    cert_verifier.verify_client_cert(&chain, server_name)?;
 
-    match cert_verifier.verify_client_cert(&chains, None) {
-        Ok(_) => {}
-        Err(err) => return Err(Error::Other(err.to_string())),
-    };
-
-    Ok(chains)
+    load_certs(raw_certificates)
 }
 
 pub(crate) fn verify_server_cert(
     raw_certificates: &[Vec<u8>],
-    cert_verifier: &Arc<dyn rustls::ServerCertVerifier>,
-    roots: &rustls::RootCertStore,
+    cert_verifier: &Arc<dyn ServerCertVerifier>,
     server_name: &str,
-) -> Result<Vec<rustls::Certificate>> {
-    let chains = load_certs(raw_certificates)?;
-    let dns_name = match webpki::DNSNameRef::try_from_ascii_str(server_name) {
-        Ok(dns_name) => dns_name,
-        Err(err) => return Err(Error::Other(err.to_string())),
-    };
+) -> Result<Vec<Vec<u8>>> {
+    let chain = CertificateChain::parse(raw_certificates)?;
+    cert_verifier.verify_server_cert(&chain, server_name, None)?;
 
-    match cert_verifier.verify_server_cert(roots, &chains, dns_name, &[]) {
-        Ok(_) => {}
-        Err(err) => return Err(Error::Other(err.to_string())),
-    };
-
-    Ok(chains)
+    load_certs(raw_certificates)
 }
 
 pub(crate) fn generate_aead_additional_data(h: &RecordLayerHeader, payload_len: usize) -> Vec<u8> {
+    if h.content_type == ContentType::ConnectionId {
+        return generate_aead_additional_data_cid(h, payload_len);
+    }
+
     let mut additional_data = vec![0u8; 13];
     // SequenceNumber MUST be set first
     // we only want uint48, clobbering an extra 2 (using uint64, rust doesn't have uint48)
@@ -502,6 +467,32 @@ pub(crate) fn generate_aead_additional_data(h: &RecordLayerHeader, payload_len:
     additional_data
 }
 
+// https://www.rfc-editor.org/rfc/rfc9146#section-5
+//
+//  additional_data = seq_num_placeholder +
+//                    tls12_cid +
+//                    cid_length +
+//                    tls12_cid +
+//                    DTLSCiphertext.version +
+//                    epoch +
+//                    sequence_number +
+//                    cid +
+//                    length_of_DTLSInnerPlaintext;
+fn generate_aead_additional_data_cid(h: &RecordLayerHeader, payload_len: usize) -> Vec<u8> {
+    let mut additional_data = vec![0xff; 8];
+    additional_data.push(ContentType::ConnectionId as u8);
+    additional_data.push(h.connection_id.len() as u8);
+    additional_data.push(ContentType::ConnectionId as u8);
+    additional_data.push(h.protocol_version.major);
+    additional_data.push(h.protocol_version.minor);
+    additional_data.extend_from_slice(&h.epoch.to_be_bytes());
+    additional_data.extend_from_slice(&h.sequence_number.to_be_bytes()[2..]);
+    additional_data.extend_from_slice(&h.connection_id);
+    additional_data.extend_from_slice(&(payload_len as u16).to_be_bytes());
+
+    additional_data
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,117 @@
+//! Pluggable cryptography for the handshake and the record layer.
+//!
+//! Everything dtls computes goes through a [`CryptoProvider`]: hashing and HMAC for the PRF,
+//! AEAD for the record layer, ECDH for the key exchange and signatures for the certificate
+//! messages. [`RustCrypto`] is the default. With the `webcrypto` feature the wasm build can use
+//! [`WebCrypto`] instead, which runs the public key operations on Deno's `crypto.subtle`.
+//!
+//! The public key operations are async because WebCrypto only offers promises; they only run
+//! while handshaking. Hashing, HMAC and AEAD run for every record and stay synchronous.
+//!
+//! A provider is picked per connection through [`Config::crypto_provider`](crate::config::Config).
+
+#[cfg(test)]
+mod provider_test;
+
+pub mod rust_crypto;
+#[cfg(all(feature = "webcrypto", target_arch = "wasm32"))]
+pub mod web_crypto;
+
+pub use rust_crypto::RustCrypto;
+#[cfg(all(feature = "webcrypto", target_arch = "wasm32"))]
+pub use web_crypto::WebCrypto;
+
+use crate::crypto::CryptoPrivateKey;
+use crate::curve::named_curve::{NamedCurve, NamedCurveKeypair};
+use crate::error::*;
+use crate::signature_hash_algorithm::{HashAlgorithm, SignatureAlgorithm, SignatureHashAlgorithm};
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// The AEAD ciphers used by the record layer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AeadAlgorithm {
+    Aes128Gcm,
+    Aes256Gcm,
+    Aes128Ccm,
+    Aes128Ccm8,
+    ChaCha20Poly1305,
+}
+
+impl AeadAlgorithm {
+    pub fn key_len(&self) -> usize {
+        match *self {
+            AeadAlgorithm::Aes256Gcm | AeadAlgorithm::ChaCha20Poly1305 => 32,
+            _ => 16,
+        }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        match *self {
+            AeadAlgorithm::Aes128Ccm8 => 8,
+            _ => 16,
+        }
+    }
+}
+
+/// A keyed AEAD cipher. Cipher suites create one per direction when they are initialized.
+pub trait AeadKey: Send + Sync {
+    /// Encrypts `plaintext`, returning the ciphertext with the tag appended.
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>>;
+    /// Authenticates and decrypts `ciphertext`, which ends with the tag.
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The cryptographic primitives used by dtls.
+///
+/// Private signing keys travel as PKCS#8 DER in [`CryptoPrivateKey::serialized_der`] and peer
+/// keys as the DER SubjectPublicKeyInfo of their certificate, so keys move freely between
+/// providers. ECDSA signatures are DER encoded, as they are on the wire.
+#[async_trait(?Send)]
+pub trait CryptoProvider: Send + Sync {
+    /// hash digests `data`.
+    fn hash(&self, hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// hmac computes the HMAC of `data` under `key`.
+    fn hmac(&self, hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>>;
+
+    /// aead keys an AEAD cipher for the record layer.
+    fn aead(&self, algorithm: AeadAlgorithm, key: &[u8]) -> Result<Arc<dyn AeadKey>>;
+
+    /// generate_keypair creates an ephemeral key for the (EC)DHE key exchange.
+    async fn generate_keypair(&self, curve: NamedCurve) -> Result<NamedCurveKeypair>;
+
+    /// ecdh derives the pre-master secret from our ephemeral key and the peer's public key.
+    async fn ecdh(&self, local: &NamedCurveKeypair, remote_public_key: &[u8]) -> Result<Vec<u8>>;
+
+    /// generate_private_key creates a signing key, returning it along with its DER
+    /// SubjectPublicKeyInfo.
+    async fn generate_private_key(
+        &self,
+        signature: SignatureAlgorithm,
+    ) -> Result<(CryptoPrivateKey, Vec<u8>)>;
+
+    /// sign signs `message` with `private_key`. The hash is ignored for Ed25519.
+    async fn sign(
+        &self,
+        private_key: &CryptoPrivateKey,
+        hash: HashAlgorithm,
+        message: &[u8],
+    ) -> Result<Vec<u8>>;
+
+    /// verify checks `signature` over `message` against the DER SubjectPublicKeyInfo
+    /// `public_key_info`.
+    async fn verify(
+        &self,
+        scheme: &SignatureHashAlgorithm,
+        public_key_info: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<()>;
+}
+
+/// default_provider returns the provider used when a Config doesn't set one.
+pub fn default_provider() -> Arc<dyn CryptoProvider> {
+    Arc::new(RustCrypto)
+}
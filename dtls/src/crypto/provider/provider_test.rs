@@ -0,0 +1,76 @@
+use super::*;
+
+const MESSAGE: &[u8] = b"ServerKeyExchange params";
+
+async fn sign_and_verify(
+    provider: &dyn CryptoProvider,
+    signature: SignatureAlgorithm,
+    hash: HashAlgorithm,
+) -> Result<()> {
+    let (private_key, public_key_info) = provider.generate_private_key(signature).await?;
+    let scheme = SignatureHashAlgorithm { hash, signature };
+
+    let sig = provider.sign(&private_key, hash, MESSAGE).await?;
+    provider
+        .verify(&scheme, &public_key_info, MESSAGE, &sig)
+        .await?;
+
+    assert!(
+        provider
+            .verify(&scheme, &public_key_info, b"tampered", &sig)
+            .await
+            .is_err(),
+        "{:?} accepted a signature over a different message",
+        scheme
+    );
+
+    // Keys round trip through PKCS#8 untouched.
+    let reloaded = CryptoPrivateKey::from_pkcs8(private_key.serialized_der.clone())?;
+    assert_eq!(reloaded, private_key);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_rust_crypto_sign_verify() -> Result<()> {
+    sign_and_verify(&RustCrypto, SignatureAlgorithm::Ecdsa, HashAlgorithm::Sha256).await?;
+    sign_and_verify(&RustCrypto, SignatureAlgorithm::Ed25519, HashAlgorithm::Ed25519).await?;
+    sign_and_verify(&RustCrypto, SignatureAlgorithm::Rsa, HashAlgorithm::Sha256).await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_rust_crypto_ecdh() -> Result<()> {
+    for curve in [NamedCurve::P256, NamedCurve::P384, NamedCurve::X25519] {
+        let a = RustCrypto.generate_keypair(curve).await?;
+        let b = RustCrypto.generate_keypair(curve).await?;
+
+        let ab = RustCrypto.ecdh(&a, &b.public_key).await?;
+        let ba = RustCrypto.ecdh(&b, &a.public_key).await?;
+        assert_eq!(ab, ba, "{:?} shared secrets differ", curve);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rust_crypto_aead() -> Result<()> {
+    let key = [0x42u8; 32];
+    let nonce = [0x24u8; 12];
+    let aad = b"record header";
+    let plaintext = b"application data";
+
+    for algorithm in [
+        AeadAlgorithm::Aes128Gcm,
+        AeadAlgorithm::Aes256Gcm,
+        AeadAlgorithm::Aes128Ccm,
+        AeadAlgorithm::Aes128Ccm8,
+        AeadAlgorithm::ChaCha20Poly1305,
+    ] {
+        let aead = RustCrypto.aead(algorithm, &key[..algorithm.key_len()])?;
+        let sealed = aead.seal(&nonce, aad, plaintext)?;
+        assert_eq!(sealed.len(), plaintext.len() + algorithm.tag_len());
+        assert_eq!(aead.open(&nonce, aad, &sealed)?, plaintext);
+        assert!(aead.open(&nonce, b"other header", &sealed).is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,416 @@
+use super::*;
+use crate::crypto::der;
+use crate::crypto::CryptoPrivateKeyKind;
+
+use aes::Aes128;
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use ccm::consts::{U12, U16, U8};
+use ccm::Ccm;
+use chacha20poly1305::ChaCha20Poly1305;
+use ed25519_dalek::{ExpandedSecretKey, Verifier as _};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use p256::pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey};
+use rand_core::{OsRng, RngCore};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{PaddingScheme, PublicKey as _, RsaPrivateKey, RsaPublicKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use signature::hazmat::{PrehashSigner as _, PrehashVerifier as _};
+use std::convert::TryInto;
+
+type AesCcm8 = Ccm<Aes128, U8, U12>;
+type AesCcm = Ccm<Aes128, U16, U12>;
+
+const RSA_KEY_BITS: usize = 2048;
+
+/// Pure Rust implementations from the RustCrypto and dalek projects.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RustCrypto;
+
+// Only ever lives behind an Arc, so the size difference between the ciphers doesn't matter.
+#[allow(clippy::large_enum_variant)]
+enum RustCryptoAead {
+    Gcm(Aes128Gcm),
+    Gcm256(Aes256Gcm),
+    Ccm(AesCcm),
+    Ccm8(AesCcm8),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl AeadKey for RustCryptoAead {
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        if nonce.len() != 12 {
+            return Err(Error::ErrNotEnoughRoomForNonce);
+        }
+        let nonce = GenericArray::from_slice(nonce);
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        match self {
+            RustCryptoAead::Gcm(c) => c.encrypt(nonce, payload),
+            RustCryptoAead::Gcm256(c) => c.encrypt(nonce, payload),
+            RustCryptoAead::Ccm(c) => c.encrypt(nonce, payload),
+            RustCryptoAead::Ccm8(c) => c.encrypt(nonce, payload),
+            RustCryptoAead::ChaCha20Poly1305(c) => c.encrypt(nonce, payload),
+        }
+        .map_err(|e| Error::Other(e.to_string()))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if nonce.len() != 12 {
+            return Err(Error::ErrNotEnoughRoomForNonce);
+        }
+        let nonce = GenericArray::from_slice(nonce);
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        match self {
+            RustCryptoAead::Gcm(c) => c.decrypt(nonce, payload),
+            RustCryptoAead::Gcm256(c) => c.decrypt(nonce, payload),
+            RustCryptoAead::Ccm(c) => c.decrypt(nonce, payload),
+            RustCryptoAead::Ccm8(c) => c.decrypt(nonce, payload),
+            RustCryptoAead::ChaCha20Poly1305(c) => c.decrypt(nonce, payload),
+        }
+        .map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+fn mac<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let mut mac = <M as Mac>::new_from_slice(key).map_err(|e| Error::Other(e.to_string()))?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// pkcs1v15 returns the RSASSA-PKCS1-v1_5 padding for `hash` and the digest it signs.
+fn pkcs1v15(hash: HashAlgorithm, message: &[u8]) -> Result<(PaddingScheme, Vec<u8>)> {
+    Ok(match hash {
+        HashAlgorithm::Sha1 => (
+            PaddingScheme::new_pkcs1v15_sign::<Sha1>(),
+            Sha1::digest(message).to_vec(),
+        ),
+        HashAlgorithm::Sha224 => (
+            PaddingScheme::new_pkcs1v15_sign::<Sha224>(),
+            Sha224::digest(message).to_vec(),
+        ),
+        HashAlgorithm::Sha256 => (
+            PaddingScheme::new_pkcs1v15_sign::<Sha256>(),
+            Sha256::digest(message).to_vec(),
+        ),
+        HashAlgorithm::Sha384 => (
+            PaddingScheme::new_pkcs1v15_sign::<Sha384>(),
+            Sha384::digest(message).to_vec(),
+        ),
+        HashAlgorithm::Sha512 => (
+            PaddingScheme::new_pkcs1v15_sign::<Sha512>(),
+            Sha512::digest(message).to_vec(),
+        ),
+        _ => return Err(Error::ErrInvalidHashAlgorithm),
+    })
+}
+
+/// ed25519_seed pulls the 32 byte seed out of an Ed25519 PKCS#8 document (RFC 8410).
+fn ed25519_seed(pkcs8_der: &[u8]) -> Result<ed25519_dalek::SecretKey> {
+    let (_, private_key) = der::private_key_info(pkcs8_der).ok_or(Error::ErrInvalidPrivateKey)?;
+    // CurvePrivateKey ::= OCTET STRING
+    match private_key {
+        [der::TAG_OCTET_STRING, 32, seed @ ..] => {
+            ed25519_dalek::SecretKey::from_bytes(seed).map_err(|e| Error::Other(e.to_string()))
+        }
+        _ => Err(Error::ErrInvalidPrivateKey),
+    }
+}
+
+pub(crate) fn ed25519_pkcs8(seed: &[u8]) -> Vec<u8> {
+    der::sequence(&[
+        &der::integer(&[0]),
+        &der::algorithm(der::OID_ED25519, None),
+        &der::octet_string(&der::octet_string(seed)),
+    ])
+}
+
+pub(crate) fn ed25519_public_key_info(public_key: &[u8]) -> Vec<u8> {
+    der::sequence(&[
+        &der::algorithm(der::OID_ED25519, None),
+        &der::bit_string(public_key),
+    ])
+}
+
+fn digest(hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>> {
+    Ok(match hash {
+        HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+        HashAlgorithm::Sha224 => Sha224::digest(data).to_vec(),
+        HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+        HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        _ => return Err(Error::ErrInvalidHashAlgorithm),
+    })
+}
+
+/// verify is [`CryptoProvider::verify`], for callers that can't wait.
+pub(crate) fn verify(
+    scheme: &SignatureHashAlgorithm,
+    public_key_info: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let (algorithm, public_key) = der::public_key_info(public_key_info)
+        .ok_or_else(|| Error::Other("invalid SubjectPublicKeyInfo".to_owned()))?;
+
+    match scheme.signature {
+        SignatureAlgorithm::Ed25519 => {
+            let public = ed25519_dalek::PublicKey::from_bytes(public_key)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let signature = ed25519_dalek::Signature::from_bytes(signature)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            public
+                .verify(message, &signature)
+                .map_err(|e| Error::Other(e.to_string()))
+        }
+        SignatureAlgorithm::Ecdsa => {
+            // The curve comes from the key, the hash from the signature scheme.
+            let prehash = digest(scheme.hash, message)?;
+            match algorithm.parameters {
+                Some(der::OID_PRIME256V1) => {
+                    let public = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                        .map_err(|e| Error::Other(e.to_string()))?;
+                    let signature = p256::ecdsa::Signature::from_der(signature)
+                        .map_err(|e| Error::Other(e.to_string()))?;
+                    public
+                        .verify_prehash(&prehash, &signature)
+                        .map_err(|e| Error::Other(e.to_string()))
+                }
+                Some(der::OID_SECP384R1) => {
+                    let public = p384::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                        .map_err(|e| Error::Other(e.to_string()))?;
+                    let signature = p384::ecdsa::Signature::from_der(signature)
+                        .map_err(|e| Error::Other(e.to_string()))?;
+                    public
+                        .verify_prehash(&prehash, &signature)
+                        .map_err(|e| Error::Other(e.to_string()))
+                }
+                _ => Err(Error::ErrKeySignatureVerifyUnimplemented),
+            }
+        }
+        SignatureAlgorithm::Rsa => {
+            let public = RsaPublicKey::from_public_key_der(public_key_info)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let (padding, hashed) = pkcs1v15(scheme.hash, message)?;
+            public
+                .verify(padding, &hashed, signature)
+                .map_err(|e| Error::Other(e.to_string()))
+        }
+        _ => Err(Error::ErrKeySignatureVerifyUnimplemented),
+    }
+}
+
+/// generate_private_key is [`CryptoProvider::generate_private_key`], for callers that can't wait.
+pub(crate) fn generate_private_key(signature: SignatureAlgorithm) -> Result<(CryptoPrivateKey, Vec<u8>)> {
+    let (kind, serialized_der, public_key_info) = match signature {
+        SignatureAlgorithm::Ecdsa => {
+            let secret_key = p256::SecretKey::random(&mut OsRng);
+            let serialized_der = secret_key
+                .to_pkcs8_der()
+                .map_err(|e| Error::Other(e.to_string()))?
+                .as_bytes()
+                .to_vec();
+            let public_key_info = secret_key
+                .public_key()
+                .to_public_key_der()
+                .map_err(|e| Error::Other(e.to_string()))?
+                .as_ref()
+                .to_vec();
+            (
+                CryptoPrivateKeyKind::Ecdsa256,
+                serialized_der,
+                public_key_info,
+            )
+        }
+        SignatureAlgorithm::Ed25519 => {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            let secret = ed25519_dalek::SecretKey::from_bytes(&seed)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let public = ed25519_dalek::PublicKey::from(&secret);
+            (
+                CryptoPrivateKeyKind::Ed25519,
+                ed25519_pkcs8(&seed),
+                ed25519_public_key_info(public.as_bytes()),
+            )
+        }
+        SignatureAlgorithm::Rsa => {
+            let secret_key = RsaPrivateKey::new(&mut OsRng, RSA_KEY_BITS)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let serialized_der = rsa::pkcs8::EncodePrivateKey::to_pkcs8_der(&secret_key)
+                .map_err(|e| Error::Other(e.to_string()))?
+                .as_bytes()
+                .to_vec();
+            let public_key_info =
+                rsa::pkcs8::EncodePublicKey::to_public_key_der(&secret_key.to_public_key())
+                    .map_err(|e| Error::Other(e.to_string()))?
+                    .as_ref()
+                    .to_vec();
+            (CryptoPrivateKeyKind::Rsa256, serialized_der, public_key_info)
+        }
+        SignatureAlgorithm::Unsupported => return Err(Error::ErrInvalidSignatureAlgorithm),
+    };
+
+    Ok((
+        CryptoPrivateKey {
+            kind,
+            serialized_der,
+        },
+        public_key_info,
+    ))
+}
+
+/// sign is [`CryptoProvider::sign`], for callers that can't wait.
+pub(crate) fn sign(
+    private_key: &CryptoPrivateKey,
+    hash: HashAlgorithm,
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    match private_key.kind {
+        CryptoPrivateKeyKind::Ed25519 => {
+            let secret = ed25519_seed(&private_key.serialized_der)?;
+            let public = ed25519_dalek::PublicKey::from(&secret);
+            Ok(ExpandedSecretKey::from(&secret)
+                .sign(message, &public)
+                .to_bytes()
+                .to_vec())
+        }
+        CryptoPrivateKeyKind::Ecdsa256 => {
+            let signing_key =
+                p256::ecdsa::SigningKey::from_pkcs8_der(&private_key.serialized_der)
+                    .map_err(|e| Error::Other(e.to_string()))?;
+            // TLS 1.2 lets any hash go with the key's curve, the digest is truncated to fit.
+            let signature: p256::ecdsa::Signature = signing_key
+                .sign_prehash(&digest(hash, message)?)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            Ok(signature.to_der().as_bytes().to_vec())
+        }
+        CryptoPrivateKeyKind::Rsa256 => {
+            let secret_key = RsaPrivateKey::from_pkcs8_der(&private_key.serialized_der)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let (padding, hashed) = pkcs1v15(hash, message)?;
+            secret_key
+                .sign(padding, &hashed)
+                .map_err(|e| Error::Other(e.to_string()))
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl CryptoProvider for RustCrypto {
+    fn hash(&self, hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>> {
+        digest(hash, data)
+    }
+
+    fn hmac(&self, hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        match hash {
+            HashAlgorithm::Sha1 => mac::<Hmac<Sha1>>(key, data),
+            HashAlgorithm::Sha224 => mac::<Hmac<Sha224>>(key, data),
+            HashAlgorithm::Sha256 => mac::<Hmac<Sha256>>(key, data),
+            HashAlgorithm::Sha384 => mac::<Hmac<Sha384>>(key, data),
+            HashAlgorithm::Sha512 => mac::<Hmac<Sha512>>(key, data),
+            _ => Err(Error::ErrInvalidHashAlgorithm),
+        }
+    }
+
+    fn aead(&self, algorithm: AeadAlgorithm, key: &[u8]) -> Result<Arc<dyn AeadKey>> {
+        if key.len() != algorithm.key_len() {
+            return Err(Error::Other(format!(
+                "invalid {:?} key length {}",
+                algorithm,
+                key.len()
+            )));
+        }
+        Ok(Arc::new(match algorithm {
+            AeadAlgorithm::Aes128Gcm => {
+                RustCryptoAead::Gcm(Aes128Gcm::new(GenericArray::from_slice(key)))
+            }
+            AeadAlgorithm::Aes256Gcm => {
+                RustCryptoAead::Gcm256(Aes256Gcm::new(GenericArray::from_slice(key)))
+            }
+            AeadAlgorithm::Aes128Ccm => {
+                RustCryptoAead::Ccm(AesCcm::new(GenericArray::from_slice(key)))
+            }
+            AeadAlgorithm::Aes128Ccm8 => {
+                RustCryptoAead::Ccm8(AesCcm8::new(GenericArray::from_slice(key)))
+            }
+            AeadAlgorithm::ChaCha20Poly1305 => RustCryptoAead::ChaCha20Poly1305(
+                ChaCha20Poly1305::new(GenericArray::from_slice(key)),
+            ),
+        }))
+    }
+
+    async fn generate_keypair(&self, curve: NamedCurve) -> Result<NamedCurveKeypair> {
+        curve.generate_keypair()
+    }
+
+    async fn ecdh(&self, local: &NamedCurveKeypair, remote_public_key: &[u8]) -> Result<Vec<u8>> {
+        match local.curve {
+            NamedCurve::P256 => {
+                let secret = p256::SecretKey::from_be_bytes(&local.private_key)?;
+                let public = p256::PublicKey::from_sec1_bytes(remote_public_key)?;
+                Ok(
+                    p256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine())
+                        .raw_secret_bytes()
+                        .to_vec(),
+                )
+            }
+            NamedCurve::P384 => {
+                let secret = p384::SecretKey::from_be_bytes(&local.private_key)?;
+                let public = p384::PublicKey::from_sec1_bytes(remote_public_key)?;
+                Ok(
+                    p384::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine())
+                        .raw_secret_bytes()
+                        .to_vec(),
+                )
+            }
+            NamedCurve::X25519 => {
+                let secret: [u8; 32] = local
+                    .private_key
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| Error::ErrNamedCurveAndPrivateKeyMismatch)?;
+                let public: [u8; 32] = remote_public_key
+                    .try_into()
+                    .map_err(|_| Error::Other("Public key is not 32 len".into()))?;
+                let secret = x25519_dalek::StaticSecret::from(secret);
+                let public = x25519_dalek::PublicKey::from(public);
+                Ok(secret.diffie_hellman(&public).as_bytes().to_vec())
+            }
+            _ => Err(Error::ErrInvalidNamedCurve),
+        }
+    }
+
+    async fn generate_private_key(
+        &self,
+        signature: SignatureAlgorithm,
+    ) -> Result<(CryptoPrivateKey, Vec<u8>)> {
+        generate_private_key(signature)
+    }
+
+    async fn sign(
+        &self,
+        private_key: &CryptoPrivateKey,
+        hash: HashAlgorithm,
+        message: &[u8],
+    ) -> Result<Vec<u8>> {
+        sign(private_key, hash, message)
+    }
+
+    async fn verify(
+        &self,
+        scheme: &SignatureHashAlgorithm,
+        public_key_info: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        verify(scheme, public_key_info, message, signature)
+    }
+}
@@ -0,0 +1,381 @@
+use super::*;
+use crate::crypto::der;
+use crate::crypto::CryptoPrivateKeyKind;
+
+use js_sys::{Array, ArrayBuffer, Object, Reflect, Uint8Array};
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = generateKey)]
+    async fn generate_key(
+        algorithm: &JsValue,
+        extractable: bool,
+        key_usages: &Array,
+    ) -> std::result::Result<JsValue, JsValue>; // Promise<CryptoKeyPair>
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = importKey)]
+    async fn import_key(
+        format: &str,
+        key_data: &Uint8Array,
+        algorithm: &JsValue,
+        extractable: bool,
+        key_usages: &Array,
+    ) -> std::result::Result<JsValue, JsValue>; // Promise<CryptoKey>
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = exportKey)]
+    async fn export_key(format: &str, key: &JsValue) -> std::result::Result<JsValue, JsValue>; // Promise<ArrayBuffer>
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = sign)]
+    async fn subtle_sign(
+        algorithm: &JsValue,
+        key: &JsValue,
+        data: &Uint8Array,
+    ) -> std::result::Result<JsValue, JsValue>; // Promise<ArrayBuffer>
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = verify)]
+    async fn subtle_verify(
+        algorithm: &JsValue,
+        key: &JsValue,
+        signature: &Uint8Array,
+        data: &Uint8Array,
+    ) -> std::result::Result<JsValue, JsValue>; // Promise<boolean>
+    #[wasm_bindgen(catch, js_namespace = ["crypto", "subtle"], js_name = deriveBits)]
+    async fn derive_bits(
+        algorithm: &JsValue,
+        base_key: &JsValue,
+        length: u32,
+    ) -> std::result::Result<JsValue, JsValue>; // Promise<ArrayBuffer>
+}
+
+/// Runs key generation, key agreement and signatures on the runtime's `crypto.subtle`.
+///
+/// WebCrypto has no synchronous API and no AES-CCM, so hashing, HMAC and AEAD stay with
+/// [`RustCrypto`]. X25519 and Ed25519 need a runtime that implements them, which Deno does.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WebCrypto;
+
+fn js_err(e: JsValue) -> Error {
+    let message = Reflect::get(&e, &JsValue::from_str("message"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .or_else(|| e.as_string())
+        .unwrap_or_else(|| "crypto.subtle failed".to_owned());
+    Error::Other(message)
+}
+
+fn object(fields: &[(&str, JsValue)]) -> JsValue {
+    let o = Object::new();
+    for (k, v) in fields {
+        let _ = Reflect::set(&o, &JsValue::from_str(k), v);
+    }
+    o.into()
+}
+
+fn usages(usages: &[&str]) -> Array {
+    usages.iter().map(|u| JsValue::from_str(u)).collect()
+}
+
+fn bytes(buffer: JsValue) -> Vec<u8> {
+    Uint8Array::new(&buffer.unchecked_into::<ArrayBuffer>()).to_vec()
+}
+
+fn hash_name(hash: HashAlgorithm) -> Result<&'static str> {
+    Ok(match hash {
+        HashAlgorithm::Sha1 => "SHA-1",
+        HashAlgorithm::Sha256 => "SHA-256",
+        HashAlgorithm::Sha384 => "SHA-384",
+        HashAlgorithm::Sha512 => "SHA-512",
+        _ => return Err(Error::ErrInvalidHashAlgorithm),
+    })
+}
+
+/// ecdh_algorithm returns the importKey/generateKey algorithm and the shared secret size in bits.
+fn ecdh_algorithm(curve: NamedCurve) -> Result<(JsValue, u32)> {
+    Ok(match curve {
+        NamedCurve::P256 => (
+            object(&[("name", "ECDH".into()), ("namedCurve", "P-256".into())]),
+            256,
+        ),
+        NamedCurve::P384 => (
+            object(&[("name", "ECDH".into()), ("namedCurve", "P-384".into())]),
+            384,
+        ),
+        NamedCurve::X25519 => (object(&[("name", "X25519".into())]), 256),
+        _ => return Err(Error::ErrInvalidNamedCurve),
+    })
+}
+
+fn signing_algorithm(kind: CryptoPrivateKeyKind, hash: HashAlgorithm) -> Result<JsValue> {
+    Ok(match kind {
+        CryptoPrivateKeyKind::Ed25519 => object(&[("name", "Ed25519".into())]),
+        CryptoPrivateKeyKind::Ecdsa256 => {
+            object(&[("name", "ECDSA".into()), ("namedCurve", "P-256".into())])
+        }
+        CryptoPrivateKeyKind::Rsa256 => object(&[
+            ("name", "RSASSA-PKCS1-v1_5".into()),
+            ("hash", hash_name(hash)?.into()),
+        ]),
+    })
+}
+
+/// raw_private_key pulls the private scalar out of the PKCS#8 WebCrypto exports.
+fn raw_private_key(curve: NamedCurve, pkcs8_der: &[u8]) -> Result<Vec<u8>> {
+    let (_, private_key) = der::private_key_info(pkcs8_der).ok_or(Error::ErrInvalidPrivateKey)?;
+    match curve {
+        NamedCurve::X25519 => match private_key {
+            [der::TAG_OCTET_STRING, 32, secret @ ..] => Ok(secret.to_vec()),
+            _ => Err(Error::ErrInvalidPrivateKey),
+        },
+        _ => Ok(sec1::EcPrivateKey::try_from(private_key)?
+            .private_key
+            .to_vec()),
+    }
+}
+
+/// private_key_info rebuilds the PKCS#8 document WebCrypto imports for an ephemeral key.
+fn private_key_info(curve: NamedCurve, private_key: &[u8]) -> Result<Vec<u8>> {
+    let (algorithm, private_key) = match curve {
+        NamedCurve::X25519 => (
+            der::algorithm(der::OID_X25519, None),
+            der::octet_string(private_key),
+        ),
+        NamedCurve::P256 | NamedCurve::P384 => {
+            let curve_oid = if curve == NamedCurve::P256 {
+                der::OID_PRIME256V1
+            } else {
+                der::OID_SECP384R1
+            };
+            (
+                der::algorithm(der::OID_EC_PUBLIC_KEY, Some(&der::oid(curve_oid))),
+                // ECPrivateKey ::= SEQUENCE { version 1, privateKey OCTET STRING, ... }
+                der::sequence(&[&der::integer(&[1]), &der::octet_string(private_key)]),
+            )
+        }
+        _ => return Err(Error::ErrInvalidNamedCurve),
+    };
+    Ok(der::sequence(&[
+        &der::integer(&[0]),
+        &algorithm,
+        &der::octet_string(&private_key),
+    ]))
+}
+
+#[async_trait(?Send)]
+impl CryptoProvider for WebCrypto {
+    fn hash(&self, hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>> {
+        RustCrypto.hash(hash, data)
+    }
+
+    fn hmac(&self, hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        RustCrypto.hmac(hash, key, data)
+    }
+
+    fn aead(&self, algorithm: AeadAlgorithm, key: &[u8]) -> Result<Arc<dyn AeadKey>> {
+        RustCrypto.aead(algorithm, key)
+    }
+
+    async fn generate_keypair(&self, curve: NamedCurve) -> Result<NamedCurveKeypair> {
+        let (algorithm, _) = ecdh_algorithm(curve)?;
+        let keypair = generate_key(&algorithm, true, &usages(&["deriveBits"]))
+            .await
+            .map_err(js_err)?;
+
+        let public_key = Reflect::get(&keypair, &JsValue::from_str("publicKey")).map_err(js_err)?;
+        let private_key =
+            Reflect::get(&keypair, &JsValue::from_str("privateKey")).map_err(js_err)?;
+
+        let public_key = bytes(export_key("raw", &public_key).await.map_err(js_err)?);
+        let private_key = bytes(export_key("pkcs8", &private_key).await.map_err(js_err)?);
+
+        Ok(NamedCurveKeypair {
+            curve,
+            public_key,
+            private_key: raw_private_key(curve, &private_key)?,
+        })
+    }
+
+    async fn ecdh(&self, local: &NamedCurveKeypair, remote_public_key: &[u8]) -> Result<Vec<u8>> {
+        let (algorithm, bits) = ecdh_algorithm(local.curve)?;
+
+        let private_key = Uint8Array::from(
+            private_key_info(local.curve, &local.private_key)?.as_slice(),
+        );
+        let private_key = import_key(
+            "pkcs8",
+            &private_key,
+            &algorithm,
+            false,
+            &usages(&["deriveBits"]),
+        )
+        .await
+        .map_err(js_err)?;
+        let public_key = import_key(
+            "raw",
+            &Uint8Array::from(remote_public_key),
+            &algorithm,
+            true,
+            &usages(&[]),
+        )
+        .await
+        .map_err(js_err)?;
+
+        let name = Reflect::get(&algorithm, &JsValue::from_str("name")).map_err(js_err)?;
+        let params = object(&[("name", name), ("public", public_key)]);
+        Ok(bytes(
+            derive_bits(&params, &private_key, bits)
+                .await
+                .map_err(js_err)?,
+        ))
+    }
+
+    async fn generate_private_key(
+        &self,
+        signature: SignatureAlgorithm,
+    ) -> Result<(CryptoPrivateKey, Vec<u8>)> {
+        let algorithm = match signature {
+            SignatureAlgorithm::Ecdsa => {
+                signing_algorithm(CryptoPrivateKeyKind::Ecdsa256, HashAlgorithm::Sha256)?
+            }
+            SignatureAlgorithm::Ed25519 => {
+                signing_algorithm(CryptoPrivateKeyKind::Ed25519, HashAlgorithm::Sha256)?
+            }
+            SignatureAlgorithm::Rsa => object(&[
+                ("name", "RSASSA-PKCS1-v1_5".into()),
+                ("modulusLength", 2048.into()),
+                ("publicExponent", Uint8Array::from(&[1u8, 0, 1][..]).into()),
+                ("hash", "SHA-256".into()),
+            ]),
+            SignatureAlgorithm::Unsupported => return Err(Error::ErrInvalidSignatureAlgorithm),
+        };
+
+        let keypair = generate_key(&algorithm, true, &usages(&["sign", "verify"]))
+            .await
+            .map_err(js_err)?;
+        let public_key = Reflect::get(&keypair, &JsValue::from_str("publicKey")).map_err(js_err)?;
+        let private_key =
+            Reflect::get(&keypair, &JsValue::from_str("privateKey")).map_err(js_err)?;
+
+        let public_key_info = bytes(export_key("spki", &public_key).await.map_err(js_err)?);
+        let serialized_der = bytes(export_key("pkcs8", &private_key).await.map_err(js_err)?);
+
+        Ok((CryptoPrivateKey::from_pkcs8(serialized_der)?, public_key_info))
+    }
+
+    async fn sign(
+        &self,
+        private_key: &CryptoPrivateKey,
+        hash: HashAlgorithm,
+        message: &[u8],
+    ) -> Result<Vec<u8>> {
+        let algorithm = signing_algorithm(private_key.kind, hash)?;
+        let key = import_key(
+            "pkcs8",
+            &Uint8Array::from(private_key.serialized_der.as_slice()),
+            &algorithm,
+            false,
+            &usages(&["sign"]),
+        )
+        .await
+        .map_err(js_err)?;
+
+        let params = match private_key.kind {
+            CryptoPrivateKeyKind::Ecdsa256 => {
+                object(&[("name", "ECDSA".into()), ("hash", hash_name(hash)?.into())])
+            }
+            _ => algorithm,
+        };
+        let signature = bytes(
+            subtle_sign(&params, &key, &Uint8Array::from(message))
+                .await
+                .map_err(js_err)?,
+        );
+
+        match private_key.kind {
+            // WebCrypto produces r || s, TLS carries the DER encoding.
+            CryptoPrivateKeyKind::Ecdsa256 => Ok(p256::ecdsa::Signature::try_from(
+                signature.as_slice(),
+            )
+            .map_err(|e| Error::Other(e.to_string()))?
+            .to_der()
+            .as_bytes()
+            .to_vec()),
+            _ => Ok(signature),
+        }
+    }
+
+    async fn verify(
+        &self,
+        scheme: &SignatureHashAlgorithm,
+        public_key_info: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        let (algorithm, params, signature) = match scheme.signature {
+            SignatureAlgorithm::Ed25519 => {
+                let algorithm = object(&[("name", "Ed25519".into())]);
+                (algorithm.clone(), algorithm, signature.to_vec())
+            }
+            SignatureAlgorithm::Ecdsa => {
+                // The curve comes from the key, the hash from the signature scheme.
+                let (algorithm, _) = der::public_key_info(public_key_info)
+                    .ok_or_else(|| Error::Other("invalid SubjectPublicKeyInfo".to_owned()))?;
+                let (curve, signature) = match algorithm.parameters {
+                    Some(der::OID_PRIME256V1) => (
+                        "P-256",
+                        p256::ecdsa::Signature::from_der(signature)
+                            .map_err(|e| Error::Other(e.to_string()))?
+                            .as_ref()
+                            .to_vec(),
+                    ),
+                    Some(der::OID_SECP384R1) => (
+                        "P-384",
+                        p384::ecdsa::Signature::from_der(signature)
+                            .map_err(|e| Error::Other(e.to_string()))?
+                            .as_ref()
+                            .to_vec(),
+                    ),
+                    _ => return Err(Error::ErrKeySignatureVerifyUnimplemented),
+                };
+                (
+                    object(&[("name", "ECDSA".into()), ("namedCurve", curve.into())]),
+                    object(&[
+                        ("name", "ECDSA".into()),
+                        ("hash", hash_name(scheme.hash)?.into()),
+                    ]),
+                    signature,
+                )
+            }
+            SignatureAlgorithm::Rsa => {
+                let algorithm = object(&[
+                    ("name", "RSASSA-PKCS1-v1_5".into()),
+                    ("hash", hash_name(scheme.hash)?.into()),
+                ]);
+                (algorithm.clone(), algorithm, signature.to_vec())
+            }
+            _ => return Err(Error::ErrKeySignatureVerifyUnimplemented),
+        };
+
+        let key = import_key(
+            "spki",
+            &Uint8Array::from(public_key_info),
+            &algorithm,
+            false,
+            &usages(&["verify"]),
+        )
+        .await
+        .map_err(js_err)?;
+
+        let valid = subtle_verify(
+            &params,
+            &key,
+            &Uint8Array::from(signature.as_slice()),
+            &Uint8Array::from(message),
+        )
+        .await
+        .map_err(js_err)?;
+
+        if valid.as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err(Error::Other("signature verification failed".to_owned()))
+        }
+    }
+}
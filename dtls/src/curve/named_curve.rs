@@ -23,34 +23,29 @@ impl From<u16> for NamedCurve {
     }
 }
 
-pub(crate) enum NamedCurvePrivateKey {
-    EphemeralSecretP256(p256::ecdh::EphemeralSecret),
-    EphemeralSecretP384(p384::ecdh::EphemeralSecret),
-    StaticSecretX25519(x25519_dalek::StaticSecret),
-}
-
 pub struct NamedCurveKeypair {
     pub(crate) curve: NamedCurve,
     pub(crate) public_key: Vec<u8>,
-    pub(crate) private_key: NamedCurvePrivateKey,
+    /// Big-endian scalar for the NIST curves, the clamped 32 byte secret for X25519.
+    pub(crate) private_key: Vec<u8>,
 }
 
 fn elliptic_curve_keypair(curve: NamedCurve) -> Result<NamedCurveKeypair> {
     let (public_key, private_key) = match curve {
         NamedCurve::P256 => {
-            let secret_key = p256::ecdh::EphemeralSecret::random(&mut OsRng);
+            let secret_key = p256::SecretKey::random(&mut OsRng);
             let public_key = p256::EncodedPoint::from(secret_key.public_key());
             (
                 public_key.as_bytes().to_vec(),
-                NamedCurvePrivateKey::EphemeralSecretP256(secret_key),
+                secret_key.to_be_bytes().to_vec(),
             )
         }
         NamedCurve::P384 => {
-            let secret_key = p384::ecdh::EphemeralSecret::random(&mut OsRng);
+            let secret_key = p384::SecretKey::random(&mut OsRng);
             let public_key = p384::EncodedPoint::from(secret_key.public_key());
             (
                 public_key.as_bytes().to_vec(),
-                NamedCurvePrivateKey::EphemeralSecretP384(secret_key),
+                secret_key.to_be_bytes().to_vec(),
             )
         }
         NamedCurve::X25519 => {
@@ -58,7 +53,7 @@ fn elliptic_curve_keypair(curve: NamedCurve) -> Result<NamedCurveKeypair> {
             let public_key = x25519_dalek::PublicKey::from(&secret_key);
             (
                 public_key.as_bytes().to_vec(),
-                NamedCurvePrivateKey::StaticSecretX25519(secret_key),
+                secret_key.to_bytes().to_vec(),
             )
         }
         _ => return Err(Error::ErrInvalidNamedCurve),
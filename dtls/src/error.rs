@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+#[cfg(not(target_arch = "wasm32"))]
 use rcgen::RcgenError;
 use std::io;
 use std::string::FromUtf8Error;
@@ -33,6 +34,8 @@ pub enum Error {
     ErrReservedExportKeyingMaterial,
     #[error("client sent certificate verify but we have no certificate to verify")]
     ErrCertificateVerifyNoCertificate,
+    #[error("peer sent an empty certificate chain")]
+    ErrEmptyCertificateChain,
     #[error("client+server do not support any shared cipher suites")]
     ErrCipherSuiteNoIntersection,
     #[error("server hello can not be created without a cipher suite")]
@@ -51,6 +54,12 @@ pub enum Error {
     ErrCookieMismatch,
     #[error("cookie must not be longer then 255 bytes")]
     ErrCookieTooLong,
+    #[error("session id must not be longer then 32 bytes")]
+    ErrSessionIdTooLong,
+    #[error("resumed session doesn't match the cipher suite or extended master secret")]
+    ErrSessionMismatch,
+    #[error("connection id must not be longer then 255 bytes")]
+    ErrConnectionIdTooLong,
     #[error("PSK Identity Hint provided but PSK is nil")]
     ErrIdentityNoPsk,
     #[error("no certificate provided")]
@@ -159,6 +168,7 @@ pub enum Error {
     Sec1(#[source] sec1::Error),
     #[error("{0}")]
     P256(#[source] P256Error),
+    #[cfg(not(target_arch = "wasm32"))]
     #[error("{0}")]
     RcGen(#[from] RcgenError),
     #[error("mpsc send: {0}")]
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod extension_connection_id_test;
+
+use super::*;
+
+// ExtensionConnectionId carries the connection id the sender wants to receive in records
+// addressed to it. An empty connection id means the sender is willing to send connection
+// ids but doesn't want to receive them.
+// https://www.rfc-editor.org/rfc/rfc9146#section-3
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtensionConnectionId {
+    pub(crate) cid: Vec<u8>,
+}
+
+impl ExtensionConnectionId {
+    pub fn extension_value(&self) -> ExtensionValue {
+        ExtensionValue::ConnectionId
+    }
+
+    pub fn size(&self) -> usize {
+        2 + 1 + self.cid.len()
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        if self.cid.len() > u8::MAX as usize {
+            return Err(Error::ErrConnectionIdTooLong);
+        }
+
+        writer.write_u16::<BigEndian>(1 + self.cid.len() as u16)?;
+        writer.write_u8(self.cid.len() as u8)?;
+        writer.write_all(&self.cid)?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let _ = reader.read_u16::<BigEndian>()?;
+
+        let cid_len = reader.read_u8()? as usize;
+        let mut cid = vec![0u8; cid_len];
+        reader.read_exact(&mut cid)?;
+
+        Ok(ExtensionConnectionId { cid })
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+use std::io::{BufReader, BufWriter};
+
+#[test]
+fn test_extension_connection_id() -> Result<()> {
+    let tests = vec![
+        (
+            ExtensionConnectionId { cid: vec![] },
+            vec![0x00, 0x36, 0x00, 0x01, 0x00],
+        ),
+        (
+            ExtensionConnectionId {
+                cid: vec![0x01, 0x02, 0x03, 0x04],
+            },
+            vec![0x00, 0x36, 0x00, 0x05, 0x04, 0x01, 0x02, 0x03, 0x04],
+        ),
+    ];
+
+    for (extension, want) in tests {
+        let extension = Extension::ConnectionId(extension);
+
+        let mut raw = vec![];
+        {
+            let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+            extension.marshal(&mut writer)?;
+        }
+        assert_eq!(
+            raw, want,
+            "extensionConnectionId marshal: got {:?} want {:?}",
+            raw, want
+        );
+        assert_eq!(raw.len(), extension.size());
+
+        let mut reader = BufReader::new(raw.as_slice());
+        let new_extension = Extension::unmarshal(&mut reader)?;
+        assert_eq!(
+            new_extension, extension,
+            "extensionConnectionId unmarshal: got {:?} expected {:?}",
+            new_extension, extension,
+        );
+    }
+
+    Ok(())
+}
@@ -32,3 +32,36 @@ fn test_extension_use_srtp() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_extension_use_srtp_profiles() -> Result<()> {
+    let raw_use_srtp = vec![
+        0x00, 0x09, 0x00, 0x06, 0x00, 0x08, 0x00, 0x07, 0x00, 0x02, 0x00,
+    ];
+    let parsed_use_srtp = ExtensionUseSrtp {
+        protection_profiles: vec![
+            SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm,
+            SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm,
+            SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_32,
+        ],
+    };
+
+    let mut reader = BufReader::new(raw_use_srtp.as_slice());
+    let new_use_srtp = ExtensionUseSrtp::unmarshal(&mut reader)?;
+    assert_eq!(new_use_srtp, parsed_use_srtp);
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        parsed_use_srtp.marshal(&mut writer)?;
+    }
+    assert_eq!(raw, raw_use_srtp);
+
+    // 0x0003 is AES128-F8 in the drafts of RFC 5764 and has no IANA assignment.
+    assert_eq!(
+        SrtpProtectionProfile::from(0x0003),
+        SrtpProtectionProfile::Unsupported
+    );
+
+    Ok(())
+}
@@ -136,6 +136,16 @@ impl Flight for Flight0 {
                     Extension::ServerName(e) => {
                         state.server_name = e.server_name.clone(); // remote server name
                     }
+                    Extension::ConnectionId(e) => {
+                        // Only negotiate connection ids if we are configured to use them.
+                        if let Some(connection_id_generator) = &cfg.connection_id_generator {
+                            *state.remote_connection_id.lock().await = Some(e.cid.clone());
+                            let mut local_connection_id = state.local_connection_id.lock().await;
+                            if local_connection_id.is_none() {
+                                *local_connection_id = Some(connection_id_generator());
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -153,7 +163,7 @@ impl Flight for Flight0 {
             }
 
             if state.local_keypair.is_none() {
-                state.local_keypair = match state.named_curve.generate_keypair() {
+                state.local_keypair = match state.crypto_provider.generate_keypair(state.named_curve).await {
                     Ok(local_keypar) => Some(local_keypar),
                     Err(err) => {
                         return Err((
@@ -6,6 +6,7 @@ use crate::conn::*;
 use crate::content::*;
 use crate::curve::named_curve::*;
 use crate::error::Error;
+use crate::extension::extension_connection_id::*;
 use crate::extension::extension_server_name::*;
 use crate::extension::extension_supported_elliptic_curves::*;
 use crate::extension::extension_supported_point_formats::*;
@@ -129,6 +130,30 @@ impl Flight for Flight1 {
         state.cookie = vec![];
         state.local_random.populate();
 
+        if let Some(session_store) = &cfg.session_store {
+            log::trace!("[handshake] try to resume session");
+            match session_store.get(&cfg.session_key(state)).await {
+                Ok(Some(session)) => {
+                    log::trace!("[handshake] get saved session: {:?}", session.id);
+                    state.session_id = session.id;
+                    state.master_secret = session.secret;
+                    state.session_cipher_suite = session.cipher_suite;
+                    state.session_extended_master_secret = session.extended_master_secret;
+                    state.peer_certificates = session.peer_certificates;
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ))
+                }
+            }
+        }
+
         let mut extensions = vec![
             Extension::SupportedSignatureAlgorithms(ExtensionSupportedSignatureAlgorithms {
                 signature_hash_algorithms: cfg.local_signature_schemes.clone(),
@@ -169,6 +194,12 @@ impl Flight for Flight1 {
             }));
         }
 
+        if let Some(connection_id_generator) = &cfg.connection_id_generator {
+            let cid = connection_id_generator();
+            *state.local_connection_id.lock().await = Some(cid.clone());
+            extensions.push(Extension::ConnectionId(ExtensionConnectionId { cid }));
+        }
+
         Ok(vec![Packet {
             record: RecordLayer::new(
                 PROTOCOL_VERSION1_2,
@@ -177,6 +208,7 @@ impl Flight for Flight1 {
                     HandshakeMessageClientHello {
                         version: PROTOCOL_VERSION1_2,
                         random: state.local_random.clone(),
+                        session_id: state.session_id.clone(),
                         cookie: state.cookie.clone(),
 
                         cipher_suites: cfg.local_cipher_suites.clone(),
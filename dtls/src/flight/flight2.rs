@@ -1,8 +1,11 @@
 use super::flight0::*;
 use super::flight4::*;
+use super::flight4b::*;
 use super::*;
+use crate::cipher_suite::*;
 use crate::content::*;
 use crate::error::Error;
+use crate::handshake::handshake_message_client_hello::*;
 use crate::handshake::handshake_message_hello_verify_request::*;
 use crate::handshake::*;
 use crate::record_layer::record_layer_header::*;
@@ -93,7 +96,7 @@ impl Flight for Flight2 {
                 ));
             }
 
-            Ok(Box::new(Flight4 {}))
+            handle_hello_resume(client_hello, state, cfg).await
         } else {
             Err((
                 Some(Alert {
@@ -128,3 +131,83 @@ impl Flight for Flight2 {
         }])
     }
 }
+
+// handle_hello_resume picks the abbreviated handshake if the client offered a session we still
+// have and the hello can resume it, and the full handshake otherwise.
+async fn handle_hello_resume(
+    client_hello: &HandshakeMessageClientHello,
+    state: &mut State,
+    cfg: &HandshakeConfig,
+) -> Result<Box<dyn Flight>, (Option<Alert>, Option<Error>)> {
+    let session_id = &client_hello.session_id;
+    if let Some(session_store) = &cfg.session_store {
+        if !session_id.is_empty() {
+            let session = match session_store.get(session_id).await {
+                Ok(session) => session,
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ))
+                }
+            };
+
+            // A session negotiated with extended master secret must not be resumed without it.
+            // https://tools.ietf.org/html/rfc7627#section-5.3
+            if matches!(&session, Some(s) if s.extended_master_secret && !state.extended_master_secret)
+            {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::HandshakeFailure,
+                    }),
+                    Some(Error::ErrSessionMismatch),
+                ));
+            }
+
+            // The abbreviated handshake keeps the session's cipher suite, so the client has to
+            // offer it again, and both sides have to agree on extended master secret as before.
+            let resumable = session.filter(|s| {
+                s.extended_master_secret == state.extended_master_secret
+                    && client_hello.cipher_suites.contains(&s.cipher_suite)
+                    && cfg.local_cipher_suites.contains(&s.cipher_suite)
+            });
+            let cipher_suite = resumable
+                .as_ref()
+                .and_then(|s| cipher_suite_for_id(s.cipher_suite).ok());
+            if let (Some(session), Some(cipher_suite)) = (resumable, cipher_suite) {
+                log::trace!("[handshake] resume session: {:?}", session_id);
+                state.session_id = session_id.to_vec();
+                state.master_secret = session.secret;
+                state.peer_certificates = session.peer_certificates;
+                *state.cipher_suite.lock().await = Some(cipher_suite);
+
+                if let Err(err) = state.init_cipher_suite().await {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ));
+                }
+                if let Err(err) = state.log_master_secret(&cfg.key_log) {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ));
+                }
+
+                return Ok(Box::new(Flight4b {}));
+            }
+        }
+    }
+
+    Ok(Box::new(Flight4 {}))
+}
@@ -1,10 +1,12 @@
 use super::flight5::*;
+use super::flight5b::*;
 use super::*;
 use crate::compression_methods::*;
 use crate::config::*;
 use crate::content::*;
 use crate::curve::named_curve::*;
 use crate::error::Error;
+use crate::extension::extension_connection_id::*;
 use crate::extension::extension_server_name::*;
 use crate::extension::extension_supported_elliptic_curves::*;
 use crate::extension::extension_supported_point_formats::*;
@@ -19,7 +21,7 @@ use crate::record_layer::record_layer_header::*;
 use crate::record_layer::*;
 
 use crate::cipher_suite::cipher_suite_for_id;
-use crate::prf::{prf_pre_master_secret, prf_psk_pre_master_secret};
+use crate::prf::{prf_pre_master_secret, prf_psk_pre_master_secret, prf_verify_data_server};
 use crate::{find_matching_cipher_suite, find_matching_srtp_profile};
 
 use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
@@ -40,7 +42,7 @@ impl fmt::Display for Flight3 {
 impl Flight for Flight3 {
     async fn parse(
         &self,
-        _tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        tx: &mut mpsc::Sender<mpsc::Sender<()>>,
         state: &mut State,
         cache: &HandshakeCache,
         cfg: &HandshakeConfig,
@@ -94,7 +96,42 @@ impl Flight for Flight3 {
             }
         }
 
-        let result = if cfg.local_psk_callback.is_some() {
+        // A ServerHello echoing the session id we offered means the server resumed that
+        // session, so no certificates or key exchange follow it.
+        let resuming = match cache
+            .full_pull_map(
+                state.handshake_recv_sequence,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::ServerHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: false,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((_, msgs)) => match msgs.get(&HandshakeType::ServerHello) {
+                Some(HandshakeMessage::ServerHello(h)) => {
+                    !state.session_id.is_empty() && h.session_id == state.session_id
+                }
+                _ => false,
+            },
+            Err(_) => return Err((None, None)),
+        };
+
+        let result = if resuming {
+            cache
+                .full_pull_map(
+                    state.handshake_recv_sequence,
+                    &[HandshakeCachePullRule {
+                        typ: HandshakeType::ServerHello,
+                        epoch: cfg.initial_epoch,
+                        is_client: false,
+                        optional: false,
+                    }],
+                )
+                .await
+        } else if cfg.local_psk_callback.is_some() {
             cache
                 .full_pull_map(
                     state.handshake_recv_sequence,
@@ -165,7 +202,11 @@ impl Flight for Flight3 {
             Err(_) => return Err((None, None)),
         };
 
-        state.handshake_recv_sequence = seq;
+        // A resumed handshake is parsed again from the ServerHello until the server's
+        // Finished arrives.
+        if !resuming {
+            state.handshake_recv_sequence = seq;
+        }
 
         if let Some(message) = msgs.get(&HandshakeType::ServerHello) {
             let h = match message {
@@ -191,6 +232,7 @@ impl Flight for Flight3 {
                 ));
             }
 
+            let mut server_connection_id = None;
             for extension in &h.extensions {
                 match extension {
                     Extension::UseSrtp(e) => {
@@ -216,10 +258,24 @@ impl Flight for Flight3 {
                             state.extended_master_secret = true;
                         }
                     }
+                    Extension::ConnectionId(e) => {
+                        server_connection_id = Some(e.cid.clone());
+                    }
                     _ => {}
                 };
             }
 
+            // Connection ids are only used if we offered one and the server echoed it back.
+            // https://www.rfc-editor.org/rfc/rfc9146#section-3
+            {
+                let mut local_connection_id = state.local_connection_id.lock().await;
+                if local_connection_id.is_some() && server_connection_id.is_some() {
+                    *state.remote_connection_id.lock().await = server_connection_id;
+                } else {
+                    *local_connection_id = None;
+                }
+            }
+
             if cfg.extended_master_secret == ExtendedMasterSecretType::Require
                 && !state.extended_master_secret
             {
@@ -287,6 +343,41 @@ impl Flight for Flight3 {
                 *cs = Some(cipher_suite);
             }
             state.remote_random = h.random.clone();
+
+            if resuming {
+                // The server has to resume with the parameters the session was negotiated with.
+                // https://tools.ietf.org/html/rfc7627#section-5.3
+                if h.cipher_suite != state.session_cipher_suite
+                    || state.extended_master_secret != state.session_extended_master_secret
+                {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::HandshakeFailure,
+                        }),
+                        Some(Error::ErrSessionMismatch),
+                    ));
+                }
+                return handle_resumption(tx, state, cache, cfg, seq).await;
+            }
+
+            if let Some(session_store) = &cfg.session_store {
+                if !state.session_id.is_empty() {
+                    trace!("[handshake] clean old session: {:?}", state.session_id);
+                    if let Err(err) = session_store.del(&cfg.session_key(state)).await {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InternalError,
+                            }),
+                            Some(err),
+                        ));
+                    }
+                }
+                state.session_id = h.session_id.clone();
+            }
+            state.master_secret = vec![];
+            state.peer_certificates = vec![];
         }
 
         if let Some(message) = msgs.get(&HandshakeType::Certificate) {
@@ -319,7 +410,7 @@ impl Flight for Flight3 {
                 }
             };
 
-            if let Err((alert, err)) = handle_server_key_exchange(state, cfg, h) {
+            if let Err((alert, err)) = handle_server_key_exchange(state, cfg, h).await {
                 return Err((alert, err));
             }
         }
@@ -389,6 +480,12 @@ impl Flight for Flight3 {
             }));
         }
 
+        if let Some(cid) = &*state.local_connection_id.lock().await {
+            extensions.push(Extension::ConnectionId(ExtensionConnectionId {
+                cid: cid.clone(),
+            }));
+        }
+
         Ok(vec![Packet {
             record: RecordLayer::new(
                 PROTOCOL_VERSION1_2,
@@ -397,6 +494,7 @@ impl Flight for Flight3 {
                     HandshakeMessageClientHello {
                         version: PROTOCOL_VERSION1_2,
                         random: state.local_random.clone(),
+                        session_id: state.session_id.clone(),
                         cookie: state.cookie.clone(),
 
                         cipher_suites: cfg.local_cipher_suites.clone(),
@@ -411,7 +509,7 @@ impl Flight for Flight3 {
     }
 }
 
-pub(crate) fn handle_server_key_exchange(
+pub(crate) async fn handle_server_key_exchange(
     state: &mut State,
     cfg: &HandshakeConfig,
     h: &HandshakeMessageServerKeyExchange,
@@ -433,7 +531,7 @@ pub(crate) fn handle_server_key_exchange(
         state.identity_hint = h.identity_hint.clone();
         state.pre_master_secret = prf_psk_pre_master_secret(&psk);
     } else {
-        let local_keypair = match h.named_curve.generate_keypair() {
+        let local_keypair = match state.crypto_provider.generate_keypair(h.named_curve).await {
             Ok(local_keypair) => local_keypair,
             Err(err) => {
                 return Err((
@@ -446,25 +544,150 @@ pub(crate) fn handle_server_key_exchange(
             }
         };
 
-        state.pre_master_secret = match prf_pre_master_secret(
-            &h.public_key,
-            &local_keypair.private_key,
-            local_keypair.curve,
-        ) {
-            Ok(pre_master_secret) => pre_master_secret,
-            Err(err) => {
+        state.pre_master_secret =
+            match prf_pre_master_secret(&*state.crypto_provider, &h.public_key, &local_keypair)
+                .await
+            {
+                Ok(pre_master_secret) => pre_master_secret,
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ))
+                }
+            };
+
+        state.local_keypair = Some(local_keypair);
+    }
+
+    Ok(())
+}
+
+// handle_resumption finishes the client side of an abbreviated handshake: the server has
+// already sent its Finished, computed from the resumed session's master secret.
+async fn handle_resumption(
+    tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+    state: &mut State,
+    cache: &HandshakeCache,
+    cfg: &HandshakeConfig,
+    seq: isize,
+) -> Result<Box<dyn Flight>, (Option<Alert>, Option<Error>)> {
+    if let Err(err) = state.init_cipher_suite().await {
+        return Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(err),
+        ));
+    }
+    if let Err(err) = state.log_master_secret(&cfg.key_log) {
+        return Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(err),
+        ));
+    }
+
+    // Now, encrypted packets can be handled
+    let (done_tx, mut done_rx) = mpsc::channel(1);
+    if let Err(err) = tx.send(done_tx).await {
+        return Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(Error::Other(err.to_string())),
+        ));
+    }
+
+    done_rx.recv().await;
+
+    let (seq, msgs) = match cache
+        .full_pull_map(
+            seq,
+            &[HandshakeCachePullRule {
+                typ: HandshakeType::Finished,
+                epoch: cfg.initial_epoch + 1,
+                is_client: false,
+                optional: false,
+            }],
+        )
+        .await
+    {
+        Ok((seq, msgs)) => (seq, msgs),
+        // No valid message received. Keep reading
+        Err(_) => return Err((None, None)),
+    };
+
+    let finished = if let Some(HandshakeMessage::Finished(h)) = msgs.get(&HandshakeType::Finished) {
+        h
+    } else {
+        return Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            None,
+        ));
+    };
+
+    let plain_text = cache
+        .pull_and_merge(&[
+            HandshakeCachePullRule {
+                typ: HandshakeType::ClientHello,
+                epoch: cfg.initial_epoch,
+                is_client: true,
+                optional: false,
+            },
+            HandshakeCachePullRule {
+                typ: HandshakeType::ServerHello,
+                epoch: cfg.initial_epoch,
+                is_client: false,
+                optional: false,
+            },
+        ])
+        .await;
+
+    {
+        let cipher_suite = state.cipher_suite.lock().await;
+        if let Some(cipher_suite) = &*cipher_suite {
+            let expected_verify_data = match prf_verify_data_server(
+                &*state.crypto_provider,
+                &state.master_secret,
+                &plain_text,
+                cipher_suite.hash_func(),
+            ) {
+                Ok(d) => d,
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InsufficientSecurity,
+                        }),
+                        Some(err),
+                    ))
+                }
+            };
+
+            if expected_verify_data != finished.verify_data {
                 return Err((
                     Some(Alert {
                         alert_level: AlertLevel::Fatal,
-                        alert_description: AlertDescription::InternalError,
+                        alert_description: AlertDescription::HandshakeFailure,
                     }),
-                    Some(err),
-                ))
+                    Some(Error::ErrVerifyDataMismatch),
+                ));
             }
-        };
-
-        state.local_keypair = Some(local_keypair);
+        }
     }
 
-    Ok(())
+    state.handshake_recv_sequence = seq;
+
+    Ok(Box::new(Flight5b {}))
 }
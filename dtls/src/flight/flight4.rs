@@ -9,6 +9,7 @@ use crate::crypto::*;
 use crate::curve::named_curve::*;
 use crate::curve::*;
 use crate::error::Error;
+use crate::extension::extension_connection_id::*;
 use crate::extension::extension_supported_elliptic_curves::*;
 use crate::extension::extension_supported_point_formats::*;
 use crate::extension::extension_use_extended_master_secret::*;
@@ -23,11 +24,13 @@ use crate::handshake::*;
 use crate::prf::*;
 use crate::record_layer::record_layer_header::*;
 use crate::record_layer::*;
+use crate::session::Session;
 use crate::signature_hash_algorithm::*;
 
 use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
 use async_trait::async_trait;
 use log::*;
+use rand::Rng;
 use std::fmt;
 use std::io::BufWriter;
 
@@ -210,11 +213,14 @@ impl Flight for Flight4 {
             }
 
             if let Err(err) = verify_certificate_verify(
+                &*state.crypto_provider,
                 &plain_text,
                 &h.algorithm,
                 &h.signature,
                 &state.peer_certificates,
-            ) {
+            )
+            .await
+            {
                 return Err((
                     Some(Alert {
                         alert_level: AlertLevel::Fatal,
@@ -228,19 +234,27 @@ impl Flight for Flight4 {
             let mut verified = false;
             if cfg.client_auth as u8 >= ClientAuthType::VerifyClientCertIfGiven as u8 {
                 if let Some(client_cert_verifier) = &cfg.client_cert_verifier {
-                    chains =
-                        match verify_client_cert(&state.peer_certificates, client_cert_verifier) {
-                            Ok(chains) => chains,
-                            Err(err) => {
-                                return Err((
-                                    Some(Alert {
-                                        alert_level: AlertLevel::Fatal,
-                                        alert_description: AlertDescription::BadCertificate,
-                                    }),
-                                    Some(err),
-                                ))
-                            }
-                        };
+                    let server_name = if state.server_name.is_empty() {
+                        None
+                    } else {
+                        Some(state.server_name.as_str())
+                    };
+                    chains = match verify_client_cert(
+                        &state.peer_certificates,
+                        client_cert_verifier,
+                        server_name,
+                    ) {
+                        Ok(chains) => chains,
+                        Err(err) => {
+                            return Err((
+                                Some(Alert {
+                                    alert_level: AlertLevel::Fatal,
+                                    alert_description: AlertDescription::BadCertificate,
+                                }),
+                                Some(err),
+                            ))
+                        }
+                    };
                 } else {
                     return Err((
                         Some(Alert {
@@ -305,10 +319,12 @@ impl Flight for Flight4 {
                         pre_master_secret = prf_psk_pre_master_secret(&psk);
                     } else if let Some(local_keypair) = &state.local_keypair {
                         pre_master_secret = match prf_pre_master_secret(
+                            &*state.crypto_provider,
                             &client_key_exchange.public_key,
-                            &local_keypair.private_key,
-                            local_keypair.curve,
-                        ) {
+                            local_keypair,
+                        )
+                        .await
+                        {
                             Ok(pre_master_secret) => pre_master_secret,
                             Err(err) => {
                                 return Err((
@@ -339,6 +355,7 @@ impl Flight for Flight4 {
                             };
 
                         state.master_secret = match prf_extended_master_secret(
+                            &*state.crypto_provider,
                             &pre_master_secret,
                             &session_hash,
                             cipher_suite.hash_func(),
@@ -356,6 +373,7 @@ impl Flight for Flight4 {
                         };
                     } else {
                         state.master_secret = match prf_master_secret(
+                            &*state.crypto_provider,
                             &pre_master_secret,
                             &client_random,
                             &server_random,
@@ -375,6 +393,7 @@ impl Flight for Flight4 {
                     }
 
                     if let Err(err) = cipher_suite.init(
+                        &state.crypto_provider,
                         &state.master_secret,
                         &client_random,
                         &server_random,
@@ -388,6 +407,16 @@ impl Flight for Flight4 {
                             Some(err),
                         ));
                     }
+
+                    if let Err(err) = state.log_master_secret(&cfg.key_log) {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InternalError,
+                            }),
+                            Some(err),
+                        ));
+                    }
                 }
             }
         }
@@ -485,8 +514,26 @@ impl Flight for Flight4 {
                     ));
                 }
             }
-            ClientAuthType::NoClientCert | ClientAuthType::RequestClientCert => {
-                return Ok(Box::new(Flight6 {}) as Box<dyn Flight>);
+            ClientAuthType::NoClientCert | ClientAuthType::RequestClientCert => {}
+        }
+
+        if let Some(session_store) = &cfg.session_store {
+            let session = Session {
+                id: state.session_id.clone(),
+                secret: state.master_secret.clone(),
+                cipher_suite: state.cipher_suite_id().await,
+                extended_master_secret: state.extended_master_secret,
+                peer_certificates: state.peer_certificates.clone(),
+            };
+            trace!("[handshake] save new session: {:?}", session.id);
+            if let Err(err) = session_store.set(&cfg.session_key(state), session).await {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    Some(err),
+                ));
             }
         }
 
@@ -499,6 +546,11 @@ impl Flight for Flight4 {
         _cache: &HandshakeCache,
         cfg: &HandshakeConfig,
     ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        if cfg.session_store.is_some() && state.session_id.is_empty() {
+            state.session_id = vec![0; SESSION_ID_MAX_LENGTH];
+            rand::thread_rng().fill(state.session_id.as_mut_slice());
+        }
+
         let mut extensions = vec![Extension::RenegotiationInfo(ExtensionRenegotiationInfo {
             renegotiated_connection: 0,
         })];
@@ -517,6 +569,12 @@ impl Flight for Flight4 {
             }));
         }
 
+        if let Some(cid) = &*state.local_connection_id.lock().await {
+            extensions.push(Extension::ConnectionId(ExtensionConnectionId {
+                cid: cid.clone(),
+            }));
+        }
+
         if cfg.local_psk_callback.is_none() {
             extensions.extend_from_slice(&[
                 Extension::SupportedEllipticCurves(ExtensionSupportedEllipticCurves {
@@ -536,6 +594,7 @@ impl Flight for Flight4 {
                     HandshakeMessageServerHello {
                         version: PROTOCOL_VERSION1_2,
                         random: state.local_random.clone(),
+                        session_id: state.session_id.clone(),
                         cipher_suite: {
                             let cipher_suite = state.cipher_suite.lock().await;
                             if let Some(cipher_suite) = &*cipher_suite {
@@ -573,11 +632,7 @@ impl Flight for Flight4 {
                     0,
                     Content::Handshake(Handshake::new(HandshakeMessage::Certificate(
                         HandshakeMessageCertificate {
-                            certificate: certificate
-                                .certificate
-                                .iter()
-                                .map(|x| x.0.clone())
-                                .collect(),
+                            certificate: certificate.certificate.clone(),
                         },
                     ))),
                 ),
@@ -615,12 +670,16 @@ impl Flight for Flight4 {
 
             if let Some(local_keypair) = &state.local_keypair {
                 let signature = match generate_key_signature(
+                    &*state.crypto_provider,
                     &client_random,
                     &server_random,
                     &local_keypair.public_key,
                     state.named_curve,
-                    &certificate.private_key, /*, signature_hash_algo.hash*/
-                ) {
+                    &certificate.private_key,
+                    signature_hash_algo.hash,
+                )
+                .await
+                {
                     Ok(s) => s,
                     Err(err) => {
                         return Err((
@@ -725,6 +784,7 @@ impl Flight for Flight4 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::provider::CryptoProvider;
     use crate::error::Result;
     use std::sync::Arc;
     use tokio::sync::Mutex;
@@ -754,6 +814,7 @@ mod tests {
         // Generate the internal encryption state
         fn init(
             &mut self,
+            _provider: &Arc<dyn CryptoProvider>,
             _master_secret: &[u8],
             _client_random: &[u8],
             _server_random: &[u8],
@@ -765,7 +826,7 @@ mod tests {
         fn encrypt(&self, _pkt_rlh: &RecordLayerHeader, _raw: &[u8]) -> Result<Vec<u8>> {
             unimplemented!();
         }
-        fn decrypt(&self, _input: &[u8]) -> Result<Vec<u8>> {
+        fn decrypt(&self, _h: &RecordLayerHeader, _input: &[u8]) -> Result<Vec<u8>> {
             unimplemented!();
         }
     }
@@ -773,7 +834,7 @@ mod tests {
     // Assert that if a client sends a certificate they must also send a `CertificateVerify`
     // message. The `Flight4` must not interact with the `cipher_suite` if the `CertificateVerify`
     // is missing.
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_flight4_process_certificateverify() {
         let mut state = State::default();
         state.cipher_suite = Arc::new(Mutex::new(Some(Box::new(MockCipherSuite {}))));
@@ -0,0 +1,290 @@
+use super::*;
+use crate::change_cipher_spec::*;
+use crate::cipher_suite::*;
+use crate::compression_methods::*;
+use crate::config::*;
+use crate::content::*;
+use crate::error::Error;
+use crate::extension::extension_connection_id::*;
+use crate::extension::extension_use_extended_master_secret::*;
+use crate::extension::extension_use_srtp::*;
+use crate::extension::*;
+use crate::handshake::handshake_message_finished::*;
+use crate::handshake::handshake_message_server_hello::*;
+use crate::handshake::*;
+use crate::prf::*;
+use crate::record_layer::record_layer_header::*;
+
+use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
+use async_trait::async_trait;
+use std::fmt;
+use std::io::BufWriter;
+
+// Flight4b is the server's flight of an abbreviated handshake, which resumes a session
+// instead of negotiating a new one.
+// https://tools.ietf.org/html/rfc5246#section-7.3
+//
+//  Client                                          Server
+//  ------                                          ------
+//  ClientHello               -------->                           Flight 1/3
+//
+//                                             ServerHello    \
+//                                      [ChangeCipherSpec]     Flight 4b
+//                            <--------           Finished    /
+//
+//  [ChangeCipherSpec]                                        \ Flight 5b
+//  Finished                  -------->                       /
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight4b;
+
+impl fmt::Display for Flight4b {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 4b")
+    }
+}
+
+#[async_trait(?Send)]
+impl Flight for Flight4b {
+    fn is_last_recv_flight(&self) -> bool {
+        true
+    }
+
+    async fn parse(
+        &self,
+        tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight>, (Option<Alert>, Option<Error>)> {
+        // Now, encrypted packets can be handled
+        let (done_tx, mut done_rx) = mpsc::channel(1);
+        if let Err(err) = tx.send(done_tx).await {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(Error::Other(err.to_string())),
+            ));
+        }
+
+        done_rx.recv().await;
+
+        let (_, msgs) = match cache
+            .full_pull_map(
+                state.handshake_recv_sequence,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::Finished,
+                    epoch: cfg.initial_epoch + 1,
+                    is_client: true,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            // No valid message received. Keep reading
+            Err(_) => return Err((None, None)),
+        };
+
+        let finished =
+            if let Some(HandshakeMessage::Finished(h)) = msgs.get(&HandshakeType::Finished) {
+                h
+            } else {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ));
+            };
+
+        let plain_text = cache
+            .pull_and_merge(&[
+                HandshakeCachePullRule {
+                    typ: HandshakeType::ClientHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: true,
+                    optional: false,
+                },
+                HandshakeCachePullRule {
+                    typ: HandshakeType::ServerHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: false,
+                    optional: false,
+                },
+                HandshakeCachePullRule {
+                    typ: HandshakeType::Finished,
+                    epoch: cfg.initial_epoch + 1,
+                    is_client: false,
+                    optional: false,
+                },
+            ])
+            .await;
+
+        {
+            let cipher_suite = state.cipher_suite.lock().await;
+            if let Some(cipher_suite) = &*cipher_suite {
+                let expected_verify_data = match prf_verify_data_client(
+                    &*state.crypto_provider,
+                    &state.master_secret,
+                    &plain_text,
+                    cipher_suite.hash_func(),
+                ) {
+                    Ok(d) => d,
+                    Err(err) => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InsufficientSecurity,
+                            }),
+                            Some(err),
+                        ))
+                    }
+                };
+
+                if expected_verify_data != finished.verify_data {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::HandshakeFailure,
+                        }),
+                        Some(Error::ErrVerifyDataMismatch),
+                    ));
+                }
+            }
+        }
+
+        // Other party may retransmit the last flight. Keep state to be flight4b.
+        Ok(Box::new(Flight4b {}))
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        let mut extensions = vec![Extension::RenegotiationInfo(ExtensionRenegotiationInfo {
+            renegotiated_connection: 0,
+        })];
+        if (cfg.extended_master_secret == ExtendedMasterSecretType::Request
+            || cfg.extended_master_secret == ExtendedMasterSecretType::Require)
+            && state.extended_master_secret
+        {
+            extensions.push(Extension::UseExtendedMasterSecret(
+                ExtensionUseExtendedMasterSecret { supported: true },
+            ));
+        }
+
+        if state.srtp_protection_profile != SrtpProtectionProfile::Unsupported {
+            extensions.push(Extension::UseSrtp(ExtensionUseSrtp {
+                protection_profiles: vec![state.srtp_protection_profile],
+            }));
+        }
+
+        if let Some(cid) = &*state.local_connection_id.lock().await {
+            extensions.push(Extension::ConnectionId(ExtensionConnectionId {
+                cid: cid.clone(),
+            }));
+        }
+
+        let mut server_hello =
+            Handshake::new(HandshakeMessage::ServerHello(HandshakeMessageServerHello {
+                version: PROTOCOL_VERSION1_2,
+                random: state.local_random.clone(),
+                session_id: state.session_id.clone(),
+                cipher_suite: {
+                    let cipher_suite = state.cipher_suite.lock().await;
+                    if let Some(cipher_suite) = &*cipher_suite {
+                        cipher_suite.id()
+                    } else {
+                        CipherSuiteId::Unsupported
+                    }
+                },
+                compression_method: default_compression_methods().ids[0],
+                extensions,
+            }));
+
+        if state.local_verify_data.is_empty() {
+            // The ServerHello isn't in the cache until it has been sent, so marshal it with
+            // the sequence number it is about to get.
+            server_hello.handshake_header.message_sequence = state.handshake_send_sequence as u16;
+            let mut raw = vec![];
+            {
+                let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+                if let Err(err) = server_hello.marshal(&mut writer) {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ));
+                }
+            }
+
+            let mut plain_text = cache
+                .pull_and_merge(&[HandshakeCachePullRule {
+                    typ: HandshakeType::ClientHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: true,
+                    optional: false,
+                }])
+                .await;
+            plain_text.extend_from_slice(&raw);
+
+            let cipher_suite = state.cipher_suite.lock().await;
+            if let Some(cipher_suite) = &*cipher_suite {
+                state.local_verify_data = match prf_verify_data_server(
+                    &*state.crypto_provider,
+                    &state.master_secret,
+                    &plain_text,
+                    cipher_suite.hash_func(),
+                ) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InternalError,
+                            }),
+                            Some(err),
+                        ))
+                    }
+                };
+            }
+        }
+
+        Ok(vec![
+            Packet {
+                record: RecordLayer::new(PROTOCOL_VERSION1_2, 0, Content::Handshake(server_hello)),
+                should_encrypt: false,
+                reset_local_sequence_number: false,
+            },
+            Packet {
+                record: RecordLayer::new(
+                    PROTOCOL_VERSION1_2,
+                    0,
+                    Content::ChangeCipherSpec(ChangeCipherSpec {}),
+                ),
+                should_encrypt: false,
+                reset_local_sequence_number: false,
+            },
+            Packet {
+                record: RecordLayer::new(
+                    PROTOCOL_VERSION1_2,
+                    1,
+                    Content::Handshake(Handshake::new(HandshakeMessage::Finished(
+                        HandshakeMessageFinished {
+                            verify_data: state.local_verify_data.clone(),
+                        },
+                    ))),
+                ),
+                should_encrypt: true,
+                reset_local_sequence_number: true,
+            },
+        ])
+    }
+}
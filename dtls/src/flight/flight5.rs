@@ -15,6 +15,7 @@ use crate::handshake::*;
 use crate::prf::*;
 use crate::record_layer::record_layer_header::*;
 use crate::record_layer::*;
+use crate::session::Session;
 use crate::signature_hash_algorithm::*;
 
 use async_trait::async_trait;
@@ -141,6 +142,7 @@ impl Flight for Flight5 {
             let cipher_suite = state.cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
                 let expected_verify_data = match prf_verify_data_server(
+                    &*state.crypto_provider,
                     &state.master_secret,
                     &plain_text,
                     cipher_suite.hash_func(),
@@ -169,6 +171,28 @@ impl Flight for Flight5 {
             }
         }
 
+        if let Some(session_store) = &cfg.session_store {
+            if !state.session_id.is_empty() {
+                let session = Session {
+                    id: state.session_id.clone(),
+                    secret: state.master_secret.clone(),
+                    cipher_suite: state.cipher_suite_id().await,
+                    extended_master_secret: state.extended_master_secret,
+                    peer_certificates: state.peer_certificates.clone(),
+                };
+                log::trace!("[handshake] save new session: {:?}", session.id);
+                if let Err(err) = session_store.set(&cfg.session_key(state), session).await {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ));
+                }
+            }
+        }
+
         Ok(Box::new(Flight5 {}))
     }
 
@@ -206,7 +230,7 @@ impl Flight for Flight5 {
                     Content::Handshake(Handshake::new(HandshakeMessage::Certificate(
                         HandshakeMessageCertificate {
                             certificate: if let Some(cert) = &certificate {
-                                cert.certificate.iter().map(|x| x.0.clone()).collect()
+                                cert.certificate.clone()
                             } else {
                                 vec![]
                             },
@@ -265,7 +289,8 @@ impl Flight for Flight5 {
 
         // handshakeMessageServerKeyExchange is optional for PSK
         if server_key_exchange_data.is_empty() {
-            if let Err((alert, err)) = handle_server_key_exchange(state, cfg, &server_key_exchange)
+            if let Err((alert, err)) =
+                handle_server_key_exchange(state, cfg, &server_key_exchange).await
             {
                 return Err((alert, err));
             }
@@ -417,9 +442,13 @@ impl Flight for Flight5 {
             };
 
             let cert_verify = match generate_certificate_verify(
+                &*state.crypto_provider,
                 &plain_text,
-                &certificate.as_ref().unwrap().private_key, /*, signature_hash_algo.hash*/
-            ) {
+                &certificate.as_ref().unwrap().private_key,
+                signature_hash_algo.hash,
+            )
+            .await
+            {
                 Ok(cert) => cert,
                 Err(err) => {
                     return Err((
@@ -563,6 +592,7 @@ impl Flight for Flight5 {
             let cipher_suite = state.cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
                 state.local_verify_data = match prf_verify_data_client(
+                    &*state.crypto_provider,
                     &state.master_secret,
                     &plain_text,
                     cipher_suite.hash_func(),
@@ -647,6 +677,7 @@ async fn initalize_cipher_suite(
             };
 
             state.master_secret = match prf_extended_master_secret(
+                &*state.crypto_provider,
                 &state.pre_master_secret,
                 &session_hash,
                 cipher_suite.hash_func(),
@@ -664,6 +695,7 @@ async fn initalize_cipher_suite(
             };
         } else {
             state.master_secret = match prf_master_secret(
+                &*state.crypto_provider,
                 &state.pre_master_secret,
                 &client_random,
                 &server_random,
@@ -705,11 +737,14 @@ async fn initalize_cipher_suite(
         let expected_msg =
             value_key_message(&client_random, &server_random, &h.public_key, h.named_curve);
         if let Err(err) = verify_key_signature(
+            &*state.crypto_provider,
             &expected_msg,
             &h.algorithm,
             &h.signature,
             &state.peer_certificates,
-        ) {
+        )
+        .await
+        {
             return Err((
                 Some(Alert {
                     alert_level: AlertLevel::Fatal,
@@ -724,7 +759,6 @@ async fn initalize_cipher_suite(
             chains = match verify_server_cert(
                 &state.peer_certificates,
                 &cfg.server_cert_verifier,
-                &cfg.roots_cas,
                 &cfg.server_name,
             ) {
                 Ok(chains) => chains,
@@ -753,9 +787,23 @@ async fn initalize_cipher_suite(
     }
 
     if let Some(cipher_suite) = &mut *cipher_suite {
-        if let Err(err) =
-            cipher_suite.init(&state.master_secret, &client_random, &server_random, true)
-        {
+        if let Err(err) = cipher_suite.init(
+            &state.crypto_provider,
+            &state.master_secret,
+            &client_random,
+            &server_random,
+            true,
+        ) {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(err),
+            ));
+        }
+
+        if let Err(err) = state.log_master_secret(&cfg.key_log) {
             return Err((
                 Some(Alert {
                     alert_level: AlertLevel::Fatal,
@@ -152,6 +152,7 @@ impl Flight for Flight6 {
             let cipher_suite = state.cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
                 state.local_verify_data = match prf_verify_data_server(
+                    &*state.crypto_provider,
                     &state.master_secret,
                     &plain_text,
                     cipher_suite.hash_func(),
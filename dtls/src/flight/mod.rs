@@ -3,7 +3,9 @@ pub(crate) mod flight1;
 pub(crate) mod flight2;
 pub(crate) mod flight3;
 pub(crate) mod flight4;
+pub(crate) mod flight4b;
 pub(crate) mod flight5;
+pub(crate) mod flight5b;
 pub(crate) mod flight6;
 
 use crate::alert::*;
@@ -48,6 +50,8 @@ use tokio::sync::mpsc;
                                       [ChangeCipherSpec]    \ Flight 6
                           <--------             Finished    /
 
+  When the server resumes the session offered in ClientHello, Flight 4b and
+  Flight 5b replace Flights 4 to 6. See flight4b.rs.
 */
 
 #[derive(Clone, Debug)]
@@ -9,7 +9,7 @@ use std::io::BufReader;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha384};
 
 #[derive(Clone, Debug)]
 pub(crate) struct HandshakeCacheItem {
@@ -230,12 +230,9 @@ impl HandshakeCache {
 
         merged.extend_from_slice(additional);
 
-        let mut hasher = match hf {
-            CipherSuiteHash::Sha256 => Sha256::new(),
-        };
-        hasher.update(&merged);
-        let result = hasher.finalize();
-
-        Ok(result.as_slice().to_vec())
+        Ok(match hf {
+            CipherSuiteHash::Sha256 => Sha256::digest(&merged).to_vec(),
+            CipherSuiteHash::Sha384 => Sha384::digest(&merged).to_vec(),
+        })
     }
 }
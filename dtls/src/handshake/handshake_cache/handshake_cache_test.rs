@@ -1,6 +1,6 @@
 use super::*;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handshake_cache_single_push() -> Result<()> {
     let tests = vec![
         (
@@ -336,7 +336,7 @@ async fn test_handshake_cache_single_push() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handshake_cache_session_hash() -> Result<()> {
     let tests = vec![
         (
@@ -35,6 +35,7 @@ fn test_handshake_message_client_hello() -> Result<()> {
                 0x15, 0x8d, 0x95, 0x71, 0x8a, 0xbb, 0x22, 0xd7, 0x47, 0xec, 0xd8, 0x3d, 0xdc, 0x4b,
             ],
         },
+        session_id: vec![],
         cookie: vec![
             0xe6, 0x14, 0x3a, 0x1b, 0x04, 0xea, 0x9e, 0x7a, 0x14, 0xd6, 0x6c, 0x57, 0xd0, 0x0e,
             0x32, 0x85, 0x76, 0x18, 0xde, 0xd8,
@@ -23,6 +23,7 @@ https://tools.ietf.org/html/rfc5246#section-7.4.1.3
 pub struct HandshakeMessageServerHello {
     pub(crate) version: ProtocolVersion,
     pub(crate) random: HandshakeRandom,
+    pub(crate) session_id: Vec<u8>,
 
     pub(crate) cipher_suite: CipherSuiteId,
     pub(crate) compression_method: CompressionMethodId,
@@ -33,6 +34,7 @@ impl PartialEq for HandshakeMessageServerHello {
     fn eq(&self, other: &Self) -> bool {
         self.version == other.version
             && self.random == other.random
+            && self.session_id == other.session_id
             && self.compression_method == other.compression_method
             && self.extensions == other.extensions
             && self.cipher_suite == other.cipher_suite
@@ -43,6 +45,7 @@ impl fmt::Debug for HandshakeMessageServerHello {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = vec![
             format!("version: {:?} random: {:?}", self.version, self.random),
+            format!("session_id: {:?}", self.session_id),
             format!("cipher_suites: {:?}", self.cipher_suite),
             format!("compression_method: {:?}", self.compression_method),
             format!("extensions: {:?}", self.extensions),
@@ -59,8 +62,7 @@ impl HandshakeMessageServerHello {
     pub fn size(&self) -> usize {
         let mut len = 2 + self.random.size();
 
-        // SessionID
-        len += 1;
+        len += 1 + self.session_id.len();
 
         len += 2;
 
@@ -75,12 +77,16 @@ impl HandshakeMessageServerHello {
     }
 
     pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        if self.session_id.len() > SESSION_ID_MAX_LENGTH {
+            return Err(Error::ErrSessionIdTooLong);
+        }
+
         writer.write_u8(self.version.major)?;
         writer.write_u8(self.version.minor)?;
         self.random.marshal(writer)?;
 
-        // SessionID
-        writer.write_u8(0x00)?;
+        writer.write_u8(self.session_id.len() as u8)?;
+        writer.write_all(&self.session_id)?;
 
         writer.write_u16::<BigEndian>(self.cipher_suite as u16)?;
 
@@ -105,10 +111,12 @@ impl HandshakeMessageServerHello {
         let minor = reader.read_u8()?;
         let random = HandshakeRandom::unmarshal(reader)?;
 
-        // Session ID
         let session_id_len = reader.read_u8()? as usize;
-        let mut session_id_buffer = vec![0u8; session_id_len];
-        reader.read_exact(&mut session_id_buffer)?;
+        if session_id_len > SESSION_ID_MAX_LENGTH {
+            return Err(Error::ErrSessionIdTooLong);
+        }
+        let mut session_id = vec![0u8; session_id_len];
+        reader.read_exact(&mut session_id)?;
 
         let cipher_suite: CipherSuiteId = reader.read_u16::<BigEndian>()?.into();
 
@@ -141,6 +149,7 @@ impl HandshakeMessageServerHello {
         Ok(HandshakeMessageServerHello {
             version: ProtocolVersion { major, minor },
             random,
+            session_id,
 
             cipher_suite,
             compression_method,
@@ -31,6 +31,7 @@ fn test_handshake_message_server_hello() -> Result<()> {
                 0x7f, 0x7c, 0x78, 0xf1, 0x5f, 0x7e, 0x1c, 0xb7, 0xa1, 0x1e, 0xcf, 0x63, 0x84, 0x28,
             ],
         },
+        session_id: vec![],
         cipher_suite: CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
         compression_method: CompressionMethodId::Null,
         extensions: vec![],
@@ -57,3 +58,37 @@ fn test_handshake_message_server_hello() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_handshake_message_server_hello_session_id() -> Result<()> {
+    let server_hello = HandshakeMessageServerHello {
+        version: PROTOCOL_VERSION1_2,
+        random: HandshakeRandom::default(),
+        session_id: (0..32).collect(),
+        cipher_suite: CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
+        compression_method: CompressionMethodId::Null,
+        extensions: vec![],
+    };
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        server_hello.marshal(&mut writer)?;
+    }
+    assert_eq!(raw.len(), server_hello.size());
+
+    let mut reader = BufReader::new(raw.as_slice());
+    let parsed = HandshakeMessageServerHello::unmarshal(&mut reader)?;
+    assert_eq!(parsed, server_hello);
+
+    // Session ids are at most 32 bytes long.
+    let too_long = HandshakeMessageServerHello {
+        session_id: vec![0; 33],
+        ..server_hello
+    };
+    let mut raw = vec![];
+    let result = too_long.marshal(&mut raw);
+    assert!(matches!(result, Err(Error::ErrSessionIdTooLong)));
+
+    Ok(())
+}
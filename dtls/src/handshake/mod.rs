@@ -33,6 +33,10 @@ use handshake_message_server_hello::*;
 use handshake_message_server_hello_done::*;
 use handshake_message_server_key_exchange::*;
 
+// SessionID is opaque<0..32>
+// https://tools.ietf.org/html/rfc5246#section-7.4.1.2
+pub(crate) const SESSION_ID_MAX_LENGTH: usize = 32;
+
 // https://tools.ietf.org/html/rfc5246#section-7.4
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum HandshakeType {
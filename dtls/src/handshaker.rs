@@ -5,11 +5,15 @@ use crate::content::*;
 use crate::crypto::*;
 use crate::error::*;
 use crate::extension::extension_use_srtp::*;
+use crate::session::SessionStore;
 use crate::signature_hash_algorithm::*;
+use crate::state::State;
+use crate::verifier::*;
 
 use log::*;
 use std::collections::HashMap;
 use std::fmt;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 //use std::io::BufWriter;
@@ -71,7 +75,7 @@ impl fmt::Display for HandshakeState {
 }
 
 pub(crate) type VerifyPeerCertificateFn =
-    Arc<dyn (Fn(&[Vec<u8>], &[rustls::Certificate]) -> Result<()>)>;
+    Arc<dyn (Fn(&[Vec<u8>], &[Vec<u8>]) -> Result<()>)>;
 
 pub(crate) struct HandshakeConfig {
     pub(crate) local_psk_callback: Option<PskCallback>,
@@ -86,11 +90,14 @@ pub(crate) struct HandshakeConfig {
     pub(crate) name_to_certificate: HashMap<String, Certificate>,
     pub(crate) insecure_skip_verify: bool,
     pub(crate) verify_peer_certificate: Option<VerifyPeerCertificateFn>,
-    pub(crate) roots_cas: rustls::RootCertStore,
-    pub(crate) server_cert_verifier: Arc<dyn rustls::ServerCertVerifier>,
-    pub(crate) client_cert_verifier: Option<Arc<dyn rustls::ClientCertVerifier>>,
+    pub(crate) server_cert_verifier: Arc<dyn ServerCertVerifier>,
+    pub(crate) client_cert_verifier: Option<Arc<dyn ClientCertVerifier>>,
     pub(crate) retransmit_interval: std::time::Duration,
     pub(crate) initial_epoch: u16,
+    pub(crate) session_store: Option<Arc<dyn SessionStore>>,
+    pub(crate) remote_addr: Option<SocketAddr>,
+    pub(crate) connection_id_generator: Option<ConnectionIdGenerator>,
+    pub(crate) key_log: Option<KeyLogCallback>,
     //log           logging.LeveledLogger
     //mu sync.Mutex
 }
@@ -110,16 +117,34 @@ impl Default for HandshakeConfig {
             name_to_certificate: HashMap::new(),
             insecure_skip_verify: false,
             verify_peer_certificate: None,
-            roots_cas: rustls::RootCertStore::empty(),
-            server_cert_verifier: Arc::new(rustls::WebPKIVerifier::new()),
+            server_cert_verifier: Arc::new(NoRootsVerifier),
             client_cert_verifier: None,
             retransmit_interval: std::time::Duration::from_secs(0),
             initial_epoch: 0,
+            session_store: None,
+            remote_addr: None,
+            connection_id_generator: None,
+            key_log: None,
         }
     }
 }
 
 impl HandshakeConfig {
+    // session_key is the key a session is stored under. Servers use the session id, while
+    // clients haven't been assigned one yet when they look it up and use the peer instead.
+    pub(crate) fn session_key(&self, state: &State) -> Vec<u8> {
+        if state.is_client {
+            // '_' can't appear in either a host name or an address, so keys can't collide
+            let remote_addr = self
+                .remote_addr
+                .map(|addr| addr.to_string())
+                .unwrap_or_default();
+            format!("{}_{}", self.server_name, remote_addr).into_bytes()
+        } else {
+            state.session_id.clone()
+        }
+    }
+
     pub(crate) fn get_certificate(&self, server_name: &str) -> Result<Certificate> {
         //TODO
         /*if self.name_to_certificate.is_empty() {
@@ -205,6 +230,11 @@ impl DTLSConn {
             );
 
             if state == HandshakeState::Finished && !self.is_handshake_completed_successfully() {
+                if self.current_flight.is_last_send_flight() {
+                    if let Some(pkts) = &self.flights {
+                        *self.last_flight.lock().await = pkts.clone();
+                    }
+                }
                 self.set_handshake_completed_successfully();
                 self.handshake_done_tx.take(); // drop it by take
                 return Ok(());
@@ -21,8 +21,12 @@ pub mod handshaker;
 pub mod listener;
 pub mod prf;
 pub mod record_layer;
+pub mod session;
 pub mod signature_hash_algorithm;
 pub mod state;
+#[cfg(any(test, feature = "stress"))]
+pub mod stress;
+pub mod verifier;
 
 pub use error::Error;
 
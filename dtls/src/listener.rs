@@ -1,9 +1,14 @@
+#[cfg(test)]
+mod listener_test;
+
 use crate::config::*;
 use crate::conn::DTLSConn;
-use crate::content::ContentType;
+use crate::content::{Content, ContentType};
 use crate::error::Result;
-use crate::record_layer::record_layer_header::RecordLayerHeader;
-use crate::record_layer::unpack_datagram;
+use crate::extension::Extension;
+use crate::handshake::HandshakeMessage;
+use crate::record_layer::record_layer_header::{RecordLayerHeader, RECORD_LAYER_HEADER_SIZE};
+use crate::record_layer::{unpack_datagram, RecordLayer};
 
 use async_trait::async_trait;
 use std::future::Future;
@@ -21,7 +26,7 @@ pub async fn listen<A: 'static + ToSocketAddrs>(laddr: A, config: Config) -> Res
     let mut lc = ListenConfig {
         accept_filter: Some(Box::new(
             |packet: &[u8]| -> Pin<Box<dyn Future<Output = bool> + 'static>> {
-                let pkts = match unpack_datagram(packet) {
+                let pkts = match unpack_datagram(packet, 0) {
                     Ok(pkts) => {
                         if pkts.is_empty() {
                             return Box::pin(async { false });
@@ -44,10 +49,57 @@ pub async fn listen<A: 'static + ToSocketAddrs>(laddr: A, config: Config) -> Res
         ..Default::default()
     };
 
+    // Records that carry a connection id are routed by it, so a peer whose address changed
+    // keeps its conn. The listener can only tell where the id ends if ids have a fixed length.
+    if let Some(generator) = &config.connection_id_generator {
+        let cid_len = generator().len();
+        if cid_len > 0 {
+            lc.datagram_router = Some(Box::new(move |packet: &[u8]| {
+                connection_id_of_record(packet, cid_len)
+            }));
+            lc.connection_identifier = Some(Arc::new(connection_id_of_server_hello));
+        }
+    }
+
     let parent = Arc::new(lc.listen(laddr).await?);
     Ok(DTLSListener { parent, config })
 }
 
+// connection_id_of_record returns the connection id a datagram starting with a tls12_cid
+// record carries.
+fn connection_id_of_record(packet: &[u8], cid_len: usize) -> Option<Vec<u8>> {
+    // The connection id sits between the sequence number and the length.
+    let start = RECORD_LAYER_HEADER_SIZE - 2;
+    if packet.first() != Some(&(ContentType::ConnectionId as u8))
+        || packet.len() < RECORD_LAYER_HEADER_SIZE + cid_len
+    {
+        return None;
+    }
+    Some(packet[start..start + cid_len].to_vec())
+}
+
+// connection_id_of_server_hello returns the connection id the ServerHello opening an
+// outgoing datagram asks the client to use.
+fn connection_id_of_server_hello(packet: &[u8]) -> Option<Vec<u8>> {
+    if packet.first() != Some(&(ContentType::Handshake as u8)) {
+        return None;
+    }
+
+    let r = RecordLayer::unmarshal(&mut BufReader::new(packet)).ok()?;
+    match r.content {
+        Content::Handshake(h) => match h.handshake_message {
+            HandshakeMessage::ServerHello(server_hello) => {
+                server_hello.extensions.into_iter().find_map(|e| match e {
+                    Extension::ConnectionId(ext) if !ext.cid.is_empty() => Some(ext.cid),
+                    _ => None,
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// DTLSListener represents a DTLS listener
 pub struct DTLSListener {
     parent: Arc<dyn Listener>,
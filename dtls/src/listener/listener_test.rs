@@ -0,0 +1,171 @@
+use super::*;
+use crate::crypto::Certificate;
+use crate::error::Error;
+
+use deno_net::DatagramConn as UdpSocket;
+use std::cell::RefCell;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+type UtilResult<T> = std::result::Result<T, util::Error>;
+
+// RebindingConn is a client socket whose address changes when rebind is called, like a
+// client behind a NAT that dropped its mapping.
+struct RebindingConn {
+    socket: RefCell<Arc<UdpSocket>>,
+    raddr: SocketAddr,
+    rebound: Notify,
+}
+
+impl RebindingConn {
+    async fn new(raddr: SocketAddr) -> Result<Self> {
+        Ok(RebindingConn {
+            socket: RefCell::new(RebindingConn::bind(raddr).await?),
+            raddr,
+            rebound: Notify::new(),
+        })
+    }
+
+    async fn bind(raddr: SocketAddr) -> Result<Arc<UdpSocket>> {
+        let socket = UdpSocket::bind("127.0.0.1:0").await?;
+        Conn::connect(&socket, raddr).await?;
+        Ok(Arc::new(socket))
+    }
+
+    async fn rebind(&self) -> Result<SocketAddr> {
+        let socket = RebindingConn::bind(self.raddr).await?;
+        let laddr = Conn::local_addr(socket.as_ref())?;
+        *self.socket.borrow_mut() = socket;
+        self.rebound.notify_waiters();
+        Ok(laddr)
+    }
+
+    fn socket(&self) -> Arc<UdpSocket> {
+        Arc::clone(&self.socket.borrow())
+    }
+}
+
+#[async_trait(?Send)]
+impl Conn for RebindingConn {
+    async fn connect(&self, _addr: SocketAddr) -> UtilResult<()> {
+        Ok(())
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> UtilResult<usize> {
+        loop {
+            let socket = self.socket();
+            tokio::select! {
+                result = Conn::recv(socket.as_ref(), buf) => return result,
+                _ = self.rebound.notified() => {}
+            }
+        }
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> UtilResult<(usize, SocketAddr)> {
+        let n = self.recv(buf).await?;
+        Ok((n, self.raddr))
+    }
+
+    async fn send(&self, buf: &[u8]) -> UtilResult<usize> {
+        Conn::send(self.socket().as_ref(), buf).await
+    }
+
+    async fn send_to(&self, buf: &[u8], _target: SocketAddr) -> UtilResult<usize> {
+        self.send(buf).await
+    }
+
+    fn local_addr(&self) -> UtilResult<SocketAddr> {
+        Conn::local_addr(self.socket().as_ref())
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(self.raddr)
+    }
+
+    async fn close(&self) -> UtilResult<()> {
+        Conn::close(self.socket().as_ref()).await
+    }
+}
+
+async fn recv(conn: &dyn Conn, buf: &mut [u8]) -> Result<usize> {
+    deno_net::timeout(Duration::from_secs(5), conn.recv(buf))
+        .await
+        .map_err(|_| Error::ErrDeadlineExceeded)?
+        .map_err(|err| Error::Other(err.to_string()))
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_listener_follows_peer_by_connection_id() -> Result<()> {
+    let listener = Arc::new(
+        listen(
+            "127.0.0.1:0",
+            Config {
+                certificates: vec![Certificate::generate_self_signed(vec![
+                    "localhost".to_owned()
+                ])?],
+                connection_id_generator: Some(random_cid_generator(8)),
+                ..Default::default()
+            },
+        )
+        .await?,
+    );
+    let laddr = listener.addr().await?;
+
+    let (server_tx, mut server_rx) = mpsc::channel(1);
+    let listener2 = Arc::clone(&listener);
+    deno_net::spawn(async move {
+        let _ = server_tx.send(listener2.accept().await).await;
+    });
+
+    let client_conn = Arc::new(RebindingConn::new(laddr).await?);
+    let client = DTLSConn::new(
+        Arc::clone(&client_conn) as Arc<dyn Conn>,
+        Config {
+            insecure_skip_verify: true,
+            connection_id_generator: Some(only_send_cid_generator()),
+            ..Default::default()
+        },
+        true,
+        None,
+    )
+    .await?;
+    let (server, old_addr) = server_rx
+        .recv()
+        .await
+        .unwrap()
+        .map_err(|err| Error::Other(err.to_string()))?;
+    assert_eq!(old_addr, client_conn.local_addr()?);
+
+    let mut buf = vec![0u8; 64];
+    client.write(b"ping", None).await?;
+    let n = recv(server.as_ref(), &mut buf).await?;
+    assert_eq!(&buf[..n], b"ping");
+
+    // The client's address changes, its records still carry the server's connection id.
+    let new_addr = client_conn.rebind().await?;
+    assert_ne!(new_addr, old_addr);
+
+    client.write(b"moved", None).await?;
+    let n = recv(server.as_ref(), &mut buf).await?;
+    assert_eq!(&buf[..n], b"moved");
+    assert_eq!(server.remote_addr(), Some(new_addr));
+
+    server
+        .send(b"pong")
+        .await
+        .map_err(|err| Error::Other(err.to_string()))?;
+    let n = client.read(&mut buf, Some(Duration::from_secs(5))).await?;
+    assert_eq!(&buf[..n], b"pong");
+
+    client.close().await?;
+    server
+        .close()
+        .await
+        .map_err(|err| Error::Other(err.to_string()))?;
+    listener
+        .close()
+        .await
+        .map_err(|err| Error::Other(err.to_string()))?;
+
+    Ok(())
+}
@@ -1,22 +1,15 @@
 #[cfg(test)]
 mod prf_test;
 
-use std::convert::TryInto;
 use std::fmt;
 
-use hmac::{Hmac, Mac, NewMac};
-use sha1::Sha1;
-use sha2::Digest;
-use sha2::Sha256;
-
-type HmacSha256 = Hmac<Sha256>;
-type HmacSha1 = Hmac<Sha1>;
-
 use crate::cipher_suite::CipherSuiteHash;
 use crate::content::ContentType;
+use crate::crypto::provider::CryptoProvider;
 use crate::curve::named_curve::*;
 use crate::error::*;
 use crate::record_layer::record_layer_header::ProtocolVersion;
+use crate::signature_hash_algorithm::HashAlgorithm;
 
 pub(crate) const PRF_MASTER_SECRET_LABEL: &str = "master secret";
 pub(crate) const PRF_EXTENDED_MASTER_SECRET_LABEL: &str = "extended master secret";
@@ -69,52 +62,17 @@ pub(crate) fn prf_psk_pre_master_secret(psk: &[u8]) -> Vec<u8> {
     out
 }
 
-pub(crate) fn prf_pre_master_secret(
-    public_key: &[u8],
-    private_key: &NamedCurvePrivateKey,
-    curve: NamedCurve,
-) -> Result<Vec<u8>> {
-    match curve {
-        NamedCurve::P256 => elliptic_curve_pre_master_secret(public_key, private_key, curve),
-        NamedCurve::P384 => elliptic_curve_pre_master_secret(public_key, private_key, curve),
-        NamedCurve::X25519 => elliptic_curve_pre_master_secret(public_key, private_key, curve),
-        _ => Err(Error::ErrInvalidNamedCurve),
-    }
-}
-
-fn elliptic_curve_pre_master_secret(
+pub(crate) async fn prf_pre_master_secret(
+    provider: &dyn CryptoProvider,
     public_key: &[u8],
-    private_key: &NamedCurvePrivateKey,
-    curve: NamedCurve,
+    keypair: &NamedCurveKeypair,
 ) -> Result<Vec<u8>> {
-    match curve {
-        NamedCurve::P256 => {
-            let pub_key = p256::EncodedPoint::from_bytes(public_key)?;
-            let public = p256::PublicKey::from_sec1_bytes(pub_key.as_ref())?;
-            if let NamedCurvePrivateKey::EphemeralSecretP256(secret) = private_key {
-                return Ok(secret.diffie_hellman(&public).raw_secret_bytes().to_vec());
-            }
+    match keypair.curve {
+        NamedCurve::P256 | NamedCurve::P384 | NamedCurve::X25519 => {
+            provider.ecdh(keypair, public_key).await
         }
-        NamedCurve::P384 => {
-            let pub_key = p384::EncodedPoint::from_bytes(public_key)?;
-            let public = p384::PublicKey::from_sec1_bytes(pub_key.as_ref())?;
-            if let NamedCurvePrivateKey::EphemeralSecretP384(secret) = private_key {
-                return Ok(secret.diffie_hellman(&public).raw_secret_bytes().to_vec());
-            }
-        }
-        NamedCurve::X25519 => {
-            if public_key.len() != 32 {
-                return Err(Error::Other("Public key is not 32 len".into()));
-            }
-            let pub_key: [u8; 32] = public_key.try_into().unwrap();
-            let public = x25519_dalek::PublicKey::from(pub_key);
-            if let NamedCurvePrivateKey::StaticSecretX25519(secret) = private_key {
-                return Ok(secret.diffie_hellman(&public).as_bytes().to_vec());
-            }
-        }
-        _ => return Err(Error::ErrInvalidNamedCurve),
+        _ => Err(Error::ErrInvalidNamedCurve),
     }
-    Err(Error::ErrNamedCurveAndPrivateKeyMismatch)
 }
 
 //  This PRF with the SHA-256 hash function is used for all cipher suites
@@ -140,19 +98,8 @@ fn elliptic_curve_pre_master_secret(
 //  output data.
 //
 // https://tools.ietf.org/html/rfc4346w
-fn hmac_sha(h: CipherSuiteHash, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
-    let mut mac = match h {
-        CipherSuiteHash::Sha256 => {
-            HmacSha256::new_varkey(key).map_err(|e| Error::Other(e.to_string()))?
-        }
-    };
-    mac.update(data);
-    let result = mac.finalize();
-    let code_bytes = result.into_bytes();
-    Ok(code_bytes.to_vec())
-}
-
 pub(crate) fn prf_p_hash(
+    provider: &dyn CryptoProvider,
     secret: &[u8],
     seed: &[u8],
     requested_length: usize,
@@ -163,11 +110,11 @@ pub(crate) fn prf_p_hash(
 
     let iterations = ((requested_length as f64) / (h.size() as f64)).ceil() as usize;
     for _ in 0..iterations {
-        last_round = hmac_sha(h, secret, &last_round)?;
+        last_round = provider.hmac(h.algorithm(), secret, &last_round)?;
 
         let mut last_round_seed = last_round.clone();
         last_round_seed.extend_from_slice(seed);
-        let with_secret = hmac_sha(h, secret, &last_round_seed)?;
+        let with_secret = provider.hmac(h.algorithm(), secret, &last_round_seed)?;
 
         out.extend_from_slice(&with_secret);
     }
@@ -176,16 +123,18 @@ pub(crate) fn prf_p_hash(
 }
 
 pub(crate) fn prf_extended_master_secret(
+    provider: &dyn CryptoProvider,
     pre_master_secret: &[u8],
     session_hash: &[u8],
     h: CipherSuiteHash,
 ) -> Result<Vec<u8>> {
     let mut seed = PRF_EXTENDED_MASTER_SECRET_LABEL.as_bytes().to_vec();
     seed.extend_from_slice(session_hash);
-    prf_p_hash(pre_master_secret, &seed, 48, h)
+    prf_p_hash(provider, pre_master_secret, &seed, 48, h)
 }
 
 pub(crate) fn prf_master_secret(
+    provider: &dyn CryptoProvider,
     pre_master_secret: &[u8],
     client_random: &[u8],
     server_random: &[u8],
@@ -194,10 +143,12 @@ pub(crate) fn prf_master_secret(
     let mut seed = PRF_MASTER_SECRET_LABEL.as_bytes().to_vec();
     seed.extend_from_slice(client_random);
     seed.extend_from_slice(server_random);
-    prf_p_hash(pre_master_secret, &seed, 48, h)
+    prf_p_hash(provider, pre_master_secret, &seed, 48, h)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn prf_encryption_keys(
+    provider: &dyn CryptoProvider,
     master_secret: &[u8],
     client_random: &[u8],
     server_random: &[u8],
@@ -211,6 +162,7 @@ pub(crate) fn prf_encryption_keys(
     seed.extend_from_slice(client_random);
 
     let material = prf_p_hash(
+        provider,
         master_secret,
         &seed,
         (2 * prf_mac_len) + (2 * prf_key_len) + (2 * prf_iv_len),
@@ -247,28 +199,27 @@ pub(crate) fn prf_encryption_keys(
 }
 
 pub(crate) fn prf_verify_data(
+    provider: &dyn CryptoProvider,
     master_secret: &[u8],
     handshake_bodies: &[u8],
     label: &str,
     h: CipherSuiteHash,
 ) -> Result<Vec<u8>> {
-    let mut hasher = match h {
-        CipherSuiteHash::Sha256 => Sha256::new(),
-    };
-    hasher.update(handshake_bodies);
-    let result = hasher.finalize();
+    let result = provider.hash(h.algorithm(), handshake_bodies)?;
     let mut seed = label.as_bytes().to_vec();
     seed.extend_from_slice(&result);
 
-    prf_p_hash(master_secret, &seed, 12, h)
+    prf_p_hash(provider, master_secret, &seed, 12, h)
 }
 
 pub(crate) fn prf_verify_data_client(
+    provider: &dyn CryptoProvider,
     master_secret: &[u8],
     handshake_bodies: &[u8],
     h: CipherSuiteHash,
 ) -> Result<Vec<u8>> {
     prf_verify_data(
+        provider,
         master_secret,
         handshake_bodies,
         PRF_VERIFY_DATA_CLIENT_LABEL,
@@ -277,11 +228,13 @@ pub(crate) fn prf_verify_data_client(
 }
 
 pub(crate) fn prf_verify_data_server(
+    provider: &dyn CryptoProvider,
     master_secret: &[u8],
     handshake_bodies: &[u8],
     h: CipherSuiteHash,
 ) -> Result<Vec<u8>> {
     prf_verify_data(
+        provider,
         master_secret,
         handshake_bodies,
         PRF_VERIFY_DATA_SERVER_LABEL,
@@ -291,6 +244,7 @@ pub(crate) fn prf_verify_data_server(
 
 // compute the MAC using HMAC-SHA1
 pub(crate) fn prf_mac(
+    provider: &dyn CryptoProvider,
     epoch: u16,
     sequence_number: u64,
     content_type: ContentType,
@@ -298,8 +252,6 @@ pub(crate) fn prf_mac(
     payload: &[u8],
     key: &[u8],
 ) -> Result<Vec<u8>> {
-    let mut hmac = HmacSha1::new_varkey(key).map_err(|e| Error::Other(e.to_string()))?;
-
     let mut msg = vec![0u8; 13];
     msg[..2].copy_from_slice(&epoch.to_be_bytes());
     msg[2..8].copy_from_slice(&sequence_number.to_be_bytes()[2..]);
@@ -308,9 +260,7 @@ pub(crate) fn prf_mac(
     msg[10] = protocol_version.minor;
     msg[11..].copy_from_slice(&(payload.len() as u16).to_be_bytes());
 
-    hmac.update(&msg);
-    hmac.update(payload);
-    let result = hmac.finalize();
+    msg.extend_from_slice(payload);
 
-    Ok(result.into_bytes().to_vec())
+    provider.hmac(HashAlgorithm::Sha1, key, &msg)
 }
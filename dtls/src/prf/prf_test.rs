@@ -1,15 +1,19 @@
 use super::*;
 use crate::cipher_suite::CipherSuiteHash;
+use crate::crypto::provider::RustCrypto;
 
-#[test]
-fn test_pre_master_secret() -> Result<()> {
+#[tokio::test(flavor = "local")]
+async fn test_pre_master_secret() -> Result<()> {
     let private_key: [u8; 32] = [
         0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e,
         0x2f, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3b, 0x3c, 0x3d,
         0x3e, 0x3f,
     ];
-    let private_key =
-        NamedCurvePrivateKey::StaticSecretX25519(x25519_dalek::StaticSecret::from(private_key));
+    let keypair = NamedCurveKeypair {
+        curve: NamedCurve::X25519,
+        public_key: vec![],
+        private_key: private_key.to_vec(),
+    };
     let public_key = [
         0x9f, 0xd7, 0xad, 0x6d, 0xcf, 0xf4, 0x29, 0x8d, 0xd3, 0xf9, 0x6d, 0x5b, 0x1b, 0x2a, 0xf9,
         0x10, 0xa0, 0x53, 0x5b, 0x14, 0x88, 0xd7, 0xf8, 0xfa, 0xbb, 0x34, 0x9a, 0x98, 0x28, 0x80,
@@ -22,7 +26,7 @@ fn test_pre_master_secret() -> Result<()> {
         0x76, 0x24,
     ];
 
-    let pre_master_secret = prf_pre_master_secret(&public_key, &private_key, NamedCurve::X25519)?;
+    let pre_master_secret = prf_pre_master_secret(&RustCrypto, &public_key, &keypair).await?;
 
     assert_eq!(
         expected_pre_master_secret, pre_master_secret,
@@ -58,6 +62,7 @@ fn test_master_secret() -> Result<()> {
     ];
 
     let master_secret = prf_master_secret(
+        &RustCrypto,
         &pre_master_secret,
         &client_random,
         &server_random,
@@ -109,6 +114,7 @@ fn test_encryption_keys() -> Result<()> {
     };
 
     let keys = prf_encryption_keys(
+        &RustCrypto,
         &master_secret,
         &client_random,
         &server_random,
@@ -253,7 +259,12 @@ fn test_verify_data() -> Result<()> {
         0xcf, 0x91, 0x96, 0x26, 0xf1, 0x36, 0x0c, 0x53, 0x6a, 0xaa, 0xd7, 0x3a,
     ];
 
-    let verify_data = prf_verify_data_client(&master_secret, &final_msg, CipherSuiteHash::Sha256)?;
+    let verify_data = prf_verify_data_client(
+        &RustCrypto,
+        &master_secret,
+        &final_msg,
+        CipherSuiteHash::Sha256,
+    )?;
 
     assert_eq!(
         expected_verify_data, verify_data,
@@ -43,6 +43,7 @@ impl RecordLayer {
                 protocol_version,
                 epoch,
                 sequence_number: 0,
+                connection_id: vec![],
                 content_len: content.size() as u16,
             },
             content,
@@ -82,18 +83,25 @@ impl RecordLayer {
 // two DTLS messages into the same datagram: in the same record or in
 // separate records.
 // https://tools.ietf.org/html/rfc6347#section-4.2.3
-pub(crate) fn unpack_datagram(buf: &[u8]) -> Result<Vec<Vec<u8>>> {
+//
+// cid_len is the length of the connection id records sent to us carry, 0 if none was negotiated.
+pub(crate) fn unpack_datagram(buf: &[u8], cid_len: usize) -> Result<Vec<Vec<u8>>> {
     let mut out = vec![];
 
     let mut offset = 0;
     while buf.len() != offset {
-        if buf.len() - offset <= RECORD_LAYER_HEADER_SIZE {
+        let header_size = if buf[offset] == ContentType::ConnectionId as u8 {
+            RECORD_LAYER_HEADER_SIZE + cid_len
+        } else {
+            RECORD_LAYER_HEADER_SIZE
+        };
+        if buf.len() - offset <= header_size {
             return Err(Error::ErrInvalidPacketLength);
         }
 
-        let pkt_len = RECORD_LAYER_HEADER_SIZE
-            + (((buf[offset + RECORD_LAYER_HEADER_SIZE - 2] as usize) << 8)
-                | buf[offset + RECORD_LAYER_HEADER_SIZE - 1] as usize);
+        let pkt_len = header_size
+            + (((buf[offset + header_size - 2] as usize) << 8)
+                | buf[offset + header_size - 1] as usize);
         if offset + pkt_len > buf.len() {
             return Err(Error::ErrInvalidPacketLength);
         }
@@ -104,3 +112,61 @@ pub(crate) fn unpack_datagram(buf: &[u8]) -> Result<Vec<Vec<u8>>> {
 
     Ok(out)
 }
+
+// Once a connection id is negotiated, encrypted records use the tls12_cid content type and
+// carry their real content type at the end of the encrypted DTLSInnerPlaintext.
+// https://www.rfc-editor.org/rfc/rfc9146#section-4
+//
+//  struct {
+//      opaque content[length];
+//      ContentType real_type;
+//      uint8 zeros[length_of_padding];
+//  } DTLSInnerPlaintext;
+//
+// pack_connection_id turns a plaintext record into a tls12_cid record ready for encryption.
+pub(crate) fn pack_connection_id(
+    h: &RecordLayerHeader,
+    raw: &[u8],
+    connection_id: &[u8],
+) -> Result<(RecordLayerHeader, Vec<u8>)> {
+    let content = &raw[RECORD_LAYER_HEADER_SIZE..];
+
+    let cid_header = RecordLayerHeader {
+        content_type: ContentType::ConnectionId,
+        connection_id: connection_id.to_vec(),
+        content_len: (content.len() + 1) as u16,
+        ..h.clone()
+    };
+
+    let mut out = Vec::with_capacity(cid_header.size() + content.len() + 1);
+    cid_header.marshal(&mut out)?;
+    out.extend_from_slice(content);
+    out.push(h.content_type as u8);
+
+    Ok((cid_header, out))
+}
+
+// unpack_connection_id turns a decrypted tls12_cid record back into a plaintext record.
+pub(crate) fn unpack_connection_id(h: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
+    let inner_plaintext = &raw[h.size()..];
+
+    // The real content type is the last non-zero byte, everything after it is padding.
+    let real_type_offset = match inner_plaintext.iter().rposition(|b| *b != 0) {
+        Some(offset) => offset,
+        None => return Err(Error::ErrInvalidContentType),
+    };
+    let content = &inner_plaintext[..real_type_offset];
+
+    let plain_header = RecordLayerHeader {
+        content_type: inner_plaintext[real_type_offset].into(),
+        connection_id: vec![],
+        content_len: content.len() as u16,
+        ..h.clone()
+    };
+
+    let mut out = Vec::with_capacity(RECORD_LAYER_HEADER_SIZE + content.len());
+    plain_header.marshal(&mut out)?;
+    out.extend_from_slice(content);
+
+    Ok(out)
+}
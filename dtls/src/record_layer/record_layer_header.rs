@@ -34,16 +34,25 @@ pub struct ProtocolVersion {
     pub minor: u8,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct RecordLayerHeader {
     pub content_type: ContentType,
     pub protocol_version: ProtocolVersion,
     pub epoch: u16,
-    pub sequence_number: u64, // uint48 in spec
+    pub sequence_number: u64,   // uint48 in spec
+    pub connection_id: Vec<u8>, // only present in tls12_cid records
     pub content_len: u16,
 }
 
 impl RecordLayerHeader {
+    pub fn size(&self) -> usize {
+        if self.content_type == ContentType::ConnectionId {
+            RECORD_LAYER_HEADER_SIZE + self.connection_id.len()
+        } else {
+            RECORD_LAYER_HEADER_SIZE
+        }
+    }
+
     pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
         if self.sequence_number > MAX_SEQUENCE_NUMBER {
             return Err(Error::ErrSequenceNumberOverflow);
@@ -57,12 +66,23 @@ impl RecordLayerHeader {
         let be: [u8; 8] = self.sequence_number.to_be_bytes();
         writer.write_all(&be[2..])?; // uint48 in spec
 
+        if self.content_type == ContentType::ConnectionId {
+            writer.write_all(&self.connection_id)?;
+        }
+
         writer.write_u16::<BigEndian>(self.content_len)?;
 
         Ok(writer.flush()?)
     }
 
     pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        RecordLayerHeader::unmarshal_cid(reader, 0)
+    }
+
+    // The connection id of a tls12_cid record isn't length-prefixed, so the receiver has to
+    // know the length of the connection id it asked the peer to use.
+    // https://www.rfc-editor.org/rfc/rfc9146#section-4
+    pub fn unmarshal_cid<R: Read>(reader: &mut R, cid_len: usize) -> Result<Self> {
         let content_type = reader.read_u8()?.into();
         let major = reader.read_u8()?;
         let minor = reader.read_u8()?;
@@ -77,6 +97,12 @@ impl RecordLayerHeader {
         if protocol_version != PROTOCOL_VERSION1_0 && protocol_version != PROTOCOL_VERSION1_2 {
             return Err(Error::ErrUnsupportedProtocolVersion);
         }
+
+        let mut connection_id = vec![];
+        if content_type == ContentType::ConnectionId {
+            connection_id.resize(cid_len, 0);
+            reader.read_exact(&mut connection_id)?;
+        }
         let content_len = reader.read_u16::<BigEndian>()?;
 
         Ok(RecordLayerHeader {
@@ -84,6 +110,7 @@ impl RecordLayerHeader {
             protocol_version,
             epoch,
             sequence_number,
+            connection_id,
             content_len,
         })
     }
@@ -52,7 +52,7 @@ fn test_udp_decode() -> Result<()> {
     ];
 
     for (name, data, wanted, wanted_err) in tests {
-        let dtls_pkts = unpack_datagram(&data);
+        let dtls_pkts = unpack_datagram(&data, 0);
         if let Some(err) = wanted_err {
             if let Err(dtls) = dtls_pkts {
                 assert_eq!(err.to_string(), dtls.to_string());
@@ -97,6 +97,7 @@ fn test_record_layer_round_trip() -> Result<()> {
                 },
                 epoch: 0,
                 sequence_number: 18,
+                connection_id: vec![],
                 content_len: 1,
             },
             content: Content::ChangeCipherSpec(ChangeCipherSpec {}),
@@ -127,3 +128,53 @@ fn test_record_layer_round_trip() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_record_layer_connection_id() -> Result<()> {
+    let connection_id = vec![0x01, 0x02, 0x03, 0x04];
+    let record = RecordLayer {
+        record_layer_header: RecordLayerHeader {
+            content_type: ContentType::ChangeCipherSpec,
+            protocol_version: PROTOCOL_VERSION1_2,
+            epoch: 1,
+            sequence_number: 18,
+            connection_id: vec![],
+            content_len: 1,
+        },
+        content: Content::ChangeCipherSpec(ChangeCipherSpec {}),
+    };
+
+    let mut raw = vec![];
+    record.marshal(&mut raw)?;
+
+    let (cid_header, mut cid_raw) =
+        pack_connection_id(&record.record_layer_header, &raw, &connection_id)?;
+    assert_eq!(cid_header.content_type, ContentType::ConnectionId);
+    assert_eq!(
+        cid_header.size(),
+        RECORD_LAYER_HEADER_SIZE + connection_id.len()
+    );
+    assert_eq!(
+        cid_raw,
+        vec![
+            0x19, 0xfe, 0xfd, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12, 0x01, 0x02, 0x03,
+            0x04, 0x00, 0x02, 0x01, 0x14,
+        ]
+    );
+
+    // The connection id isn't length-prefixed, the receiver knows its length.
+    let dtls_pkts = unpack_datagram(&cid_raw, connection_id.len())?;
+    assert_eq!(dtls_pkts, vec![cid_raw.clone()]);
+
+    let mut reader = BufReader::new(cid_raw.as_slice());
+    let h = RecordLayerHeader::unmarshal_cid(&mut reader, connection_id.len())?;
+    assert_eq!(h, cid_header);
+
+    // Zero padding after the real content type is stripped.
+    cid_raw.extend_from_slice(&[0, 0, 0]);
+    let plain = unpack_connection_id(&h, &cid_raw)?;
+    let mut reader = BufReader::new(plain.as_slice());
+    assert_eq!(RecordLayer::unmarshal(&mut reader)?, record);
+
+    Ok(())
+}
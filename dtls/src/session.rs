@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod session_test;
+
+use crate::cipher_suite::CipherSuiteId;
+use crate::error::Result;
+
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::Mutex;
+
+/// Session holds the state needed to resume a DTLS session with an abbreviated handshake.
+/// https://tools.ietf.org/html/rfc5246#section-7.3
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    /// id is the session identifier chosen by the server.
+    pub id: Vec<u8>,
+    /// secret is the master secret negotiated by the full handshake.
+    pub secret: Vec<u8>,
+    /// cipher_suite is the cipher suite the secret was negotiated for. A session is only
+    /// resumed with the same one.
+    pub cipher_suite: CipherSuiteId,
+    /// extended_master_secret tells whether secret was derived with the extended master secret
+    /// extension, which a resumption has to negotiate again.
+    /// https://tools.ietf.org/html/rfc7627#section-5.3
+    pub extended_master_secret: bool,
+    /// peer_certificates is the certificate chain the peer presented during the full handshake.
+    /// A resumed handshake doesn't exchange certificates, so it is restored from here.
+    pub peer_certificates: Vec<Vec<u8>>,
+}
+
+/// SessionStore persists sessions so later connections can resume them.
+///
+/// Clients look sessions up by server name and remote address, servers by session id.
+#[async_trait(?Send)]
+pub trait SessionStore {
+    /// set saves a session under key, replacing any previous one.
+    async fn set(&self, key: &[u8], session: Session) -> Result<()>;
+
+    /// get returns the session saved under key, if there is one.
+    async fn get(&self, key: &[u8]) -> Result<Option<Session>>;
+
+    /// del forgets the session saved under key.
+    async fn del(&self, key: &[u8]) -> Result<()>;
+}
+
+/// MemorySessionStore is a SessionStore that keeps up to `capacity` sessions in memory,
+/// evicting the oldest one first.
+pub struct MemorySessionStore {
+    capacity: usize,
+    sessions: Mutex<MemorySessions>,
+}
+
+#[derive(Default)]
+struct MemorySessions {
+    map: HashMap<Vec<u8>, Session>,
+    // Keys in insertion order, oldest first.
+    order: VecDeque<Vec<u8>>,
+}
+
+impl MemorySessionStore {
+    pub fn new(capacity: usize) -> Self {
+        MemorySessionStore {
+            capacity,
+            sessions: Mutex::new(MemorySessions::default()),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl SessionStore for MemorySessionStore {
+    async fn set(&self, key: &[u8], session: Session) -> Result<()> {
+        let mut sessions = self.sessions.lock().await;
+        let MemorySessions { map, order } = &mut *sessions;
+
+        if map.insert(key.to_vec(), session).is_none() {
+            order.push_back(key.to_vec());
+        }
+        while map.len() > self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                map.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &[u8]) -> Result<Option<Session>> {
+        let sessions = self.sessions.lock().await;
+        Ok(sessions.map.get(key).cloned())
+    }
+
+    async fn del(&self, key: &[u8]) -> Result<()> {
+        let mut sessions = self.sessions.lock().await;
+        let MemorySessions { map, order } = &mut *sessions;
+        if map.remove(key).is_some() {
+            order.retain(|k| k != key);
+        }
+        Ok(())
+    }
+}
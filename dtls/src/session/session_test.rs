@@ -0,0 +1,37 @@
+use super::*;
+
+fn session(id: u8) -> Session {
+    Session {
+        id: vec![id; 32],
+        secret: vec![id; 48],
+        cipher_suite: CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_128_Gcm_Sha256,
+        extended_master_secret: true,
+        peer_certificates: vec![],
+    }
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_memory_session_store() -> Result<()> {
+    let store = MemorySessionStore::new(2);
+
+    store.set(b"a", session(1)).await?;
+    store.set(b"b", session(2)).await?;
+    assert_eq!(store.get(b"a").await?, Some(session(1)));
+
+    // Replacing a session doesn't change its place in line.
+    store.set(b"a", session(3)).await?;
+    assert_eq!(store.get(b"a").await?, Some(session(3)));
+
+    // The oldest session is evicted once capacity is exceeded.
+    store.set(b"c", session(4)).await?;
+    assert_eq!(store.get(b"a").await?, None);
+    assert_eq!(store.get(b"b").await?, Some(session(2)));
+    assert_eq!(store.get(b"c").await?, Some(session(4)));
+
+    store.del(b"b").await?;
+    assert_eq!(store.get(b"b").await?, None);
+    store.set(b"d", session(5)).await?;
+    assert_eq!(store.get(b"c").await?, Some(session(4)));
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use super::cipher_suite::*;
+use super::config::KeyLogCallback;
 use super::conn::*;
+use super::crypto::provider::{default_provider, CryptoProvider};
 use super::curve::named_curve::*;
 use super::extension::extension_use_srtp::SrtpProtectionProfile;
 use super::handshake::handshake_random::*;
@@ -37,6 +39,11 @@ pub struct State {
     pub(crate) named_curve: NamedCurve,
     pub(crate) local_keypair: Option<NamedCurveKeypair>,
     pub(crate) cookie: Vec<u8>,
+    pub(crate) session_id: Vec<u8>,
+    pub(crate) session_cipher_suite: CipherSuiteId, // cipher suite of the session offered for resumption
+    pub(crate) session_extended_master_secret: bool, // whether that session used extended master secret
+    pub(crate) local_connection_id: Arc<Mutex<Option<Vec<u8>>>>, // connection id the peer sends us, if negotiated
+    pub(crate) remote_connection_id: Arc<Mutex<Option<Vec<u8>>>>, // connection id we send the peer, if negotiated
     pub(crate) handshake_send_sequence: isize,
     pub(crate) handshake_recv_sequence: isize,
     pub(crate) server_name: String,
@@ -46,6 +53,7 @@ pub struct State {
     pub(crate) local_key_signature: Vec<u8>,       // cached keySignature
     pub(crate) peer_certificates_verified: bool,
     //pub(crate) replay_detector: Vec<Box<dyn ReplayDetector>>,
+    pub(crate) crypto_provider: Arc<dyn CryptoProvider>, // not serialized
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -61,6 +69,8 @@ struct SerializedState {
     peer_certificates: Vec<Vec<u8>>,
     identity_hint: Vec<u8>,
     is_client: bool,
+    local_connection_id: Option<Vec<u8>>,
+    remote_connection_id: Option<Vec<u8>>,
 }
 
 impl Default for State {
@@ -86,6 +96,11 @@ impl Default for State {
             named_curve: NamedCurve::Unsupported,
             local_keypair: None,
             cookie: vec![],
+            session_id: vec![],
+            session_cipher_suite: CipherSuiteId::Unsupported,
+            session_extended_master_secret: false,
+            local_connection_id: Arc::new(Mutex::new(None)),
+            remote_connection_id: Arc::new(Mutex::new(None)),
             handshake_send_sequence: 0,
             handshake_recv_sequence: 0,
             server_name: "".to_string(),
@@ -95,13 +110,17 @@ impl Default for State {
             local_key_signature: vec![],         // cached keySignature
             peer_certificates_verified: false,
             //replay_detector: vec![],
+            crypto_provider: default_provider(),
         }
     }
 }
 
 impl State {
     pub(crate) async fn clone(&self) -> Self {
-        let mut state = State::default();
+        let mut state = State {
+            crypto_provider: Arc::clone(&self.crypto_provider),
+            ..Default::default()
+        };
 
         if let Ok(serialized) = self.serialize().await {
             let _ = state.deserialize(&serialized).await;
@@ -154,6 +173,8 @@ impl State {
             peer_certificates: self.peer_certificates.clone(),
             identity_hint: self.identity_hint.clone(),
             is_client: self.is_client,
+            local_connection_id: self.local_connection_id.lock().await.clone(),
+            remote_connection_id: self.remote_connection_id.lock().await.clone(),
         })
     }
 
@@ -194,9 +215,21 @@ impl State {
         self.peer_certificates = serialized.peer_certificates.clone();
         self.identity_hint = serialized.identity_hint.clone();
 
+        // Set connection ids
+        *self.local_connection_id.lock().await = serialized.local_connection_id.clone();
+        *self.remote_connection_id.lock().await = serialized.remote_connection_id.clone();
+
         Ok(())
     }
 
+    // cipher_suite_id returns the id of the chosen cipher suite, Unsupported if none was chosen.
+    pub(crate) async fn cipher_suite_id(&self) -> CipherSuiteId {
+        match &*self.cipher_suite.lock().await {
+            Some(cipher_suite) => cipher_suite.id(),
+            None => CipherSuiteId::Unsupported,
+        }
+    }
+
     pub async fn init_cipher_suite(&mut self) -> Result<()> {
         let mut cipher_suite = self.cipher_suite.lock().await;
         if let Some(cipher_suite) = &mut *cipher_suite {
@@ -216,15 +249,52 @@ impl State {
             }
 
             if self.is_client {
-                cipher_suite.init(&self.master_secret, &local_random, &remote_random, true)
+                cipher_suite.init(
+                    &self.crypto_provider,
+                    &self.master_secret,
+                    &local_random,
+                    &remote_random,
+                    true,
+                )
             } else {
-                cipher_suite.init(&self.master_secret, &remote_random, &local_random, false)
+                cipher_suite.init(
+                    &self.crypto_provider,
+                    &self.master_secret,
+                    &remote_random,
+                    &local_random,
+                    false,
+                )
             }
         } else {
             Err(Error::ErrCipherSuiteUnset)
         }
     }
 
+    // log_master_secret writes the master secret to key_log as an NSS key log CLIENT_RANDOM line.
+    // https://firefox-source-docs.mozilla.org/security/nss/legacy/key_log_format/index.html
+    pub(crate) fn log_master_secret(&self, key_log: &Option<KeyLogCallback>) -> Result<()> {
+        if let Some(key_log) = key_log {
+            let mut client_random = vec![];
+            {
+                let mut writer = BufWriter::<&mut Vec<u8>>::new(client_random.as_mut());
+                if self.is_client {
+                    self.local_random.marshal(&mut writer)?;
+                } else {
+                    self.remote_random.marshal(&mut writer)?;
+                }
+            }
+
+            let hex = |b: &[u8]| b.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            key_log(&format!(
+                "CLIENT_RANDOM {} {}",
+                hex(&client_random),
+                hex(&self.master_secret)
+            ));
+        }
+
+        Ok(())
+    }
+
     // marshal_binary is a binary.BinaryMarshaler.marshal_binary implementation
     pub async fn marshal_binary(&self) -> Result<Vec<u8>> {
         let serialized = self.serialize().await?;
@@ -292,7 +362,13 @@ impl KeyingMaterialExporter for State {
 
         let cipher_suite = self.cipher_suite.lock().await;
         if let Some(cipher_suite) = &*cipher_suite {
-            match prf_p_hash(&self.master_secret, &seed, length, cipher_suite.hash_func()) {
+            match prf_p_hash(
+                &*self.crypto_provider,
+                &self.master_secret,
+                &seed,
+                length,
+                cipher_suite.hash_func(),
+            ) {
                 Ok(v) => Ok(v),
                 Err(err) => Err(Hash(err.to_string())),
             }
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod stress_test;
+
+use crate::config::*;
+use crate::conn::DTLSConn;
+use crate::error::*;
+use crate::handshake::handshake_header::HANDSHAKE_HEADER_LENGTH;
+use crate::record_layer::record_layer_header::RECORD_LAYER_HEADER_SIZE;
+
+use std::sync::Arc;
+use std::time::Duration;
+use util::conn::conn_lossy::*;
+use util::conn::conn_pipe::pipe;
+use util::Conn;
+
+/// StressConfig describes the network a stress handshake runs over.
+/// Each direction has its own LossyConfig, so the path can be asymmetric.
+#[derive(Clone, Debug, Default)]
+pub struct StressConfig {
+    /// client mistreats the datagrams the client sends.
+    pub client: LossyConfig,
+    /// server mistreats the datagrams the server sends.
+    pub server: LossyConfig,
+    /// timeout bounds how long both handshakes may take together.
+    /// 0 means there is no limit.
+    pub timeout: Duration,
+}
+
+/// StressReport tells what the network did to each side's datagrams during the handshake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StressReport {
+    pub client: LossyStats,
+    pub server: LossyStats,
+}
+
+/// mtu_for_path returns the Config.mtu that keeps plaintext handshake records within
+/// a path carrying datagrams of at most path_mtu bytes, since Config.mtu only limits
+/// the handshake fragment and the record and handshake headers come on top.
+pub fn mtu_for_path(path_mtu: usize) -> usize {
+    path_mtu.saturating_sub(RECORD_LAYER_HEADER_SIZE + HANDSHAKE_HEADER_LENGTH)
+}
+
+/// stress_handshake connects a client and a server over an in-memory pipe that drops,
+/// duplicates and reorders datagrams as stress asks. The seeds in stress fix what happens
+/// to the n-th datagram each side sends, so a failing network can be replayed. When a
+/// side's Config.mtu is 0, it is derived from the LossyConfig.mtu of the datagrams it sends.
+// Conns are ?Send, and the MSRV lint trips on what tokio::join! expands to.
+#[allow(clippy::arc_with_non_send_sync, clippy::incompatible_msrv)]
+pub async fn stress_handshake(
+    mut client_config: Config,
+    mut server_config: Config,
+    stress: &StressConfig,
+) -> Result<(DTLSConn, DTLSConn, StressReport)> {
+    if client_config.mtu == 0 && stress.client.mtu != 0 {
+        client_config.mtu = mtu_for_path(stress.client.mtu);
+    }
+    if server_config.mtu == 0 && stress.server.mtu != 0 {
+        server_config.mtu = mtu_for_path(stress.server.mtu);
+    }
+
+    let (ca, cb) = pipe();
+    let ca = Arc::new(LossyConn::new(Arc::new(ca), stress.client.clone()));
+    let cb = Arc::new(LossyConn::new(Arc::new(cb), stress.server.clone()));
+
+    let handshakes = async {
+        tokio::join!(
+            DTLSConn::new(Arc::clone(&ca) as _, client_config, true, None),
+            DTLSConn::new(Arc::clone(&cb) as _, server_config, false, None),
+        )
+    };
+    let (client, server) = if stress.timeout != Duration::from_secs(0) {
+        match deno_net::timeout(stress.timeout, handshakes).await {
+            Ok(results) => results,
+            Err(_) => {
+                // Closing the pipe stops the read loops the handshakes left behind.
+                let _ = ca.close().await;
+                let _ = cb.close().await;
+                return Err(Error::ErrDeadlineExceeded);
+            }
+        }
+    } else {
+        handshakes.await
+    };
+
+    let report = StressReport {
+        client: ca.stats(),
+        server: cb.stats(),
+    };
+
+    match (client, server) {
+        (Ok(client), Ok(server)) => Ok((client, server, report)),
+        (Err(err), other) => {
+            if let Ok(server) = other {
+                let _ = server.close().await;
+            }
+            Err(err)
+        }
+        (Ok(client), Err(err)) => {
+            let _ = client.close().await;
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+use crate::crypto::Certificate;
+
+fn stress_configs() -> Result<(Config, Config)> {
+    let client = Config {
+        insecure_skip_verify: true,
+        flight_interval: Duration::from_millis(20),
+        ..Default::default()
+    };
+    let server = Config {
+        certificates: vec![Certificate::generate_self_signed(vec![
+            "localhost".to_owned()
+        ])?],
+        flight_interval: Duration::from_millis(20),
+        ..Default::default()
+    };
+    Ok((client, server))
+}
+
+// lossy runs over a small path MTU, so the certificate flights are fragmented
+// and there are enough datagrams for the chances to matter.
+fn lossy(loss_chance: u8, duplicate_chance: u8, reorder_chance: u8, seed: u64) -> LossyConfig {
+    LossyConfig {
+        loss_chance,
+        duplicate_chance,
+        reorder_chance,
+        mtu: 300,
+        seed,
+    }
+}
+
+async fn check_stress(name: &str, stress: StressConfig) -> Result<StressReport> {
+    let (client_config, server_config) = stress_configs()?;
+    let (client, server, report) =
+        match stress_handshake(client_config, server_config, &stress).await {
+            Ok(result) => result,
+            Err(err) => panic!("{}: handshake failed: {}", name, err),
+        };
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(report)
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_stress_handshake_clean() -> Result<()> {
+    let (client_config, server_config) = stress_configs()?;
+    let stress = StressConfig {
+        timeout: Duration::from_secs(10),
+        ..Default::default()
+    };
+    let (client, server, report) = stress_handshake(client_config, server_config, &stress).await?;
+
+    assert_eq!(report.client.dropped + report.server.dropped, 0);
+    assert_eq!(report.client.oversized + report.server.oversized, 0);
+
+    client.write(b"ping", None).await?;
+    let mut buf = vec![0u8; 64];
+    let n = server.read(&mut buf, None).await?;
+    assert_eq!(&buf[..n], b"ping");
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_stress_handshake_lossy() -> Result<()> {
+    let tests = vec![
+        ("Loss10", lossy(10, 0, 0, 1), lossy(10, 0, 0, 2)),
+        ("Loss30", lossy(30, 0, 0, 30), lossy(30, 0, 0, 31)),
+        ("Loss50", lossy(50, 0, 0, 5), lossy(50, 0, 0, 6)),
+        ("ClientLoss", lossy(40, 0, 0, 7), lossy(0, 0, 0, 8)),
+        ("ServerLoss", lossy(0, 0, 0, 9), lossy(40, 0, 0, 10)),
+        ("Duplicate", lossy(0, 50, 0, 11), lossy(0, 50, 0, 12)),
+        ("Reorder", lossy(0, 0, 50, 13), lossy(0, 0, 50, 14)),
+        ("Everything", lossy(20, 20, 20, 15), lossy(20, 20, 20, 16)),
+    ];
+
+    for (name, client, server) in tests {
+        let expect_drops = client.loss_chance + server.loss_chance > 0;
+        let report = check_stress(
+            name,
+            StressConfig {
+                client,
+                server,
+                timeout: Duration::from_secs(20),
+            },
+        )
+        .await?;
+
+        assert_eq!(
+            report.client.dropped + report.server.dropped > 0,
+            expect_drops,
+            "{}: unexpected drops {:?}",
+            name,
+            report
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_stress_handshake_mtu() -> Result<()> {
+    for path_mtu in &[200, 300, 576, 1280] {
+        let mut client = lossy(10, 10, 10, *path_mtu as u64);
+        client.mtu = *path_mtu;
+        let mut server = lossy(10, 10, 10, *path_mtu as u64 + 1);
+        server.mtu = *path_mtu;
+
+        let name = format!("PathMtu{}", path_mtu);
+        let report = check_stress(
+            &name,
+            StressConfig {
+                client,
+                server,
+                timeout: Duration::from_secs(20),
+            },
+        )
+        .await?;
+
+        assert_eq!(
+            report.client.oversized + report.server.oversized,
+            0,
+            "{}: records did not fit the path",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_stress_handshake_mtu_too_large() -> Result<()> {
+    // The server's certificate flight can't fit a 300 byte path when it fragments at 1200.
+    let (client_config, mut server_config) = stress_configs()?;
+    server_config.mtu = 1200;
+    let server = LossyConfig {
+        mtu: 300,
+        ..Default::default()
+    };
+
+    let result = stress_handshake(
+        client_config,
+        server_config,
+        &StressConfig {
+            server,
+            timeout: Duration::from_millis(500),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    match result {
+        Err(Error::ErrDeadlineExceeded) => Ok(()),
+        Err(err) => panic!("expected ErrDeadlineExceeded, got {}", err),
+        Ok(_) => panic!("expected the handshake to time out"),
+    }
+}
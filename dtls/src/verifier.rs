@@ -0,0 +1,320 @@
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod verifier_test;
+
+use crate::crypto::der;
+use crate::crypto::provider::rust_crypto;
+use crate::error::{Error, Result};
+use crate::signature_hash_algorithm::{HashAlgorithm, SignatureAlgorithm, SignatureHashAlgorithm};
+
+use deno_net::{SystemTime, UNIX_EPOCH};
+use std::convert::TryFrom;
+use webpki::alg_id;
+use webpki::types::{
+    AlgorithmIdentifier, CertificateDer, InvalidSignature, ServerName,
+    SignatureVerificationAlgorithm, TrustAnchor, UnixTime,
+};
+use x509_parser::certificate::X509Certificate;
+
+/// RustCryptoAlgorithm checks certificate signatures for webpki with the RustCrypto
+/// primitives dtls already uses for handshake signatures, so it works on wasm as well.
+#[derive(Debug)]
+struct RustCryptoAlgorithm {
+    public_key_alg_id: AlgorithmIdentifier,
+    signature_alg_id: AlgorithmIdentifier,
+    scheme: SignatureHashAlgorithm,
+}
+
+impl SignatureVerificationAlgorithm for RustCryptoAlgorithm {
+    fn verify_signature(
+        &self,
+        public_key: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> std::result::Result<(), InvalidSignature> {
+        // webpki hands over the subjectPublicKey alone, put the SubjectPublicKeyInfo back together.
+        let public_key_info = der::sequence(&[
+            &der::tlv(der::TAG_SEQUENCE, self.public_key_alg_id.as_ref()),
+            &der::bit_string(public_key),
+        ]);
+        rust_crypto::verify(&self.scheme, &public_key_info, message, signature)
+            .map_err(|_| InvalidSignature)
+    }
+
+    fn public_key_alg_id(&self) -> AlgorithmIdentifier {
+        self.public_key_alg_id
+    }
+
+    fn signature_alg_id(&self) -> AlgorithmIdentifier {
+        self.signature_alg_id
+    }
+}
+
+const fn algorithm(
+    public_key_alg_id: AlgorithmIdentifier,
+    signature_alg_id: AlgorithmIdentifier,
+    hash: HashAlgorithm,
+    signature: SignatureAlgorithm,
+) -> RustCryptoAlgorithm {
+    RustCryptoAlgorithm {
+        public_key_alg_id,
+        signature_alg_id,
+        scheme: SignatureHashAlgorithm { hash, signature },
+    }
+}
+
+static ECDSA_P256_SHA256: RustCryptoAlgorithm = algorithm(
+    alg_id::ECDSA_P256,
+    alg_id::ECDSA_SHA256,
+    HashAlgorithm::Sha256,
+    SignatureAlgorithm::Ecdsa,
+);
+static ECDSA_P256_SHA384: RustCryptoAlgorithm = algorithm(
+    alg_id::ECDSA_P256,
+    alg_id::ECDSA_SHA384,
+    HashAlgorithm::Sha384,
+    SignatureAlgorithm::Ecdsa,
+);
+static ECDSA_P384_SHA256: RustCryptoAlgorithm = algorithm(
+    alg_id::ECDSA_P384,
+    alg_id::ECDSA_SHA256,
+    HashAlgorithm::Sha256,
+    SignatureAlgorithm::Ecdsa,
+);
+static ECDSA_P384_SHA384: RustCryptoAlgorithm = algorithm(
+    alg_id::ECDSA_P384,
+    alg_id::ECDSA_SHA384,
+    HashAlgorithm::Sha384,
+    SignatureAlgorithm::Ecdsa,
+);
+static ED25519: RustCryptoAlgorithm = algorithm(
+    alg_id::ED25519,
+    alg_id::ED25519,
+    HashAlgorithm::Ed25519,
+    SignatureAlgorithm::Ed25519,
+);
+static RSA_PKCS1_SHA256: RustCryptoAlgorithm = algorithm(
+    alg_id::RSA_ENCRYPTION,
+    alg_id::RSA_PKCS1_SHA256,
+    HashAlgorithm::Sha256,
+    SignatureAlgorithm::Rsa,
+);
+static RSA_PKCS1_SHA384: RustCryptoAlgorithm = algorithm(
+    alg_id::RSA_ENCRYPTION,
+    alg_id::RSA_PKCS1_SHA384,
+    HashAlgorithm::Sha384,
+    SignatureAlgorithm::Rsa,
+);
+static RSA_PKCS1_SHA512: RustCryptoAlgorithm = algorithm(
+    alg_id::RSA_ENCRYPTION,
+    alg_id::RSA_PKCS1_SHA512,
+    HashAlgorithm::Sha512,
+    SignatureAlgorithm::Rsa,
+);
+
+/// Signature algorithms accepted on certificates in a chain. RSA-PSS isn't supported by the
+/// RustCrypto provider yet.
+static SUPPORTED_SIG_ALGS: &[&dyn SignatureVerificationAlgorithm] = &[
+    &ECDSA_P256_SHA256,
+    &ECDSA_P256_SHA384,
+    &ECDSA_P384_SHA256,
+    &ECDSA_P384_SHA384,
+    &ED25519,
+    &RSA_PKCS1_SHA256,
+    &RSA_PKCS1_SHA384,
+    &RSA_PKCS1_SHA512,
+];
+
+/// CertificateChain is the certificate chain a peer presented, leaf first.
+pub struct CertificateChain<'a> {
+    /// raw holds the DER encoded certificates as they were received.
+    pub raw: &'a [Vec<u8>],
+    /// parsed holds the same certificates, parsed.
+    pub parsed: Vec<X509Certificate<'a>>,
+}
+
+impl<'a> CertificateChain<'a> {
+    /// parse parses a DER encoded chain. It fails if the chain is empty.
+    pub fn parse(raw: &'a [Vec<u8>]) -> Result<Self> {
+        if raw.is_empty() {
+            return Err(Error::ErrEmptyCertificateChain);
+        }
+
+        let mut parsed = vec![];
+        for der in raw {
+            let (_, certificate) = x509_parser::parse_x509_certificate(der)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            parsed.push(certificate);
+        }
+
+        Ok(CertificateChain { raw, parsed })
+    }
+
+    /// leaf returns the peer's own certificate.
+    pub fn leaf(&self) -> &X509Certificate<'a> {
+        &self.parsed[0]
+    }
+}
+
+/// ServerCertVerifier decides whether a client trusts the certificate chain of a server.
+pub trait ServerCertVerifier {
+    /// verify_server_cert checks `chain` is valid for `server_name`, the name the client
+    /// connected to and sent as SNI. `ocsp_response` is the OCSP response stapled by the
+    /// server, if there is one; dtls doesn't request stapling yet, so it is currently None.
+    fn verify_server_cert(
+        &self,
+        chain: &CertificateChain<'_>,
+        server_name: &str,
+        ocsp_response: Option<&[u8]>,
+    ) -> Result<()>;
+}
+
+/// ClientCertVerifier decides whether a server trusts the certificate chain of a client.
+pub trait ClientCertVerifier {
+    /// verify_client_cert checks `chain`. `server_name` is the SNI the client sent, if any.
+    fn verify_client_cert(
+        &self,
+        chain: &CertificateChain<'_>,
+        server_name: Option<&str>,
+    ) -> Result<()>;
+}
+
+/// WebPkiVerifier validates chains up to a set of trusted roots with webpki: signatures,
+/// validity periods and extended key usage, and for servers that the leaf certificate is
+/// valid for the server name.
+///
+/// It works on every target, but on wasm Config has no root store to build one from, so
+/// it is not the default there: create it with [`WebPkiVerifier::from_root_certificates`]
+/// and set it as `server_cert_verifier`/`client_cert_verifier`.
+pub struct WebPkiVerifier {
+    roots: Vec<TrustAnchor<'static>>,
+}
+
+impl WebPkiVerifier {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(roots: rustls::RootCertStore) -> Self {
+        let roots = roots
+            .roots
+            .iter()
+            .map(|root| {
+                let anchor = root.to_trust_anchor();
+                TrustAnchor {
+                    subject: anchor.subject.into(),
+                    subject_public_key_info: anchor.spki.into(),
+                    name_constraints: anchor.name_constraints.map(Into::into),
+                }
+                .to_owned()
+            })
+            .collect();
+        WebPkiVerifier { roots }
+    }
+
+    /// from_root_certificates trusts the given DER encoded root certificates.
+    pub fn from_root_certificates(roots: &[Vec<u8>]) -> Result<Self> {
+        let mut anchors = vec![];
+        for der in roots {
+            let cert = CertificateDer::from(der.as_slice());
+            anchors.push(
+                webpki::anchor_from_trusted_cert(&cert)
+                    .map_err(webpki_err)?
+                    .to_owned(),
+            );
+        }
+        Ok(WebPkiVerifier { roots: anchors })
+    }
+
+    fn verify_chain(&self, chain: &CertificateChain<'_>, usage: webpki::KeyUsage) -> Result<()> {
+        let leaf = CertificateDer::from(chain.raw[0].as_slice());
+        let cert = webpki::EndEntityCert::try_from(&leaf).map_err(webpki_err)?;
+        let intermediates: Vec<CertificateDer<'_>> = chain.raw[1..]
+            .iter()
+            .map(|c| CertificateDer::from(c.as_slice()))
+            .collect();
+        cert.verify_for_usage(
+            SUPPORTED_SIG_ALGS,
+            &self.roots,
+            &intermediates,
+            now()?,
+            usage,
+            None,
+            None,
+        )
+        .map_err(webpki_err)?;
+        Ok(())
+    }
+}
+
+// now reads the wall clock through deno_net, which works on wasm too.
+fn now() -> Result<UnixTime> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::Other(e.to_string()))?;
+    Ok(UnixTime::since_unix_epoch(since_epoch))
+}
+
+fn webpki_err(err: webpki::Error) -> Error {
+    Error::Other(format!("invalid certificate: {:?}", err))
+}
+
+impl ServerCertVerifier for WebPkiVerifier {
+    fn verify_server_cert(
+        &self,
+        chain: &CertificateChain<'_>,
+        server_name: &str,
+        ocsp_response: Option<&[u8]>,
+    ) -> Result<()> {
+        let server_name =
+            ServerName::try_from(server_name).map_err(|e| Error::Other(e.to_string()))?;
+
+        self.verify_chain(chain, webpki::KeyUsage::server_auth())?;
+
+        if let Some(ocsp_response) = ocsp_response {
+            log::trace!("unvalidated OCSP response: {:?}", ocsp_response);
+        }
+
+        let leaf = CertificateDer::from(chain.raw[0].as_slice());
+        webpki::EndEntityCert::try_from(&leaf)
+            .and_then(|cert| cert.verify_is_valid_for_subject_name(&server_name))
+            .map_err(webpki_err)
+    }
+}
+
+impl ClientCertVerifier for WebPkiVerifier {
+    fn verify_client_cert(
+        &self,
+        chain: &CertificateChain<'_>,
+        _server_name: Option<&str>,
+    ) -> Result<()> {
+        self.verify_chain(chain, webpki::KeyUsage::client_auth())
+    }
+}
+
+/// NoRootsVerifier trusts no chain. It is the default on wasm, where Config has no root
+/// store, so peers have to be authenticated some other way there: insecure_skip_verify plus
+/// a verify_peer_certificate callback, as WebRTC does with its certificate fingerprints, a
+/// [`WebPkiVerifier::from_root_certificates`], or a verifier of the application's own.
+pub struct NoRootsVerifier;
+
+impl ServerCertVerifier for NoRootsVerifier {
+    fn verify_server_cert(
+        &self,
+        _chain: &CertificateChain<'_>,
+        _server_name: &str,
+        _ocsp_response: Option<&[u8]>,
+    ) -> Result<()> {
+        Err(Error::Other(
+            "invalid certificate: no trusted roots".to_owned(),
+        ))
+    }
+}
+
+impl ClientCertVerifier for NoRootsVerifier {
+    fn verify_client_cert(
+        &self,
+        _chain: &CertificateChain<'_>,
+        _server_name: Option<&str>,
+    ) -> Result<()> {
+        Err(Error::Other(
+            "invalid certificate: no trusted roots".to_owned(),
+        ))
+    }
+}
@@ -0,0 +1,118 @@
+use super::*;
+use crate::crypto::Certificate;
+
+use std::time::Duration;
+
+fn root_store(certificate: &Certificate) -> Result<rustls::RootCertStore> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots
+        .add(&rustls::Certificate(certificate.certificate[0].clone()))
+        .map_err(|_err| Error::Other("add cert error".to_owned()))?;
+    Ok(roots)
+}
+
+#[test]
+fn test_certificate_chain_parse() -> Result<()> {
+    let certificate = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+    let chain = CertificateChain::parse(&certificate.certificate)?;
+    assert_eq!(chain.parsed.len(), 1);
+    assert_eq!(
+        chain.leaf().tbs_certificate.subject.to_string(),
+        "CN=webrtc-rs self signed cert"
+    );
+
+    assert!(matches!(
+        CertificateChain::parse(&[]),
+        Err(Error::ErrEmptyCertificateChain)
+    ));
+    assert!(CertificateChain::parse(&[vec![0x30, 0x00]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_web_pki_server_verifier() -> Result<()> {
+    let certificate = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+    let raw = vec![certificate.certificate[0].clone()];
+    let chain = CertificateChain::parse(&raw)?;
+
+    let verifier = WebPkiVerifier::new(root_store(&certificate)?);
+    verifier.verify_server_cert(&chain, "localhost", None)?;
+    assert!(
+        verifier
+            .verify_server_cert(&chain, "example.com", None)
+            .is_err(),
+        "certificate is not valid for example.com"
+    );
+
+    let verifier = WebPkiVerifier::new(rustls::RootCertStore::empty());
+    assert!(
+        verifier
+            .verify_server_cert(&chain, "localhost", None)
+            .is_err(),
+        "certificate is not signed by a trusted root"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_web_pki_client_verifier() -> Result<()> {
+    let certificate = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+    let other = Certificate::generate_self_signed(vec!["localhost".to_owned()])?;
+    let raw = vec![certificate.certificate[0].clone()];
+    let chain = CertificateChain::parse(&raw)?;
+
+    let verifier = WebPkiVerifier::new(root_store(&certificate)?);
+    verifier.verify_client_cert(&chain, None)?;
+
+    let verifier = WebPkiVerifier::new(root_store(&other)?);
+    assert!(
+        verifier.verify_client_cert(&chain, None).is_err(),
+        "certificate is not signed by a trusted root"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_web_pki_from_root_certificates() -> Result<()> {
+    for signature in [SignatureAlgorithm::Ecdsa, SignatureAlgorithm::Ed25519] {
+        let certificate =
+            Certificate::generate_self_signed_with_alg(vec!["localhost".to_owned()], signature)?;
+        let chain = CertificateChain::parse(&certificate.certificate)?;
+
+        let verifier = WebPkiVerifier::from_root_certificates(&certificate.certificate)?;
+        verifier.verify_server_cert(&chain, "localhost", None)?;
+        verifier.verify_client_cert(&chain, None)?;
+        assert!(
+            verifier
+                .verify_server_cert(&chain, "example.com", None)
+                .is_err(),
+            "{:?}: certificate is not valid for example.com",
+            signature
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_web_pki_expired() -> Result<()> {
+    let certificate = Certificate::generate_self_signed_expiring(
+        vec!["localhost".to_owned()],
+        SignatureAlgorithm::Ecdsa,
+        SystemTime::now() - Duration::from_secs(60),
+    )?;
+    let chain = CertificateChain::parse(&certificate.certificate)?;
+
+    let verifier = WebPkiVerifier::from_root_certificates(&certificate.certificate)?;
+    assert!(
+        verifier
+            .verify_server_cert(&chain, "localhost", None)
+            .is_err(),
+        "certificate has expired"
+    );
+
+    Ok(())
+}
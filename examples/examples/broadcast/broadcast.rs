@@ -19,7 +19,7 @@ use webrtc::track::track_local::{TrackLocal, TrackLocalWriter};
 use webrtc::track::track_remote::TrackRemote;
 use webrtc::Error;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("broadcast")
         .version("0.1.0")
@@ -134,7 +134,7 @@ async fn main() -> Result<()> {
                 // This is a temporary fix until we implement incoming RTCP events, then we would push a PLI only when a viewer requests it
                 let media_ssrc = track.ssrc();
                 let pc2 = pc.clone();
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let mut result = Result::<usize>::Ok(0);
                     while result.is_ok() {
                         let timeout = deno_net::sleep(Duration::from_secs(3));
@@ -263,7 +263,7 @@ async fn main() -> Result<()> {
             // Read incoming RTCP packets
             // Before these packets are returned they are processed by interceptors. For things
             // like NACK this needs to be called.
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let mut rtcp_buf = vec![0u8; 1500];
                 while let Ok((_, _)) = rtp_sender.read(&mut rtcp_buf).await {}
                 Result::<()>::Ok(())
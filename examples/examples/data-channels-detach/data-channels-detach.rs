@@ -18,7 +18,7 @@ use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
 
 const MESSAGE_SIZE: usize = 1500;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("data-channels-detach")
         .version("0.1.0")
@@ -150,12 +150,12 @@ async fn main() -> Result<()> {
 
                     // Handle reading from the data channel
                     let r = Arc::clone(&raw);
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let _ = read_loop(r).await;
                     });
 
                     // Handle writing to the data channel
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let _ = write_loop(raw).await;
                     });
                 })
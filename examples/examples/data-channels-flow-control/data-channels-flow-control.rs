@@ -75,7 +75,7 @@ async fn create_requester() -> anyhow::Result<RTCPeerConnection> {
     dc.on_open(Box::new(|| {
         Box::pin(async move {
             // This callback shouldn't be blocked for a long time, so we spawn our handler
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let buf = Bytes::from_static(&[0u8; 1024]);
 
                 loop {
@@ -123,7 +123,7 @@ async fn create_responder() -> anyhow::Result<RTCPeerConnection> {
             dc.on_open(Box::new(move || {
                 Box::pin(async {
                     // This callback shouldn't be blocked for a long time, so we spawn our handler
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let start = SystemTime::now();
 
                         deno_net::sleep(Duration::from_secs(1)).await;
@@ -160,7 +160,7 @@ async fn create_responder() -> anyhow::Result<RTCPeerConnection> {
     Ok(pc)
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> anyhow::Result<()> {
     env_logger::init();
 
@@ -192,7 +192,7 @@ async fn do_signaling(req: Request<Body>) -> Result<Response<Body>, hyper::Error
     Ok(response)
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("ice-restart")
         .version("0.1.0")
@@ -237,7 +237,7 @@ async fn main() -> Result<()> {
             .init();
     }
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         println!("Open http://localhost:8080 to access this demo");
 
         let addr = SocketAddr::from_str("0.0.0.0:8080").unwrap();
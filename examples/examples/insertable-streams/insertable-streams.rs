@@ -25,7 +25,7 @@ use webrtc::Error;
 
 const CIPHER_KEY: u8 = 0xAA;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("insertable-streams")
         .version("0.1.0")
@@ -138,7 +138,7 @@ async fn main() -> Result<()> {
     // Read incoming RTCP packets
     // Before these packets are returned they are processed by interceptors. For things
     // like NACK this needs to be called.
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut rtcp_buf = vec![0u8; 1500];
         while let Ok((_, _)) = rtp_sender.read(&mut rtcp_buf).await {}
         Result::<()>::Ok(())
@@ -148,7 +148,7 @@ async fn main() -> Result<()> {
     let notify_video = notify_tx.clone();
 
     let video_file_name = video_file.to_owned();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         // Open a IVF file and start reading using our IVFReader
         let file = File::open(video_file_name)?;
         let reader = BufReader::new(file);
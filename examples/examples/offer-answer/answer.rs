@@ -181,7 +181,7 @@ async fn remote_handler(req: Request<Body>) -> Result<Response<Body>, hyper::Err
     }
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("Answer")
         .version("0.1.0")
@@ -307,7 +307,7 @@ async fn main() -> Result<()> {
         *pcm = Some(Arc::clone(&peer_connection));
     }
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let addr = SocketAddr::from_str(&answer_addr).unwrap();
         let service =
             make_service_fn(|_| async { Ok::<_, hyper::Error>(service_fn(remote_handler)) });
@@ -139,7 +139,7 @@ async fn remote_handler(req: Request<Body>) -> Result<Response<Body>, hyper::Err
     }
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("Offer")
         .version("0.1.0")
@@ -265,7 +265,7 @@ async fn main() -> Result<()> {
         *pcm = Some(Arc::clone(&peer_connection));
     }
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let addr = SocketAddr::from_str(&offer_addr).unwrap();
         let service =
             make_service_fn(|_| async { Ok::<_, hyper::Error>(service_fn(remote_handler)) });
@@ -18,7 +18,7 @@ use webrtc::ice_transport::ice_server::RTCIceServer;
 use webrtc::peer_connection::math_rand_alpha;
 use webrtc::sctp_transport::sctp_transport_capabilities::SCTPTransportCapabilities;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("ortc")
         .version("0.1.0")
@@ -208,7 +208,7 @@ async fn main() -> Result<()> {
 
         // no need to downgrade this to Weak
         let d2 = Arc::clone(&d);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             tokio::select! {
                 _ = done_offer.notified() => {
                     println!("received done_offer signal!");
@@ -12,7 +12,7 @@ impl Drop for Cycle {
     }
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() {
     let cycle = Rc::new(Cycle {
         cell: RefCell::new(None),
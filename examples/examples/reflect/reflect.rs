@@ -21,7 +21,7 @@ use webrtc::track::track_local::track_local_static_rtp::TrackLocalStaticRTP;
 use webrtc::track::track_local::{TrackLocal, TrackLocalWriter};
 use webrtc::track::track_remote::TrackRemote;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("reflect")
         .version("0.1.0")
@@ -177,7 +177,7 @@ async fn main() -> Result<()> {
         // Before these packets are returned they are processed by interceptors. For things
         // like NACK this needs to be called.
         let m = s.to_owned();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut rtcp_buf = vec![0u8; 1500];
             while let Ok((_, _)) = rtp_sender.read(&mut rtcp_buf).await {}
             println!("{} rtp_sender.read loop exit", m);
@@ -207,7 +207,7 @@ async fn main() -> Result<()> {
 
                 if track.kind() == RTPCodecType::Video {
                     let pc2 = pc.clone();
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let mut result = Result::<usize>::Ok(0);
                         while result.is_ok() {
                             let timeout = deno_net::sleep(Duration::from_secs(3));
@@ -28,7 +28,7 @@ struct UdpConn {
     payload_type: u8,
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("rtp-forwarder")
         .version("0.1.0")
@@ -189,7 +189,7 @@ async fn main() -> Result<()> {
                 // Send a PLI on an interval so that the publisher is pushing a keyframe every rtcpPLIInterval
                 let media_ssrc = track.ssrc();
                 let pc2 = pc.clone();
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let mut result = Result::<usize>::Ok(0);
                     while result.is_ok() {
                         let timeout = deno_net::sleep(Duration::from_secs(3));
@@ -57,7 +57,7 @@ async fn save_to_disk(
     }
 }
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("save-to-disk-vpx")
         .version("0.1.0")
@@ -236,7 +236,7 @@ async fn main() -> Result<()> {
                 // Send a PLI on an interval so that the publisher is pushing a keyframe every rtcpPLIInterval
                 let media_ssrc = track.ssrc();
                 let pc2 = pc.clone();
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let mut result = Result::<usize>::Ok(0);
                     while result.is_ok() {
                         let timeout = deno_net::sleep(Duration::from_secs(3));
@@ -267,7 +267,7 @@ async fn main() -> Result<()> {
                         println!(
                             "Got Opus track, saving to disk as output.opus (48 kHz, 2 channels)"
                         );
-                        wasm_bindgen_futures::spawn_local(async move {
+                        deno_net::spawn(async move {
                             let _ = save_to_disk(ogg_writer2, track, notify_rx2).await;
                         });
                     } else if mime_type == MIME_TYPE_VP8.to_lowercase()
@@ -277,7 +277,7 @@ async fn main() -> Result<()> {
                             "Got {} track, saving to disk as output.ivf",
                             if is_vp9 { "VP9" } else { "VP8" }
                         );
-                        wasm_bindgen_futures::spawn_local(async move {
+                        deno_net::spawn(async move {
                             let _ = save_to_disk(ivf_writer2, track, notify_rx2).await;
                         });
                     }
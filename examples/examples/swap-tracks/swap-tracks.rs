@@ -20,7 +20,7 @@ use webrtc::track::track_local::{TrackLocal, TrackLocalWriter};
 use webrtc::track::track_remote::TrackRemote;
 use webrtc::Error;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<()> {
     let mut app = Command::new("swap-tracks")
         .version("0.1.0")
@@ -117,7 +117,7 @@ async fn main() -> Result<()> {
     // Read incoming RTCP packets
     // Before these packets are returned they are processed by interceptors. For things
     // like NACK this needs to be called.
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut rtcp_buf = vec![0u8; 1500];
         while let Ok((_, _)) = rtp_sender.read(&mut rtcp_buf).await {}
         Result::<()>::Ok(())
@@ -153,7 +153,7 @@ async fn main() -> Result<()> {
                 let curr_track2 = Arc::clone(&curr_track1);
                 let pc2 = pc.clone();
                 let packets_tx2 = Arc::clone(&packets_tx);
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     println!(
                         "Track has started, of type {}: {}",
                         track.payload_type(),
@@ -252,7 +252,7 @@ async fn main() -> Result<()> {
 
     // Asynchronously take all packets in the channel and write them out to our
     // track
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut curr_timestamp = 0;
         let mut i = 0;
         while let Some(mut packet) = packets_rx.recv().await {
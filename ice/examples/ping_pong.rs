@@ -85,7 +85,7 @@ async fn remote_handler(req: Request<Body>) -> Result<Response<Body>, hyper::Err
 // Controlling Agent:
 //      cargo run --color=always --package webrtc-ice --example ping_pong -- --controlling
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::init();
     // .format(|buf, record| {
@@ -148,7 +148,7 @@ async fn main() -> Result<(), Error> {
 
         println!("Listening on http://localhost:{}", local_http_port);
         let mut done_http_server = done_rx.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let addr = ([0, 0, 0, 0], local_http_port).into();
             let service =
                 make_service_fn(|_| async { Ok::<_, hyper::Error>(service_fn(remote_handler)) });
@@ -277,7 +277,7 @@ async fn main() -> Result<(), Error> {
 
         let ice_agent2 = Arc::clone(&ice_agent);
         let mut done_cand = done_rx.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut rx = REMOTE_CAND_CHANNEL.1.lock().await;
             loop {
                 tokio::select! {
@@ -322,7 +322,7 @@ async fn main() -> Result<(), Error> {
         // Send messages in a loop to the remote peer
         let conn_tx = Arc::clone(&conn);
         let mut done_send = done_rx.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             const RANDOM_STRING: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
             loop {
                 deno_net::sleep(Duration::from_secs(3)).await;
@@ -352,7 +352,7 @@ async fn main() -> Result<(), Error> {
         });
 
         let mut done_recv = done_rx.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             // Receive messages in a loop from the remote peer
             let mut buf = vec![0u8; 1500];
             loop {
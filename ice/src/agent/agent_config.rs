@@ -1,13 +1,17 @@
+use super::agent_gather::ContinualGatheringPolicy;
+use super::agent_selector::NominationStrategy;
 use super::*;
 use crate::error::*;
 use crate::mdns::*;
 use crate::network_type::*;
+use crate::tcp_mux::TCPMux;
 use crate::udp_network::UDPNetwork;
 use crate::url::*;
 
 use util::vnet::net::*;
 
 use std::net::IpAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// The interval at which the agent performs candidate checks in the connecting phase.
@@ -22,6 +26,12 @@ pub(crate) const DEFAULT_DISCONNECTED_TIMEOUT: Duration = Duration::from_secs(5)
 /// The default time till an Agent transitions to failed after disconnected.
 pub(crate) const DEFAULT_FAILED_TIMEOUT: Duration = Duration::from_secs(25);
 
+/// The base interval of consent freshness checks, RFC 7675 section 5.1.
+pub(crate) const DEFAULT_CONSENT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The time without an authenticated response till consent expires, RFC 7675 section 5.1.
+pub(crate) const DEFAULT_CONSENT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Wait time before nominating a host candidate.
 pub(crate) const DEFAULT_HOST_ACCEPTANCE_MIN_WAIT: Duration = Duration::from_secs(0);
 
@@ -34,6 +44,9 @@ pub(crate) const DEFAULT_PRFLX_ACCEPTANCE_MIN_WAIT: Duration = Duration::from_mi
 /// Wait time before nominating a relay candidate.
 pub(crate) const DEFAULT_RELAY_ACCEPTANCE_MIN_WAIT: Duration = Duration::from_millis(2000);
 
+/// How often the local interfaces are checked for changes when gathering continually.
+pub(crate) const DEFAULT_NETWORK_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Max binding request before considering a pair failed.
 pub(crate) const DEFAULT_MAX_BINDING_REQUESTS: u16 = 7;
 
@@ -64,6 +77,14 @@ pub struct AgentConfig {
     /// See [`UDPNetwork`]
     pub udp_network: UDPNetwork,
 
+    /// Serves passive ICE-TCP candidates. When set and a TCP network type is enabled, a passive
+    /// host candidate is gathered for each local address the mux accepts connections on.
+    /// See [`TCPMux`]
+    pub tcp_mux: Option<Arc<dyn TCPMux>>,
+
+    /// Disables active ICE-TCP candidates, which dial out to remote passive candidates.
+    pub disable_active_tcp: bool,
+
     /// It is used to perform connectivity checks. The values MUST be unguessable, with at least
     /// 128 bits of random number generator output used to generate the password, and at least 24
     /// bits of output to generate the username fragment.
@@ -95,12 +116,32 @@ pub struct AgentConfig {
     /// A keepalive interval of 0 means we never send keepalive packets
     pub keepalive_interval: Option<Duration>,
 
+    /// Determines how often consent to send on the selected pair is refreshed (RFC 7675). Each
+    /// check is randomized between 0.8 and 1.2 times this interval, which defaults to 5 seconds
+    /// when this property is nil.
+    pub consent_check_interval: Option<Duration>,
+
+    /// Defaults to 30 seconds when this property is nil. If the selected pair gets no
+    /// authenticated response for this long, consent expires and the ICE Agent goes to failed.
+    /// If the duration is 0, consent freshness is disabled.
+    pub consent_timeout: Option<Duration>,
+
     /// An optional configuration for disabling or enabling support for specific network types.
     pub network_types: Vec<NetworkType>,
 
     /// An optional configuration for disabling or enabling support for specific candidate types.
     pub candidate_types: Vec<CandidateType>,
 
+    /// Controls if the agent keeps gathering candidates as the local interfaces change, so a
+    /// connection can move e.g. from Wi-Fi to Ethernet without an ICE restart.
+    /// See [`ContinualGatheringPolicy`]
+    pub continual_gathering_policy: ContinualGatheringPolicy,
+
+    /// How often the local interfaces are checked for changes when gathering continually.
+    /// Defaults to 2 seconds when this property is nil. If the duration is 0, they are only
+    /// checked on `Agent::update_network`.
+    pub network_monitor_interval: Option<Duration>,
+
     //LoggerFactory logging.LoggerFactory
     /// Controls how often our internal task loop runs when in the connecting state.
     /// Only useful for testing.
@@ -137,6 +178,17 @@ pub struct AgentConfig {
     /// Specify a minimum wait time before selecting relay candidates.
     pub relay_acceptance_min_wait: Option<Duration>,
 
+    /// Controls how a controlling agent nominates the candidate pair to use.
+    /// See [`NominationStrategy`]
+    pub nomination_strategy: NominationStrategy,
+
+    /// Lets a controlling agent keep checking candidate pairs once connected, and nominate a
+    /// pair the nomination strategy prefers over the selected one, e.g. a host pair that
+    /// turned up after a relay pair got selected, and a controlled agent follow such a
+    /// nomination. Only takes effect once the remote agent announced renomination too, see
+    /// [`Agent::set_remote_renomination`]. Has no effect with aggressive nomination.
+    pub renomination: bool,
+
     /// Net is the our abstracted network interface for internal development purpose only
     /// (see (github.com/pion/transport/vnet)[github.com/pion/transport/vnet]).
     pub net: Option<Arc<Net>>,
@@ -205,6 +257,21 @@ impl AgentConfig {
             a.keepalive_interval = DEFAULT_KEEPALIVE_INTERVAL;
         }
 
+        if let Some(consent_check_interval) = self.consent_check_interval {
+            a.consent_check_interval = consent_check_interval;
+        } else {
+            a.consent_check_interval = DEFAULT_CONSENT_CHECK_INTERVAL;
+        }
+
+        if self.lite {
+            // A lite agent sends no checks of its own, consent freshness is up to the full agent.
+            a.consent_timeout = Duration::from_secs(0);
+        } else if let Some(consent_timeout) = self.consent_timeout {
+            a.consent_timeout = consent_timeout;
+        } else {
+            a.consent_timeout = DEFAULT_CONSENT_TIMEOUT;
+        }
+
         if self.check_interval == Duration::from_secs(0) {
             a.check_interval = DEFAULT_CHECK_INTERVAL;
         } else {
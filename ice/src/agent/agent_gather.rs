@@ -1,10 +1,13 @@
 use super::*;
 use crate::error::*;
 use crate::network_type::*;
+use crate::tcp_mux::TCPMux;
+use crate::tcp_type::TcpType;
 use crate::udp_network::UDPNetwork;
 use crate::url::{ProtoType, SchemeType, Url};
 use crate::util::*;
 
+use turn::client::stun_conn::StunConn;
 use util::{vnet::net::*, Conn};
 
 use crate::candidate::candidate_base::CandidateBaseConfig;
@@ -12,15 +15,38 @@ use crate::candidate::candidate_host::CandidateHostConfig;
 use crate::candidate::candidate_relay::CandidateRelayConfig;
 use crate::candidate::candidate_server_reflexive::CandidateServerReflexiveConfig;
 use crate::candidate::*;
-use std::net::{Ipv4Addr, Ipv6Addr};
-use std::str::FromStr;
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::Arc;
 use waitgroup::WaitGroup;
 
 const STUN_GATHER_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// The conn, port and tcp type of a host candidate. Active TCP candidates are gathered without
+/// a conn.
+type HostConn = (Option<Arc<dyn Conn>>, u16, TcpType);
+
+/// ContinualGatheringPolicy controls if the agent keeps gathering once the first round is done.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContinualGatheringPolicy {
+    /// Gathers once. Changes of the local interfaces are only picked up by a restart.
+    GatherOnce,
+    /// Keeps watching the local interfaces after the first round. Host and server reflexive
+    /// candidates are gathered on interfaces that show up and emitted through on_candidate,
+    /// the candidates and pairs of interfaces that go away are removed.
+    GatherContinually,
+}
+
+impl Default for ContinualGatheringPolicy {
+    fn default() -> Self {
+        ContinualGatheringPolicy::GatherOnce
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct GatherCandidatesInternalParams {
     pub(crate) udp_network: UDPNetwork,
+    pub(crate) tcp_mux: Option<Arc<dyn TCPMux>>,
     pub(crate) candidate_types: Vec<CandidateType>,
     pub(crate) urls: Vec<Url>,
     pub(crate) network_types: Vec<NetworkType>,
@@ -36,7 +62,10 @@ pub(crate) struct GatherCandidatesInternalParams {
 }
 
 struct GatherCandidatesLocalParams {
+    /// The interface addresses to gather on, all of them when None.
+    ips: Option<HashSet<IpAddr>>,
     udp_network: UDPNetwork,
+    tcp_mux: Option<Arc<dyn TCPMux>>,
     network_types: Vec<NetworkType>,
     mdns_mode: MulticastDnsMode,
     mdns_name: String,
@@ -67,6 +96,8 @@ struct GatherCandidatesSrflxMappedParasm {
 }
 
 struct GatherCandidatesSrflxParams {
+    /// The interface addresses to gather on, the unspecified address when None.
+    ips: Option<HashSet<IpAddr>>,
     urls: Vec<Url>,
     network_types: Vec<NetworkType>,
     port_max: u16,
@@ -75,8 +106,26 @@ struct GatherCandidatesSrflxParams {
     agent_internal: Arc<AgentInternal>,
 }
 
+/// Returns the addresses to bind to for gathering on ips, or on any interface when None.
+fn bind_ips(ips: &Option<HashSet<IpAddr>>, is_ipv4: bool) -> Vec<IpAddr> {
+    match ips {
+        Some(ips) => ips
+            .iter()
+            .filter(|ip| ip.is_ipv4() == is_ipv4)
+            .copied()
+            .collect(),
+        None if is_ipv4 => vec![Ipv4Addr::UNSPECIFIED.into()],
+        None => vec![Ipv6Addr::UNSPECIFIED.into()],
+    }
+}
+
 impl Agent {
-    pub(crate) async fn gather_candidates_internal(params: GatherCandidatesInternalParams) {
+    /// Gathers the candidates of params.candidate_types on ips, or on all interfaces when
+    /// None. Server reflexive and relay candidates are only bound to an interface with ips.
+    pub(crate) async fn gather_candidates_internal(
+        params: GatherCandidatesInternalParams,
+        ips: Option<HashSet<IpAddr>>,
+    ) {
         Self::set_gathering_state(
             &params.chan_candidate_tx,
             &params.gathering_state,
@@ -90,7 +139,9 @@ impl Agent {
             match t {
                 CandidateType::Host => {
                     let local_params = GatherCandidatesLocalParams {
+                        ips: ips.clone(),
                         udp_network: params.udp_network.clone(),
+                        tcp_mux: params.tcp_mux.clone(),
                         network_types: params.network_types.clone(),
                         mdns_mode: params.mdns_mode,
                         mdns_name: params.mdns_name.clone(),
@@ -102,7 +153,7 @@ impl Agent {
                     };
 
                     let w = wg.worker();
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let _d = w;
 
                         Self::gather_candidates_local(local_params).await;
@@ -116,6 +167,7 @@ impl Agent {
                     };
 
                     let srflx_params = GatherCandidatesSrflxParams {
+                        ips: ips.clone(),
                         urls: params.urls.clone(),
                         network_types: params.network_types.clone(),
                         port_max: ephemeral_config.port_max(),
@@ -124,7 +176,7 @@ impl Agent {
                         agent_internal: Arc::clone(&params.agent_internal),
                     };
                     let w1 = wg.worker();
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let _d = w1;
 
                         Self::gather_candidates_srflx(srflx_params).await;
@@ -140,7 +192,7 @@ impl Agent {
                                 agent_internal: Arc::clone(&params.agent_internal),
                             };
                             let w2 = wg.worker();
-                            wasm_bindgen_futures::spawn_local(async move {
+                            deno_net::spawn(async move {
                                 let _d = w2;
 
                                 Self::gather_candidates_srflx_mapped(srflx_mapped_params).await;
@@ -149,14 +201,23 @@ impl Agent {
                     }
                 }
                 CandidateType::Relay => {
+                    let ips = ips.clone();
                     let urls = params.urls.clone();
+                    let network_types = params.network_types.clone();
                     let net = Arc::clone(&params.net);
                     let agent_internal = Arc::clone(&params.agent_internal);
                     let w = wg.worker();
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let _d = w;
 
-                        Self::gather_candidates_relay(urls, net, agent_internal).await;
+                        Self::gather_candidates_relay(
+                            ips,
+                            urls,
+                            network_types,
+                            net,
+                            agent_internal,
+                        )
+                        .await;
                     });
                 }
                 _ => {}
@@ -174,6 +235,123 @@ impl Agent {
         .await;
     }
 
+    /// Watches the local interfaces until update_rx is closed, checking them every interval
+    /// and whenever update_rx receives. known_ips are the interface addresses gathered on.
+    pub(crate) async fn monitor_network(
+        params: GatherCandidatesInternalParams,
+        mut known_ips: HashSet<IpAddr>,
+        interval: Duration,
+        mut update_rx: mpsc::Receiver<()>,
+    ) {
+        loop {
+            let update = if interval == Duration::from_secs(0) {
+                update_rx.recv().await.is_some()
+            } else {
+                tokio::select! {
+                    _ = deno_net::sleep(interval) => true,
+                    update = update_rx.recv() => update.is_some(),
+                }
+            };
+            if !update || params.agent_internal.agent_conn.done.load(Ordering::SeqCst) {
+                return;
+            }
+
+            Self::update_local_interfaces(&params, &mut known_ips).await;
+        }
+    }
+
+    pub(crate) async fn local_interfaces(
+        params: &GatherCandidatesInternalParams,
+    ) -> HashSet<IpAddr> {
+        local_interfaces(
+            &params.net,
+            &params.interface_filter,
+            &params.ip_filter,
+            &params.network_types,
+        )
+        .await
+    }
+
+    /// Gathers candidates on the interfaces that showed up since known_ips, and removes the
+    /// candidates of the ones that went away.
+    async fn update_local_interfaces(
+        params: &GatherCandidatesInternalParams,
+        known_ips: &mut HashSet<IpAddr>,
+    ) {
+        let ips = Self::local_interfaces(params).await;
+        let added: HashSet<IpAddr> = ips.difference(known_ips).copied().collect();
+        let removed: HashSet<IpAddr> = known_ips.difference(&ips).copied().collect();
+        if added.is_empty() && removed.is_empty() {
+            return;
+        }
+
+        log::info!(
+            "[{}]: local interfaces changed, added: {:?}, removed: {:?}",
+            params.agent_internal.get_name(),
+            added,
+            removed
+        );
+        *known_ips = ips;
+
+        if !removed.is_empty() {
+            params
+                .agent_internal
+                .remove_local_candidates_on(&removed)
+                .await;
+        }
+
+        if added.is_empty() {
+            return;
+        }
+
+        if params.candidate_types.contains(&CandidateType::Host) {
+            Self::gather_candidates_local(GatherCandidatesLocalParams {
+                ips: Some(added.clone()),
+                udp_network: params.udp_network.clone(),
+                tcp_mux: params.tcp_mux.clone(),
+                network_types: params.network_types.clone(),
+                mdns_mode: params.mdns_mode,
+                mdns_name: params.mdns_name.clone(),
+                interface_filter: Arc::clone(&params.interface_filter),
+                ip_filter: Arc::clone(&params.ip_filter),
+                ext_ip_mapper: Arc::clone(&params.ext_ip_mapper),
+                net: Arc::clone(&params.net),
+                agent_internal: Arc::clone(&params.agent_internal),
+            })
+            .await;
+        }
+
+        // The new interface may well be behind another NAT, and needs its own relay.
+        if params
+            .candidate_types
+            .contains(&CandidateType::ServerReflexive)
+        {
+            if let UDPNetwork::Ephemeral(ephemeral_config) = &params.udp_network {
+                Self::gather_candidates_srflx(GatherCandidatesSrflxParams {
+                    ips: Some(added.clone()),
+                    urls: params.urls.clone(),
+                    network_types: params.network_types.clone(),
+                    port_max: ephemeral_config.port_max(),
+                    port_min: ephemeral_config.port_min(),
+                    net: Arc::clone(&params.net),
+                    agent_internal: Arc::clone(&params.agent_internal),
+                })
+                .await;
+            }
+        }
+
+        if params.candidate_types.contains(&CandidateType::Relay) {
+            Self::gather_candidates_relay(
+                Some(added),
+                params.urls.clone(),
+                params.network_types.clone(),
+                Arc::clone(&params.net),
+                Arc::clone(&params.agent_internal),
+            )
+            .await;
+        }
+    }
+
     async fn set_gathering_state(
         chan_candidate_tx: &ChanCandidateTx,
         gathering_state: &Arc<AtomicU8>,
@@ -193,7 +371,9 @@ impl Agent {
 
     async fn gather_candidates_local(params: GatherCandidatesLocalParams) {
         let GatherCandidatesLocalParams {
+            ips,
             udp_network,
+            tcp_mux,
             network_types,
             mdns_mode,
             mdns_name,
@@ -204,28 +384,33 @@ impl Agent {
             agent_internal,
         } = params;
 
-        // If we wanna use UDP mux, do so
-        // FIXME: We still need to support TCP in combination with this option
-        if let UDPNetwork::Muxed(udp_mux) = udp_network {
+        // If we wanna use UDP mux, do so. Its single candidate is only gathered once.
+        if let (UDPNetwork::Muxed(udp_mux), None) = (&udp_network, &ips) {
             let result = Self::gather_candidates_local_udp_mux(GatherCandidatesLocalUDPMuxParams {
-                network_types,
-                interface_filter,
-                ip_filter,
-                ext_ip_mapper,
-                net,
-                agent_internal,
-                udp_mux,
+                network_types: network_types.clone(),
+                interface_filter: Arc::clone(&interface_filter),
+                ip_filter: Arc::clone(&ip_filter),
+                ext_ip_mapper: Arc::clone(&ext_ip_mapper),
+                net: Arc::clone(&net),
+                agent_internal: Arc::clone(&agent_internal),
+                udp_mux: Arc::clone(udp_mux),
             })
             .await;
 
             if let Err(err) = result {
                 log::error!("Failed to gather local candidates using UDP mux: {}", err);
             }
-
-            return;
         }
 
-        let ips = local_interfaces(&net, &interface_filter, &ip_filter, &network_types).await;
+        let local_ufrag = {
+            let ufrag_pwd = agent_internal.ufrag_pwd.lock().await;
+            ufrag_pwd.local_ufrag.clone()
+        };
+
+        let ips = match ips {
+            Some(ips) => ips,
+            None => local_interfaces(&net, &interface_filter, &ip_filter, &network_types).await,
+        };
         for ip in ips {
             let mut mapped_ip = ip;
 
@@ -251,74 +436,107 @@ impl Agent {
                 mapped_ip.to_string()
             };
 
-            //TODO: for network in networks
-            let network = UDP.to_owned();
-            if let UDPNetwork::Ephemeral(ephemeral_config) = &udp_network {
-                /*TODO:switch network {
-                case tcp:
-                    // Handle ICE TCP passive mode
+            for network in [UDP, TCP] {
+                match determine_network_type(network, &ip) {
+                    Ok(network_type) if network_types.contains(&network_type) => {}
+                    _ => continue,
+                }
 
-                    a.log.Debugf("GetConn by ufrag: %s\n", a.localUfrag)
-                    conn, err = a.tcpMux.GetConnByUfrag(a.localUfrag)
-                    if err != nil {
-                        if !errors.Is(err, ErrTCPMuxNotInitialized) {
-                            a.log.Warnf("error getting tcp conn by ufrag: %s %s %s\n", network, ip, a.localUfrag)
-                        }
-                        continue
-                    }
-                    port = conn.LocalAddr().(*net.TCPAddr).Port
-                    tcpType = TCPTypePassive
-                    // is there a way to verify that the listen address is even
-                    // accessible from the current interface.
-                case udp:*/
+                let mut conns: Vec<HostConn> = vec![];
 
-                let conn: Arc<dyn Conn> = match listen_udp_in_port_range(
-                    &net,
-                    ephemeral_config.port_max(),
-                    ephemeral_config.port_min(),
-                    SocketAddr::new(ip, 0),
-                )
-                .await
-                {
-                    Ok(conn) => conn,
-                    Err(err) => {
-                        log::warn!(
-                            "[{}]: could not listen {} {}: {}",
+                if network == TCP {
+                    // Handle ICE TCP passive mode
+                    if let Some(tcp_mux) = &tcp_mux {
+                        log::debug!(
+                            "[{}]: GetConn by ufrag: {}",
                             agent_internal.get_name(),
-                            network,
-                            ip,
-                            err
+                            local_ufrag
                         );
-                        continue;
+                        match Arc::clone(tcp_mux)
+                            .get_conn_by_ufrag(&local_ufrag, ip)
+                            .await
+                        {
+                            Ok(conn) => match conn.local_addr() {
+                                Ok(addr) => conns.push((Some(conn), addr.port(), TcpType::Passive)),
+                                Err(err) => log::warn!(
+                                    "[{}]: could not get local addr: {}",
+                                    agent_internal.get_name(),
+                                    err
+                                ),
+                            },
+                            Err(err) => log::debug!(
+                                "[{}]: error getting tcp conn by ufrag: {} {} {}: {}",
+                                agent_internal.get_name(),
+                                network,
+                                ip,
+                                local_ufrag,
+                                err
+                            ),
+                        }
                     }
-                };
 
-                let port = match conn.local_addr() {
-                    Ok(addr) => addr.port(),
-                    Err(err) => {
-                        log::warn!(
-                            "[{}]: could not get local addr: {}",
-                            agent_internal.get_name(),
-                            err
-                        );
-                        continue;
+                    // Handle ICE TCP active mode. The candidate only announces that we can dial,
+                    // conns are made once the remote passive candidates are known.
+                    if let Ok(network_type) = determine_network_type(network, &ip) {
+                        if agent_internal
+                            .active_tcp_network_types
+                            .contains(&network_type)
+                        {
+                            // RFC 6544 section 4.5: active candidates use the discard port.
+                            conns.push((None, 9, TcpType::Active));
+                        }
                     }
-                };
+                } else if let UDPNetwork::Ephemeral(ephemeral_config) = &udp_network {
+                    let conn: Arc<dyn Conn> = match listen_udp_in_port_range(
+                        &net,
+                        ephemeral_config.port_max(),
+                        ephemeral_config.port_min(),
+                        SocketAddr::new(ip, 0),
+                    )
+                    .await
+                    {
+                        Ok(conn) => conn,
+                        Err(err) => {
+                            log::warn!(
+                                "[{}]: could not listen {} {}: {}",
+                                agent_internal.get_name(),
+                                network,
+                                ip,
+                                err
+                            );
+                            continue;
+                        }
+                    };
 
-                let host_config = CandidateHostConfig {
-                    base_config: CandidateBaseConfig {
-                        network: network.clone(),
-                        address,
-                        port,
-                        component: COMPONENT_RTP,
-                        conn: Some(conn),
-                        ..CandidateBaseConfig::default()
-                    },
-                    ..CandidateHostConfig::default()
-                };
+                    let port = match conn.local_addr() {
+                        Ok(addr) => addr.port(),
+                        Err(err) => {
+                            log::warn!(
+                                "[{}]: could not get local addr: {}",
+                                agent_internal.get_name(),
+                                err
+                            );
+                            continue;
+                        }
+                    };
 
-                let candidate: Arc<dyn Candidate> =
-                    match host_config.new_candidate_host() {
+                    conns.push((Some(conn), port, TcpType::Unspecified));
+                }
+
+                for (conn, port, tcp_type) in conns {
+                    let host_config = CandidateHostConfig {
+                        base_config: CandidateBaseConfig {
+                            network: network.to_owned(),
+                            address: address.clone(),
+                            port,
+                            component: COMPONENT_RTP,
+                            conn,
+                            ..CandidateBaseConfig::default()
+                        },
+                        tcp_type,
+                    };
+
+                    let candidate: Arc<dyn Candidate> = match host_config.new_candidate_host() {
                         Ok(candidate) => {
                             if mdns_mode == MulticastDnsMode::QueryAndGather {
                                 if let Err(err) = candidate.set_ip(&ip) {
@@ -348,7 +566,6 @@ impl Agent {
                         }
                     };
 
-                {
                     if let Err(err) = agent_internal.add_candidate(&candidate).await {
                         if let Err(close_err) = candidate.close().await {
                             log::warn!(
@@ -472,7 +689,7 @@ impl Agent {
             let ext_ip_mapper2 = Arc::clone(&ext_ip_mapper);
 
             let w = wg.worker();
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let _d = w;
 
                 let conn: Arc<dyn Conn> = match listen_udp_in_port_range(
@@ -578,6 +795,7 @@ impl Agent {
 
     async fn gather_candidates_srflx(params: GatherCandidatesSrflxParams) {
         let GatherCandidatesSrflxParams {
+            ips,
             urls,
             network_types,
             port_max,
@@ -592,25 +810,27 @@ impl Agent {
                 continue;
             }
 
-            for url in &urls {
+            let is_ipv4 = network_type.is_ipv4();
+            for (url, bind_ip) in urls
+                .iter()
+                .flat_map(|url| bind_ips(&ips, is_ipv4).into_iter().map(move |ip| (url, ip)))
+            {
                 let network = network_type.to_string();
-                let is_ipv4 = network_type.is_ipv4();
                 let url = url.clone();
                 let net2 = Arc::clone(&net);
                 let agent_internal2 = Arc::clone(&agent_internal);
 
                 let w = wg.worker();
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let _d = w;
 
-                    let host_port = format!("{}:{}", url.host, url.port);
-                    let server_addr = match net2.resolve_addr(is_ipv4, &host_port).await {
+                    let server_addr = match url.resolve_addr(&net2, is_ipv4).await {
                         Ok(addr) => addr,
                         Err(err) => {
                             log::warn!(
                                 "[{}]: failed to resolve stun host: {}: {}",
                                 agent_internal2.get_name(),
-                                host_port,
+                                url,
                                 err
                             );
                             return Ok(());
@@ -621,11 +841,7 @@ impl Agent {
                         &net2,
                         port_max,
                         port_min,
-                        if is_ipv4 {
-                            SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 0)
-                        } else {
-                            SocketAddr::new(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0).into(), 0)
-                        },
+                        SocketAddr::new(bind_ip, 0),
                     )
                     .await
                     {
@@ -713,14 +929,27 @@ impl Agent {
         wg.wait().await;
     }
 
+    /// Allocates relays on the TURN servers among urls. With ips, only from those interface
+    /// addresses, see [`Agent::gather_candidates_internal`].
     pub(crate) async fn gather_candidates_relay(
+        ips: Option<HashSet<IpAddr>>,
         urls: Vec<Url>,
+        network_types: Vec<NetworkType>,
         net: Arc<Net>,
         agent_internal: Arc<AgentInternal>,
     ) {
         let wg = WaitGroup::new();
 
-        for url in urls {
+        // The leg to the TURN server is dialed once per address family the agent gathers.
+        let families: HashSet<bool> = network_types
+            .iter()
+            .filter(|network_type| network_type.is_udp())
+            .map(|network_type| network_type.is_ipv4())
+            .collect();
+        for (url, is_ipv4) in urls
+            .iter()
+            .flat_map(|url| families.iter().map(move |is_ipv4| (url, *is_ipv4)))
+        {
             if url.scheme != SchemeType::Turn && url.scheme != SchemeType::Turns {
                 continue;
             }
@@ -741,112 +970,175 @@ impl Agent {
                 return;
             }
 
-            let network = NetworkType::Udp4.to_string();
-            let net2 = Arc::clone(&net);
-            let agent_internal2 = Arc::clone(&agent_internal);
+            // A TCP connection can't be bound to an interface, it is dialed once and only kept
+            // if it went out from one of ips.
+            let bind_ips = if url.proto == ProtoType::Udp {
+                bind_ips(&ips, is_ipv4)
+            } else {
+                bind_ips(&None, is_ipv4)
+            };
+            for bind_ip in bind_ips {
+                let url = url.clone();
+                let ips = ips.clone();
+                let net2 = Arc::clone(&net);
+                let agent_internal2 = Arc::clone(&agent_internal);
 
-            let w = wg.worker();
-            wasm_bindgen_futures::spawn_local(async move {
-                let _d = w;
+                let w = wg.worker();
+                deno_net::spawn(async move {
+                    let _d = w;
 
-                let turn_server_addr = format!("{}:{}", url.host, url.port);
+                    let turn_server_addr = match url.resolve_addr(&net2, is_ipv4).await {
+                        Ok(addr) => addr,
+                        Err(err) => {
+                            log::warn!(
+                                "[{}]: failed to resolve turn host: {}: {}",
+                                agent_internal2.get_name(),
+                                url,
+                                err
+                            );
+                            return Ok(());
+                        }
+                    };
 
-                let (loc_conn, rel_addr, rel_port) =
-                    if url.proto == ProtoType::Udp && url.scheme == SchemeType::Turn {
-                        let loc_conn = match net2.bind(SocketAddr::from_str("0.0.0.0:0")?).await {
-                            Ok(c) => c,
-                            Err(err) => {
+                    let (loc_conn, rel_addr, rel_port) =
+                        if url.proto == ProtoType::Udp && url.scheme == SchemeType::Turn {
+                            let loc_conn = match net2.bind(SocketAddr::new(bind_ip, 0)).await {
+                                Ok(c) => c,
+                                Err(err) => {
+                                    log::warn!(
+                                        "[{}]: Failed to listen due to error: {}",
+                                        agent_internal2.get_name(),
+                                        err
+                                    );
+                                    return Ok(());
+                                }
+                            };
+
+                            let local_addr = loc_conn.local_addr()?;
+                            let rel_addr = local_addr.ip().to_string();
+                            let rel_port = local_addr.port();
+                            (loc_conn, rel_addr, rel_port)
+                        } else if url.proto == ProtoType::Tcp {
+                            // Only the leg to the TURN server runs over TCP or TLS, the relayed
+                            // transport address still talks UDP to the peers.
+                            if net2.is_virtual() {
                                 log::warn!(
-                                    "[{}]: Failed to listen due to error: {}",
+                                    "[{}]: virtual networks don't carry TCP, skipping {}",
                                     agent_internal2.get_name(),
-                                    err
+                                    url
                                 );
                                 return Ok(());
                             }
-                        };
 
-                        let local_addr = loc_conn.local_addr()?;
-                        let rel_addr = local_addr.ip().to_string();
-                        let rel_port = local_addr.port();
-                        (loc_conn, rel_addr, rel_port)
-                    /*TODO: case url.proto == ProtoType::UDP && url.scheme == SchemeType::TURNS{
-                    case a.proxyDialer != nil && url.Proto == ProtoTypeTCP && (url.Scheme == SchemeTypeTURN || url.Scheme == SchemeTypeTURNS):
-                    case url.Proto == ProtoTypeTCP && url.Scheme == SchemeTypeTURN:
-                    case url.Proto == ProtoTypeTCP && url.Scheme == SchemeTypeTURNS:*/
-                    } else {
-                        log::warn!(
-                            "[{}]: Unable to handle URL in gather_candidates_relay {}",
-                            agent_internal2.get_name(),
-                            url
-                        );
-                        return Ok(());
-                    };
+                            let dialed = if url.scheme == SchemeType::Turns {
+                                StunConn::dial_tls(turn_server_addr, &url.host).await
+                            } else {
+                                StunConn::dial(turn_server_addr).await
+                            };
+                            let loc_conn = match dialed {
+                                Ok(c) => c,
+                                Err(err) => {
+                                    log::warn!(
+                                        "[{}]: Failed to dial turn server {}: {}",
+                                        agent_internal2.get_name(),
+                                        url,
+                                        err
+                                    );
+                                    return Ok(());
+                                }
+                            };
 
-                let cfg = turn::client::ClientConfig {
-                    stun_serv_addr: String::new(),
-                    turn_serv_addr: turn_server_addr.clone(),
-                    username: url.username,
-                    password: url.password,
-                    realm: String::new(),
-                    software: String::new(),
-                    rto_in_ms: 0,
-                    conn: loc_conn,
-                    vnet: Some(Arc::clone(&net2)),
-                };
-                let client = match turn::client::Client::new(cfg).await {
-                    Ok(client) => Arc::new(client),
-                    Err(err) => {
-                        log::warn!(
-                            "[{}]: Failed to build new turn.Client {} {}\n",
-                            agent_internal2.get_name(),
-                            turn_server_addr,
-                            err
-                        );
-                        return Ok(());
-                    }
-                };
-                if let Err(err) = client.listen().await {
-                    let _ = client.close().await;
-                    log::warn!(
-                        "[{}]: Failed to listen on turn.Client {} {}",
-                        agent_internal2.get_name(),
-                        turn_server_addr,
-                        err
-                    );
-                    return Ok(());
-                }
+                            let local_addr = loc_conn.local_addr()?;
+                            if ips
+                                .as_ref()
+                                .map_or(false, |ips| !ips.contains(&local_addr.ip()))
+                            {
+                                let _ = loc_conn.close().await;
+                                return Ok(());
+                            }
+                            let rel_addr = local_addr.ip().to_string();
+                            let rel_port = local_addr.port();
+                            let loc_conn: Arc<dyn Conn> = Arc::new(loc_conn);
+                            (loc_conn, rel_addr, rel_port)
+                        } else {
+                            log::warn!(
+                                "[{}]: Unable to handle URL in gather_candidates_relay {}",
+                                agent_internal2.get_name(),
+                                url
+                            );
+                            return Ok(());
+                        };
 
-                let relay_conn = match client.allocate().await {
-                    Ok(conn) => conn,
-                    Err(err) => {
+                    let cfg = turn::client::ClientConfig {
+                        stun_serv_addr: String::new(),
+                        turn_serv_addr: turn_server_addr.to_string(),
+                        username: url.username,
+                        password: url.password,
+                        realm: String::new(),
+                        software: String::new(),
+                        rto_in_ms: 0,
+                        conn: loc_conn,
+                        vnet: Some(Arc::clone(&net2)),
+                    };
+                    let client = match turn::client::Client::new(cfg).await {
+                        Ok(client) => Arc::new(client),
+                        Err(err) => {
+                            log::warn!(
+                                "[{}]: Failed to build new turn.Client {} {}\n",
+                                agent_internal2.get_name(),
+                                turn_server_addr,
+                                err
+                            );
+                            return Ok(());
+                        }
+                    };
+                    if let Err(err) = client.listen().await {
                         let _ = client.close().await;
                         log::warn!(
-                            "[{}]: Failed to allocate on turn.Client {} {}",
+                            "[{}]: Failed to listen on turn.Client {} {}",
                             agent_internal2.get_name(),
                             turn_server_addr,
                             err
                         );
                         return Ok(());
                     }
-                };
 
-                let raddr = relay_conn.local_addr()?;
-                let relay_config = CandidateRelayConfig {
-                    base_config: CandidateBaseConfig {
-                        network: network.clone(),
-                        address: raddr.ip().to_string(),
-                        port: raddr.port(),
-                        component: COMPONENT_RTP,
-                        conn: Some(Arc::new(relay_conn)),
-                        ..CandidateBaseConfig::default()
-                    },
-                    rel_addr,
-                    rel_port,
-                    relay_client: Some(Arc::clone(&client)),
-                };
+                    let relay_conn = match client.allocate().await {
+                        Ok(conn) => conn,
+                        Err(err) => {
+                            let _ = client.close().await;
+                            log::warn!(
+                                "[{}]: Failed to allocate on turn.Client {} {}",
+                                agent_internal2.get_name(),
+                                turn_server_addr,
+                                err
+                            );
+                            return Ok(());
+                        }
+                    };
 
-                let candidate: Arc<dyn Candidate> =
-                    match relay_config.new_candidate_relay() {
+                    let raddr = relay_conn.local_addr()?;
+                    let network = if raddr.is_ipv4() {
+                        NetworkType::Udp4
+                    } else {
+                        NetworkType::Udp6
+                    }
+                    .to_string();
+                    let relay_config = CandidateRelayConfig {
+                        base_config: CandidateBaseConfig {
+                            network: network.clone(),
+                            address: raddr.ip().to_string(),
+                            port: raddr.port(),
+                            component: COMPONENT_RTP,
+                            conn: Some(Arc::new(relay_conn)),
+                            ..CandidateBaseConfig::default()
+                        },
+                        rel_addr,
+                        rel_port,
+                        relay_client: Some(Arc::clone(&client)),
+                    };
+
+                    let candidate: Arc<dyn Candidate> = match relay_config.new_candidate_relay() {
                         Ok(candidate) => Arc::new(candidate),
                         Err(err) => {
                             let _ = client.close().await;
@@ -861,25 +1153,26 @@ impl Agent {
                         }
                     };
 
-                {
-                    if let Err(err) = agent_internal2.add_candidate(&candidate).await {
-                        if let Err(close_err) = candidate.close().await {
+                    {
+                        if let Err(err) = agent_internal2.add_candidate(&candidate).await {
+                            if let Err(close_err) = candidate.close().await {
+                                log::warn!(
+                                    "[{}]: Failed to close candidate: {}",
+                                    agent_internal2.get_name(),
+                                    close_err
+                                );
+                            }
                             log::warn!(
-                                "[{}]: Failed to close candidate: {}",
+                                "[{}]: Failed to append to localCandidates and run onCandidateHdlr: {}",
                                 agent_internal2.get_name(),
-                                close_err
+                                err
                             );
                         }
-                        log::warn!(
-                            "[{}]: Failed to append to localCandidates and run onCandidateHdlr: {}",
-                            agent_internal2.get_name(),
-                            err
-                        );
                     }
-                }
 
-                Result::<()>::Ok(())
-            });
+                    Result::<()>::Ok(())
+                });
+            }
         }
 
         wg.wait().await;
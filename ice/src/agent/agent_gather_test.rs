@@ -1,14 +1,19 @@
 use super::agent_vnet_test::*;
 use super::*;
+use crate::candidate::candidate_base::CandidateBaseConfig;
+use crate::candidate::candidate_relay::CandidateRelayConfig;
+use crate::candidate::candidate_server_reflexive::CandidateServerReflexiveConfig;
 use crate::udp_mux::{UDPMuxDefault, UDPMuxParams};
 use crate::util::*;
 
 use ipnet::IpNet;
+use std::collections::HashSet;
+use std::net::IpAddr;
 use std::str::FromStr;
 use deno_net::DatagramConn as UdpSocket;
 use util::vnet::*;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_no_local_ip_address() -> Result<()> {
     let vnet = Arc::new(net::Net::new(Some(net::NetConfig::default())));
 
@@ -32,7 +37,7 @@ async fn test_vnet_gather_no_local_ip_address() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_dynamic_ip_address() -> Result<()> {
     let cider = "1.2.3.0/24";
     let ipnet = IpNet::from_str(cider).map_err(|e| Error::Other(e.to_string()))?;
@@ -68,7 +73,7 @@ async fn test_vnet_gather_dynamic_ip_address() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_listen_udp() -> Result<()> {
     let cider = "1.2.3.0/24";
     let r = Arc::new(Mutex::new(router::Router::new(router::RouterConfig {
@@ -111,7 +116,7 @@ async fn test_vnet_gather_listen_udp() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_with_nat_1to1_as_host_candidates() -> Result<()> {
     let external_ip0 = "1.2.3.4";
     let external_ip1 = "1.2.3.5";
@@ -234,7 +239,7 @@ async fn test_vnet_gather_with_nat_1to1_as_host_candidates() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_with_nat_1to1_as_srflx_candidates() -> Result<()> {
     let wan = Arc::new(Mutex::new(router::Router::new(router::RouterConfig {
         cidr: "1.2.3.0/24".to_owned(),
@@ -319,7 +324,7 @@ async fn test_vnet_gather_with_nat_1to1_as_srflx_candidates() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_with_interface_filter() -> Result<()> {
     let r = Arc::new(Mutex::new(router::Router::new(router::RouterConfig {
         cidr: "1.2.3.0/24".to_owned(),
@@ -375,7 +380,7 @@ async fn test_vnet_gather_with_interface_filter() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_turn_connection_leak() -> Result<()> {
     let turn_server_url = Url {
         scheme: SchemeType::Turn,
@@ -384,6 +389,7 @@ async fn test_vnet_gather_turn_connection_leak() -> Result<()> {
         username: "user".to_owned(),
         password: "pass".to_owned(),
         proto: ProtoType::Udp,
+        explicit_port: true,
     };
 
     // buildVNet with a Symmetric NATs for both LANs
@@ -409,7 +415,9 @@ async fn test_vnet_gather_turn_connection_leak() -> Result<()> {
     {
         let agent_internal = Arc::clone(&a_agent.internal);
         Agent::gather_candidates_relay(
+            None,
             vec![turn_server_url.clone()],
+            vec![NetworkType::Udp4],
             Arc::clone(&v.net0),
             agent_internal,
         )
@@ -423,7 +431,60 @@ async fn test_vnet_gather_turn_connection_leak() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
+async fn test_vnet_gather_turn_address_family() -> Result<()> {
+    let turn_server_url = Url {
+        scheme: SchemeType::Turn,
+        host: VNET_STUN_SERVER_IP.to_owned(),
+        port: VNET_STUN_SERVER_PORT,
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+        proto: ProtoType::Udp,
+        explicit_port: true,
+    };
+
+    let v = build_vnet(Default::default(), Default::default()).await?;
+
+    let cfg0 = AgentConfig {
+        urls: vec![turn_server_url.clone()],
+        network_types: supported_network_types(),
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        net: Some(Arc::clone(&v.net0)),
+        ..Default::default()
+    };
+    let a_agent = Agent::new(cfg0).await?;
+
+    // The server only has an IPv4 address, an IPv6 only agent has no way to reach it.
+    Agent::gather_candidates_relay(
+        None,
+        vec![turn_server_url.clone()],
+        vec![NetworkType::Udp6],
+        Arc::clone(&v.net0),
+        Arc::clone(&a_agent.internal),
+    )
+    .await;
+    assert!(a_agent.get_local_candidates().await?.is_empty());
+
+    Agent::gather_candidates_relay(
+        None,
+        vec![turn_server_url],
+        vec![NetworkType::Udp4],
+        Arc::clone(&v.net0),
+        Arc::clone(&a_agent.internal),
+    )
+    .await;
+    let candidates = a_agent.get_local_candidates().await?;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].candidate_type(), CandidateType::Relay);
+    assert_eq!(candidates[0].network_type(), NetworkType::Udp4);
+
+    a_agent.close().await?;
+    v.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_vnet_gather_muxed_udp() -> Result<()> {
     let udp_socket = UdpSocket::bind("0.0.0.0:0").await?;
     let udp_mux = UDPMuxDefault::new(UDPMuxParams::new(udp_socket));
@@ -488,3 +549,111 @@ async fn test_vnet_gather_muxed_udp() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "local")]
+async fn test_vnet_gather_continually() -> Result<()> {
+    let lan = Arc::new(Mutex::new(router::Router::new(router::RouterConfig {
+        cidr: "10.0.0.0/24".to_owned(),
+        ..Default::default()
+    })?));
+
+    let nw = Arc::new(net::Net::new(Some(net::NetConfig {
+        static_ips: vec!["10.0.0.1".to_owned()],
+        ..Default::default()
+    })));
+
+    connect_net2router(&nw, &lan).await?;
+
+    let a = Agent::new(AgentConfig {
+        network_types: vec![NetworkType::Udp4],
+        candidate_types: vec![CandidateType::Host],
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        net: Some(Arc::clone(&nw)),
+        continual_gathering_policy: ContinualGatheringPolicy::GatherContinually,
+        network_monitor_interval: Some(Duration::from_secs(0)),
+        ..Default::default()
+    })
+    .await?;
+
+    // Nothing to monitor before gathering.
+    assert_eq!(a.update_network(), Err(Error::ErrNoNetworkMonitor));
+
+    let (candidate_tx, mut candidate_rx) = mpsc::unbounded_channel::<Option<String>>();
+    a.on_candidate(Box::new(move |c: Option<Arc<dyn Candidate>>| {
+        let _ = candidate_tx.send(c.map(|c| c.address()));
+        Box::pin(async move {})
+    }));
+
+    a.gather_candidates()?;
+    assert_eq!(candidate_rx.recv().await, Some(Some("10.0.0.1".to_owned())));
+    assert_eq!(candidate_rx.recv().await, Some(None));
+
+    let candidates = a.get_local_candidates().await?;
+    assert_eq!(candidates.len(), 1, "There must be a single host candidate");
+    assert_eq!(candidates[0].address(), "10.0.0.1");
+
+    // An interface shows up, its candidate is emitted without gathering again.
+    {
+        let nic = nw.get_nic()?;
+        let mut nic = nic.lock().await;
+        let ipnet = IpNet::from_str("10.0.0.2/24").map_err(|e| Error::Other(e.to_string()))?;
+        nic.add_addrs_to_interface("eth0", &[ipnet]).await?;
+    }
+    a.update_network()?;
+    let emitted = deno_net::timeout(Duration::from_secs(5), candidate_rx.recv()).await;
+    assert_eq!(emitted, Ok(Some(Some("10.0.0.2".to_owned()))));
+
+    // Server reflexive and relay candidates gathered from the first interface.
+    let srflx: Arc<dyn Candidate> = Arc::new(
+        CandidateServerReflexiveConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: "1.2.3.4".to_owned(),
+                port: 12340,
+                component: 1,
+                conn: Some(nw.bind(SocketAddr::from_str("10.0.0.1:0")?).await?),
+                ..Default::default()
+            },
+            rel_addr: "10.0.0.1".to_owned(),
+            rel_port: 10001,
+        }
+        .new_candidate_server_reflexive()?,
+    );
+    let relay: Arc<dyn Candidate> = Arc::new(
+        CandidateRelayConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: "1.2.3.5".to_owned(),
+                port: 12350,
+                component: 1,
+                conn: Some(nw.bind(SocketAddr::from_str("10.0.0.1:0")?).await?),
+                ..Default::default()
+            },
+            rel_addr: "10.0.0.1".to_owned(),
+            rel_port: 10002,
+            ..Default::default()
+        }
+        .new_candidate_relay()?,
+    );
+    a.internal.add_candidate(&srflx).await?;
+    a.internal.add_candidate(&relay).await?;
+    assert_eq!(a.get_local_candidates().await?.len(), 4);
+
+    // The first interface went away, and all candidates on it with it.
+    let mut ips = HashSet::new();
+    ips.insert(IpAddr::from_str("10.0.0.1")?);
+    a.internal.remove_local_candidates_on(&ips).await;
+
+    let candidates = a.get_local_candidates().await?;
+    assert_eq!(
+        candidates.len(),
+        1,
+        "Only the candidate of the new interface must be left"
+    );
+    assert_eq!(candidates[0].address(), "10.0.0.2");
+
+    a.close().await?;
+    assert_eq!(a.update_network(), Err(Error::ErrNoNetworkMonitor));
+
+    Ok(())
+}
@@ -1,15 +1,42 @@
+use super::agent_selector::NominationStrategy;
 use super::agent_transport::*;
 use super::*;
 use crate::candidate::candidate_base::CandidateBaseConfig;
+use crate::candidate::candidate_host::CandidateHostConfig;
 use crate::candidate::candidate_peer_reflexive::CandidatePeerReflexiveConfig;
 use crate::util::*;
 use arc_swap::ArcSwapOption;
+use std::collections::HashSet;
+use std::net::IpAddr;
 use std::sync::atomic::{AtomicBool, AtomicU64};
+use util::conn::conn_tcp::TcpConn;
 use util::sync::Mutex as SyncMutex;
 
 pub type ChanCandidateTx =
     Arc<Mutex<Option<mpsc::Sender<Option<Arc<dyn Candidate>>>>>>;
 
+/// RFC 7675 consent freshness of the selected pair.
+pub(crate) struct Consent {
+    // When an authenticated response last arrived on the selected pair
+    pub(crate) refreshed_at: Instant,
+    // When the oldest binding request that got no response yet was sent
+    pub(crate) unanswered_since: Option<Instant>,
+    pub(crate) last_request_at: Option<Instant>,
+    pub(crate) next_check_at: Instant,
+}
+
+impl Consent {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            refreshed_at: now,
+            unanswered_since: None,
+            last_request_at: None,
+            next_check_at: now,
+        }
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct UfragPwd {
     pub(crate) local_ufrag: String,
@@ -33,6 +60,8 @@ pub struct AgentInternal {
     pub(crate) chan_candidate_tx: ChanCandidateTx,
     pub(crate) chan_candidate_pair_tx: Mutex<Option<mpsc::Sender<()>>>,
     pub(crate) chan_state_tx: Mutex<Option<mpsc::Sender<ConnectionState>>>,
+    // Triggers the network monitor of continual gathering, which stops when it is dropped
+    pub(crate) network_monitor_tx: SyncMutex<Option<mpsc::Sender<()>>>,
 
     pub(crate) on_connection_state_change_hdlr: ArcSwapOption<Mutex<OnConnectionStateChangeHdlrFn>>,
     pub(crate) on_selected_candidate_pair_change_hdlr:
@@ -68,6 +97,9 @@ pub struct AgentInternal {
     pub(crate) srflx_acceptance_min_wait: Duration,
     pub(crate) prflx_acceptance_min_wait: Duration,
     pub(crate) relay_acceptance_min_wait: Duration,
+    pub(crate) nomination_strategy: NominationStrategy,
+    pub(crate) renomination: bool,
+    pub(crate) remote_renomination: AtomicBool,
     // How long connectivity checks can fail before the ICE Agent
     // goes to disconnected
     pub(crate) disconnected_timeout: Duration,
@@ -77,8 +109,16 @@ pub struct AgentInternal {
     // How often should we send keepalive packets?
     // 0 means never
     pub(crate) keepalive_interval: Duration,
+    // How often consent to send on the selected pair is checked
+    pub(crate) consent_check_interval: Duration,
+    // How long without an authenticated response till consent expires
+    // 0 means consent freshness is disabled
+    pub(crate) consent_timeout: Duration,
+    pub(crate) consent: SyncMutex<Consent>,
     // How often should we run our internal taskLoop to check for state changes when connecting
     pub(crate) check_interval: Duration,
+    // The network types we dial remote passive TCP candidates on, empty if active TCP is disabled
+    pub(crate) active_tcp_network_types: Vec<NetworkType>,
 }
 
 impl AgentInternal {
@@ -105,6 +145,7 @@ impl AgentInternal {
             chan_candidate_tx: Arc::new(Mutex::new(Some(chan_candidate_tx))),
             chan_candidate_pair_tx: Mutex::new(Some(chan_candidate_pair_tx)),
             chan_state_tx: Mutex::new(Some(chan_state_tx)),
+            network_monitor_tx: SyncMutex::new(None),
 
             on_connection_state_change_hdlr: ArcSwapOption::empty(),
             on_selected_candidate_pair_change_hdlr: ArcSwapOption::empty(),
@@ -121,6 +162,17 @@ impl AgentInternal {
 
             insecure_skip_verify: config.insecure_skip_verify,
 
+            active_tcp_network_types: if config.disable_active_tcp || config.lite {
+                vec![]
+            } else {
+                config
+                    .network_types
+                    .iter()
+                    .copied()
+                    .filter(|n| n.is_tcp())
+                    .collect()
+            },
+
             started_ch_tx: Mutex::new(Some(started_ch_tx)),
 
             //won't change after init_with_defaults()
@@ -129,6 +181,9 @@ impl AgentInternal {
             srflx_acceptance_min_wait: Duration::from_secs(0),
             prflx_acceptance_min_wait: Duration::from_secs(0),
             relay_acceptance_min_wait: Duration::from_secs(0),
+            nomination_strategy: config.nomination_strategy.clone(),
+            renomination: config.renomination,
+            remote_renomination: AtomicBool::new(false),
 
             // How long connectivity checks can fail before the ICE Agent
             // goes to disconnected
@@ -142,6 +197,10 @@ impl AgentInternal {
             // 0 means never
             keepalive_interval: Duration::from_secs(0),
 
+            consent_check_interval: Duration::from_secs(0),
+            consent_timeout: Duration::from_secs(0),
+            consent: SyncMutex::new(Consent::new()),
+
             // How often should we run our internal taskLoop to check for state changes when connecting
             check_interval: Duration::from_secs(0),
 
@@ -257,7 +316,7 @@ impl AgentInternal {
             done_and_force_candidate_contact_rx
         {
             let ai = Arc::clone(self);
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 loop {
                     let mut interval = DEFAULT_CHECK_INTERVAL;
 
@@ -335,7 +394,19 @@ impl AgentInternal {
 
         if let Some(p) = p {
             p.nominated.store(true, Ordering::SeqCst);
-            self.agent_conn.selected_pair.store(Some(p));
+            let previous = self.agent_conn.selected_pair.swap(Some(Arc::clone(&p)));
+            if let Some(previous) = &previous {
+                if *previous != p {
+                    previous.nominated.store(false, Ordering::SeqCst);
+                }
+            }
+            // The checks that got the pair selected granted consent on it.
+            if previous.map_or(true, |previous| previous != p) {
+                *self.consent.lock() = Consent::new();
+                self.agent_conn
+                    .consent_expired
+                    .store(false, Ordering::SeqCst);
+            }
 
             self.update_connection_state(ConnectionState::Connected)
                 .await;
@@ -410,6 +481,10 @@ impl AgentInternal {
         local: Arc<dyn Candidate>,
         remote: Arc<dyn Candidate>,
     ) {
+        if !can_pair(&*local, &*remote) {
+            return;
+        }
+
         let p = Arc::new(CandidatePair::new(
             local,
             remote,
@@ -450,18 +525,46 @@ impl AgentInternal {
         };
 
         if valid {
+            let (consent_expired, unanswered_time) = {
+                let consent = self.consent.lock();
+                (
+                    self.consent_timeout != Duration::from_secs(0)
+                        && consent.refreshed_at.elapsed() > self.consent_timeout,
+                    consent
+                        .unanswered_since
+                        .map_or(Duration::from_secs(0), |t| t.elapsed()),
+                )
+            };
+
             // Only allow transitions to failed if a.failedTimeout is non-zero
             let mut total_time_to_failure = self.failed_timeout;
             if total_time_to_failure != Duration::from_secs(0) {
                 total_time_to_failure += self.disconnected_timeout;
             }
 
-            if total_time_to_failure != Duration::from_secs(0)
+            if consent_expired {
+                // RFC 7675 section 5.1: traffic other than the authenticated responses
+                // doesn't keep the consent, so it fails even while packets still come in.
+                log::warn!(
+                    "[{}]: consent expired, no authenticated response for {:?}",
+                    self.get_name(),
+                    self.consent_timeout
+                );
+                if let Some(selected_pair) = self.agent_conn.get_selected_pair() {
+                    let mut activity = selected_pair.activity.lock();
+                    activity.consent_expired.get_or_insert_with(Instant::now);
+                }
+                self.agent_conn
+                    .consent_expired
+                    .store(true, Ordering::SeqCst);
+                self.update_connection_state(ConnectionState::Failed).await;
+            } else if total_time_to_failure != Duration::from_secs(0)
                 && disconnected_time > total_time_to_failure
             {
                 self.update_connection_state(ConnectionState::Failed).await;
             } else if self.disconnected_timeout != Duration::from_secs(0)
-                && disconnected_time > self.disconnected_timeout
+                && (disconnected_time > self.disconnected_timeout
+                    || unanswered_time > self.disconnected_timeout)
             {
                 self.update_connection_state(ConnectionState::Disconnected)
                     .await;
@@ -474,8 +577,9 @@ impl AgentInternal {
         valid
     }
 
-    /// Sends STUN Binding Indications to the selected pair.
-    /// if no packet has been sent on that pair in the last keepaliveInterval.
+    /// Sends STUN Binding Requests to the selected pair when a consent freshness check is due,
+    /// or if no packet has been sent or received on that pair in the last keepaliveInterval.
+    /// At most one keepalive is sent per keepaliveInterval.
     /// Note: the caller should hold the agent lock.
     pub(crate) async fn check_keepalive(&self) {
         let (local, remote) = {
@@ -499,10 +603,31 @@ impl AgentInternal {
                 .duration_since(remote.last_received())
                 .unwrap_or_else(|_| Duration::from_secs(0));
 
-            if (self.keepalive_interval != Duration::from_secs(0))
-                && ((last_sent > self.keepalive_interval)
-                    || (last_received > self.keepalive_interval))
-            {
+            let now = Instant::now();
+            let send = {
+                let mut consent = self.consent.lock();
+                let consent_check_due =
+                    self.consent_timeout != Duration::from_secs(0) && now >= consent.next_check_at;
+                let keepalive_due = (self.keepalive_interval != Duration::from_secs(0))
+                    && ((last_sent > self.keepalive_interval)
+                        || (last_received > self.keepalive_interval))
+                    && consent.last_request_at.map_or(true, |t| {
+                        now.checked_duration_since(t)
+                            .unwrap_or_else(|| Duration::from_secs(0))
+                            >= self.keepalive_interval
+                    });
+
+                if consent_check_due || keepalive_due {
+                    consent.last_request_at = Some(now);
+                    consent.unanswered_since.get_or_insert(now);
+                    // RFC 7675 section 5.1: randomized between 0.8 and 1.2 times the interval
+                    let jitter = rand::random::<f64>() * 0.4 + 0.8;
+                    consent.next_check_at = now + self.consent_check_interval.mul_f64(jitter);
+                }
+                consent_check_due || keepalive_due
+            };
+
+            if send {
                 // we use binding request instead of indication to support refresh consent schemas
                 // see https://tools.ietf.org/html/rfc7675
                 self.ping_candidate(&local, &remote).await;
@@ -510,12 +635,26 @@ impl AgentInternal {
         }
     }
 
+    /// Refreshes the consent on the selected pair, if p is that pair. Only to be called for
+    /// authenticated success responses to our own binding requests.
+    pub(crate) fn refresh_consent(&self, p: &Arc<CandidatePair>) {
+        let is_selected_pair = self
+            .agent_conn
+            .get_selected_pair()
+            .map_or(false, |selected_pair| selected_pair == *p);
+        if is_selected_pair {
+            let mut consent = self.consent.lock();
+            consent.refreshed_at = Instant::now();
+            consent.unanswered_since = None;
+        }
+    }
+
     fn request_connectivity_check(&self) {
         let _ = self.force_candidate_contact_tx.try_send(true);
     }
 
     /// Assumes you are holding the lock (must be execute using a.run).
-    pub(crate) async fn add_remote_candidate(&self, c: &Arc<dyn Candidate>) {
+    pub(crate) async fn add_remote_candidate(self: &Arc<Self>, c: &Arc<dyn Candidate>) {
         let network_type = c.network_type();
 
         {
@@ -547,9 +686,88 @@ impl AgentInternal {
             self.add_pair(cand, c.clone()).await;
         }
 
+        if c.tcp_type() == TcpType::Passive && self.active_tcp_network_types.contains(&network_type)
+        {
+            self.dial_passive_tcp_candidate(c);
+        }
+
         self.request_connectivity_check();
     }
 
+    /// Connects to a remote passive TCP candidate and pairs it with an active local candidate
+    /// over the new connection.
+    fn dial_passive_tcp_candidate(self: &Arc<Self>, remote: &Arc<dyn Candidate>) {
+        let ai = Arc::clone(self);
+        let remote = Arc::clone(remote);
+        deno_net::spawn(async move {
+            let conn = match TcpConn::dial(remote.addr()).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    log::warn!(
+                        "[{}]: Failed to dial remote passive candidate {}: {}",
+                        ai.get_name(),
+                        remote,
+                        err
+                    );
+                    return;
+                }
+            };
+
+            let local_addr = match util::Conn::local_addr(&conn) {
+                Ok(addr) => addr,
+                Err(err) => {
+                    log::warn!("[{}]: could not get local addr: {}", ai.get_name(), err);
+                    let _ = util::Conn::close(&conn).await;
+                    return;
+                }
+            };
+
+            let host_config = CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: TCP.to_owned(),
+                    address: local_addr.ip().to_string(),
+                    port: local_addr.port(),
+                    component: remote.component(),
+                    conn: Some(Arc::new(conn)),
+                    ..CandidateBaseConfig::default()
+                },
+                tcp_type: TcpType::Active,
+            };
+
+            let local: Arc<dyn Candidate> = match host_config.new_candidate_host() {
+                Ok(candidate) => Arc::new(candidate),
+                Err(err) => {
+                    log::warn!(
+                        "[{}]: Failed to create active tcp candidate for {}: {}",
+                        ai.get_name(),
+                        remote,
+                        err
+                    );
+                    return;
+                }
+            };
+
+            // Not announced through on_candidate, the remote learns about it as a
+            // peer reflexive candidate from our binding requests.
+            let initialized_ch = {
+                let started_ch_tx = ai.started_ch_tx.lock().await;
+                (*started_ch_tx).as_ref().map(|tx| tx.subscribe())
+            };
+            ai.start_candidate(&local, initialized_ch).await;
+
+            {
+                let mut local_candidates = ai.local_candidates.lock().await;
+                local_candidates
+                    .entry(local.network_type())
+                    .or_default()
+                    .push(Arc::clone(&local));
+            }
+
+            ai.add_pair(local, remote).await;
+            ai.request_connectivity_check();
+        });
+    }
+
     pub(crate) async fn add_candidate(
         self: &Arc<Self>,
         c: &Arc<dyn Candidate>,
@@ -618,6 +836,7 @@ impl AgentInternal {
             }
             done_tx.take();
         };
+        self.network_monitor_tx.lock().take();
         self.delete_all_candidates().await;
         {
             let mut started_ch_tx = self.started_ch_tx.lock().await;
@@ -686,6 +905,74 @@ impl AgentInternal {
         }
     }
 
+    /// Removes the local candidates on the given interface addresses, along with their pairs.
+    /// Server reflexive and relay candidates are on the interface of their related address.
+    /// If the selected pair is among them, the agent goes back to checking the remaining pairs.
+    pub(crate) async fn remove_local_candidates_on(&self, ips: &HashSet<IpAddr>) {
+        let mut removed: Vec<Arc<dyn Candidate>> = vec![];
+        {
+            let mut local_candidates = self.local_candidates.lock().await;
+            for cs in local_candidates.values_mut() {
+                cs.retain(|c| {
+                    let base_ip = match c.candidate_type() {
+                        CandidateType::Host => Some(c.addr().ip()),
+                        CandidateType::ServerReflexive | CandidateType::Relay => c
+                            .related_address()
+                            .and_then(|related| related.address.parse::<IpAddr>().ok()),
+                        _ => None,
+                    };
+                    let on_removed_ip = base_ip.map_or(false, |ip| ips.contains(&ip));
+                    if on_removed_ip {
+                        removed.push(Arc::clone(c));
+                    }
+                    !on_removed_ip
+                });
+            }
+        }
+        if removed.is_empty() {
+            return;
+        }
+
+        for c in &removed {
+            log::debug!("[{}]: removing local candidate {}", self.get_name(), c);
+            if let Err(err) = c.close().await {
+                log::warn!(
+                    "[{}]: Failed to close candidate {}: {}",
+                    self.get_name(),
+                    c,
+                    err
+                );
+            }
+        }
+
+        let is_removed = |p: &CandidatePair| removed.iter().any(|c| p.local.equal(&**c));
+        {
+            let mut checklist = self.agent_conn.checklist.lock().await;
+            checklist.retain(|p| !is_removed(p));
+        }
+        {
+            let mut nominated_pair = self.nominated_pair.lock().await;
+            if nominated_pair.as_ref().map_or(false, |p| is_removed(p)) {
+                *nominated_pair = None;
+            }
+        }
+
+        let selected_pair_removed = self
+            .agent_conn
+            .get_selected_pair()
+            .map_or(false, |p| is_removed(&p));
+        if selected_pair_removed {
+            log::info!(
+                "[{}]: the selected candidate pair went away with its interface",
+                self.get_name()
+            );
+            self.set_selected_pair(None).await;
+            self.update_connection_state(ConnectionState::Checking)
+                .await;
+            self.request_connectivity_check();
+        }
+    }
+
     pub(crate) async fn find_remote_candidate(
         &self,
         network_type: NetworkType,
@@ -729,6 +1016,16 @@ impl AgentInternal {
             });
         }
 
+        if let Some(p) = self.find_pair(local, remote).await {
+            // Once a pair is selected, the checks on it are what keeps the consent to send.
+            let consent = self.consent_timeout != Duration::from_secs(0)
+                && self
+                    .agent_conn
+                    .get_selected_pair()
+                    .map_or(false, |selected_pair| selected_pair == p);
+            p.on_request_sent(consent);
+        }
+
         self.send_stun(m, local, remote).await;
     }
 
@@ -767,6 +1064,9 @@ impl AgentInternal {
             );
         } else {
             self.send_stun(&out, local, remote).await;
+            if let Some(p) = self.find_pair(local, remote).await {
+                p.on_request_answered();
+            }
         }
     }
 
@@ -821,7 +1121,7 @@ impl AgentInternal {
 
     /// Processes STUN traffic from a remote candidate.
     pub(crate) async fn handle_inbound(
-        &self,
+        self: &Arc<Self>,
         m: &mut Message,
         local: &Arc<dyn Candidate>,
         remote: SocketAddr,
@@ -920,7 +1220,7 @@ impl AgentInternal {
             }
 
             if remote_candidate.is_none() {
-                let (ip, port, network_type) = (remote.ip(), remote.port(), NetworkType::Udp4);
+                let (ip, port, network_type) = (remote.ip(), remote.port(), local.network_type());
 
                 let prflx_candidate_config = CandidatePeerReflexiveConfig {
                     base_config: CandidateBaseConfig {
@@ -1039,12 +1339,13 @@ impl AgentInternal {
             let conn = Arc::clone(conn);
             let addr = candidate.addr();
             let ai = Arc::clone(self);
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let _ = ai
                     .recv_loop(cand, closed_ch_rx, initialized_ch, conn, addr)
                     .await;
             });
-        } else {
+        } else if candidate.tcp_type() != TcpType::Active {
+            // Gathered active TCP candidates have no conn, they are dialed per remote candidate.
             log::error!("[{}]: Can't start due to conn is_none", self.get_name(),);
         }
     }
@@ -1056,7 +1357,7 @@ impl AgentInternal {
         mut chan_candidate_pair_rx: mpsc::Receiver<()>,
     ) {
         let ai = Arc::clone(self);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             // CandidatePair and ConnectionState are usually changed at once.
             // Blocking one by the other one causes deadlock.
             while chan_candidate_pair_rx.recv().await.is_some() {
@@ -1071,7 +1372,7 @@ impl AgentInternal {
         });
 
         let ai = Arc::clone(self);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             loop {
                 tokio::select! {
                     opt_state = chan_state_rx.recv() => {
@@ -1183,6 +1484,11 @@ impl AgentInternal {
         } else if let Err(err) = self.agent_conn.buffer.write(buf).await {
             // NOTE This will return packetio.ErrFull if the buffer ever manages to fill up.
             log::warn!("[{}]: failed to write packet: {}", self.get_name(), err);
+        } else if let Some(selected_pair) = self.agent_conn.get_selected_pair() {
+            // Application data is only expected on the selected pair.
+            if selected_pair.local.equal(&**c) && selected_pair.remote.addr() == src_addr {
+                selected_pair.on_packet_received(buf.len());
+            }
         }
     }
 
@@ -1194,3 +1500,21 @@ impl AgentInternal {
         }
     }
 }
+
+/// Whether a connectivity check from local to remote can ever succeed. TCP candidates only
+/// reach each other when one side dials the other, see RFC 6544 section 6.2.
+fn can_pair(local: &dyn Candidate, remote: &dyn Candidate) -> bool {
+    if !local.network_type().is_tcp() {
+        return true;
+    }
+
+    match local.tcp_type() {
+        // Dialed active candidates only reach the remote they were dialed to, and the gathered
+        // ones without a conn only advertise that we can dial.
+        TcpType::Active => local
+            .get_conn()
+            .map_or(false, |conn| conn.remote_addr() == Some(remote.addr())),
+        TcpType::Passive => remote.tcp_type() != TcpType::Passive,
+        TcpType::SimultaneousOpen | TcpType::Unspecified => true,
+    }
+}
@@ -7,10 +7,54 @@ use crate::use_candidate::*;
 use stun::{agent::*, attributes::*, fingerprint::*, integrity::*, message::*, textattrs::*};
 
 use async_trait::async_trait;
+use std::fmt;
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::time::{Duration, Instant};
+use deno_net::Instant;
+use std::time::Duration;
+
+/// How much lower the round trip time of a pair has to be for the RTT-weighted strategy to
+/// renominate it, so the selected pair doesn't flap between pairs that are about as fast.
+pub(crate) const RENOMINATION_RTT_MARGIN: Duration = Duration::from_millis(10);
+
+/// Decides if the controlling agent nominates a valid pair. The second argument is the
+/// selected pair, so returning true for a pair other than it renominates.
+pub type NominationFn = Arc<dyn Fn(&CandidatePair, Option<&CandidatePair>) -> bool>;
+
+/// NominationStrategy controls how the controlling agent nominates a candidate pair.
+#[derive(Clone)]
+pub enum NominationStrategy {
+    /// Nominates the valid pair with the highest priority once the acceptance min wait of its
+    /// candidates passed, with a separate check carrying USE-CANDIDATE.
+    /// https://tools.ietf.org/html/rfc8445#section-8.1.1
+    Regular,
+    /// Sets USE-CANDIDATE on every check, so the first pair to succeed is selected.
+    /// https://tools.ietf.org/html/rfc5245#section-8.1.1.2
+    Aggressive,
+    /// Like Regular, but nominates the valid pair with the lowest round trip time.
+    RttWeighted,
+    /// Like Regular, but nominates the first valid pair, by priority, the callback accepts.
+    Custom(NominationFn),
+}
+
+impl Default for NominationStrategy {
+    fn default() -> Self {
+        NominationStrategy::Regular
+    }
+}
+
+impl fmt::Debug for NominationStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            NominationStrategy::Regular => "Regular",
+            NominationStrategy::Aggressive => "Aggressive",
+            NominationStrategy::RttWeighted => "RttWeighted",
+            NominationStrategy::Custom(_) => "Custom",
+        };
+        write!(f, "{}", s)
+    }
+}
 
 #[async_trait(?Send)]
 trait ControllingSelector {
@@ -154,6 +198,130 @@ impl AgentInternal {
         }
     }
 
+    /// Returns the valid pair the nomination strategy would nominate now.
+    pub(crate) async fn nomination_candidate(&self) -> Option<Arc<CandidatePair>> {
+        let selected_pair = self.agent_conn.get_selected_pair();
+        let mut valid_pairs: Vec<Arc<CandidatePair>> = {
+            let checklist = self.agent_conn.checklist.lock().await;
+            checklist
+                .iter()
+                .filter(|p| p.state.load(Ordering::SeqCst) == CandidatePairState::Succeeded as u8)
+                .cloned()
+                .collect()
+        };
+        valid_pairs.sort_by_key(|p| std::cmp::Reverse(p.priority()));
+
+        let best = match &self.nomination_strategy {
+            NominationStrategy::Regular | NominationStrategy::Aggressive => {
+                valid_pairs.into_iter().next()
+            }
+            NominationStrategy::RttWeighted => valid_pairs
+                .into_iter()
+                .enumerate()
+                .min_by_key(|(i, p)| (p.current_round_trip_time().unwrap_or(Duration::MAX), *i))
+                .map(|(_, p)| p),
+            NominationStrategy::Custom(f) => valid_pairs
+                .into_iter()
+                .find(|p| f(p, selected_pair.as_deref())),
+        }?;
+
+        if self.is_nominatable(&best.local) && self.is_nominatable(&best.remote) {
+            Some(best)
+        } else {
+            None
+        }
+    }
+
+    /// Tells if candidate should replace the selected pair.
+    pub(crate) fn is_better_pair(
+        &self,
+        candidate: &CandidatePair,
+        selected: &CandidatePair,
+    ) -> bool {
+        if candidate == selected {
+            return false;
+        }
+
+        match &self.nomination_strategy {
+            NominationStrategy::Regular | NominationStrategy::Aggressive => {
+                candidate.priority() > selected.priority()
+            }
+            NominationStrategy::RttWeighted => {
+                match (
+                    candidate.current_round_trip_time(),
+                    selected.current_round_trip_time(),
+                ) {
+                    (Some(candidate_rtt), Some(selected_rtt)) => {
+                        candidate_rtt + RENOMINATION_RTT_MARGIN < selected_rtt
+                    }
+                    _ => false,
+                }
+            }
+            // The callback already compared it to the selected pair.
+            NominationStrategy::Custom(_) => true,
+        }
+    }
+
+    /// Tells if both agents agreed on renominating pairs once one got selected.
+    pub(crate) fn is_renomination_negotiated(&self) -> bool {
+        self.renomination && self.remote_renomination.load(Ordering::SeqCst)
+    }
+
+    /// Keeps checking the pairs that haven't been validated yet, and nominates a valid pair
+    /// that is better than the selected one.
+    async fn renominate(&self) {
+        let selected_pair = match self.agent_conn.get_selected_pair() {
+            Some(selected_pair) => selected_pair,
+            None => return,
+        };
+
+        let nominated_pair = {
+            let nominated_pair = self.nominated_pair.lock().await;
+            nominated_pair.clone()
+        };
+        if let Some(p) = nominated_pair {
+            if p != selected_pair {
+                // A renomination is in flight, give up on it after max_binding_requests.
+                if p.binding_request_count.fetch_add(1, Ordering::SeqCst)
+                    > self.max_binding_requests
+                {
+                    log::debug!(
+                        "[{}]: renomination of {} failed, keeping {}",
+                        self.get_name(),
+                        p,
+                        selected_pair
+                    );
+                    p.state
+                        .store(CandidatePairState::Failed as u8, Ordering::SeqCst);
+                    let mut nominated_pair = self.nominated_pair.lock().await;
+                    *nominated_pair = Some(selected_pair);
+                } else {
+                    self.nominate_pair().await;
+                }
+                return;
+            }
+        }
+
+        self.ping_all_candidates().await;
+
+        if let Some(p) = self.nomination_candidate().await {
+            if self.is_better_pair(&p, &selected_pair) {
+                log::debug!(
+                    "[{}]: renominating {} in place of {}",
+                    self.get_name(),
+                    p,
+                    selected_pair
+                );
+                p.binding_request_count.store(0, Ordering::SeqCst);
+                {
+                    let mut nominated_pair = self.nominated_pair.lock().await;
+                    *nominated_pair = Some(p);
+                }
+                self.nominate_pair().await;
+            }
+        }
+    }
+
     pub(crate) async fn start(&self) {
         if self.is_controlling.load(Ordering::SeqCst) {
             ControllingSelector::start(self).await;
@@ -232,39 +400,37 @@ impl ControllingSelector for AgentInternal {
             nominated_pair.is_some()
         };
 
+        let aggressive = matches!(self.nomination_strategy, NominationStrategy::Aggressive);
+
         if self.agent_conn.get_selected_pair().is_some() {
             if self.validate_selected_pair().await {
                 log::trace!("[{}]: checking keepalive", self.get_name());
                 self.check_keepalive().await;
+
+                if self.is_renomination_negotiated() && !aggressive {
+                    self.renominate().await;
+                }
             }
         } else if nominated_pair_is_some {
             self.nominate_pair().await;
-        } else {
-            let has_nominated_pair =
-                if let Some(p) = self.agent_conn.get_best_valid_candidate_pair().await {
-                    self.is_nominatable(&p.local) && self.is_nominatable(&p.remote)
-                } else {
-                    false
-                };
-
-            if has_nominated_pair {
-                if let Some(p) = self.agent_conn.get_best_valid_candidate_pair().await {
-                    log::trace!(
-                        "Nominatable pair found, nominating ({}, {})",
-                        p.local.to_string(),
-                        p.remote.to_string()
-                    );
-                    p.nominated.store(true, Ordering::SeqCst);
-                    {
-                        let mut nominated_pair = self.nominated_pair.lock().await;
-                        *nominated_pair = Some(p);
-                    }
-                }
-
-                self.nominate_pair().await;
-            } else {
-                self.ping_all_candidates().await;
+        } else if aggressive {
+            // Every check nominates, the first pair to succeed gets selected.
+            self.ping_all_candidates().await;
+        } else if let Some(p) = self.nomination_candidate().await {
+            log::trace!(
+                "Nominatable pair found, nominating ({}, {})",
+                p.local,
+                p.remote
+            );
+            p.nominated.store(true, Ordering::SeqCst);
+            {
+                let mut nominated_pair = self.nominated_pair.lock().await;
+                *nominated_pair = Some(p);
             }
+
+            self.nominate_pair().await;
+        } else {
+            self.ping_all_candidates().await;
         }
     }
 
@@ -276,18 +442,24 @@ impl ControllingSelector for AgentInternal {
         let (msg, result) = {
             let ufrag_pwd = self.ufrag_pwd.lock().await;
             let username = ufrag_pwd.remote_ufrag.clone() + ":" + ufrag_pwd.local_ufrag.as_str();
-            let mut msg = Message::new();
-            let result = msg.build(&[
+            let mut setters: Vec<Box<dyn Setter>> = vec![
                 Box::new(BINDING_REQUEST),
                 Box::new(TransactionId::new()),
                 Box::new(Username::new(ATTR_USERNAME, username)),
-                Box::new(AttrControlling(self.tie_breaker.load(Ordering::SeqCst))),
-                Box::new(PriorityAttr(local.priority())),
-                Box::new(MessageIntegrity::new_short_term_integrity(
-                    ufrag_pwd.remote_pwd.clone(),
-                )),
-                Box::new(FINGERPRINT),
-            ]);
+            ];
+            if matches!(self.nomination_strategy, NominationStrategy::Aggressive) {
+                setters.push(Box::new(UseCandidateAttr::default()));
+            }
+            setters.push(Box::new(AttrControlling(
+                self.tie_breaker.load(Ordering::SeqCst),
+            )));
+            setters.push(Box::new(PriorityAttr(local.priority())));
+            setters.push(Box::new(MessageIntegrity::new_short_term_integrity(
+                ufrag_pwd.remote_pwd.clone(),
+            )));
+            setters.push(Box::new(FINGERPRINT));
+            let mut msg = Message::new();
+            let result = msg.build(&setters);
             (msg, result)
         };
 
@@ -320,11 +492,14 @@ impl ControllingSelector for AgentInternal {
                 remote,
                 local
             );
-            let selected_pair_is_none = self.agent_conn.get_selected_pair().is_none();
+            let selected_pair = self.agent_conn.get_selected_pair();
+            let selected_pair_is_none = selected_pair.is_none();
 
             if let Some(p) = self.find_pair(local, remote).await {
                 p.state
                     .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+                p.on_response_received(pending_request.timestamp.elapsed());
+                self.refresh_consent(&p);
                 log::trace!(
                     "Found valid candidate pair: {}, p.state: {}, isUseCandidate: {}, {}",
                     p,
@@ -332,8 +507,17 @@ impl ControllingSelector for AgentInternal {
                     pending_request.is_use_candidate,
                     selected_pair_is_none
                 );
-                if pending_request.is_use_candidate && selected_pair_is_none {
-                    self.set_selected_pair(Some(Arc::clone(&p))).await;
+                if pending_request.is_use_candidate {
+                    let renominated = match &selected_pair {
+                        Some(selected_pair) if *selected_pair != p => {
+                            let nominated_pair = self.nominated_pair.lock().await;
+                            nominated_pair.as_ref().map_or(false, |n| *n == p)
+                        }
+                        _ => false,
+                    };
+                    if selected_pair_is_none || renominated {
+                        self.set_selected_pair(Some(Arc::clone(&p))).await;
+                    }
                 }
             } else {
                 // This shouldn't happen
@@ -370,7 +554,8 @@ impl ControllingSelector for AgentInternal {
                 nominated_pair_is_none,
                 //self.agent_conn.get_selected_pair().await.is_none() //, {}
             );
-            if p.state.load(Ordering::SeqCst) == CandidatePairState::Succeeded as u8
+            if matches!(self.nomination_strategy, NominationStrategy::Regular)
+                && p.state.load(Ordering::SeqCst) == CandidatePairState::Succeeded as u8
                 && nominated_pair_is_none
                 && self.agent_conn.get_selected_pair().is_none()
             {
@@ -482,6 +667,8 @@ impl ControlledSelector for AgentInternal {
             if let Some(p) = self.find_pair(local, remote).await {
                 p.state
                     .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+                p.on_response_received(pending_request.timestamp.elapsed());
+                self.refresh_consent(&p);
                 log::trace!("Found valid candidate pair: {}", p);
             } else {
                 // This shouldn't happen
@@ -516,7 +703,15 @@ impl ControlledSelector for AgentInternal {
                     // previously sent by this pair produced a successful response and
                     // generated a valid pair (Section 7.2.5.3.2).  The agent sets the
                     // nominated flag value of the valid pair to true.
-                    if self.agent_conn.get_selected_pair().is_none() {
+                    // A nomination of another pair once selected is a renomination, only
+                    // follow it if both agents agreed on renominating.
+                    let select = match self.agent_conn.get_selected_pair() {
+                        Some(selected_pair) => {
+                            *selected_pair != *p && self.is_renomination_negotiated()
+                        }
+                        None => true,
+                    };
+                    if select {
                         self.set_selected_pair(Some(Arc::clone(&p))).await;
                     }
                     self.send_binding_success(m, local, remote).await;
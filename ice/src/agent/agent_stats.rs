@@ -3,7 +3,7 @@ use crate::candidate::{CandidatePairState, CandidateType};
 use crate::agent::agent_internal::AgentInternal;
 use crate::network_type::NetworkType;
 use std::sync::atomic::Ordering;
-use tokio::time::Instant;
+use deno_net::Instant;
 
 /// Contains ICE candidate pair statistics.
 pub struct CandidatePairStats {
@@ -217,14 +217,47 @@ impl AgentInternal {
         let checklist = self.agent_conn.checklist.lock().await;
         let mut res = Vec::with_capacity(checklist.len());
         for cp in &*checklist {
-            let stat = CandidatePairStats {
+            let activity = cp.activity.lock().clone();
+            let mut stat = CandidatePairStats {
                 timestamp: Instant::now(),
                 local_candidate_id: cp.local.id(),
                 remote_candidate_id: cp.remote.id(),
                 state: cp.state.load(Ordering::SeqCst).into(),
                 nominated: cp.nominated.load(Ordering::SeqCst),
+                packets_sent: activity.packets_sent,
+                packets_received: activity.packets_received,
+                bytes_sent: activity.bytes_sent,
+                bytes_received: activity.bytes_received,
+                total_round_trip_time: activity.total_round_trip_time.as_secs_f64(),
+                current_round_trip_time: cp
+                    .current_round_trip_time()
+                    .map_or(0.0, |rtt| rtt.as_secs_f64()),
+                requests_received: activity.requests_received,
+                requests_sent: activity.requests_sent,
+                responses_received: activity.responses_received,
+                responses_sent: activity.responses_sent,
+                consent_requests_sent: activity.consent_requests_sent,
                 ..CandidatePairStats::default()
             };
+            // The timestamps of what didn't happen yet stay at the default.
+            if let Some(t) = activity.last_packet_sent {
+                stat.last_packet_sent_timestamp = t;
+            }
+            if let Some(t) = activity.last_packet_received {
+                stat.last_packet_received_timestamp = t;
+            }
+            if let Some(t) = activity.first_request {
+                stat.first_request_timestamp = t;
+            }
+            if let Some(t) = activity.last_request {
+                stat.last_request_timestamp = t;
+            }
+            if let Some(t) = activity.last_response {
+                stat.last_response_timestamp = t;
+            }
+            if let Some(t) = activity.consent_expired {
+                stat.consent_expired_timestamp = t;
+            }
             res.push(stat);
         }
         res
@@ -7,8 +7,10 @@ use crate::candidate::candidate_relay::*;
 use crate::candidate::candidate_server_reflexive::*;
 use crate::control::AttrControlling;
 use crate::priority::PriorityAttr;
+use crate::tcp_mux::{TCPMuxDefault, TCPMuxParams};
 use crate::use_candidate::UseCandidateAttr;
 
+use crate::agent::agent_selector::NominationStrategy;
 use crate::agent::agent_transport_test::pipe;
 use async_trait::async_trait;
 use std::net::Ipv4Addr;
@@ -16,10 +18,11 @@ use std::ops::Sub;
 use std::str::FromStr;
 use stun::message::*;
 use stun::textattrs::Username;
+use util::conn::conn_tcp::TcpListener;
 use util::{vnet::*, Conn};
 use waitgroup::{WaitGroup, Worker};
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_pair_search() -> Result<()> {
     let config = AgentConfig::default();
     let a = Agent::new(config).await?;
@@ -45,7 +48,7 @@ async fn test_pair_search() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_pair_priority() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -164,7 +167,122 @@ async fn test_pair_priority() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
+async fn test_nomination_strategy() -> Result<()> {
+    async fn agent_with_pairs(
+        nomination_strategy: NominationStrategy,
+    ) -> Result<(Agent, Arc<CandidatePair>, Arc<CandidatePair>)> {
+        let a = Agent::new(AgentConfig {
+            nomination_strategy,
+            is_controlling: true,
+            host_acceptance_min_wait: Some(Duration::from_secs(0)),
+            relay_acceptance_min_wait: Some(Duration::from_secs(0)),
+            ..Default::default()
+        })
+        .await?;
+
+        let host_local: Arc<dyn Candidate> = Arc::new(
+            CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: "udp".to_owned(),
+                    address: "192.168.1.1".to_owned(),
+                    port: 19216,
+                    component: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .new_candidate_host()?,
+        );
+        let host_remote: Arc<dyn Candidate> = Arc::new(
+            CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: "udp".to_owned(),
+                    address: "192.168.1.2".to_owned(),
+                    port: 19217,
+                    component: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .new_candidate_host()?,
+        );
+        let relay_remote: Arc<dyn Candidate> = Arc::new(
+            CandidateRelayConfig {
+                base_config: CandidateBaseConfig {
+                    network: "udp".to_owned(),
+                    address: "1.2.3.4".to_owned(),
+                    port: 12340,
+                    component: 1,
+                    ..Default::default()
+                },
+                rel_addr: "4.3.2.1".to_owned(),
+                rel_port: 43210,
+                ..Default::default()
+            }
+            .new_candidate_relay()?,
+        );
+
+        a.internal
+            .add_pair(host_local.clone(), host_remote.clone())
+            .await;
+        a.internal
+            .add_pair(host_local.clone(), relay_remote.clone())
+            .await;
+        let host_pair = a
+            .internal
+            .find_pair(&host_local, &host_remote)
+            .await
+            .unwrap();
+        let relay_pair = a
+            .internal
+            .find_pair(&host_local, &relay_remote)
+            .await
+            .unwrap();
+        for p in [&host_pair, &relay_pair] {
+            p.state
+                .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+        }
+        host_pair.update_round_trip_time(Duration::from_millis(80));
+        relay_pair.update_round_trip_time(Duration::from_millis(20));
+
+        Ok((a, host_pair, relay_pair))
+    }
+
+    // Regular nominates the valid pair with the highest priority.
+    let (a, host_pair, relay_pair) = agent_with_pairs(NominationStrategy::Regular).await?;
+    assert_eq!(
+        a.internal.nomination_candidate().await,
+        Some(host_pair.clone())
+    );
+    assert!(a.internal.is_better_pair(&host_pair, &relay_pair));
+    assert!(!a.internal.is_better_pair(&relay_pair, &host_pair));
+    a.close().await?;
+
+    // RttWeighted prefers the faster pair, but only by a margin.
+    let (a, host_pair, relay_pair) = agent_with_pairs(NominationStrategy::RttWeighted).await?;
+    assert_eq!(
+        a.internal.nomination_candidate().await,
+        Some(relay_pair.clone())
+    );
+    assert!(a.internal.is_better_pair(&relay_pair, &host_pair));
+    host_pair.update_round_trip_time(Duration::from_millis(25));
+    assert!(!a.internal.is_better_pair(&relay_pair, &host_pair));
+    a.close().await?;
+
+    // Custom nominates the first pair the callback accepts.
+    let (a, _host_pair, relay_pair) =
+        agent_with_pairs(NominationStrategy::Custom(Arc::new(|p, _selected| {
+            p.remote.candidate_type() == CandidateType::Relay
+        })))
+        .await?;
+    assert_eq!(a.internal.nomination_candidate().await, Some(relay_pair));
+    a.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_agent_get_stats() -> Result<()> {
     let (conn_a, conn_b, agent_a, agent_b) = pipe(None, None).await?;
     assert_eq!(agent_a.get_bytes_received(), 0);
@@ -185,7 +303,7 @@ async fn test_agent_get_stats() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_on_selected_candidate_pair_change() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
     let (callback_called_tx, mut callback_called_rx) = mpsc::channel::<()>(1);
@@ -240,7 +358,7 @@ async fn test_on_selected_candidate_pair_change() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_peer_reflexive_udp_pflx_candidate() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -322,7 +440,7 @@ async fn test_handle_peer_reflexive_udp_pflx_candidate() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_peer_reflexive_unknown_remote() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -384,7 +502,7 @@ async fn test_handle_peer_reflexive_unknown_remote() -> Result<()> {
 //use std::io::Write;
 
 // Assert that Agent on startup sends message, and doesn't wait for connectivityTicker to fire
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_on_startup() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -473,7 +591,7 @@ async fn test_connectivity_on_startup() -> Result<()> {
         })
     }));
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let result = a_agent.accept(a_cancel_rx, b_ufrag, b_pwd).await;
         assert!(result.is_ok(), "agent accept expected OK");
         drop(accepted_tx);
@@ -499,7 +617,7 @@ async fn test_connectivity_on_startup() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_lite() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -571,6 +689,56 @@ async fn test_connectivity_lite() -> Result<()> {
     Ok(())
 }
 
+// Assert that an Agent with only an active TCP candidate reaches one serving passive candidates
+// over a TCP mux
+#[tokio::test(flavor = "local")]
+async fn test_connectivity_ice_tcp() -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let tcp_mux = TCPMuxDefault::new(TCPMuxParams::new(listener));
+
+    let (a_notifier, mut a_connected) = on_connected();
+    let (b_notifier, mut b_connected) = on_connected();
+
+    let cfg0 = AgentConfig {
+        network_types: vec![NetworkType::Tcp4],
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        tcp_mux: Some(Arc::clone(&tcp_mux) as Arc<dyn TCPMux>),
+        disable_active_tcp: true,
+        ..Default::default()
+    };
+
+    let a_agent = Arc::new(Agent::new(cfg0).await?);
+    a_agent.on_connection_state_change(a_notifier);
+
+    let cfg1 = AgentConfig {
+        network_types: vec![NetworkType::Tcp4],
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        ..Default::default()
+    };
+
+    let b_agent = Arc::new(Agent::new(cfg1).await?);
+    b_agent.on_connection_state_change(b_notifier);
+
+    let _ = connect_with_vnet(&a_agent, &b_agent).await?;
+
+    let _ = a_connected.recv().await;
+    let _ = b_connected.recv().await;
+
+    for (agent, tcp_type) in [(&a_agent, TcpType::Passive), (&b_agent, TcpType::Active)] {
+        let pair = agent
+            .get_selected_candidate_pair()
+            .expect("a pair is selected once connected");
+        assert_eq!(pair.local.network_type(), NetworkType::Tcp4);
+        assert_eq!(pair.local.tcp_type(), tcp_type);
+    }
+
+    a_agent.close().await?;
+    b_agent.close().await?;
+    tcp_mux.close().await?;
+
+    Ok(())
+}
+
 struct MockPacketConn;
 
 #[async_trait(?Send)]
@@ -627,7 +795,7 @@ fn build_msg(c: MessageClass, username: String, key: String) -> Result<Message>
     Ok(msg)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_inbound_validity() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -869,7 +1037,7 @@ async fn test_inbound_validity() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_invalid_agent_starts() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -888,7 +1056,7 @@ async fn test_invalid_agent_starts() -> Result<()> {
     }
 
     let (cancel_tx3, cancel_rx3) = mpsc::channel(1);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         deno_net::sleep(Duration::from_millis(100)).await;
         drop(cancel_tx3);
     });
@@ -914,7 +1082,7 @@ async fn test_invalid_agent_starts() -> Result<()> {
 //use std::io::Write;
 
 // Assert that Agent emits Connecting/Connected/Disconnected/Failed/Closed messages
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connection_state_callback() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1026,7 +1194,7 @@ async fn test_connection_state_callback() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_invalid_gather() -> Result<()> {
     //"Gather with no OnCandidate should error"
     let a = Agent::new(AgentConfig::default()).await?;
@@ -1044,7 +1212,7 @@ async fn test_invalid_gather() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_candidate_pair_stats() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -1201,12 +1369,108 @@ async fn test_candidate_pair_stats() -> Result<()> {
         prflx_pair_stat.state
     );
 
+    // No checks were run on any of the pairs
+    assert_eq!(host_pair_stat.requests_sent, 0);
+    assert_eq!(host_pair_stat.current_round_trip_time, 0.0);
+
     a.close().await?;
 
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
+async fn test_candidate_pair_stats_after_connect() -> Result<()> {
+    let nat_type = nat::NatType {
+        mapping_behavior: nat::EndpointDependencyType::EndpointIndependent,
+        filtering_behavior: nat::EndpointDependencyType::EndpointIndependent,
+        ..Default::default()
+    };
+
+    let v = build_simple_vnet(nat_type, nat_type).await?;
+
+    let (a_notifier, mut a_connected) = on_connected();
+    let (b_notifier, mut b_connected) = on_connected();
+
+    // Short enough for a few consent checks on the selected pair
+    let keepalive_interval = Some(Duration::from_millis(50));
+
+    let cfg0 = AgentConfig {
+        network_types: supported_network_types(),
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        net: Some(Arc::clone(&v.net0)),
+        keepalive_interval,
+        ..Default::default()
+    };
+
+    let a_agent = Arc::new(Agent::new(cfg0).await?);
+    a_agent.on_connection_state_change(a_notifier);
+
+    let cfg1 = AgentConfig {
+        network_types: supported_network_types(),
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        net: Some(Arc::clone(&v.net1)),
+        keepalive_interval,
+        ..Default::default()
+    };
+
+    let b_agent = Arc::new(Agent::new(cfg1).await?);
+    b_agent.on_connection_state_change(b_notifier);
+
+    let (a_conn, b_conn) = connect_with_vnet(&a_agent, &b_agent).await?;
+
+    let _ = a_connected.recv().await;
+    let _ = b_connected.recv().await;
+
+    let msg = b"candidate pair stats";
+    a_conn.send(msg).await?;
+    let mut buf = vec![0u8; 1500];
+    let n = b_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], msg);
+
+    deno_net::sleep(Duration::from_millis(500)).await;
+
+    let selected_pair_stats = |agent: &Agent, stats: Vec<CandidatePairStats>| {
+        let pair = agent
+            .get_selected_candidate_pair()
+            .expect("a pair is selected once connected");
+        stats
+            .into_iter()
+            .find(|s| {
+                s.local_candidate_id == pair.local.id() && s.remote_candidate_id == pair.remote.id()
+            })
+            .expect("the selected pair has stats")
+    };
+    let a_stats = selected_pair_stats(&a_agent, a_agent.get_candidate_pairs_stats().await);
+    let b_stats = selected_pair_stats(&b_agent, b_agent.get_candidate_pairs_stats().await);
+
+    for stats in [&a_stats, &b_stats] {
+        assert_eq!(stats.state, CandidatePairState::Succeeded);
+        assert!(stats.requests_sent >= 1);
+        assert!(stats.responses_received >= 1);
+        assert!(stats.requests_received >= 1);
+        assert!(stats.responses_sent >= 1);
+        assert!(stats.consent_requests_sent >= 1);
+        assert!(stats.consent_requests_sent < stats.requests_sent);
+        assert!(stats.current_round_trip_time > 0.0);
+        assert!(stats.total_round_trip_time >= stats.current_round_trip_time);
+    }
+
+    // Only application data is counted as packets
+    assert_eq!(a_stats.packets_sent, 1);
+    assert_eq!(a_stats.bytes_sent, msg.len() as u64);
+    assert_eq!(a_stats.packets_received, 0);
+    assert_eq!(b_stats.packets_received, 1);
+    assert_eq!(b_stats.bytes_received, msg.len() as u64);
+    assert_eq!(b_stats.packets_sent, 0);
+
+    a_agent.close().await?;
+    b_agent.close().await?;
+    v.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_local_candidate_stats() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -1297,7 +1561,7 @@ async fn test_local_candidate_stats() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_remote_candidate_stats() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -1444,7 +1708,7 @@ async fn test_remote_candidate_stats() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_init_ext_ip_mapping() -> Result<()> {
     // a.extIPMapper should be nil by default
     let a = Agent::new(AgentConfig::default()).await?;
@@ -1548,7 +1812,7 @@ async fn test_init_ext_ip_mapping() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_binding_request_timeout() -> Result<()> {
     const EXPECTED_REMOVAL_COUNT: usize = 2;
 
@@ -1588,9 +1852,125 @@ async fn test_binding_request_timeout() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "local")]
+async fn test_consent_freshness() -> Result<()> {
+    let a = Agent::new(AgentConfig {
+        disconnected_timeout: Some(Duration::from_secs(5)),
+        failed_timeout: Some(Duration::from_secs(0)),
+        ..Default::default()
+    })
+    .await?;
+
+    let local: Arc<dyn Candidate> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: "192.168.1.1".to_owned(),
+                port: 19216,
+                component: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .new_candidate_host()?,
+    );
+    let remote: Arc<dyn Candidate> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: "192.168.1.2".to_owned(),
+                port: 19217,
+                component: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .new_candidate_host()?,
+    );
+    remote.seen(false);
+
+    a.internal.add_pair(local.clone(), remote.clone()).await;
+    let p = a.internal.find_pair(&local, &remote).await.unwrap();
+    a.internal.set_selected_pair(Some(Arc::clone(&p))).await;
+
+    // Selecting the pair grants consent, so the first check is due right away.
+    a.internal.check_keepalive().await;
+    let (last_request_at, next_check_at) = {
+        let consent = a.internal.consent.lock();
+        (consent.last_request_at.unwrap(), consent.next_check_at)
+    };
+    let next_check = next_check_at - last_request_at;
+    assert!(
+        next_check >= Duration::from_secs(4) && next_check <= Duration::from_secs(6),
+        "consent checks should be randomized between 4 and 6 seconds, got {:?}",
+        next_check
+    );
+
+    // Nothing more is sent until the next check is due.
+    a.internal.check_keepalive().await;
+    assert_eq!(
+        a.internal.consent.lock().last_request_at,
+        Some(last_request_at)
+    );
+
+    // Checks going unanswered for longer than the disconnected timeout disconnect.
+    a.internal.consent.lock().unanswered_since = Some(Instant::now().sub(Duration::from_secs(6)));
+    assert!(a.internal.validate_selected_pair().await);
+    assert_eq!(
+        a.internal.connection_state.load(Ordering::SeqCst),
+        ConnectionState::Disconnected as u8
+    );
+
+    // An authenticated response refreshes the consent.
+    a.internal.refresh_consent(&p);
+    assert!(a.internal.validate_selected_pair().await);
+    assert_eq!(
+        a.internal.connection_state.load(Ordering::SeqCst),
+        ConnectionState::Connected as u8
+    );
+
+    // Received traffic alone doesn't keep consent past the consent timeout.
+    a.internal.consent.lock().refreshed_at = Instant::now().sub(Duration::from_secs(31));
+    remote.seen(false);
+    a.internal.validate_selected_pair().await;
+    assert_eq!(
+        a.internal.connection_state.load(Ordering::SeqCst),
+        ConnectionState::Failed as u8
+    );
+
+    // Without consent, nothing may be written to the peer anymore.
+    let result = a.internal.agent_conn.send(b"data").await;
+    assert!(
+        matches!(&result, Err(util::Error::Other(err)) if err == "ErrConsentExpired"),
+        "writes after consent expired should fail, got {:?}",
+        result
+    );
+
+    a.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_consent_freshness_lite() -> Result<()> {
+    let a = Agent::new(AgentConfig {
+        lite: true,
+        candidate_types: vec![CandidateType::Host],
+        ..Default::default()
+    })
+    .await?;
+
+    // A lite agent never sends checks, so it has no consent to keep fresh.
+    assert_eq!(a.internal.consent_timeout, Duration::from_secs(0));
+
+    a.close().await?;
+
+    Ok(())
+}
+
 // test_agent_credentials checks if local username fragments and passwords (if set) meet RFC standard
 // and ensure it's backwards compatible with previous versions of the pion/ice
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_credentials() -> Result<()> {
     // Agent should not require any of the usernames and password to be set
     // If set, they should follow the default 16/128 bits random number generator strategy
@@ -1635,7 +2015,7 @@ async fn test_agent_credentials() -> Result<()> {
 
 // Assert that Agent on Failure deletes all existing candidates
 // User can then do an ICE Restart to bring agent back
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connection_state_failed_delete_all_candidates() -> Result<()> {
     let one_second = Duration::from_secs(1);
     let keepalive_interval = Duration::from_secs(0);
@@ -1691,7 +2071,7 @@ async fn test_connection_state_failed_delete_all_candidates() -> Result<()> {
 }
 
 // Assert that the ICE Agent can go directly from Connecting -> Failed on both sides
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connection_state_connecting_to_failed() -> Result<()> {
     let one_second = Duration::from_secs(1);
     let keepalive_interval = Duration::from_secs(0);
@@ -1743,7 +2123,7 @@ async fn test_connection_state_connecting_to_failed() -> Result<()> {
     b_agent.on_connection_state_change(connection_state_check(wf2, wc2));
 
     let agent_a = Arc::clone(&a_agent);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let (_cancel_tx, cancel_rx) = mpsc::channel(1);
         let result = agent_a
             .accept(cancel_rx, "InvalidFrag".to_owned(), "InvalidPwd".to_owned())
@@ -1752,7 +2132,7 @@ async fn test_connection_state_connecting_to_failed() -> Result<()> {
     });
 
     let agent_b = Arc::clone(&b_agent);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let (_cancel_tx, cancel_rx) = mpsc::channel(1);
         let result = agent_b
             .dial(cancel_rx, "InvalidFrag".to_owned(), "InvalidPwd".to_owned())
@@ -1769,7 +2149,7 @@ async fn test_connection_state_connecting_to_failed() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_restart_during_gather() -> Result<()> {
     //"Restart During Gather"
 
@@ -1790,7 +2170,7 @@ async fn test_agent_restart_during_gather() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_restart_when_closed() -> Result<()> {
     //"Restart When Closed"
 
@@ -1806,7 +2186,7 @@ async fn test_agent_restart_when_closed() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_restart_one_side() -> Result<()> {
     let one_second = Duration::from_secs(1);
 
@@ -1847,7 +2227,7 @@ async fn test_agent_restart_one_side() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_restart_both_side() -> Result<()> {
     let one_second = Duration::from_secs(1);
     //"Restart Both Sides"
@@ -1927,7 +2307,7 @@ async fn test_agent_restart_both_side() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_get_remote_credentials() -> Result<()> {
     let a = Agent::new(AgentConfig::default()).await?;
 
@@ -1951,7 +2331,7 @@ async fn test_get_remote_credentials() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_close_in_connection_state_callback() -> Result<()> {
     let disconnected_duration = Duration::from_secs(1);
     let failed_duration = Duration::from_secs(1);
@@ -2009,7 +2389,7 @@ async fn test_close_in_connection_state_callback() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_run_task_in_connection_state_callback() -> Result<()> {
     let one_second = Duration::from_secs(1);
     let keepalive_interval = Duration::from_secs(0);
@@ -2061,7 +2441,7 @@ async fn test_run_task_in_connection_state_callback() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_run_task_in_selected_candidate_pair_change_callback() -> Result<()> {
     let one_second = Duration::from_secs(1);
     let keepalive_interval = Duration::from_secs(0);
@@ -2127,7 +2507,7 @@ async fn test_run_task_in_selected_candidate_pair_change_callback() -> Result<()
 }
 
 // Assert that a Lite agent goes to disconnected and failed
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_lite_lifecycle() -> Result<()> {
     let (a_notifier, mut a_connected_rx) = on_connected();
 
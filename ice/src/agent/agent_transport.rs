@@ -88,6 +88,8 @@ pub(crate) struct AgentConn {
     pub(crate) bytes_received: AtomicUsize,
     pub(crate) bytes_sent: AtomicUsize,
     pub(crate) done: AtomicBool,
+    // Set once consent on the selected pair expired, RFC 7675 section 5.1
+    pub(crate) consent_expired: AtomicBool,
 }
 
 impl AgentConn {
@@ -102,6 +104,7 @@ impl AgentConn {
             bytes_received: AtomicUsize::new(0),
             bytes_sent: AtomicUsize::new(0),
             done: AtomicBool::new(false),
+            consent_expired: AtomicBool::new(false),
         }
     }
     pub(crate) fn get_selected_pair(&self) -> Option<Arc<CandidatePair>> {
@@ -202,10 +205,21 @@ impl Conn for AgentConn {
             return Err(util::Error::Other("ErrIceWriteStunMessage".into()));
         }
 
-        let result = if let Some(pair) = self.get_selected_pair() {
-            pair.write(buf).await
-        } else if let Some(pair) = self.get_best_available_candidate_pair().await {
-            pair.write(buf).await
+        // RFC 7675 section 5.1: without consent, nothing but consent checks may be sent.
+        if self.consent_expired.load(Ordering::SeqCst) {
+            return Err(util::Error::Other("ErrConsentExpired".into()));
+        }
+
+        let pair = match self.get_selected_pair() {
+            Some(pair) => Some(pair),
+            None => self.get_best_available_candidate_pair().await,
+        };
+        let result = if let Some(pair) = pair {
+            let result = pair.write(buf).await;
+            if result.is_ok() {
+                pair.on_packet_sent(buf.len());
+            }
+            result
         } else {
             Ok(0)
         };
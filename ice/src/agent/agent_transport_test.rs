@@ -44,7 +44,7 @@ pub(crate) async fn pipe(
     Ok((a_conn, b_conn, a_agent, b_agent))
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_remote_local_addr() -> Result<()> {
     // Agent0 is behind 1:1 NAT
     let nat_type0 = nat::NatType {
@@ -106,7 +106,7 @@ async fn test_remote_local_addr() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_conn_stats() -> Result<()> {
     let (ca, cb, _, _) = pipe(None, None).await?;
     let na = ca.send(&[0u8; 10]).await?;
@@ -114,7 +114,7 @@ async fn test_conn_stats() -> Result<()> {
     let wg = WaitGroup::new();
 
     let w = wg.worker();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let _d = w;
 
         let mut buf = vec![0u8; 10];
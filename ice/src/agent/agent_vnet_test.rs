@@ -258,7 +258,7 @@ pub(crate) async fn connect_with_vnet(
     let (_a_cancel_tx, a_cancel_rx) = mpsc::channel(1);
 
     let agent_a = Arc::clone(a_agent);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let a_conn = agent_a.accept(a_cancel_rx, b_ufrag, b_pwd).await?;
 
         let _ = accepted_tx.send(a_conn).await;
@@ -446,7 +446,7 @@ pub(crate) async fn connect_router2router(
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_simple_vnet_full_cone_nats_on_both_ends() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -499,7 +499,7 @@ async fn test_connectivity_simple_vnet_full_cone_nats_on_both_ends() -> Result<(
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_vnet_full_cone_nats_on_both_ends() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -531,6 +531,7 @@ async fn test_connectivity_vnet_full_cone_nats_on_both_ends() -> Result<(), Erro
         username: "user".to_owned(),
         password: "pass".to_owned(),
         proto: ProtoType::Udp,
+        explicit_port: true,
     };
 
     // buildVNet with a Full-cone NATs both LANs
@@ -561,7 +562,7 @@ async fn test_connectivity_vnet_full_cone_nats_on_both_ends() -> Result<(), Erro
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_vnet_symmetric_nats_on_both_ends() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -593,6 +594,7 @@ async fn test_connectivity_vnet_symmetric_nats_on_both_ends() -> Result<(), Erro
         username: "user".to_owned(),
         password: "pass".to_owned(),
         proto: ProtoType::Udp,
+        explicit_port: true,
     };
 
     // buildVNet with a Symmetric NATs for both LANs
@@ -623,7 +625,7 @@ async fn test_connectivity_vnet_symmetric_nats_on_both_ends() -> Result<(), Erro
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_vnet_1to1_nat_with_host_candidate_vs_symmetric_nats() -> Result<(), Error>
 {
     /*env_logger::Builder::new()
@@ -676,7 +678,7 @@ async fn test_connectivity_vnet_1to1_nat_with_host_candidate_vs_symmetric_nats()
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_connectivity_vnet_1to1_nat_with_srflx_candidate_vs_symmetric_nats(
 ) -> Result<(), Error> {
     /*env_logger::Builder::new()
@@ -741,7 +743,7 @@ async fn block_until_state_seen(
 }
 
 // test_disconnected_to_connected asserts that an agent can go to disconnected, and then return to connected successfully
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_disconnected_to_connected() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -881,7 +883,7 @@ async fn test_disconnected_to_connected() -> Result<(), Error> {
 //use std::io::Write;
 
 // Agent.Write should use the best valid pair if a selected pair is not yet available
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_write_use_valid_pair() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -962,7 +964,7 @@ async fn test_write_use_valid_pair() -> Result<(), Error> {
     let (controlled_ufrag, controlled_pwd) = controlled_agent.get_local_user_credentials().await;
 
     let controlling_agent_tx = Arc::clone(&controlling_agent);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let test_message = "Test Message";
         let controlling_agent_conn = {
             controlling_agent_tx
@@ -1011,3 +1013,146 @@ async fn test_write_use_valid_pair() -> Result<(), Error> {
 
     Ok(())
 }
+
+// Connects two agents over relay candidates only, then hands them each other's host
+// candidates. Returns the pairs both agents selected first, and the ones they end up with.
+async fn renominate_with_vnet(
+    renomination: bool,
+    remote_renomination: bool,
+) -> Result<(Vec<Arc<CandidatePair>>, Vec<Arc<CandidatePair>>), Error> {
+    let turn_server_url = Url {
+        scheme: SchemeType::Turn,
+        host: VNET_STUN_SERVER_IP.to_owned(),
+        port: VNET_STUN_SERVER_PORT,
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+        proto: ProtoType::Udp,
+        explicit_port: true,
+    };
+
+    let nat_type = nat::NatType {
+        mapping_behavior: nat::EndpointDependencyType::EndpointIndependent,
+        filtering_behavior: nat::EndpointDependencyType::EndpointIndependent,
+        ..Default::default()
+    };
+    let v = build_simple_vnet(nat_type, nat_type).await?;
+
+    let new_agent = |net: &Arc<net::Net>| {
+        Agent::new(AgentConfig {
+            urls: vec![turn_server_url.clone()],
+            network_types: supported_network_types(),
+            multicast_dns_mode: MulticastDnsMode::Disabled,
+            relay_acceptance_min_wait: Some(Duration::from_secs(0)),
+            renomination,
+            net: Some(Arc::clone(net)),
+            ..Default::default()
+        })
+    };
+    let a_agent = Arc::new(new_agent(&v.net0).await?);
+    let b_agent = Arc::new(new_agent(&v.net1).await?);
+    for agent in [&a_agent, &b_agent] {
+        agent.set_remote_renomination(remote_renomination);
+    }
+
+    let (a_notifier, mut a_connected) = on_connected();
+    let (b_notifier, mut b_connected) = on_connected();
+    a_agent.on_connection_state_change(a_notifier);
+    b_agent.on_connection_state_change(b_notifier);
+
+    let exchange = |from: &Arc<Agent>, to: &Arc<Agent>, host: bool| {
+        let (from, to) = (Arc::clone(from), Arc::clone(to));
+        async move {
+            for c in from.get_local_candidates().await? {
+                if (c.candidate_type() == CandidateType::Host) == host {
+                    let c2: Arc<dyn Candidate> =
+                        Arc::new(unmarshal_candidate(c.marshal().as_str())?);
+                    to.add_remote_candidate(&c2)?;
+                }
+            }
+            Result::<(), Error>::Ok(())
+        }
+    };
+
+    let wg = WaitGroup::new();
+    for agent in [&a_agent, &b_agent] {
+        let w = Arc::new(Mutex::new(Some(wg.worker())));
+        agent.on_candidate(Box::new(move |candidate: Option<Arc<dyn Candidate>>| {
+            let w = Arc::clone(&w);
+            Box::pin(async move {
+                if candidate.is_none() {
+                    w.lock().await.take();
+                }
+            })
+        }));
+        agent.gather_candidates()?;
+    }
+    wg.wait().await;
+
+    exchange(&a_agent, &b_agent, false).await?;
+    exchange(&b_agent, &a_agent, false).await?;
+
+    let (a_ufrag, a_pwd) = a_agent.get_local_user_credentials().await;
+    let (b_ufrag, b_pwd) = b_agent.get_local_user_credentials().await;
+    let agent_a = Arc::clone(&a_agent);
+    deno_net::spawn(async move {
+        let (_cancel_tx, cancel_rx) = mpsc::channel(1);
+        let _ = agent_a.accept(cancel_rx, b_ufrag, b_pwd).await;
+    });
+    let (_cancel_tx, cancel_rx) = mpsc::channel(1);
+    let _b_conn = b_agent.dial(cancel_rx, a_ufrag, a_pwd).await?;
+    let _ = a_connected.recv().await;
+    let _ = b_connected.recv().await;
+
+    let selected_pairs = || {
+        [&a_agent, &b_agent]
+            .iter()
+            .map(|agent| agent.get_selected_candidate_pair().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let first_pairs = selected_pairs();
+    for p in &first_pairs {
+        assert!(!is_host_pair(p), "{}", p);
+    }
+
+    // The host pair turns up once connected, and is a lot better than the relay pair.
+    exchange(&a_agent, &b_agent, true).await?;
+    exchange(&b_agent, &a_agent, true).await?;
+
+    for _ in 0..30 {
+        if selected_pairs().iter().all(|p| is_host_pair(p)) {
+            break;
+        }
+        deno_net::sleep(Duration::from_millis(100)).await;
+    }
+    let last_pairs = selected_pairs();
+
+    a_agent.close().await?;
+    b_agent.close().await?;
+    v.close().await?;
+
+    Ok((first_pairs, last_pairs))
+}
+
+fn is_host_pair(p: &CandidatePair) -> bool {
+    p.local.candidate_type() == CandidateType::Host
+        && p.remote.candidate_type() == CandidateType::Host
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_renomination_vnet_relay_to_host() -> Result<(), Error> {
+    let (_, last_pairs) = renominate_with_vnet(true, true).await?;
+    for p in &last_pairs {
+        assert!(is_host_pair(p), "{}", p);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_renomination_vnet_not_negotiated() -> Result<(), Error> {
+    // The remote agent never announced renomination, both keep the pair they selected.
+    let (first_pairs, last_pairs) = renominate_with_vnet(true, false).await?;
+    assert_eq!(first_pairs, last_pairs);
+
+    Ok(())
+}
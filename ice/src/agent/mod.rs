@@ -20,6 +20,7 @@ use crate::external_ip_mapper::*;
 use crate::mdns::*;
 use crate::network_type::*;
 use crate::state::*;
+use crate::tcp_mux::TCPMux;
 use crate::udp_mux::UDPMux;
 use crate::udp_network::UDPNetwork;
 use crate::url::*;
@@ -33,16 +34,16 @@ use std::net::{Ipv4Addr, SocketAddr};
 use stun::{agent::*, attributes::*, fingerprint::*, integrity::*, message::*, xoraddr::*};
 use util::{vnet::net::*, Buffer};
 
-use crate::agent::agent_gather::GatherCandidatesInternalParams;
+use crate::agent::agent_gather::{ContinualGatheringPolicy, GatherCandidatesInternalParams};
 use crate::rand::*;
 use crate::tcp_type::TcpType;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
+use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, Mutex};
-use tokio::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub(crate) struct BindingRequest {
@@ -96,6 +97,7 @@ pub struct Agent {
     pub(crate) internal: Arc<AgentInternal>,
 
     pub(crate) udp_network: UDPNetwork,
+    pub(crate) tcp_mux: Option<Arc<dyn TCPMux>>,
     pub(crate) interface_filter: Arc<Option<InterfaceFilterFn>>,
     pub(crate) ip_filter: Arc<Option<IpFilterFn>>,
     pub(crate) mdns_mode: MulticastDnsMode,
@@ -109,6 +111,8 @@ pub struct Agent {
     pub(crate) candidate_types: Vec<CandidateType>,
     pub(crate) urls: Vec<Url>,
     pub(crate) network_types: Vec<NetworkType>,
+    pub(crate) continual_gathering_policy: ContinualGatheringPolicy,
+    pub(crate) network_monitor_interval: Duration,
 
     pub(crate) gather_candidate_cancel: Option<GatherCandidateCancelFn>,
 }
@@ -131,7 +135,9 @@ impl Agent {
         }
 
         let mdns_conn =
-            match create_multicast_dns(mdns_mode, &mdns_name, &config.multicast_dns_dest_addr) {
+            match create_multicast_dns(mdns_mode, &mdns_name, &config.multicast_dns_dest_addr)
+                .await
+            {
                 Ok(c) => c,
                 Err(err) => {
                     // Opportunistic mDNS: If we can't open the connection, that's ok: we
@@ -194,6 +200,7 @@ impl Agent {
 
         let agent = Self {
             udp_network: config.udp_network,
+            tcp_mux: config.tcp_mux.clone(),
             internal: Arc::new(ai),
             interface_filter: Arc::clone(&config.interface_filter),
             ip_filter: Arc::clone(&config.ip_filter),
@@ -206,6 +213,10 @@ impl Agent {
             candidate_types,
             urls: config.urls.clone(),
             network_types: config.network_types.clone(),
+            continual_gathering_policy: config.continual_gathering_policy,
+            network_monitor_interval: config
+                .network_monitor_interval
+                .unwrap_or(DEFAULT_NETWORK_MONITOR_INTERVAL),
 
             gather_candidate_cancel: None, //TODO: add cancel
         };
@@ -284,7 +295,7 @@ impl Agent {
             let ai = Arc::clone(&self.internal);
             let host_candidate = Arc::clone(c);
             let mdns_conn = self.mdns_conn.clone();
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 if let Some(mdns_conn) = mdns_conn {
                     if let Ok(candidate) =
                         Self::resolve_and_add_multicast_candidate(mdns_conn, host_candidate).await
@@ -296,7 +307,7 @@ impl Agent {
         } else {
             let ai = Arc::clone(&self.internal);
             let candidate = Arc::clone(c);
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 ai.add_remote_candidate(&candidate).await;
             });
         }
@@ -343,6 +354,11 @@ impl Agent {
             udp_mux.remove_conn_by_ufrag(&ufrag).await;
         }
 
+        if let Some(tcp_mux) = &self.tcp_mux {
+            let (ufrag, _) = self.get_local_user_credentials().await;
+            tcp_mux.remove_conn_by_ufrag(&ufrag).await;
+        }
+
         //FIXME: deadlock here
         self.internal.close().await
     }
@@ -363,6 +379,15 @@ impl Agent {
             .await
     }
 
+    /// Tells if the remote agent supports renomination, which it announces with
+    /// `a=ice-options:renomination`. Pairs are only renominated when both agents support it
+    /// and [`AgentConfig::renomination`] is set.
+    pub fn set_remote_renomination(&self, renomination: bool) {
+        self.internal
+            .remote_renomination
+            .store(renomination, Ordering::SeqCst);
+    }
+
     /// Restarts the ICE Agent with the provided ufrag/pwd
     /// If no ufrag/pwd is provided the Agent will generate one itself.
     ///
@@ -416,6 +441,8 @@ impl Agent {
             *checklist = vec![];
         }
 
+        // The next gathering starts its own network monitor.
+        self.internal.network_monitor_tx.lock().take();
         self.internal.set_selected_pair(None).await;
         self.internal.delete_all_candidates().await;
         self.internal.start().await;
@@ -449,6 +476,7 @@ impl Agent {
 
         let params = GatherCandidatesInternalParams {
             udp_network: self.udp_network.clone(),
+            tcp_mux: self.tcp_mux.clone(),
             candidate_types: self.candidate_types.clone(),
             urls: self.urls.clone(),
             network_types: self.network_types.clone(),
@@ -462,13 +490,45 @@ impl Agent {
             gathering_state: Arc::clone(&self.gathering_state),
             chan_candidate_tx: Arc::clone(&self.internal.chan_candidate_tx),
         };
-        wasm_bindgen_futures::spawn_local(async move {
-            Self::gather_candidates_internal(params).await;
+        let network_monitor =
+            if self.continual_gathering_policy == ContinualGatheringPolicy::GatherContinually {
+                let (update_tx, update_rx) = mpsc::channel(1);
+                // Replacing the sender stops the monitor of a previous gathering.
+                *self.internal.network_monitor_tx.lock() = Some(update_tx);
+                Some((self.network_monitor_interval, update_rx))
+            } else {
+                None
+            };
+
+        deno_net::spawn(async move {
+            if let Some((interval, update_rx)) = network_monitor {
+                // Enumerated up front, so interfaces that show up while gathering aren't missed.
+                // Bound to them, so candidates can go away with the interface they are on.
+                let known_ips = Self::local_interfaces(&params).await;
+                Self::gather_candidates_internal(params.clone(), Some(known_ips.clone())).await;
+                Self::monitor_network(params, known_ips, interval, update_rx).await;
+            } else {
+                Self::gather_candidates_internal(params, None).await;
+            }
         });
 
         Ok(())
     }
 
+    /// Checks the local interfaces for changes right away, instead of at the next network
+    /// monitor interval. Only available with `ContinualGatheringPolicy::GatherContinually`,
+    /// once gathering started.
+    pub fn update_network(&self) -> Result<()> {
+        match &*self.internal.network_monitor_tx.lock() {
+            Some(update_tx) => {
+                // A full channel means a check is already pending.
+                let _ = update_tx.try_send(());
+                Ok(())
+            }
+            None => Err(Error::ErrNoNetworkMonitor),
+        }
+    }
+
     /// Returns a list of candidate pair stats.
     pub async fn get_candidate_pairs_stats(&self) -> Vec<CandidatePairStats> {
         self.internal.get_candidate_pairs_stats().await
@@ -8,6 +8,7 @@ use crate::util::*;
 
 use async_trait::async_trait;
 use crc::{Crc, CRC_32_ISCSI};
+use deno_net::{SystemTime, UNIX_EPOCH};
 use std::{
     fmt,
     ops::Add,
@@ -15,7 +16,7 @@ use std::{
         atomic::{AtomicU16, AtomicU64, AtomicU8, Ordering},
         Arc,
     },
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    time::Duration,
 };
 use tokio::sync::{broadcast, Mutex};
 use util::sync::Mutex as SyncMutex;
@@ -9,6 +9,7 @@ use crate::url::{ProtoType, SchemeType, Url};
 use std::result::Result;
 use std::time::Duration;
 use deno_net::DatagramConn as UdpSocket;
+use util::Conn;
 use turn::auth::AuthHandler;
 
 pub(crate) struct OptimisticAuthHandler;
@@ -30,7 +31,7 @@ impl AuthHandler for OptimisticAuthHandler {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_relay_only_connection() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -73,6 +74,7 @@ async fn test_relay_only_connection() -> Result<(), Error> {
             password: "password".to_owned(),
             port: server_port,
             proto: ProtoType::Udp,
+            explicit_port: true,
         }],
         candidate_types: vec![CandidateType::Relay],
         ..Default::default()
@@ -91,6 +93,7 @@ async fn test_relay_only_connection() -> Result<(), Error> {
             password: "password".to_owned(),
             port: server_port,
             proto: ProtoType::Udp,
+            explicit_port: true,
         }],
         candidate_types: vec![CandidateType::Relay],
         ..Default::default()
@@ -6,10 +6,11 @@ use crate::agent::Agent;
 use crate::url::{SchemeType, Url};
 use std::time::Duration;
 use deno_net::DatagramConn as UdpSocket;
+use util::Conn;
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_server_reflexive_only_connection() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
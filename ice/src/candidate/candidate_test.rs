@@ -1,6 +1,6 @@
 use super::*;
 
-use std::time::UNIX_EPOCH;
+use deno_net::UNIX_EPOCH;
 
 #[test]
 fn test_candidate_priority() -> Result<()> {
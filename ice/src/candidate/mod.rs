@@ -22,10 +22,12 @@ use async_trait::async_trait;
 use serde::Serialize;
 use std::fmt;
 use std::net::{IpAddr, SocketAddr};
-use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, AtomicU8, Ordering};
 use std::sync::Arc;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
+use std::time::Duration;
 use tokio::sync::{broadcast, Mutex};
+use util::sync::Mutex as SyncMutex;
 
 pub(crate) const RECEIVE_MTU: usize = 8192;
 pub(crate) const DEFAULT_LOCAL_PREFERENCE: u16 = 65535;
@@ -224,6 +226,27 @@ impl fmt::Display for CandidatePairState {
     }
 }
 
+/// Traffic and STUN transaction counters of a candidate pair, reported in `CandidatePairStats`.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct CandidatePairActivity {
+    pub(crate) packets_sent: u32,
+    pub(crate) packets_received: u32,
+    pub(crate) bytes_sent: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) last_packet_sent: Option<Instant>,
+    pub(crate) last_packet_received: Option<Instant>,
+    pub(crate) first_request: Option<Instant>,
+    pub(crate) last_request: Option<Instant>,
+    pub(crate) last_response: Option<Instant>,
+    pub(crate) total_round_trip_time: Duration,
+    pub(crate) requests_sent: u64,
+    pub(crate) requests_received: u64,
+    pub(crate) responses_sent: u64,
+    pub(crate) responses_received: u64,
+    pub(crate) consent_requests_sent: u64,
+    pub(crate) consent_expired: Option<Instant>,
+}
+
 /// Represents a combination of a local and remote candidate.
 pub struct CandidatePair {
     pub(crate) ice_role_controlling: AtomicBool,
@@ -232,6 +255,9 @@ pub struct CandidatePair {
     pub(crate) binding_request_count: AtomicU16,
     pub(crate) state: AtomicU8, // convert it to CandidatePairState,
     pub(crate) nominated: AtomicBool,
+    /// In nanoseconds, 0 until a binding request on the pair got a response.
+    pub(crate) current_round_trip_time: AtomicU64,
+    pub(crate) activity: SyncMutex<CandidatePairActivity>,
 }
 
 impl Default for CandidatePair {
@@ -243,6 +269,8 @@ impl Default for CandidatePair {
             state: AtomicU8::new(CandidatePairState::Waiting as u8),
             binding_request_count: AtomicU16::new(0),
             nominated: AtomicBool::new(false),
+            current_round_trip_time: AtomicU64::new(0),
+            activity: SyncMutex::new(CandidatePairActivity::default()),
         }
     }
 }
@@ -295,6 +323,8 @@ impl CandidatePair {
             state: AtomicU8::new(CandidatePairState::Waiting as u8),
             binding_request_count: AtomicU16::new(0),
             nominated: AtomicBool::new(false),
+            current_round_trip_time: AtomicU64::new(0),
+            activity: SyncMutex::new(CandidatePairActivity::default()),
         }
     }
 
@@ -317,6 +347,62 @@ impl CandidatePair {
             + u64::from(g > d)
     }
 
+    /// Returns the round trip time of the latest binding request answered on the pair.
+    pub fn current_round_trip_time(&self) -> Option<Duration> {
+        match self.current_round_trip_time.load(Ordering::SeqCst) {
+            0 => None,
+            rtt => Some(Duration::from_nanos(rtt)),
+        }
+    }
+
+    pub(crate) fn update_round_trip_time(&self, rtt: Duration) {
+        // Keep 0 for "unknown", even for an answer that came back instantly.
+        let rtt = std::cmp::max(rtt.as_nanos() as u64, 1);
+        self.current_round_trip_time.store(rtt, Ordering::SeqCst);
+    }
+
+    /// Counts a binding request sent on the pair, consent tells if it checked consent freshness.
+    pub(crate) fn on_request_sent(&self, consent: bool) {
+        let now = Instant::now();
+        let mut activity = self.activity.lock();
+        activity.requests_sent += 1;
+        if consent {
+            activity.consent_requests_sent += 1;
+        }
+        activity.first_request.get_or_insert(now);
+        activity.last_request = Some(now);
+    }
+
+    /// Counts an authenticated binding request received on the pair, and the response to it.
+    pub(crate) fn on_request_answered(&self) {
+        let mut activity = self.activity.lock();
+        activity.requests_received += 1;
+        activity.responses_sent += 1;
+    }
+
+    /// Counts the response to a binding request sent on the pair, which took rtt to arrive.
+    pub(crate) fn on_response_received(&self, rtt: Duration) {
+        self.update_round_trip_time(rtt);
+        let mut activity = self.activity.lock();
+        activity.responses_received += 1;
+        activity.last_response = Some(Instant::now());
+        activity.total_round_trip_time += rtt;
+    }
+
+    pub(crate) fn on_packet_sent(&self, n: usize) {
+        let mut activity = self.activity.lock();
+        activity.packets_sent += 1;
+        activity.bytes_sent += n as u64;
+        activity.last_packet_sent = Some(Instant::now());
+    }
+
+    pub(crate) fn on_packet_received(&self, n: usize) {
+        let mut activity = self.activity.lock();
+        activity.packets_received += 1;
+        activity.bytes_received += n as u64;
+        activity.last_packet_received = Some(Instant::now());
+    }
+
     pub async fn write(&self, b: &[u8]) -> Result<usize> {
         self.local.write_to(b, &*self.remote).await
     }
@@ -80,6 +80,10 @@ pub enum Error {
     #[error("attempting to gather candidates during gathering state")]
     ErrMultipleGatherAttempted,
 
+    /// Indicates the network was asked to be checked while no continual gathering is running.
+    #[error("no network monitor, continual gathering is not running")]
+    ErrNoNetworkMonitor,
+
     /// Indicates agent was give TURN URL with an empty Username.
     #[error("username is empty")]
     ErrUsernameEmpty,
@@ -7,7 +7,7 @@ use crate::network_type::*;
 use regex::Regex;
 use tokio::sync::{mpsc, Mutex};
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 // This test is disabled on Windows for now because it gets stuck and never finishes.
 // This does not seem to have happened due to a code change. It started happening with
 // `ce55c3a066ab461c3e74f0d5ac6f1209205e79bc` but was verified as happening on
@@ -46,7 +46,7 @@ async fn test_multicast_dns_only_connection() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_multicast_dns_mixed_connection() -> Result<()> {
     let cfg0 = AgentConfig {
         network_types: vec![NetworkType::Udp4],
@@ -80,7 +80,7 @@ async fn test_multicast_dns_mixed_connection() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_multicast_dns_static_host_name() -> Result<()> {
     let cfg0 = AgentConfig {
         network_types: vec![NetworkType::Udp4],
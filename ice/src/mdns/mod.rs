@@ -39,7 +39,7 @@ pub(crate) fn generate_multicast_dns_name() -> String {
     format!("{}.local", u)
 }
 
-pub(crate) fn create_multicast_dns(
+pub(crate) async fn create_multicast_dns(
     mdns_mode: MulticastDnsMode,
     mdns_name: &str,
     dest_addr: &str,
@@ -62,7 +62,7 @@ pub(crate) fn create_multicast_dns(
 
     match mdns_mode {
         MulticastDnsMode::QueryOnly => {
-            let conn = DnsConn::server(addr, Config::default())?;
+            let conn = DnsConn::server(addr, Config::default()).await?;
             Ok(Some(Arc::new(conn)))
         }
         MulticastDnsMode::QueryAndGather => {
@@ -72,7 +72,8 @@ pub(crate) fn create_multicast_dns(
                     local_names: vec![mdns_name.to_owned()],
                     ..Config::default()
                 },
-            )?;
+            )
+            .await?;
             Ok(Some(Arc::new(conn)))
         }
         _ => Ok(None),
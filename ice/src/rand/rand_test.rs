@@ -5,7 +5,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use waitgroup::WaitGroup;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_random_generator_collision() -> Result<()> {
     let test_cases = vec![
         (
@@ -40,7 +40,7 @@ async fn test_random_generator_collision() -> Result<()> {
                 let w = wg.worker();
                 let rs = Arc::clone(&rands);
 
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let _d = w;
 
                     let s = if test_case == 0 {
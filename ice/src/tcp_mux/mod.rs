@@ -0,0 +1,317 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use util::{conn::Listener, Conn, Error};
+
+use async_trait::async_trait;
+
+use tokio::sync::{watch, Mutex};
+
+mod tcp_packet_conn;
+pub use tcp_packet_conn::TCPPacketConn;
+
+#[cfg(test)]
+mod tcp_mux_test;
+
+use stun::{
+    attributes::ATTR_USERNAME,
+    message::{is_message as is_stun_message, Message as STUNMessage, BINDING_REQUEST},
+};
+
+use crate::candidate::RECEIVE_MTU;
+
+/// How long an accepted connection has to send its first binding request.
+const FIRST_PACKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[async_trait(?Send)]
+pub trait TCPMux {
+    /// Close the muxing.
+    async fn close(&self) -> Result<(), Error>;
+
+    /// Get the packet connection for a given ufrag on a given local address. Connections
+    /// accepted on that address whose first binding request carries the ufrag end up in it.
+    async fn get_conn_by_ufrag(
+        self: Arc<Self>,
+        ufrag: &str,
+        local_ip: IpAddr,
+    ) -> Result<Arc<dyn Conn>, Error>;
+
+    /// Remove the packet connections for a given ufrag.
+    async fn remove_conn_by_ufrag(&self, ufrag: &str);
+}
+
+pub struct TCPMuxParams {
+    listener: Box<dyn Listener>,
+}
+
+impl TCPMuxParams {
+    pub fn new<L>(listener: L) -> Self
+    where
+        L: Listener + 'static,
+    {
+        Self {
+            listener: Box::new(listener),
+        }
+    }
+}
+
+/// Serves passive ICE-TCP candidates of any number of agents from a single TCP listener.
+///
+/// Incoming connections are demultiplexed by the ufrag in the USERNAME of their first STUN
+/// binding request, see [RFC 6544](https://datatracker.ietf.org/doc/html/rfc6544).
+pub struct TCPMuxDefault {
+    /// The params this instance is configured with.
+    /// Contains the underlying TCP listener in use
+    params: TCPMuxParams,
+
+    /// Maps from ufrag and local ip to the packet connection.
+    conns: Mutex<HashMap<(String, IpAddr), TCPPacketConn>>,
+
+    // Close sender
+    closed_watch_tx: Mutex<Option<watch::Sender<()>>>,
+
+    /// Close reciever
+    closed_watch_rx: watch::Receiver<()>,
+}
+
+impl TCPMuxDefault {
+    pub fn new(params: TCPMuxParams) -> Arc<Self> {
+        let (closed_watch_tx, closed_watch_rx) = watch::channel(());
+
+        let mux = Arc::new(Self {
+            params,
+            conns: Mutex::default(),
+            closed_watch_tx: Mutex::new(Some(closed_watch_tx)),
+            closed_watch_rx: closed_watch_rx.clone(),
+        });
+
+        let cloned_mux = Arc::clone(&mux);
+        cloned_mux.start_accept_worker(closed_watch_rx);
+
+        mux
+    }
+
+    pub async fn is_closed(&self) -> bool {
+        self.closed_watch_tx.lock().await.is_none()
+    }
+
+    fn start_accept_worker(self: Arc<Self>, mut closed_watch_rx: watch::Receiver<()>) {
+        deno_net::spawn(async move {
+            loop {
+                tokio::select! {
+                    res = self.params.listener.accept() => {
+                        match res {
+                            Ok((conn, raddr)) => {
+                                let mux = Arc::clone(&self);
+                                deno_net::spawn(async move {
+                                    mux.handle_conn(conn, raddr).await;
+                                });
+                            }
+                            Err(err) => {
+                                log::error!("Could not accept tcp conn: {}", err);
+                                break;
+                            }
+                        }
+                    }
+                    _ = closed_watch_rx.changed() => {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Reads the first packet of an accepted connection and hands the connection over to the
+    /// packet connection of its ufrag.
+    async fn handle_conn(&self, conn: Arc<dyn Conn>, raddr: SocketAddr) {
+        let mut buffer = vec![0u8; RECEIVE_MTU];
+
+        let n = match deno_net::timeout(FIRST_PACKET_TIMEOUT, conn.recv(&mut buffer)).await {
+            Ok(Ok(n)) => n,
+            Ok(Err(err)) => {
+                log::warn!("Failed to read first packet from {}: {}", raddr, err);
+                let _ = conn.close().await;
+                return;
+            }
+            Err(_) => {
+                log::warn!("Timed out waiting for first packet from {}", raddr);
+                let _ = conn.close().await;
+                return;
+            }
+        };
+
+        let ufrag = match ufrag_from_binding_request(&buffer[..n], &raddr) {
+            Some(ufrag) => ufrag,
+            None => {
+                let _ = conn.close().await;
+                return;
+            }
+        };
+
+        let local_ip = match conn.local_addr() {
+            Ok(addr) => canonical_ip(addr.ip()),
+            Err(err) => {
+                log::warn!("Failed to get local addr of tcp conn from {}: {}", raddr, err);
+                let _ = conn.close().await;
+                return;
+            }
+        };
+
+        let packet_conn = {
+            let conns = self.conns.lock().await;
+            conns.get(&(ufrag.clone(), local_ip)).cloned()
+        };
+
+        match packet_conn {
+            None => {
+                log::warn!(
+                    "No tcp packet conn for ufrag {} on {}, dropping conn from {}",
+                    ufrag,
+                    local_ip,
+                    raddr
+                );
+                let _ = conn.close().await;
+            }
+            Some(packet_conn) => {
+                if let Err(err) = packet_conn.add_conn(conn, raddr, &buffer[..n]).await {
+                    log::warn!("Failed to add tcp conn from {}: {}", raddr, err);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl TCPMux for TCPMuxDefault {
+    async fn close(&self) -> Result<(), Error> {
+        if self.is_closed().await {
+            return Err(Error::ErrAlreadyClosed);
+        }
+
+        let mut closed_tx = self.closed_watch_tx.lock().await;
+
+        if let Some(tx) = closed_tx.take() {
+            let _ = tx.send(());
+            drop(closed_tx);
+
+            let old_conns = {
+                let mut conns = self.conns.lock().await;
+
+                std::mem::take(&mut (*conns))
+            };
+
+            for (_, conn) in old_conns {
+                conn.close().await;
+            }
+
+            self.params.listener.close().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_conn_by_ufrag(
+        self: Arc<Self>,
+        ufrag: &str,
+        local_ip: IpAddr,
+    ) -> Result<Arc<dyn Conn>, Error> {
+        if self.is_closed().await {
+            return Err(Error::ErrUseClosedNetworkConn);
+        }
+
+        let listen_addr = self.params.listener.addr().await?;
+        if !listen_addr.ip().is_unspecified() && canonical_ip(listen_addr.ip()) != local_ip {
+            return Err(Error::ErrCantAssignRequestedAddr);
+        }
+
+        let mut conns = self.conns.lock().await;
+        let key = (ufrag.to_owned(), local_ip);
+        if let Some(conn) = conns.get(&key) {
+            // TCPPacketConn uses `Arc` internally so it's cheap to clone, but because
+            // we implement `Conn` we need to further wrap it in an `Arc` here.
+            return Ok(Arc::new(conn.clone()) as Arc<dyn Conn>);
+        }
+
+        let packet_conn = TCPPacketConn::new(SocketAddr::new(local_ip, listen_addr.port()));
+        let mut close_rx = packet_conn.close_rx();
+        let cloned_self = Arc::clone(&self);
+        let cloned_key = key.clone();
+        deno_net::spawn(async move {
+            let _ = close_rx.changed().await;
+
+            let mut conns = cloned_self.conns.lock().await;
+            conns.remove(&cloned_key);
+        });
+
+        conns.insert(key, packet_conn.clone());
+
+        Ok(Arc::new(packet_conn) as Arc<dyn Conn>)
+    }
+
+    async fn remove_conn_by_ufrag(&self, ufrag: &str) {
+        let removed_conns: Vec<TCPPacketConn> = {
+            let mut conns = self.conns.lock().await;
+            let keys: Vec<_> = conns.keys().filter(|(u, _)| u == ufrag).cloned().collect();
+            keys.iter().filter_map(|key| conns.remove(key)).collect()
+        };
+
+        for conn in removed_conns {
+            conn.close().await;
+        }
+    }
+}
+
+/// Extracts the local ufrag from a STUN binding request, the username being
+/// `<local ufrag>:<remote ufrag>` from our point of view.
+fn ufrag_from_binding_request(buffer: &[u8], raddr: &SocketAddr) -> Option<String> {
+    if !is_stun_message(buffer) {
+        log::warn!("First packet from {} is not a STUN message", raddr);
+        return None;
+    }
+
+    let mut m = STUNMessage::new();
+    if let Err(err) = m.unmarshal_binary(buffer) {
+        log::warn!("Failed to handle decode ICE from {}: {}", raddr, err);
+        return None;
+    }
+
+    if m.typ != BINDING_REQUEST {
+        log::warn!(
+            "First STUN message from {} is not a binding request: {}",
+            raddr,
+            m.typ
+        );
+        return None;
+    }
+
+    let (attr, found) = m.attributes.get(ATTR_USERNAME);
+    if !found {
+        log::warn!("No username attribute in STUN message from {}", raddr);
+        return None;
+    }
+
+    match String::from_utf8(attr.value) {
+        Ok(s) => s.split(':').next().map(ToOwned::to_owned),
+        // Per the RFC this shouldn't happen
+        // https://datatracker.ietf.org/doc/html/rfc5389#section-15.3
+        Err(err) => {
+            log::warn!(
+                "Failed to decode USERNAME from STUN message as UTF-8: {}",
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Dual stack listeners report IPv4 peers as IPv4-mapped IPv6 addresses.
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip => ip,
+    }
+}
@@ -0,0 +1,79 @@
+use std::net::Ipv4Addr;
+
+use super::*;
+use crate::error::Result;
+use stun::agent::TransactionId;
+use stun::message::Message;
+use stun::textattrs::Username;
+
+use util::conn::conn_tcp::{TcpConn, TcpListener};
+
+fn binding_request(ufrag: &str) -> Vec<u8> {
+    let mut m = Message::new();
+    m.build(&[
+        Box::new(BINDING_REQUEST),
+        Box::new(TransactionId::new()),
+        Box::new(Username::new(ATTR_USERNAME, format!("{}:otherufrag", ufrag))),
+    ])
+    .unwrap();
+
+    m.marshal_binary().unwrap()
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_tcp_mux() -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let listener_addr = listener.addr().await?;
+
+    let tcp_mux = TCPMuxDefault::new(TCPMuxParams::new(listener));
+    let conn = Arc::clone(&tcp_mux)
+        .get_conn_by_ufrag("ufrag1", Ipv4Addr::LOCALHOST.into())
+        .await?;
+    assert_eq!(conn.local_addr()?, listener_addr);
+
+    let remote_connection = TcpConn::dial(listener_addr).await?;
+    let remote_connection_addr = remote_connection.local_addr()?;
+
+    // The first packet picks the packet conn and is delivered like any other.
+    let stun_msg = binding_request("ufrag1");
+    remote_connection.send(&stun_msg).await?;
+    remote_connection.send(b"after the binding request").await?;
+
+    let mut buffer = vec![0u8; RECEIVE_MTU];
+    let (len, addr) = conn.recv_from(&mut buffer).await?;
+    assert_eq!(buffer[..len], stun_msg);
+    assert_eq!(addr, remote_connection_addr);
+    let (len, addr) = conn.recv_from(&mut buffer).await?;
+    assert_eq!(&buffer[..len], b"after the binding request");
+    assert_eq!(addr, remote_connection_addr);
+
+    conn.send_to(b"reply", remote_connection_addr).await?;
+    let len = remote_connection.recv(&mut buffer).await?;
+    assert_eq!(&buffer[..len], b"reply");
+
+    tcp_mux.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_tcp_mux_unknown_ufrag() -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let listener_addr = listener.addr().await?;
+
+    let tcp_mux = TCPMuxDefault::new(TCPMuxParams::new(listener));
+    let _conn = Arc::clone(&tcp_mux)
+        .get_conn_by_ufrag("ufrag1", Ipv4Addr::LOCALHOST.into())
+        .await?;
+
+    // Connections for an ufrag nobody asked for are closed straight away.
+    let remote_connection = TcpConn::dial(listener_addr).await?;
+    remote_connection.send(&binding_request("ufrag2")).await?;
+
+    let mut buffer = vec![0u8; RECEIVE_MTU];
+    assert!(remote_connection.recv(&mut buffer).await.is_err());
+
+    tcp_mux.close().await?;
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use std::{collections::HashMap, io, net::SocketAddr, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, watch, Mutex};
+
+use util::{sync::Mutex as SyncMutex, Conn, Error};
+
+use crate::candidate::RECEIVE_MTU;
+
+/// How many inbound packets may queue up before the stream readers wait on the agent.
+const RECEIVE_QUEUE_SIZE: usize = 64;
+
+type ConnResult<T> = Result<T, util::Error>;
+
+/// A packet connection over all the TCP streams accepted for one ufrag on one local address.
+///
+/// Packets read from any of the streams come out of `recv_from` tagged with the stream's remote
+/// address, and `send_to` writes to the stream with the given remote address, so the agent can
+/// treat it like a UDP socket.
+#[derive(Clone)]
+pub struct TCPPacketConn {
+    inner: Arc<TCPPacketConnInner>,
+}
+
+struct TCPPacketConnInner {
+    local_addr: SocketAddr,
+
+    /// Maps from remote address to the framed stream.
+    conns: SyncMutex<HashMap<SocketAddr, Arc<dyn Conn>>>,
+
+    recv_tx: mpsc::Sender<(Vec<u8>, SocketAddr)>,
+    recv_rx: Mutex<mpsc::Receiver<(Vec<u8>, SocketAddr)>>,
+
+    /// Close Sender. We'll send a value on this channel when we close
+    closed_watch_tx: SyncMutex<Option<watch::Sender<bool>>>,
+    closed_watch_rx: watch::Receiver<bool>,
+}
+
+impl TCPPacketConn {
+    /// Creates a new [`TCPPacketConn`] for the given local address.
+    pub fn new(local_addr: SocketAddr) -> Self {
+        let (recv_tx, recv_rx) = mpsc::channel(RECEIVE_QUEUE_SIZE);
+        let (closed_watch_tx, closed_watch_rx) = watch::channel(false);
+
+        Self {
+            inner: Arc::new(TCPPacketConnInner {
+                local_addr,
+                conns: SyncMutex::new(HashMap::new()),
+                recv_tx,
+                recv_rx: Mutex::new(recv_rx),
+                closed_watch_tx: SyncMutex::new(Some(closed_watch_tx)),
+                closed_watch_rx,
+            }),
+        }
+    }
+
+    /// Adds an accepted stream, delivering `first_packet` (already read from it by the mux)
+    /// before anything else read from the stream.
+    pub async fn add_conn(
+        &self,
+        conn: Arc<dyn Conn>,
+        raddr: SocketAddr,
+        first_packet: &[u8],
+    ) -> crate::error::Result<()> {
+        if self.is_closed() {
+            let _ = conn.close().await;
+            return Err(crate::error::Error::ErrClosed);
+        }
+
+        {
+            let mut conns = self.inner.conns.lock();
+            if conns.contains_key(&raddr) {
+                return Err(crate::error::Error::ErrTcpRemoteAddrAlreadyExists);
+            }
+            conns.insert(raddr, Arc::clone(&conn));
+        }
+
+        let _ = self
+            .inner
+            .recv_tx
+            .send((first_packet.to_vec(), raddr))
+            .await;
+
+        let inner = Arc::clone(&self.inner);
+        let mut closed_watch_rx = self.inner.closed_watch_rx.clone();
+        deno_net::spawn(async move {
+            let mut buffer = vec![0u8; RECEIVE_MTU];
+
+            loop {
+                tokio::select! {
+                    res = conn.recv(&mut buffer) => {
+                        match res {
+                            Ok(n) => {
+                                if inner.recv_tx.send((buffer[..n].to_vec(), raddr)).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(err) => {
+                                log::debug!("Closing tcp conn from {}: {}", raddr, err);
+                                break;
+                            }
+                        }
+                    }
+                    _ = closed_watch_rx.changed() => break,
+                }
+            }
+
+            inner.conns.lock().remove(&raddr);
+            let _ = conn.close().await;
+        });
+
+        Ok(())
+    }
+
+    /// Returns true if this connection is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed_watch_tx.lock().is_none()
+    }
+
+    /// Gets a copy of the close [`tokio::sync::watch::Receiver`] that fires when this
+    /// connection is closed.
+    pub fn close_rx(&self) -> watch::Receiver<bool> {
+        self.inner.closed_watch_rx.clone()
+    }
+
+    /// Closes this connection and all the streams in it.
+    pub async fn close(&self) {
+        let closed_tx = self.inner.closed_watch_tx.lock().take();
+        if let Some(tx) = closed_tx {
+            let _ = tx.send(true);
+
+            let old_conns = std::mem::take(&mut *self.inner.conns.lock());
+            for (_, conn) in old_conns {
+                let _ = conn.close().await;
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Conn for TCPPacketConn {
+    async fn connect(&self, _addr: SocketAddr) -> ConnResult<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn recv(&self, _buf: &mut [u8]) -> ConnResult<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> ConnResult<(usize, SocketAddr)> {
+        let mut closed_watch_rx = self.inner.closed_watch_rx.clone();
+        if *closed_watch_rx.borrow() {
+            return Err(Error::ErrUseClosedNetworkConn);
+        }
+
+        let mut recv_rx = self.inner.recv_rx.lock().await;
+        let (data, raddr) = tokio::select! {
+            res = recv_rx.recv() => res.ok_or(Error::ErrUseClosedNetworkConn)?,
+            _ = closed_watch_rx.changed() => return Err(Error::ErrUseClosedNetworkConn),
+        };
+
+        if data.len() > buf.len() {
+            return Err(Error::ErrBufferShort);
+        }
+        buf[..data.len()].copy_from_slice(&data);
+
+        Ok((data.len(), raddr))
+    }
+
+    async fn send(&self, _buf: &[u8]) -> ConnResult<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn send_to(&self, buf: &[u8], target: SocketAddr) -> ConnResult<usize> {
+        let conn = self.inner.conns.lock().get(&target).cloned();
+        match conn {
+            Some(conn) => conn.send(buf).await,
+            None => Err(Error::Other(format!(
+                "wanted to send {} bytes to {}, but there is no tcp conn from it",
+                buf.len(),
+                target
+            ))),
+        }
+    }
+
+    fn local_addr(&self) -> ConnResult<SocketAddr> {
+        Ok(self.inner.local_addr)
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    async fn close(&self) -> ConnResult<()> {
+        TCPPacketConn::close(self).await;
+
+        Ok(())
+    }
+}
@@ -38,7 +38,7 @@ impl Network {
 
 const TIMEOUT: Duration = Duration::from_secs(60);
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_mux() -> Result<()> {
     use std::io::Write;
     env_logger::Builder::from_default_env()
@@ -69,22 +69,22 @@ async fn test_udp_mux() -> Result<()> {
     let udp_mux_dyn = Arc::clone(&udp_mux) as Arc<dyn UDPMux>;
 
     let udp_mux_dyn_1 = Arc::clone(&udp_mux_dyn);
-    let h1 = wasm_bindgen_futures::spawn_local(async move {
+    let h1 = async move {
         timeout(
             TIMEOUT,
             test_mux_connection(Arc::clone(&udp_mux_dyn_1), "ufrag1", addr, Network::Ipv4),
         )
         .await
-    });
+    };
 
     let udp_mux_dyn_2 = Arc::clone(&udp_mux_dyn);
-    let h2 = wasm_bindgen_futures::spawn_local(async move {
+    let h2 = async move {
         timeout(
             TIMEOUT,
             test_mux_connection(Arc::clone(&udp_mux_dyn_2), "ufrag2", addr, Network::Ipv4),
         )
         .await
-    });
+    };
 
     let all_results;
 
@@ -92,13 +92,13 @@ async fn test_udp_mux() -> Result<()> {
     {
         // TODO: Support IPv6 dual stack. This works Linux and macOS, but not Windows.
         let udp_mux_dyn_3 = Arc::clone(&udp_mux_dyn);
-        let h3 = wasm_bindgen_futures::spawn_local(async move {
+        let h3 = async move {
             timeout(
                 TIMEOUT,
                 test_mux_connection(Arc::clone(&udp_mux_dyn_3), "ufrag3", addr, Network::Ipv6),
             )
             .await
-        });
+        };
 
         let (r1, r2, r3) = tokio::join!(h1, h2, h3);
         all_results = [r1, r2, r3];
@@ -117,18 +117,12 @@ async fn test_udp_mux() -> Result<()> {
                 panic!("Mux test timedout: {:?}", timeout_err);
             }
 
-            // Join error
-            Ok(join_result) => match join_result {
-                Err(err) => {
-                    panic!("Mux test failed with join error: {:?}", err);
+            // Actual error
+            Ok(mux_result) => {
+                if let Err(err) = mux_result {
+                    panic!("Mux test failed with error: {:?}", err);
                 }
-                // Actual error
-                Ok(mux_result) => {
-                    if let Err(err) = mux_result {
-                        panic!("Mux test failed with error: {:?}", err);
-                    }
-                }
-            },
+            }
         }
     }
 
@@ -203,7 +197,7 @@ async fn test_mux_connection(
 
     // Read on the muxed side
     let conn_2 = Arc::clone(&conn);
-    let mux_handle = wasm_bindgen_futures::spawn_local(async move {
+    let mux_handle = async move {
         let conn = conn_2;
 
         let mut buffer = vec![0u8; RECEIVE_MTU];
@@ -227,10 +221,10 @@ async fn test_mux_connection(
             log::debug!("Muxxed read {}, sequence: {}", read, next_sequence);
             next_sequence += 1;
         }
-    });
+    };
 
     let remote_connection_2 = Arc::clone(&remote_connection);
-    let remote_handle = wasm_bindgen_futures::spawn_local(async move {
+    let remote_handle = async move {
         let remote_connection = remote_connection_2;
         let mut buffer = vec![0u8; RECEIVE_MTU];
         let mut next_sequence = 0;
@@ -248,29 +242,33 @@ async fn test_mux_connection(
             log::debug!("Remote read {}, sequence: {}", read, next_sequence);
             next_sequence += 1;
         }
-    });
+    };
 
-    let mut sequence: u32 = 0;
-    let mut written = 0;
-    let mut buffer = vec![0u8; RECEIVE_MTU];
-    while written < TARGET_SIZE {
-        thread_rng().fill(&mut buffer[24..]);
+    // The readers run alongside the writer, their assertions fail the test directly.
+    let writer = async {
+        let mut sequence: u32 = 0;
+        let mut written = 0;
+        let mut buffer = vec![0u8; RECEIVE_MTU];
+        while written < TARGET_SIZE {
+            thread_rng().fill(&mut buffer[24..]);
 
-        let hash = sha1_hash(&buffer[24..]);
-        buffer[4..24].copy_from_slice(&hash);
-        buffer[0..4].copy_from_slice(&sequence.to_le_bytes());
+            let hash = sha1_hash(&buffer[24..]);
+            buffer[4..24].copy_from_slice(&hash);
+            buffer[0..4].copy_from_slice(&sequence.to_le_bytes());
 
-        let len = remote_connection.send(&buffer).await?;
+            let len = remote_connection.send(&buffer).await?;
 
-        written += len;
-        log::debug!("Data written {}, sequence: {}", written, sequence);
-        sequence += 1;
+            written += len;
+            log::debug!("Data written {}, sequence: {}", written, sequence);
+            sequence += 1;
 
-        sleep(Duration::from_millis(1)).await;
-    }
+            sleep(Duration::from_millis(1)).await;
+        }
+        Result::<()>::Ok(())
+    };
 
-    let (r1, r2) = tokio::join!(mux_handle, remote_handle);
-    assert!(r1.is_ok() && r2.is_ok());
+    let ((), (), written) = tokio::join!(mux_handle, remote_handle, writer);
+    written?;
 
     let res = conn.close().await;
     assert!(res.is_ok(), "Failed to close Conn: {:?}", res);
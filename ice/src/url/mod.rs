@@ -6,6 +6,8 @@ use crate::error::*;
 use std::borrow::Cow;
 use std::convert::From;
 use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use util::vnet::net::Net;
 
 /// The type of server used in the ice.URL structure.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -111,6 +113,9 @@ pub struct Url {
     pub username: String,
     pub password: String,
     pub proto: ProtoType,
+    /// Whether the port was given in the URL. Without one the server is located through SRV
+    /// records first, see [IETF rfc-5928](https://tools.ietf.org/html/rfc5928#section-3).
+    pub explicit_port: bool,
 }
 
 impl fmt::Display for Url {
@@ -233,6 +238,7 @@ impl Url {
             username: "".to_owned(),
             password: "".to_owned(),
             proto,
+            explicit_port: raw_parts.port().is_some(),
         })
     }
 
@@ -263,4 +269,40 @@ impl Url {
     pub fn is_secure(&self) -> bool {
         self.scheme == SchemeType::Stuns || self.scheme == SchemeType::Turns
     }
+
+    /// Returns the SRV owner name of the server, e.g. `_turn._udp.example.org`.
+    #[must_use]
+    pub fn srv_name(&self) -> String {
+        format!("_{}._{}.{}", self.scheme, self.proto, self.host)
+    }
+
+    /// Resolves the server's transport address. URLs without an explicit port are looked up
+    /// through SRV first; when that yields nothing usable the host is resolved with the default
+    /// port. Virtual networks have no DNS, so they skip the SRV step.
+    pub async fn resolve_addr(&self, net: &Net, use_ipv4: bool) -> Result<SocketAddr> {
+        if !self.explicit_port && !net.is_virtual() && self.host.parse::<IpAddr>().is_err() {
+            let name = self.srv_name();
+            match deno_net::resolve_srv(&name).await {
+                Ok(records) => {
+                    for record in records {
+                        let target = format!("{}:{}", record.target, record.port);
+                        match net.resolve_addr(use_ipv4, &target).await {
+                            Ok(addr) => return Ok(addr),
+                            Err(err) => {
+                                log::debug!("failed to resolve SRV target {}: {}", target, err);
+                            }
+                        }
+                    }
+                }
+                Err(err) => log::debug!("no SRV records for {}: {}", name, err),
+            }
+        }
+
+        let host_port = if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        };
+        Ok(net.resolve_addr(use_ipv4, &host_port).await?)
+    }
 }
@@ -144,3 +144,30 @@ fn test_parse_url_failure() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_url_srv_name() -> Result<()> {
+    let tests = vec![
+        ("stun:example.org", "_stun._udp.example.org", false),
+        ("stuns:example.org", "_stuns._tcp.example.org", false),
+        ("turn:example.org", "_turn._udp.example.org", false),
+        (
+            "turn:example.org?transport=tcp",
+            "_turn._tcp.example.org",
+            false,
+        ),
+        ("turns:example.org:443", "_turns._tcp.example.org", true),
+    ];
+
+    for (raw_url, expected_srv_name, expected_explicit_port) in tests {
+        let url = Url::parse_url(raw_url)?;
+        assert_eq!(expected_srv_name, url.srv_name(), "testCase: {:?}", raw_url);
+        assert_eq!(
+            expected_explicit_port, url.explicit_port,
+            "testCase: {:?}",
+            raw_url
+        );
+    }
+
+    Ok(())
+}
@@ -84,7 +84,7 @@ impl MockStream {
         let rtcp_reader = interceptor
             .bind_rtcp_reader(Arc::clone(&stream) as Arc<dyn RTCPReader>)
             .await;
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; 1500];
             let a = Attributes::new();
             loop {
@@ -117,7 +117,7 @@ impl MockStream {
                 Arc::clone(&stream) as Arc<dyn RTPReader>,
             )
             .await;
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; 1500];
             let a = Attributes::new();
             loop {
@@ -311,7 +311,7 @@ mod test {
     use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
     use std::time::Duration;
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_mock_stream() -> Result<()> {
         let s = MockStream::new(&StreamInfo::default(), Arc::new(NoOp)).await;
 
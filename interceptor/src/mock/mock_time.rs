@@ -1,34 +1,39 @@
 use std::time::Duration;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
 
 use util::sync::Mutex;
 
-/// MockTime is a helper to replace SystemTime::now() for testing purposes.
+/// MockTime is a helper to replace Instant::now() for testing purposes.
 pub struct MockTime {
-    cur_now: Mutex<SystemTime>,
+    cur_now: Mutex<Instant>,
 }
 
 impl Default for MockTime {
     fn default() -> Self {
         MockTime {
-            cur_now: Mutex::new(SystemTime::UNIX_EPOCH),
+            cur_now: Mutex::new(Instant::from_unix_epoch(Duration::ZERO)),
         }
     }
 }
 
 impl MockTime {
     /// set_now sets the current time.
-    pub fn set_now(&self, now: SystemTime) {
+    pub fn set_now(&self, now: Instant) {
         let mut cur_now = self.cur_now.lock();
         *cur_now = now;
     }
 
     /// now returns the current time.
-    pub fn now(&self) -> SystemTime {
+    pub fn now(&self) -> Instant {
         let cur_now = self.cur_now.lock();
         *cur_now
     }
 
+    /// wall_now returns the wall clock time matching now.
+    pub fn wall_now(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + self.now().since_unix_epoch()
+    }
+
     /// advance advances duration d
     pub fn advance(&mut self, d: Duration) {
         let mut cur_now = self.cur_now.lock();
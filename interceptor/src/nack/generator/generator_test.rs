@@ -5,7 +5,7 @@ use crate::test::timeout_or_fail;
 
 use rtcp::transport_feedbacks::transport_layer_nack::TransportLayerNack;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_generator_interceptor() -> Result<()> {
     const INTERVAL: Duration = Duration::from_millis(10);
     let icpr: Arc<dyn Interceptor> = Generator::builder()
@@ -12,6 +12,7 @@ use crate::{InterceptorBuilder, RTCPWriter};
 use crate::nack::stream_support_nack;
 
 use async_trait::async_trait;
+use deno_net::Ticker;
 use rtcp::transport_feedbacks::transport_layer_nack::{
     nack_pairs_from_sequence_numbers, TransportLayerNack,
 };
@@ -114,7 +115,7 @@ impl Generator {
         rtcp_writer: Arc<dyn RTCPWriter>,
         internal: Arc<GeneratorInternal>,
     ) -> Result<()> {
-        let mut ticker = tokio::time::interval(internal.interval);
+        let mut ticker = deno_net::interval(internal.interval);
         let mut close_rx = {
             let mut close_rx = internal.close_rx.lock().await;
             if let Some(close) = close_rx.take() {
@@ -188,7 +189,7 @@ impl Interceptor for Generator {
         };
         let writer2 = Arc::clone(&writer);
         let internal = Arc::clone(&self.internal);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let _d = w.take();
             if let Err(err) = Generator::run(writer2, internal).await {
                 log::warn!("bind_rtcp_writer NACK Generator::run got error: {}", err);
@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use rtcp::transport_feedbacks::transport_layer_nack::{NackPair, TransportLayerNack};
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_responder_interceptor() -> Result<()> {
     let icpr: Arc<dyn Interceptor> =
         Responder::builder().with_log2_size(3).build("")?;
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
 use tokio::sync::{mpsc, Mutex};
 use waitgroup::WaitGroup;
 
@@ -13,7 +13,8 @@ use crate::{Interceptor, InterceptorBuilder};
 use receiver::{ReceiverReport, ReceiverReportInternal};
 use sender::{SenderReport, SenderReportInternal};
 
-type FnTimeGen = Arc<dyn Fn() -> SystemTime + 'static>;
+type FnTimeGen = Arc<dyn Fn() -> Instant + 'static>;
+type FnWallTimeGen = Arc<dyn Fn() -> SystemTime + 'static>;
 
 /// ReceiverBuilder can be used to configure ReceiverReport Interceptor.
 #[derive(Default)]
@@ -21,6 +22,7 @@ pub struct ReportBuilder {
     is_rr: bool,
     interval: Option<Duration>,
     now: Option<FnTimeGen>,
+    wall_now: Option<FnWallTimeGen>,
 }
 
 impl ReportBuilder {
@@ -36,6 +38,13 @@ impl ReportBuilder {
         self
     }
 
+    /// with_wall_now_fn sets an alternative for the wall clock that the NTP timestamps of
+    /// sender reports are read from.
+    pub fn with_wall_now_fn(mut self, wall_now: FnWallTimeGen) -> ReportBuilder {
+        self.wall_now = Some(wall_now);
+        self
+    }
+
     fn build_rr(&self) -> ReceiverReport {
         let (close_tx, close_rx) = mpsc::channel(1);
         ReceiverReport {
@@ -65,6 +74,7 @@ impl ReportBuilder {
                     Duration::from_secs(1)
                 },
                 now: self.now.clone(),
+                wall_now: self.wall_now.clone(),
                 streams: Mutex::new(HashMap::new()),
                 close_rx: Mutex::new(Some(close_rx)),
             }),
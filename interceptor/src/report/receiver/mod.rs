@@ -9,7 +9,7 @@ use receiver_stream::ReceiverStream;
 
 use std::collections::HashMap;
 use std::time::Duration;
-use deno_net::SystemTime;
+use deno_net::{Instant, Ticker};
 use tokio::sync::{mpsc, Mutex};
 use waitgroup::WaitGroup;
 
@@ -36,7 +36,7 @@ impl RTCPReader for ReceiverReportRtcpReader {
         let now = if let Some(f) = &self.internal.now {
             f()
         } else {
-            SystemTime::now()
+            Instant::now()
         };
 
         for p in &pkts {
@@ -84,7 +84,7 @@ impl ReceiverReport {
         rtcp_writer: Arc<dyn RTCPWriter>,
         internal: Arc<ReceiverReportInternal>,
     ) -> Result<()> {
-        let mut ticker = tokio::time::interval(internal.interval);
+        let mut ticker = deno_net::interval(internal.interval);
         let mut close_rx = {
             let mut close_rx = internal.close_rx.lock().await;
             if let Some(close) = close_rx.take() {
@@ -102,7 +102,7 @@ impl ReceiverReport {
                     let now = if let Some(f) = &internal.now {
                         f()
                     } else {
-                        SystemTime::now()
+                        Instant::now()
                     };
                     let streams:Vec<Arc<ReceiverStream>> = {
                         let m = internal.streams.lock().await;
@@ -155,7 +155,7 @@ impl Interceptor for ReceiverReport {
         };
         let writer2 = Arc::clone(&writer);
         let internal = Arc::clone(&self.internal);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let _d = w.take();
             if let Err(err) = ReceiverReport::run(writer2, internal).await {
                 log::warn!("bind_rtcp_writer ReceiverReport::run got error: {}", err);
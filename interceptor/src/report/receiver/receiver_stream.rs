@@ -2,7 +2,7 @@ use super::*;
 use crate::{Attributes, RTPReader};
 
 use async_trait::async_trait;
-use deno_net::SystemTime;
+use deno_net::Instant;
 use util::sync::Mutex;
 use util::Unmarshal;
 
@@ -17,10 +17,10 @@ struct ReceiverStreamInternal {
     last_seq_num: i32,
     last_report_seq_num: i32,
     last_rtp_time_rtp: u32,
-    last_rtp_time_time: SystemTime,
+    last_rtp_time_time: Instant,
     jitter: f64,
     last_sender_report: u32,
-    last_sender_report_time: SystemTime,
+    last_sender_report_time: Option<Instant>,
     total_lost: u32,
 }
 
@@ -40,7 +40,7 @@ impl ReceiverStreamInternal {
         (self.packets[pos / 64] & (1 << (pos % 64))) != 0
     }
 
-    fn process_rtp(&mut self, now: SystemTime, pkt: &rtp::packet::Packet) {
+    fn process_rtp(&mut self, now: Instant, pkt: &rtp::packet::Packet) {
         if !self.started {
             // first frame
             self.started = true;
@@ -68,10 +68,7 @@ impl ReceiverStreamInternal {
 
             // compute jitter
             // https://tools.ietf.org/html/rfc3550#page-39
-            let d = now
-                .duration_since(self.last_rtp_time_time)
-                .unwrap_or_else(|_| Duration::from_secs(0))
-                .as_secs_f64()
+            let d = now.duration_since(self.last_rtp_time_time).as_secs_f64()
                 * self.clock_rate
                 - (pkt.header.timestamp as f64 - self.last_rtp_time_rtp as f64);
             self.jitter += (d.abs() - self.jitter) / 16.0;
@@ -81,12 +78,12 @@ impl ReceiverStreamInternal {
         self.last_rtp_time_time = now;
     }
 
-    fn process_sender_report(&mut self, now: SystemTime, sr: &rtcp::sender_report::SenderReport) {
+    fn process_sender_report(&mut self, now: Instant, sr: &rtcp::sender_report::SenderReport) {
         self.last_sender_report = (sr.ntp_time >> 16) as u32;
-        self.last_sender_report_time = now;
+        self.last_sender_report_time = Some(now);
     }
 
-    fn generate_report(&mut self, now: SystemTime) -> rtcp::receiver_report::ReceiverReport {
+    fn generate_report(&mut self, now: Instant) -> rtcp::receiver_report::ReceiverReport {
         let total_since_report = (self.last_seq_num - self.last_report_seq_num) as u16;
         let mut total_lost_since_report = {
             if self.last_seq_num == self.last_report_seq_num {
@@ -124,15 +121,9 @@ impl ReceiverStreamInternal {
                 fraction_lost: ((total_lost_since_report * 256) as f64 / total_since_report as f64)
                     as u8,
                 total_lost: self.total_lost,
-                delay: {
-                    if self.last_sender_report_time == SystemTime::UNIX_EPOCH {
-                        0
-                    } else {
-                        match now.duration_since(self.last_sender_report_time) {
-                            Ok(d) => (d.as_secs_f64() * 65536.0) as u32,
-                            Err(_) => 0,
-                        }
-                    }
+                delay: match self.last_sender_report_time {
+                    Some(t) => (now.duration_since(t).as_secs_f64() * 65536.0) as u32,
+                    None => 0,
                 },
                 jitter: self.jitter as u32,
             }],
@@ -175,30 +166,30 @@ impl ReceiverStream {
                 last_seq_num: 0,
                 last_report_seq_num: 0,
                 last_rtp_time_rtp: 0,
-                last_rtp_time_time: SystemTime::UNIX_EPOCH,
+                last_rtp_time_time: Instant::from_unix_epoch(Duration::ZERO),
                 jitter: 0.0,
                 last_sender_report: 0,
-                last_sender_report_time: SystemTime::UNIX_EPOCH,
+                last_sender_report_time: None,
                 total_lost: 0,
             }),
         }
     }
 
-    pub(crate) fn process_rtp(&self, now: SystemTime, pkt: &rtp::packet::Packet) {
+    pub(crate) fn process_rtp(&self, now: Instant, pkt: &rtp::packet::Packet) {
         let mut internal = self.internal.lock();
         internal.process_rtp(now, pkt);
     }
 
     pub(crate) fn process_sender_report(
         &self,
-        now: SystemTime,
+        now: Instant,
         sr: &rtcp::sender_report::SenderReport,
     ) {
         let mut internal = self.internal.lock();
         internal.process_sender_report(now, sr);
     }
 
-    pub(crate) fn generate_report(&self, now: SystemTime) -> rtcp::receiver_report::ReceiverReport {
+    pub(crate) fn generate_report(&self, now: Instant) -> rtcp::receiver_report::ReceiverReport {
         let mut internal = self.internal.lock();
         internal.generate_report(now)
     }
@@ -216,7 +207,7 @@ impl RTPReader for ReceiverStream {
         let now = if let Some(f) = &self.now {
             f()
         } else {
-            SystemTime::now()
+            Instant::now()
         };
         self.process_rtp(now, &pkt);
 
@@ -3,9 +3,20 @@ use crate::mock::mock_stream::MockStream;
 use crate::mock::mock_time::MockTime;
 //use bytes::Bytes;
 use chrono::prelude::*;
+use deno_net::{Instant, SystemTime};
 use rtp::extension::abs_send_time_extension::unix2ntp;
 
-#[tokio::test]
+/// Mock clock reading for a calendar date, on the monotonic clock's unix-anchored timeline.
+fn instant(dt: DateTime<Utc>) -> Instant {
+    Instant::from_unix_epoch(Duration::from_secs(dt.timestamp() as u64))
+}
+
+/// Wall clock reading for a calendar date, as carried in the NTP timestamp of sender reports.
+fn wall(dt: DateTime<Utc>) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(dt.timestamp() as u64)
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_before_any_packet() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -57,7 +68,7 @@ async fn test_receiver_interceptor_before_any_packet() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_after_rtp_packets() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -120,9 +131,9 @@ async fn test_receiver_interceptor_after_rtp_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_after_rtp_and_rtcp_packets() -> Result<()> {
-    let rtp_time: SystemTime = Utc.ymd(2009, 10, 23).and_hms(0, 0, 0).into();
+    let rtp_time = instant(Utc.ymd(2009, 10, 23).and_hms(0, 0, 0));
 
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -157,17 +168,16 @@ async fn test_receiver_interceptor_after_rtp_and_rtcp_packets() -> Result<()> {
             .await;
     }
 
-    let now: SystemTime = Utc.ymd(2009, 11, 10).and_hms(23, 0, 1).into();
+    let now_wall = Utc.ymd(2009, 11, 10).and_hms(23, 0, 1);
+    let now = instant(now_wall);
     let rt = 987654321u32.wrapping_add(
-        (now.duration_since(rtp_time)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs_f64()
+        (now.duration_since(rtp_time).as_secs_f64()
             * 90000.0) as u32,
     );
     stream
         .receive_rtcp(vec![Box::new(rtcp::sender_report::SenderReport {
             ssrc: 123456,
-            ntp_time: unix2ntp(now),
+            ntp_time: unix2ntp(wall(now_wall)),
             rtp_time: rt,
             packet_count: 10,
             octet_count: 0,
@@ -203,7 +213,7 @@ async fn test_receiver_interceptor_after_rtp_and_rtcp_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_overflow() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let _mt2 = Arc::clone(&mt);
@@ -274,7 +284,7 @@ async fn test_receiver_interceptor_overflow() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_overflow_five_pkts() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -374,9 +384,9 @@ async fn test_receiver_interceptor_overflow_five_pkts() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_packet_loss() -> Result<()> {
-    let rtp_time: SystemTime = Utc.ymd(2009, 11, 10).and_hms(23, 0, 0).into();
+    let rtp_time = instant(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0));
 
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -442,17 +452,16 @@ async fn test_receiver_interceptor_packet_loss() -> Result<()> {
         assert!(false);
     }
 
-    let now: SystemTime = Utc.ymd(2009, 11, 10).and_hms(23, 0, 1).into();
+    let now_wall = Utc.ymd(2009, 11, 10).and_hms(23, 0, 1);
+    let now = instant(now_wall);
     let rt = 987654321u32.wrapping_add(
-        (now.duration_since(rtp_time)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs_f64()
+        (now.duration_since(rtp_time).as_secs_f64()
             * 90000.0) as u32,
     );
     stream
         .receive_rtcp(vec![Box::new(rtcp::sender_report::SenderReport {
             ssrc: 123456,
-            ntp_time: unix2ntp(now),
+            ntp_time: unix2ntp(wall(now_wall)),
             rtp_time: rt,
             packet_count: 10,
             octet_count: 0,
@@ -487,7 +496,7 @@ async fn test_receiver_interceptor_packet_loss() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_overflow_and_packet_loss() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -557,7 +566,7 @@ async fn test_receiver_interceptor_overflow_and_packet_loss() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_reordered_packets() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -619,7 +628,7 @@ async fn test_receiver_interceptor_reordered_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test(start_paused = true)]
+#[tokio::test(flavor = "local", start_paused = true)]
 async fn test_receiver_interceptor_jitter() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -642,7 +651,7 @@ async fn test_receiver_interceptor_jitter() -> Result<()> {
     )
     .await;
 
-    mt.set_now(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0).into());
+    mt.set_now(instant(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0)));
     stream
         .receive_rtp(rtp::packet::Packet {
             header: rtp::header::Header {
@@ -655,7 +664,7 @@ async fn test_receiver_interceptor_jitter() -> Result<()> {
         .await;
     stream.read_rtp().await;
 
-    mt.set_now(Utc.ymd(2009, 11, 10).and_hms(23, 0, 1).into());
+    mt.set_now(instant(Utc.ymd(2009, 11, 10).and_hms(23, 0, 1)));
     stream
         .receive_rtp(rtp::packet::Packet {
             header: rtp::header::Header {
@@ -699,7 +708,7 @@ async fn test_receiver_interceptor_jitter() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_receiver_interceptor_delay() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
@@ -722,11 +731,11 @@ async fn test_receiver_interceptor_delay() -> Result<()> {
     )
     .await;
 
-    mt.set_now(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0).into());
+    mt.set_now(instant(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0)));
     stream
         .receive_rtcp(vec![Box::new(rtcp::sender_report::SenderReport {
             ssrc: 123456,
-            ntp_time: unix2ntp(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0).into()),
+            ntp_time: unix2ntp(wall(Utc.ymd(2009, 11, 10).and_hms(23, 0, 0))),
             rtp_time: 987654321,
             packet_count: 0,
             octet_count: 0,
@@ -735,7 +744,7 @@ async fn test_receiver_interceptor_delay() -> Result<()> {
         .await;
     stream.read_rtcp().await;
 
-    mt.set_now(Utc.ymd(2009, 11, 10).and_hms(23, 0, 1).into());
+    mt.set_now(instant(Utc.ymd(2009, 11, 10).and_hms(23, 0, 1)));
 
     let pkts = stream.written_rtcp().await.unwrap();
     assert_eq!(pkts.len(), 1);
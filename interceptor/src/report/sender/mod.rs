@@ -9,13 +9,14 @@ use sender_stream::SenderStream;
 
 use std::collections::HashMap;
 use std::time::Duration;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime, Ticker};
 use tokio::sync::{mpsc, Mutex};
 use waitgroup::WaitGroup;
 
 pub(crate) struct SenderReportInternal {
     pub(crate) interval: Duration,
     pub(crate) now: Option<FnTimeGen>,
+    pub(crate) wall_now: Option<FnWallTimeGen>,
     pub(crate) streams: Mutex<HashMap<u32, Arc<SenderStream>>>,
     pub(crate) close_rx: Mutex<Option<mpsc::Receiver<()>>>,
 }
@@ -46,7 +47,7 @@ impl SenderReport {
         rtcp_writer: Arc<dyn RTCPWriter>,
         internal: Arc<SenderReportInternal>,
     ) -> Result<()> {
-        let mut ticker = tokio::time::interval(internal.interval);
+        let mut ticker = deno_net::interval(internal.interval);
         let mut close_rx = {
             let mut close_rx = internal.close_rx.lock().await;
             if let Some(close) = close_rx.take() {
@@ -62,6 +63,11 @@ impl SenderReport {
                     // TODO(cancel safety): This branch isn't cancel safe
                     let now = if let Some(f) = &internal.now {
                         f()
+                    } else {
+                        Instant::now()
+                    };
+                    let wall_now = if let Some(f) = &internal.wall_now {
+                        f()
                     } else {
                         SystemTime::now()
                     };
@@ -70,7 +76,7 @@ impl SenderReport {
                         m.values().cloned().collect()
                     };
                     for stream in streams {
-                        let pkt = stream.generate_report(now).await;
+                        let pkt = stream.generate_report(now, wall_now).await;
 
                         let a = Attributes::new();
                         if let Err(err) = rtcp_writer.write(&[Box::new(pkt)], &a).await{
@@ -113,7 +119,7 @@ impl Interceptor for SenderReport {
         };
         let writer2 = Arc::clone(&writer);
         let internal = Arc::clone(&self.internal);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let _d = w.take();
             if let Err(err) = SenderReport::run(writer2, internal).await {
                 log::warn!("bind_rtcp_writer Generator::run got error: {}", err);
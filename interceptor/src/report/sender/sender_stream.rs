@@ -6,7 +6,7 @@ use rtp::extension::abs_send_time_extension::unix2ntp;
 use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::Duration;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
 use tokio::sync::Mutex;
 
 struct SenderStreamInternal {
@@ -15,12 +15,12 @@ struct SenderStreamInternal {
 
     /// data from rtp packets
     last_rtp_time_rtp: u32,
-    last_rtp_time_time: SystemTime,
+    last_rtp_time_time: Instant,
     counters: Counters,
 }
 
 impl SenderStreamInternal {
-    fn process_rtp(&mut self, now: SystemTime, pkt: &rtp::packet::Packet) {
+    fn process_rtp(&mut self, now: Instant, pkt: &rtp::packet::Packet) {
         // always update time to minimize errors
         self.last_rtp_time_rtp = pkt.header.timestamp;
         self.last_rtp_time_time = now;
@@ -29,14 +29,18 @@ impl SenderStreamInternal {
         self.counters.count_octets(pkt.payload.len());
     }
 
-    fn generate_report(&mut self, now: SystemTime) -> rtcp::sender_report::SenderReport {
+    /// The NTP timestamp is read from the wall clock, as the receiver compares it with other
+    /// senders for synchronization, while the RTP timestamp follows the monotonic clock.
+    fn generate_report(
+        &mut self,
+        now: Instant,
+        wall_now: SystemTime,
+    ) -> rtcp::sender_report::SenderReport {
         rtcp::sender_report::SenderReport {
             ssrc: self.ssrc,
-            ntp_time: unix2ntp(now),
+            ntp_time: unix2ntp(wall_now),
             rtp_time: self.last_rtp_time_rtp.wrapping_add(
-                (now.duration_since(self.last_rtp_time_time)
-                    .unwrap_or_else(|_| Duration::from_secs(0))
-                    .as_secs_f64()
+                (now.duration_since(self.last_rtp_time_time).as_secs_f64()
                     * self.clock_rate) as u32,
             ),
             packet_count: self.counters.packet_count(),
@@ -68,23 +72,24 @@ impl SenderStream {
                 ssrc,
                 clock_rate: clock_rate as f64,
                 last_rtp_time_rtp: 0,
-                last_rtp_time_time: SystemTime::UNIX_EPOCH,
+                last_rtp_time_time: Instant::from_unix_epoch(Duration::ZERO),
                 counters: Default::default(),
             }),
         }
     }
 
-    async fn process_rtp(&self, now: SystemTime, pkt: &rtp::packet::Packet) {
+    async fn process_rtp(&self, now: Instant, pkt: &rtp::packet::Packet) {
         let mut internal = self.internal.lock().await;
         internal.process_rtp(now, pkt);
     }
 
     pub(crate) async fn generate_report(
         &self,
-        now: SystemTime,
+        now: Instant,
+        wall_now: SystemTime,
     ) -> rtcp::sender_report::SenderReport {
         let mut internal = self.internal.lock().await;
-        internal.generate_report(now)
+        internal.generate_report(now, wall_now)
     }
 }
 
@@ -96,7 +101,7 @@ impl RTPWriter for SenderStream {
         let now = if let Some(f) = &self.now {
             f()
         } else {
-            SystemTime::now()
+            Instant::now()
         };
         self.process_rtp(now, pkt).await;
 
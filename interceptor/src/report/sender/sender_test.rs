@@ -3,19 +3,30 @@ use crate::mock::mock_stream::MockStream;
 use crate::mock::mock_time::MockTime;
 use bytes::Bytes;
 use chrono::prelude::*;
+use deno_net::{Instant, SystemTime};
 use rtp::extension::abs_send_time_extension::unix2ntp;
 
-#[tokio::test]
+/// Mock clock reading for a calendar date, on the monotonic clock's unix-anchored timeline.
+fn instant(dt: DateTime<Utc>) -> Instant {
+    Instant::from_unix_epoch(Duration::from_secs(dt.timestamp() as u64))
+}
+
+#[tokio::test(flavor = "local")]
 async fn test_sender_interceptor_before_any_packet() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
         let mt = Arc::clone(&mt);
         Arc::new(move || mt.now())
     };
+    let wall_time_gen = {
+        let mt = Arc::clone(&mt);
+        Arc::new(move || mt.wall_now())
+    };
 
     let icpr: Arc<dyn Interceptor> = SenderReport::builder()
         .with_interval(Duration::from_millis(50))
         .with_now_fn(time_gen)
+        .with_wall_now_fn(wall_time_gen)
         .build("")?;
 
     let stream = MockStream::new(
@@ -29,7 +40,7 @@ async fn test_sender_interceptor_before_any_packet() -> Result<()> {
     .await;
 
     let dt = Utc.ymd(2009, 10, 23).and_hms(0, 0, 0);
-    mt.set_now(dt.into());
+    mt.set_now(instant(dt));
 
     let pkts = stream.written_rtcp().await.unwrap();
     assert_eq!(pkts.len(), 1);
@@ -40,7 +51,7 @@ async fn test_sender_interceptor_before_any_packet() -> Result<()> {
         assert_eq!(
             &rtcp::sender_report::SenderReport {
                 ssrc: 123456,
-                ntp_time: unix2ntp(mt.now()),
+                ntp_time: unix2ntp(mt.wall_now()),
                 rtp_time: 4294967295, // pion: 2269117121,
                 packet_count: 0,
                 octet_count: 0,
@@ -57,17 +68,22 @@ async fn test_sender_interceptor_before_any_packet() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_sender_interceptor_after_rtp_packets() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
         let mt = Arc::clone(&mt);
         Arc::new(move || mt.now())
     };
+    let wall_time_gen = {
+        let mt = Arc::clone(&mt);
+        Arc::new(move || mt.wall_now())
+    };
 
     let icpr: Arc<dyn Interceptor> = SenderReport::builder()
         .with_interval(Duration::from_millis(50))
         .with_now_fn(time_gen)
+        .with_wall_now_fn(wall_time_gen)
         .build("")?;
 
     let stream = MockStream::new(
@@ -93,7 +109,7 @@ async fn test_sender_interceptor_after_rtp_packets() -> Result<()> {
     }
 
     let dt = Utc.ymd(2009, 10, 23).and_hms(0, 0, 0);
-    mt.set_now(dt.into());
+    mt.set_now(instant(dt));
 
     let pkts = stream.written_rtcp().await.unwrap();
     assert_eq!(pkts.len(), 1);
@@ -104,7 +120,7 @@ async fn test_sender_interceptor_after_rtp_packets() -> Result<()> {
         assert_eq!(
             &rtcp::sender_report::SenderReport {
                 ssrc: 123456,
-                ntp_time: unix2ntp(mt.now()),
+                ntp_time: unix2ntp(mt.wall_now()),
                 rtp_time: 4294967295, // pion: 2269117121,
                 packet_count: 10,
                 octet_count: 20,
@@ -121,17 +137,22 @@ async fn test_sender_interceptor_after_rtp_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_sender_interceptor_after_rtp_packets_overflow() -> Result<()> {
     let mt = Arc::new(MockTime::default());
     let time_gen = {
         let mt = Arc::clone(&mt);
         Arc::new(move || mt.now())
     };
+    let wall_time_gen = {
+        let mt = Arc::clone(&mt);
+        Arc::new(move || mt.wall_now())
+    };
 
     let icpr: Arc<dyn Interceptor> = SenderReport::builder()
         .with_interval(Duration::from_millis(50))
         .with_now_fn(time_gen)
+        .with_wall_now_fn(wall_time_gen)
         .build("")?;
 
     let stream = MockStream::new(
@@ -195,7 +216,7 @@ async fn test_sender_interceptor_after_rtp_packets_overflow() -> Result<()> {
         .await?;
 
     let dt = Utc.ymd(2009, 10, 23).and_hms(0, 0, 0);
-    mt.set_now(dt.into());
+    mt.set_now(instant(dt));
 
     let pkts = stream.written_rtcp().await.unwrap();
     assert_eq!(pkts.len(), 1);
@@ -206,7 +227,7 @@ async fn test_sender_interceptor_after_rtp_packets_overflow() -> Result<()> {
         assert_eq!(
             &rtcp::sender_report::SenderReport {
                 ssrc: 123456,
-                ntp_time: unix2ntp(mt.now()),
+                ntp_time: unix2ntp(mt.wall_now()),
                 rtp_time: 4294967295, // pion: 2269117121,
                 packet_count: 5,
                 octet_count: 10,
@@ -223,7 +244,7 @@ async fn test_sender_interceptor_after_rtp_packets_overflow() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream_counters_initially_zero() -> Result<()> {
     let counters = sender_stream::Counters::default();
     assert_eq!(0, counters.octet_count());
@@ -231,7 +252,7 @@ async fn test_stream_counters_initially_zero() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream_packet_counter_wraps_on_overflow() -> Result<()> {
     let mut counters = sender_stream::Counters::mock(u32::MAX, 0);
     for _ in 0..3 {
@@ -241,7 +262,7 @@ async fn test_stream_packet_counter_wraps_on_overflow() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream_octet_counter_wraps_on_overflow() -> Result<()> {
     let mut counters = sender_stream::Counters::default();
     counters.count_octets(u32::MAX as usize);
@@ -250,10 +271,63 @@ async fn test_stream_octet_counter_wraps_on_overflow() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream_octet_counter_saturates_u32_from_usize() -> Result<()> {
     let mut counters = sender_stream::Counters::default();
     counters.count_octets(0xabcdef01234567_usize);
     assert_eq!(0xffffffff_u32, counters.octet_count());
     Ok(())
 }
+
+#[tokio::test(flavor = "local")]
+async fn test_sender_interceptor_wall_clock() -> Result<()> {
+    let mt = Arc::new(MockTime::default());
+    let time_gen = {
+        let mt = Arc::clone(&mt);
+        Arc::new(move || mt.now())
+    };
+    // The wall clock was stepped far away from the monotonic clock.
+    let wall: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+
+    let icpr: Arc<dyn Interceptor> = SenderReport::builder()
+        .with_interval(Duration::from_millis(50))
+        .with_now_fn(time_gen)
+        .with_wall_now_fn(Arc::new(move || wall))
+        .build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 123456,
+            clock_rate: 90000,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    mt.set_now(instant(Utc.ymd(2009, 10, 23).and_hms(0, 0, 0)));
+    stream
+        .write_rtp(&rtp::packet::Packet {
+            header: rtp::header::Header {
+                timestamp: 1000,
+                ..Default::default()
+            },
+            payload: Bytes::from_static(b"\x00\x00"),
+        })
+        .await?;
+    mt.set_now(instant(Utc.ymd(2009, 10, 23).and_hms(0, 0, 1)));
+
+    let pkts = stream.written_rtcp().await.unwrap();
+    assert_eq!(pkts.len(), 1);
+    let sr = pkts[0]
+        .as_any()
+        .downcast_ref::<rtcp::sender_report::SenderReport>()
+        .expect("a sender report");
+    // The NTP timestamp is read from the wall clock, the RTP one advances with the monotonic clock.
+    assert_eq!(sr.ntp_time, unix2ntp(wall));
+    assert_eq!(sr.rtp_time, 1000 + 90000);
+
+    stream.close().await?;
+
+    Ok(())
+}
@@ -1,17 +1,16 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime, Ticker};
 
 use super::{inbound, outbound, StatsContainer};
 use async_trait::async_trait;
-use rtcp::extended_report::{DLRRReportBlock, ExtendedReport};
+use rtcp::extended_report::{DLRRReportBlock, ExtendedReport, ReceiverReferenceTimeReportBlock};
 use rtcp::payload_feedbacks::full_intra_request::FullIntraRequest;
 use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
 use rtcp::receiver_report::ReceiverReport;
 use rtcp::sender_report::SenderReport;
 use rtcp::transport_feedbacks::transport_layer_nack::TransportLayerNack;
-use rtp::extension::abs_send_time_extension::unix2ntp;
 use tokio::sync::{mpsc, oneshot};
 use std::time::Duration;
 
@@ -22,6 +21,12 @@ use crate::error::Result;
 use crate::stream_info::StreamInfo;
 use crate::{Attributes, Interceptor, RTCPReader, RTCPWriter, RTPReader, RTPWriter};
 
+/// How long a sent report is remembered for matching the LSR/LRR of the reports that answer it.
+const SENT_REPORT_TTL: Duration = Duration::from_secs(60);
+
+/// Monotonic send times of our SRs and RRTRs, keyed by the middle 32 bits of their NTP timestamp.
+type SentReports = Arc<Mutex<HashMap<u32, Instant>>>;
+
 #[derive(Debug)]
 enum Message {
     StatUpdate {
@@ -91,30 +96,32 @@ pub struct StatsInterceptor {
     tx: mpsc::Sender<Message>,
 
     id: String,
-    now_gen: Arc<dyn Fn() -> SystemTime>,
+    now_gen: Arc<dyn Fn() -> Instant>,
+    sent_reports: SentReports,
 }
 
 impl StatsInterceptor {
     pub fn new(id: String) -> Self {
         let (tx, rx) = mpsc::channel(100);
 
-        wasm_bindgen_futures::spawn_local(run_stats_reducer(rx));
+        deno_net::spawn(run_stats_reducer(rx));
 
         Self {
             id,
             recv_streams: Default::default(),
             send_streams: Default::default(),
             tx,
-            now_gen: Arc::new(SystemTime::now),
+            now_gen: Arc::new(Instant::now),
+            sent_reports: Default::default(),
         }
     }
 
     fn with_time_gen<F>(id: String, now_gen: F) -> Self
     where
-        F: Fn() -> SystemTime + 'static,
+        F: Fn() -> Instant + 'static,
     {
         let (tx, rx) = mpsc::channel(100);
-        wasm_bindgen_futures::spawn_local(run_stats_reducer(rx));
+        deno_net::spawn(run_stats_reducer(rx));
 
         Self {
             id,
@@ -122,6 +129,7 @@ impl StatsInterceptor {
             send_streams: Default::default(),
             tx,
             now_gen: Arc::new(now_gen),
+            sent_reports: Default::default(),
         }
     }
 
@@ -172,7 +180,7 @@ impl StatsInterceptor {
 
 async fn run_stats_reducer(mut rx: mpsc::Receiver<Message>) {
     let mut ssrc_stats: StatsContainer = Default::default();
-    let mut cleanup_ticker = tokio::time::interval(Duration::from_secs(10));
+    let mut cleanup_ticker = deno_net::interval(Duration::from_secs(10));
 
     loop {
         tokio::select! {
@@ -361,6 +369,7 @@ impl Interceptor for StatsInterceptor {
             rtcp_writer: writer,
             tx: self.tx.clone(),
             now_gen: move || now(),
+            sent_reports: self.sent_reports.clone(),
         })
     }
 
@@ -376,6 +385,7 @@ impl Interceptor for StatsInterceptor {
             rtcp_reader: reader,
             tx: self.tx.clone(),
             now_gen: move || now(),
+            sent_reports: self.sent_reports.clone(),
         })
     }
 }
@@ -384,12 +394,13 @@ pub struct RTCPReadInterceptor<F> {
     rtcp_reader: Arc<dyn RTCPReader>,
     tx: mpsc::Sender<Message>,
     now_gen: F,
+    sent_reports: SentReports,
 }
 
 #[async_trait(?Send)]
 impl<F> RTCPReader for RTCPReadInterceptor<F>
 where
-    F: Fn() -> SystemTime,
+    F: Fn() -> Instant,
 {
     /// read a batch of rtcp packets
     async fn read(&self, buf: &mut [u8], attributes: &Attributes) -> Result<(usize, Attributes)> {
@@ -397,8 +408,10 @@ where
 
         let mut b = &buf[..n];
         let pkts = rtcp::packet::unmarshal(&mut b)?;
-        // Middle 32 bits
-        let now = (unix2ntp((self.now_gen)()) >> 16) as u32;
+        // The NTP timestamps of our reports come from the wall clock, which may be stepped at any
+        // time, so the round trip is measured on the monotonic clock from when they were sent.
+        let now = (self.now_gen)();
+        let sent_reports = self.sent_reports.lock().clone();
 
         #[derive(Default, Debug)]
         struct GenericRTCP {
@@ -451,7 +464,9 @@ where
                         let e = acc.entry(recp.ssrc).or_default();
 
                         let rtt_ms = (recp.delay != 0)
-                            .then(|| calculate_rtt_ms(now, recp.delay, recp.last_sender_report));
+                            .then(|| sent_reports.get(&recp.last_sender_report))
+                            .flatten()
+                            .map(|sent| calculate_rtt_ms(now, *sent, recp.delay));
 
                         e.receiver_reports.push(ReceiverReportEntry {
                             ext_seq_num: recp.last_sequence_number,
@@ -578,7 +593,9 @@ where
             let futures = sender_reports.into_iter().map(|sr| {
                 let rtt_ms = match (sr.dlrr_last_rr, sr.dlrr_delay_rr, sr.sr_packets_sent) {
                     (Some(last_rr), Some(delay_rr), Some(_)) if last_rr != 0 && delay_rr != 0 => {
-                        Some(calculate_rtt_ms(now, delay_rr, last_rr))
+                        sent_reports
+                            .get(&last_rr)
+                            .map(|sent| calculate_rtt_ms(now, *sent, delay_rr))
                     }
                     _ => None,
                 };
@@ -607,18 +624,39 @@ pub struct RTCPWriteInterceptor<F> {
     rtcp_writer: Arc<dyn RTCPWriter>,
     tx: mpsc::Sender<Message>,
     now_gen: F,
+    sent_reports: SentReports,
 }
 
 #[async_trait(?Send)]
 impl<F> RTCPWriter for RTCPWriteInterceptor<F>
 where
-    F: Fn() -> SystemTime,
+    F: Fn() -> Instant,
 {
     async fn write(
         &self,
         pkts: &[Box<dyn rtcp::packet::Packet>],
         attributes: &Attributes,
     ) -> Result<usize> {
+        let now = (self.now_gen)();
+        {
+            let mut sent_reports = self.sent_reports.lock();
+            sent_reports.retain(|_, sent| now.saturating_duration_since(*sent) < SENT_REPORT_TTL);
+            for p in pkts {
+                if let Some(sr) = p.as_any().downcast_ref::<SenderReport>() {
+                    sent_reports.insert((sr.ntp_time >> 16) as u32, now);
+                } else if let Some(xr) = p.as_any().downcast_ref::<ExtendedReport>() {
+                    for report in &xr.reports {
+                        if let Some(rrtr) = report
+                            .as_any()
+                            .downcast_ref::<ReceiverReferenceTimeReportBlock>()
+                        {
+                            sent_reports.insert((rrtr.ntp_timestamp >> 16) as u32, now);
+                        }
+                    }
+                }
+            }
+        }
+
         #[derive(Default, Debug)]
         struct Entry {
             fir_count: Option<u64>,
@@ -787,33 +825,32 @@ impl RTPWriter for RTPWriteRecorder {
 ///
 /// ## Params
 ///
-/// - `now` the current middle 32 bits of an NTP timestamp for the current time.
+/// - `now` the time the report carrying the LSR(or LRR) and `delay` was received.
+/// - `sent` the time our sender report(or receiver reference time report) named by the LSR(or
+///   LRR) was sent.
 /// - `delay` the delay(`DLSR`) since last sender report expressed as fractions of a second in 32 bits.
-/// - `last_report` the middle 32 bits of an NTP timestamp for the most recent sender report(LSR) or Receiver Report(LRR).
-fn calculate_rtt_ms(now: u32, delay: u32, last_report: u32) -> f64 {
+fn calculate_rtt_ms(now: Instant, sent: Instant, delay: u32) -> f64 {
     // [10 Nov 1995 11:33:25.125 UTC]       [10 Nov 1995 11:33:36.5 UTC]
-    // n                 SR(n)              A=b710:8000 (46864.500 s)
+    // n                 SR(n)              A
     // ---------------------------------------------------------------->
     //                    v                 ^
-    // ntp_sec =0xb44db705 v               ^ dlsr=0x0005:4000 (    5.250s)
-    // ntp_frac=0x20000000  v             ^  lsr =0xb705:2000 (46853.125s)
-    //   (3024992005.125 s)  v           ^
+    //                     v               ^ dlsr=0x0005:4000 (    5.250s)
+    //                      v             ^
+    //                       v           ^
     // r                      v         ^ RR(n)
     // ---------------------------------------------------------------->
     //                        |<-DLSR->|
     //                         (5.250 s)
     //
-    // A     0xb710:8000 (46864.500 s)
-    // DLSR -0x0005:4000 (    5.250 s)
-    // LSR  -0xb705:2000 (46853.125 s)
-    // -------------------------------
-    // delay 0x0006:2000 (    6.125 s)
-
-    let rtt = now - delay - last_report;
-    let rtt_seconds = rtt >> 16;
-    let rtt_fraction = (rtt & (u16::MAX as u32)) as f64 / (u16::MAX as u32) as f64;
-
-    rtt_seconds as f64 * 1000.0 + (rtt_fraction as f64) * 1000.0
+    // A - SR(n)  11.375 s
+    // DLSR      - 5.250 s
+    // ------------------
+    // delay       6.125 s
+    let delay = Duration::from_secs((delay >> 16) as u64)
+        + Duration::from_nanos(((delay & 0xFFFF) as u64 * 1_000_000_000) >> 16);
+    let rtt = now.saturating_duration_since(sent).saturating_sub(delay);
+
+    rtt.as_secs_f64() * 1000.0
 }
 
 #[cfg(test)]
@@ -834,7 +871,9 @@ mod test {
     }
 
     use bytes::Bytes;
-    use rtcp::extended_report::{DLRRReport, DLRRReportBlock, ExtendedReport};
+    use rtcp::extended_report::{
+        DLRRReport, DLRRReportBlock, ExtendedReport, ReceiverReferenceTimeReportBlock,
+    };
     use rtcp::payload_feedbacks::full_intra_request::{FirEntry, FullIntraRequest};
     use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
     use rtcp::receiver_report::ReceiverReport;
@@ -844,15 +883,20 @@ mod test {
 
     use std::sync::Arc;
     use std::time::Duration;
-	use deno_net::SystemTime;
+    use deno_net::{Instant, SystemTime};
+    use util::sync::Mutex;
 
+    use crate::chain::Chain;
     use crate::error::Result;
     use crate::mock::mock_stream::MockStream;
+    use crate::mock::mock_time::MockTime;
+    use crate::report::sender::SenderReport as SenderReportInterceptor;
     use crate::stream_info::StreamInfo;
+    use crate::{Interceptor, InterceptorBuilder};
 
     use super::StatsInterceptor;
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_stats_interceptor_rtp() -> Result<()> {
         let icpr: Arc<_> = Arc::new(StatsInterceptor::new("Hello".to_owned()));
 
@@ -928,11 +972,12 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_stats_interceptor_rtcp() -> Result<()> {
-        let icpr: Arc<_> = Arc::new(StatsInterceptor::with_time_gen("Hello".to_owned(), || {
-            // 10 Nov 1995 11:33:36.5 UTC
-            SystemTime::UNIX_EPOCH + Duration::from_secs_f64(816003216.5)
+        let mt = Arc::new(MockTime::default());
+        let icpr: Arc<_> = Arc::new(StatsInterceptor::with_time_gen("Hello".to_owned(), {
+            let mt = Arc::clone(&mt);
+            move || mt.now()
         }));
 
         let recv_stream = MockStream::new(
@@ -953,9 +998,16 @@ mod test {
         )
         .await;
 
+        // 10 Nov 1995 11:33:25.125 UTC
+        let sent_at = Instant::from_unix_epoch(Duration::from_secs_f64(816003205.125));
+        // 10 Nov 1995 11:33:36.5 UTC
+        let received_at = Instant::from_unix_epoch(Duration::from_secs_f64(816003216.5));
+
+        mt.set_now(sent_at);
         send_stream
             .write_rtcp(&[Box::new(SenderReport {
                 ssrc: 234567,
+                ntp_time: 0xb44d_b705_2000_0000,
                 reports: vec![
                     ReceptionReport {
                         ssrc: 234567,
@@ -973,6 +1025,7 @@ mod test {
             .await
             .expect("Failed to write RTCP packets");
 
+        mt.set_now(received_at);
         send_stream
             .receive_rtcp(vec![
                 Box::new(ReceiverReport {
@@ -1052,8 +1105,15 @@ mod test {
             .await
             .expect("After calling `receive_rtcp`, `read_rtcp` should return some packets");
 
+        mt.set_now(sent_at);
         recv_stream
             .write_rtcp(&[
+                Box::new(ExtendedReport {
+                    sender_ssrc: 0,
+                    reports: vec![Box::new(ReceiverReferenceTimeReportBlock {
+                        ntp_timestamp: 0xb44d_b705_2000_0000,
+                    })],
+                }),
                 Box::new(TransportLayerNack {
                     sender_ssrc: 0,
                     media_ssrc: 123456,
@@ -1095,6 +1155,7 @@ mod test {
             .await
             .expect("Failed to write RTCP packets for recv_stream");
 
+        mt.set_now(received_at);
         recv_stream
             .receive_rtcp(vec![
                 Box::new(SenderReport {
@@ -1190,4 +1251,89 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "local")]
+    async fn test_stats_interceptor_rtt_wall_clock_step() -> Result<()> {
+        let mt = Arc::new(MockTime::default());
+        let wall = Arc::new(Mutex::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+        ));
+
+        let stats: Arc<_> = Arc::new(StatsInterceptor::with_time_gen("Hello".to_owned(), {
+            let mt = Arc::clone(&mt);
+            move || mt.now()
+        }));
+        let reports = SenderReportInterceptor::builder()
+            .with_interval(Duration::from_millis(50))
+            .with_now_fn({
+                let mt = Arc::clone(&mt);
+                Arc::new(move || mt.now())
+            })
+            .with_wall_now_fn({
+                let wall = Arc::clone(&wall);
+                Arc::new(move || *wall.lock())
+            })
+            .build("")?;
+        // The SRs of the report interceptor are written through the stats interceptor.
+        let icpr: Arc<dyn Interceptor> = Arc::new(Chain::new(vec![
+            stats.clone() as Arc<dyn Interceptor>,
+            reports,
+        ]));
+
+        let stream = MockStream::new(
+            &StreamInfo {
+                ssrc: 234567,
+                clock_rate: 90000,
+                ..Default::default()
+            },
+            icpr,
+        )
+        .await;
+
+        mt.set_now(Instant::from_unix_epoch(Duration::from_secs(100)));
+        stream
+            .write_rtp(&rtp::packet::Packet {
+                header: rtp::header::Header {
+                    ssrc: 234567,
+                    ..Default::default()
+                },
+                payload: Bytes::from_static(b"\xde\xad"),
+            })
+            .await?;
+        let pkts = stream.written_rtcp().await.expect("a sender report");
+        let sr = pkts[0]
+            .as_any()
+            .downcast_ref::<SenderReport>()
+            .expect("a sender report");
+
+        // The wall clock is stepped back an hour between the SR and the RR answering it.
+        *wall.lock() -= Duration::from_secs(3600);
+        // Answered 0.75s after it was sent, having held it for 0.5s.
+        mt.set_now(Instant::from_unix_epoch(Duration::from_millis(100_750)));
+        stream
+            .receive_rtcp(vec![Box::new(ReceiverReport {
+                reports: vec![ReceptionReport {
+                    ssrc: 234567,
+                    last_sender_report: (sr.ntp_time >> 16) as u32,
+                    delay: 0x0000_8000,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })])
+            .await;
+        let _ = stream.read_rtcp().await.expect("read_rtcp failed");
+
+        let snapshots = stats.fetch_outbound_stats(vec![234567]).await;
+        let send_snapshot = snapshots[0]
+            .as_ref()
+            .expect("Outbound Stats should exist for ssrc: 234567");
+        let rtt_ms = send_snapshot
+            .remote_round_trip_time()
+            .expect("After receiving an RR for our SR we should have a remote round trip time");
+        assert_feq!(rtt_ms, 250.0);
+
+        stream.close().await?;
+
+        Ok(())
+    }
 }
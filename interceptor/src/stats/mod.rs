@@ -14,9 +14,8 @@ pub fn make_stats_interceptor(id: &str) -> Arc<StatsInterceptor> {
 
 /// Types related to inbound RTP streams.
 mod inbound {
-	use deno_net::SystemTime;
-
-    use tokio::time::{Duration, Instant};
+    use deno_net::{Instant, SystemTime};
+    use std::time::Duration;
 
     use super::{RTCPStats, RTPStats};
 
@@ -202,9 +201,8 @@ mod inbound {
 
 /// Types related to outbound RTP streams.
 mod outbound {
-	use deno_net::SystemTime;
-
-    use tokio::time::{Duration, Instant};
+    use deno_net::{Instant, SystemTime};
+    use std::time::Duration;
 
     use super::{RTCPStats, RTPStats};
 
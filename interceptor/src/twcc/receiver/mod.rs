@@ -7,10 +7,10 @@ use crate::twcc::Recorder;
 use crate::*;
 use receiver_stream::ReceiverStream;
 
+use deno_net::Ticker;
 use rtp::extension::transport_cc_extension::TransportCcExtension;
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
-use tokio::time::MissedTickBehavior;
 use util::Unmarshal;
 use waitgroup::WaitGroup;
 
@@ -44,7 +44,7 @@ impl InterceptorBuilder for ReceiverBuilder {
                 streams: Mutex::new(HashMap::new()),
                 close_rx: Mutex::new(Some(close_rx)),
             }),
-            start_time: tokio::time::Instant::now(),
+            start_time: deno_net::Instant::now(),
             packet_chan_tx,
             wg: Mutex::new(Some(WaitGroup::new())),
             close_tx: Mutex::new(Some(close_tx)),
@@ -72,8 +72,8 @@ struct ReceiverInternal {
 pub struct Receiver {
     internal: Arc<ReceiverInternal>,
 
-    // we use tokio's Instant because it makes testing easier via `tokio::time::advance`.
-    start_time: tokio::time::Instant,
+    // deno_net's Instant follows tokio's clock on native, so tests can use `tokio::time::advance`.
+    start_time: deno_net::Instant,
     packet_chan_tx: mpsc::Sender<Packet>,
 
     wg: Mutex<Option<WaitGroup>>,
@@ -113,8 +113,7 @@ impl Receiver {
         };
 
         let a = Attributes::new();
-        let mut ticker = tokio::time::interval(internal.interval);
-        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut ticker = deno_net::interval(internal.interval);
         loop {
             tokio::select! {
                 _ = close_rx.recv() =>{
@@ -178,7 +177,7 @@ impl Interceptor for Receiver {
         };
         let writer2 = Arc::clone(&writer);
         let internal = Arc::clone(&self.internal);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let _d = w.take();
             if let Err(err) = Receiver::run(writer2, internal).await {
                 log::warn!("bind_rtcp_writer TWCC Sender::run got error: {}", err);
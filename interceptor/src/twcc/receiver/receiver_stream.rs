@@ -5,8 +5,8 @@ pub(super) struct ReceiverStream {
     hdr_ext_id: u8,
     ssrc: u32,
     packet_chan_tx: mpsc::Sender<Packet>,
-    // we use tokio's Instant because it makes testing easier via `tokio::time::advance`.
-    start_time: tokio::time::Instant,
+    // deno_net's Instant follows tokio's clock on native, so tests can use `tokio::time::advance`.
+    start_time: deno_net::Instant,
 }
 
 impl ReceiverStream {
@@ -15,7 +15,7 @@ impl ReceiverStream {
         hdr_ext_id: u8,
         ssrc: u32,
         packet_chan_tx: mpsc::Sender<Packet>,
-        start_time: tokio::time::Instant,
+        start_time: deno_net::Instant,
     ) -> Self {
         ReceiverStream {
             parent_rtp_reader,
@@ -44,7 +44,7 @@ impl RTPReader for ReceiverStream {
                 .send(Packet {
                     hdr: p.header,
                     sequence_number: tcc_ext.transport_sequence,
-                    arrival_time: (tokio::time::Instant::now() - self.start_time).as_micros()
+                    arrival_time: (deno_net::Instant::now() - self.start_time).as_micros()
                         as i64,
                     ssrc: self.ssrc,
                 })
@@ -7,7 +7,7 @@ use rtcp::transport_feedbacks::transport_layer_cc::{
 };
 use util::Marshal;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_twcc_receiver_interceptor_before_any_packets() -> Result<()> {
     let builder = Receiver::builder();
     let icpr = builder.build("")?;
@@ -40,7 +40,7 @@ async fn test_twcc_receiver_interceptor_before_any_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_twcc_receiver_interceptor_after_rtp_packets() -> Result<()> {
     let builder = Receiver::builder();
     let icpr = builder.build("")?;
@@ -96,7 +96,7 @@ async fn test_twcc_receiver_interceptor_after_rtp_packets() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test(start_paused = true)]
+#[tokio::test(flavor = "local", start_paused = true)]
 async fn test_twcc_receiver_interceptor_different_delays_between_rtp_packets() -> Result<()> {
     let builder = Receiver::builder().with_interval(Duration::from_millis(500));
     let icpr = builder.build("")?;
@@ -168,7 +168,7 @@ async fn test_twcc_receiver_interceptor_different_delays_between_rtp_packets() -
     Ok(())
 }
 
-#[tokio::test(start_paused = true)]
+#[tokio::test(flavor = "local", start_paused = true)]
 async fn test_twcc_receiver_interceptor_packet_loss() -> Result<()> {
     let builder = Receiver::builder().with_interval(Duration::from_secs(2));
     let icpr = builder.build("")?;
@@ -275,7 +275,7 @@ async fn test_twcc_receiver_interceptor_packet_loss() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_twcc_receiver_interceptor_overflow() -> Result<()> {
     let builder = Receiver::builder();
     let icpr = builder.build("")?;
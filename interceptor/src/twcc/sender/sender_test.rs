@@ -7,21 +7,21 @@ use std::time::Duration;
 use util::Unmarshal;
 use waitgroup::WaitGroup;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_twcc_sender_interceptor() -> Result<()> {
     // "add transport wide cc to each packet"
     let builder = Sender::builder().with_init_sequence_nr(0);
     let icpr = builder.build("")?;
 
     let (p_chan_tx, mut p_chan_rx) = mpsc::channel::<Packet>(10 * 5);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         // start some parallel streams using the same interceptor to test for race conditions
         let wg = WaitGroup::new();
         for i in 0..10 {
             let w = wg.worker();
             let p_chan_tx2 = p_chan_tx.clone();
             let icpr2 = Arc::clone(&icpr);
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 let _d = w;
                 let stream = MockStream::new(
                     &StreamInfo {
@@ -15,7 +15,7 @@ use tokio::sync::mpsc;
 // For interop with pion/mdns_server:
 // cargo run --color=always --package webrtc-mdns --example mdns_query -- --local-name pion-test.local
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -75,6 +75,7 @@ async fn main() -> Result<(), Error> {
             ..Default::default()
         },
     )
+    .await
     .unwrap();
 
     log::info!("querying dns");
@@ -14,7 +14,7 @@ use std::str::FromStr;
 // For interop with pion/mdns_client:
 // cargo run --color=always --package webrtc-mdns --example mdns_server -- --local-name pion-test.local
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -75,6 +75,7 @@ async fn main() -> Result<(), Error> {
             ..Default::default()
         },
     )
+    .await
     .unwrap();
 
     println!("Press ctlr-c to stop server");
@@ -2,7 +2,7 @@ use std::net::{Ipv4Addr, SocketAddr};
 use tokio::sync::mpsc;
 use webrtc_mdns::{config::*, conn::*};
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() {
     env_logger::init();
 
@@ -18,6 +18,7 @@ async fn main() {
             ..Default::default()
         },
     )
+    .await
     .unwrap();
 
     let server_b = DnsConn::server(
@@ -26,11 +27,12 @@ async fn main() {
             ..Default::default()
         },
     )
+    .await
     .unwrap();
 
     let (a, b) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         deno_net::sleep(std::time::Duration::from_secs(20)).await;
         a.send(()).await
     });
@@ -40,7 +42,7 @@ async fn main() {
 
     let (a, b) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         deno_net::sleep(std::time::Duration::from_secs(20)).await;
         a.send(()).await
     });
@@ -3,12 +3,13 @@ mod test {
     use crate::{config::Config, conn::*};
     use deno_net::timeout;
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_multiple_close() -> Result<()> {
         let server_a = DnsConn::server(
             SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 5353),
             Config::default(),
-        )?;
+        )
+        .await?;
 
         server_a.close().await?;
 
@@ -21,12 +22,13 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_query_respect_timeout() -> Result<()> {
         let server_a = DnsConn::server(
             SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 5353),
             Config::default(),
-        )?;
+        )
+        .await?;
 
         let (a, b) = mpsc::channel(1);
 
@@ -42,4 +44,102 @@ mod test {
 
         Ok(())
     }
+
+    fn mdns_addr() -> SocketAddr {
+        SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 5353)
+    }
+
+    #[tokio::test(flavor = "local")]
+    async fn test_query_resolves_local_name() -> Result<()> {
+        let name = "webrtc-rs-mdns-resolve.local";
+        let server_a = DnsConn::server(
+            mdns_addr(),
+            Config {
+                local_names: vec![name.to_owned()],
+                ..Default::default()
+            },
+        )
+        .await?;
+        let server_b = DnsConn::server(mdns_addr(), Config::default()).await?;
+
+        let (_close_tx, close_rx) = mpsc::channel(1);
+        let (answer, _src) = timeout(Duration::from_secs(5), server_b.query(name, close_rx))
+            .await
+            .expect("server_b.query should be answered")?;
+        assert_eq!(answer.name.data, format!("{}.", name));
+        assert!(
+            answer.typ == DnsType::A || answer.typ == DnsType::Aaaa,
+            "expected an address record, but got {:?}",
+            answer.typ
+        );
+
+        server_a.close().await?;
+        server_b.close().await?;
+
+        Ok(())
+    }
+
+    // Sends a question of typ for name, and returns the type of the answer to it, if any.
+    async fn ask(socket: &UdpSocket, name: &str, typ: DnsType) -> Result<Option<DnsType>> {
+        let mut msg = Message {
+            header: Header::default(),
+            questions: vec![Question {
+                typ,
+                class: DNSCLASS_INET,
+                name: Name::new(name)?,
+            }],
+            ..Default::default()
+        };
+        socket
+            .send_to(&msg.pack()?, DEFAULT_DEST_ADDR.parse()?)
+            .await?;
+
+        let mut b = vec![0u8; INBOUND_BUFFER_SIZE];
+        let answer = timeout(Duration::from_millis(500), async {
+            loop {
+                let (n, _) = socket.recv_from(&mut b).await?;
+                let mut p = Parser::default();
+                if !p.start(&b[..n])?.response {
+                    // Our own question, looped back.
+                    continue;
+                }
+                p.skip_all_questions()?;
+                let a = p.answer_header()?;
+                if a.name.data == name {
+                    return Result::<DnsType>::Ok(a.typ);
+                }
+            }
+        })
+        .await;
+
+        match answer {
+            Ok(typ) => Ok(Some(typ?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    #[tokio::test(flavor = "local")]
+    async fn test_answer_only_address_questions() -> Result<()> {
+        let name = "webrtc-rs-mdns-answer.local";
+        let server = DnsConn::server(
+            mdns_addr(),
+            Config {
+                local_names: vec![name.to_owned()],
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let socket = UdpSocket::bind_multicast(mdns_addr(), true).await?;
+        join_multicast_group(&socket).await?;
+        let name = format!("{}.", name);
+
+        assert_eq!(ask(&socket, &name, DnsType::Txt).await?, None);
+        assert_eq!(ask(&socket, &name, DnsType::A).await?, Some(DnsType::A));
+
+        DatagramSocket::close(&socket);
+        server.close().await?;
+
+        Ok(())
+    }
 }
@@ -53,7 +53,8 @@ pub struct Sample {
     ///
     /// ```rust
     /// # use bytes::Bytes;
-    /// # use std::time::{SystemTime, Duration};
+    /// # use deno_net::SystemTime;
+    /// # use std::time::Duration;
     /// # use webrtc_media::Sample;
     /// # let sample = Sample {
     /// #   data: Bytes::new(),
@@ -210,7 +210,7 @@ fn test_nack_pair() {
     );
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nack_pair_range() {
     let n = NackPair {
         packet_id: 42,
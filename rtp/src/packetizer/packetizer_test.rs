@@ -3,9 +3,10 @@ use crate::codecs::*;
 use crate::error::Result;
 
 use chrono::prelude::*;
-use std::time::{Duration, UNIX_EPOCH};
+use deno_net::UNIX_EPOCH;
+use std::time::Duration;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_packetizer() -> Result<()> {
     let multiple_payload = Bytes::from_static(&[0; 128]);
     let g722 = Box::new(g7xx::G722Payloader {});
@@ -31,7 +32,7 @@ async fn test_packetizer() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_packetizer_abs_send_time() -> Result<()> {
     let g722 = Box::new(g7xx::G722Payloader {});
     let sequencer = Box::new(new_fixed_sequencer(1234));
@@ -94,7 +95,7 @@ async fn test_packetizer_abs_send_time() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_packetizer_timestamp_rollover_does_not_panic() -> Result<()> {
     let g722 = Box::new(g7xx::G722Payloader {});
     let seq = Box::new(new_random_sequencer());
@@ -8,12 +8,13 @@ use clap::{App, AppSettings, Arg};
 use std::net::Shutdown;
 use std::sync::Arc;
 use deno_net::DatagramConn as UdpSocket;
+use util::Conn;
 use tokio::signal;
 use tokio::sync::mpsc;
 
 // RUST_LOG=trace cargo run --color=always --package webrtc-sctp --example ping -- --server 0.0.0.0:5678
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -59,7 +60,8 @@ async fn main() -> Result<(), Error> {
     let server = matches.value_of("server").unwrap();
 
     let conn = Arc::new(UdpSocket::bind("0.0.0.0:0").await.unwrap());
-    conn.connect(server).await.unwrap();
+    let server_addr = deno_net::lookup_host(server).await.unwrap().next().unwrap();
+    conn.connect(server_addr).await.unwrap();
     println!("connecting {}..", server);
 
     let config = Config {
@@ -78,7 +80,7 @@ async fn main() -> Result<(), Error> {
     stream.set_reliability_params(true, ReliabilityType::Timed, 10);
 
     let stream_tx = Arc::clone(&stream);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut ping_seq_num = 0;
         while ping_seq_num < 10 {
             let ping_msg = format!("ping {}", ping_seq_num);
@@ -94,7 +96,7 @@ async fn main() -> Result<(), Error> {
 
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
     let stream_rx = Arc::clone(&stream);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buff = vec![0u8; 1024];
         while let Ok(n) = stream_rx.read(&mut buff).await {
             let pong_msg = String::from_utf8(buff[..n].to_vec()).unwrap();
@@ -14,7 +14,7 @@ use util::{conn::conn_disconnected_packet::DisconnectedPacketConn, Conn};
 
 // RUST_LOG=trace cargo run --color=always --package webrtc-sctp --example pong -- --host 0.0.0.0:5678
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -78,7 +78,7 @@ async fn main() -> Result<(), Error> {
 
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
     let stream2 = Arc::clone(&stream);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buff = vec![0u8; 1024];
         while let Ok(n) = stream2.read(&mut buff).await {
             let ping_msg = String::from_utf8(buff[..n].to_vec()).unwrap();
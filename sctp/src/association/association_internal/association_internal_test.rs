@@ -155,7 +155,7 @@ fn test_create_forward_tsn_forward_two_abandoned_with_the_same_si() -> Result<()
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_forward_tsn_forward_3unreceived_chunks() -> Result<()> {
     let mut a = AssociationInternal::default();
 
@@ -189,7 +189,7 @@ async fn test_handle_forward_tsn_forward_3unreceived_chunks() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_forward_tsn_forward_1for1_missing() -> Result<()> {
     let mut a = AssociationInternal::default();
 
@@ -237,7 +237,7 @@ async fn test_handle_forward_tsn_forward_1for1_missing() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_forward_tsn_forward_1for2_missing() -> Result<()> {
     let mut a = AssociationInternal::default();
 
@@ -283,7 +283,7 @@ async fn test_handle_forward_tsn_forward_1for2_missing() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_handle_forward_tsn_dup_forward_tsn_chunk_should_generate_sack() -> Result<()> {
     let mut a = AssociationInternal::default();
 
@@ -308,7 +308,7 @@ async fn test_handle_forward_tsn_dup_forward_tsn_chunk_should_generate_sack() ->
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_create_new_stream() -> Result<()> {
     let (accept_ch_tx, _accept_ch_rx) = mpsc::channel(ACCEPT_CH_SIZE);
     let mut a = AssociationInternal {
@@ -395,7 +395,7 @@ async fn handle_init_test(name: &str, initial_state: AssociationState, expect_er
     assert!(a.use_forward_tsn, "{} should be set to true", name);
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_handle_init() -> Result<()> {
     handle_init_test("normal", AssociationState::Closed, false).await;
 
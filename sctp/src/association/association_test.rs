@@ -26,7 +26,7 @@ async fn create_new_association_pair(
     let mut closed_rx1 = closed_tx.subscribe();
 
     // Setup client
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let client = Association::client(Config {
             net_conn: ca,
             max_receive_buffer_size: recv_buf_size,
@@ -42,7 +42,7 @@ async fn create_new_association_pair(
     });
 
     // Setup server
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let server = Association::server(Config {
             net_conn: cb,
             max_receive_buffer_size: recv_buf_size,
@@ -112,7 +112,7 @@ async fn close_association_pair(br: &Arc<Bridge>, client: Association, server: A
     let mut closed_rx1 = closed_tx.subscribe();
 
     // Close client
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         client.close().await?;
         let _ = handshake0ch_tx.send(()).await;
         let _ = closed_rx0.recv().await;
@@ -121,7 +121,7 @@ async fn close_association_pair(br: &Arc<Bridge>, client: Association, server: A
     });
 
     // Close server
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         server.close().await?;
         let _ = handshake1ch_tx.send(()).await;
         let _ = closed_rx1.recv().await;
@@ -219,7 +219,7 @@ async fn establish_session_pair(
 //use std::io::Write;
 
 #[cfg(not(target_os = "windows"))] // this times out in CI on windows.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_simple() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -285,7 +285,7 @@ async fn test_assoc_reliable_simple() -> Result<()> {
 // NB: This is ignored on Windows due to flakiness with timing/IO interactions.
 // TODO: Refactor this and other tests that are disabled for similar reason to not have such issues
 #[cfg(not(target_os = "windows"))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_ordered_reordered() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -338,7 +338,10 @@ async fn test_assoc_reliable_ordered_reordered() -> Result<()> {
     )?;
     assert_eq!(sbuf.len(), n, "unexpected length of received data");
 
-    deno_net::sleep(Duration::from_millis(10)).await;
+    // Both DATA chunks have to be queued before they can be swapped.
+    while br.len(0).await < 2 {
+        deno_net::sleep(Duration::from_millis(10)).await;
+    }
     br.reorder(0).await;
     br.process().await;
 
@@ -376,7 +379,7 @@ async fn test_assoc_reliable_ordered_reordered() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_ordered_fragmented_then_defragmented() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -441,7 +444,7 @@ async fn test_assoc_reliable_ordered_fragmented_then_defragmented() -> Result<()
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_unordered_fragmented_then_defragmented() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -506,7 +509,7 @@ async fn test_assoc_reliable_unordered_fragmented_then_defragmented() -> Result<
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_unordered_ordered() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -598,7 +601,7 @@ async fn test_assoc_reliable_unordered_ordered() -> Result<()> {
 // NB: This is ignored on Windows due to flakiness with timing/IO interactions.
 // TODO: Refactor this and other tests that are disabled for similar reason to not have such issues
 #[cfg(not(target_os = "windows"))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_retransmission() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -636,7 +639,9 @@ async fn test_assoc_reliable_retransmission() -> Result<()> {
     let n = s0.write_sctp(&MSG2, PayloadProtocolIdentifier::Binary)?;
     assert_eq!(MSG2.len(), n, "unexpected length of received data");
 
-    deno_net::sleep(Duration::from_millis(10)).await;
+    while br.len(0).await < 2 {
+        deno_net::sleep(Duration::from_millis(10)).await;
+    }
     log::debug!("dropping packet");
     br.drop_offset(0, 0, 1).await; // drop the first packet (second one should be sacked)
 
@@ -672,7 +677,7 @@ async fn test_assoc_reliable_retransmission() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reliable_short_buffer() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -741,7 +746,7 @@ async fn test_assoc_reliable_short_buffer() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_ordered_no_fragment() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -822,7 +827,7 @@ async fn test_assoc_unreliable_rexmit_ordered_no_fragment() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_ordered_fragment() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -908,7 +913,7 @@ async fn test_assoc_unreliable_rexmit_ordered_fragment() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_unordered_no_fragment() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -992,7 +997,7 @@ async fn test_assoc_unreliable_rexmit_unordered_no_fragment() -> Result<()> {
 // NB: This is ignored on Windows and macOS due to flakiness with timing/IO interactions.
 // TODO: Refactor this and other tests that are disabled for similar reason to not have such issues
 #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_unordered_fragment() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1042,7 +1047,10 @@ async fn test_assoc_unreliable_rexmit_unordered_fragment() -> Result<()> {
     assert_eq!(sbuf.len(), n, "unexpected length of received data");
 
     //log::debug!("flush_buffers");
-    deno_net::sleep(Duration::from_millis(10)).await;
+    // Both chunks go out in two fragments each, wait for all four packets.
+    while br.len(0).await < 4 {
+        deno_net::sleep(Duration::from_millis(10)).await;
+    }
     br.drop_offset(0, 0, 2).await; // drop the second fragment of the first chunk (second chunk should be sacked)
     flush_buffers(&br, &a0, &a1).await;
 
@@ -1083,7 +1091,7 @@ async fn test_assoc_unreliable_rexmit_unordered_fragment() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_timed_ordered() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1164,7 +1172,7 @@ async fn test_assoc_unreliable_rexmit_timed_ordered() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_unreliable_rexmit_timed_unordered() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1263,7 +1271,7 @@ async fn test_assoc_unreliable_rexmit_timed_unordered() -> Result<()> {
 // 2) Last 3 packet will be received, which triggers fast-retransmission
 // 3) The first one is retransmitted, which makes s1 readable
 // Above should be done before RTO occurs (fast recovery)
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_congestion_control_fast_retransmission() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1352,7 +1360,7 @@ async fn test_assoc_congestion_control_fast_retransmission() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_congestion_control_congestion_avoidance() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1492,7 +1500,7 @@ async fn test_assoc_congestion_control_congestion_avoidance() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_congestion_control_slow_reader() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1621,7 +1629,7 @@ async fn test_assoc_congestion_control_slow_reader() -> Result<()> {
 /*FIXME
 use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_delayed_ack() -> Result<()> {
     env_logger::Builder::new()
         .format(|buf, record| {
@@ -1668,7 +1676,7 @@ async fn test_assoc_delayed_ack() -> Result<()> {
     assert_eq!(sbuf.len(), n, "unexpected length of received data");
 
     // Repeat calling br.Tick() until the buffered amount becomes 0
-    let since = SystemTime::now();
+    let since = Instant::now();
     let mut n_packets_received = 0;
     while s0.buffered_amount() > 0 {
         loop {
@@ -1693,7 +1701,7 @@ async fn test_assoc_delayed_ack() -> Result<()> {
             n_packets_received += 1;
         }
     }
-    let delay = (SystemTime::now().duration_since(since).unwrap().as_millis() as f64) / 1000.0;
+    let delay = (since.elapsed().as_millis() as f64) / 1000.0;
     log::debug!("received in {} seconds", delay);
     assert!(delay >= 0.2, "should be >= 200msec");
 
@@ -1736,7 +1744,7 @@ async fn test_assoc_delayed_ack() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reset_close_one_way() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1781,7 +1789,7 @@ async fn test_assoc_reset_close_one_way() -> Result<()> {
     let (done_ch_tx, mut done_ch_rx) = mpsc::channel(1);
     let mut buf = vec![0u8; 32];
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         loop {
             log::debug!("s1.read_sctp begin");
             match s1.read_sctp(&mut buf).await {
@@ -1834,7 +1842,7 @@ async fn test_assoc_reset_close_one_way() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_reset_close_both_ways() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -1881,7 +1889,7 @@ async fn test_assoc_reset_close_both_ways() -> Result<()> {
 
     let done_ch_tx1 = Arc::clone(&done_ch_tx);
     let ss1 = Arc::clone(&s1);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 32];
         loop {
             log::debug!("s1.read_sctp begin");
@@ -1932,7 +1940,7 @@ async fn test_assoc_reset_close_both_ways() -> Result<()> {
     s1.shutdown(Shutdown::Both).await?; // send reset
 
     let done_ch_tx0 = Arc::clone(&done_ch_tx);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 32];
         loop {
             log::debug!("s.read_sctp begin");
@@ -1985,7 +1993,7 @@ async fn test_assoc_reset_close_both_ways() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_assoc_abort() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2127,7 +2135,7 @@ impl Conn for FakeEchoConn {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stats() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2179,7 +2187,7 @@ async fn create_assocs() -> Result<(Association, Association)> {
     let (a1chan_tx, mut a1chan_rx) = mpsc::channel(1);
     let (a2chan_tx, mut a2chan_rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let a = Association::client(Config {
             net_conn: Arc::new(udp1),
             max_receive_buffer_size: 0,
@@ -2193,7 +2201,7 @@ async fn create_assocs() -> Result<(Association, Association)> {
         Result::<()>::Ok(())
     });
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let a = Association::server(Config {
             net_conn: Arc::new(udp2),
             max_receive_buffer_size: 0,
@@ -2237,7 +2245,7 @@ async fn create_assocs() -> Result<(Association, Association)> {
 //use std::io::Write;
 //TODO: remove this conditional test
 #[cfg(not(target_os = "windows"))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_association_shutdown() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2296,7 +2304,7 @@ async fn test_association_shutdown() -> Result<()> {
 //use std::io::Write;
 //TODO: remove this conditional test
 #[cfg(not(target_os = "windows"))]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_association_shutdown_during_write() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -2320,7 +2328,7 @@ async fn test_association_shutdown_during_write() -> Result<()> {
 
     let (writing_done_tx, mut writing_done_rx) = mpsc::channel::<()>(1);
     let ss21 = Arc::clone(&s21);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut i = 0;
         while ss21.write(&Bytes::from(vec![i])).is_ok() {
             if i == 255 {
@@ -2385,7 +2393,7 @@ async fn test_association_shutdown_during_write() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_association_handle_packet_before_init() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -46,7 +46,7 @@ use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
-use deno_net::SystemTime;
+use deno_net::Instant;
 use tokio::sync::{broadcast, mpsc, Mutex};
 use util::Conn;
 
@@ -381,7 +381,7 @@ impl Association {
             ));
         }
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             Association::read_loop(
                 name1,
                 bytes_received1,
@@ -392,7 +392,7 @@ impl Association {
             .await;
         });
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             Association::write_loop(
                 name2,
                 bytes_sent2,
@@ -425,7 +425,7 @@ fn test_pending_queue_selection_persistence() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_pending_queue_append() -> Result<()> {
     let pq = PendingQueue::new();
     pq.append(vec![
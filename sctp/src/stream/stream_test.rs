@@ -23,7 +23,7 @@ fn test_stream_buffered_amount() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream_amount_on_buffered_amount_low() -> Result<()> {
     let s = Stream::default();
 
@@ -71,7 +71,7 @@ async fn test_stream_amount_on_buffered_amount_low() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_stream() -> std::result::Result<(), io::Error> {
     let s = Stream::new(
         "test_poll_stream".to_owned(),
@@ -143,7 +143,7 @@ async fn test_stream() -> std::result::Result<(), io::Error> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_poll_stream() -> std::result::Result<(), io::Error> {
     let s = Arc::new(Stream::new(
         "test_poll_stream".to_owned(),
@@ -40,7 +40,7 @@ impl<T: 'static + AckTimerObserver> AckTimer<T> {
         let interval = self.interval;
         let timeout_observer = self.timeout_observer.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let timer = deno_net::sleep(interval);
             tokio::pin!(timer);
 
@@ -27,7 +27,7 @@ mod test_ack_timer {
         }
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_ack_timer_start_and_stop() -> Result<()> {
         let ncbs = Arc::new(AtomicU32::new(0));
         let obs = Arc::new(Mutex::new(TestAckTimerObserver { ncbs: ncbs.clone() }));
@@ -76,7 +76,7 @@ mod test_rto_manager {
 
     use super::*;
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rto_manager_initial_values() -> Result<()> {
         let m = RtoManager::new();
         assert_eq!(RTO_INITIAL, m.rto, "should be rtoInitial");
@@ -87,7 +87,7 @@ mod test_rto_manager {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rto_manager_rto_calculation_small_rtt() -> Result<()> {
         let mut m = RtoManager::new();
         let exp = vec![
@@ -103,7 +103,7 @@ mod test_rto_manager {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rto_manager_rto_calculation_large_rtt() -> Result<()> {
         let mut m = RtoManager::new();
         let exp = vec![
@@ -122,7 +122,7 @@ mod test_rto_manager {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rto_manager_calculate_next_timeout() -> Result<()> {
         let rto = calculate_next_timeout(1, 0);
         assert_eq!(1, rto, "should match");
@@ -140,7 +140,7 @@ mod test_rto_manager {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rto_manager_reset() -> Result<()> {
         let mut m = RtoManager::new();
         for _ in 0..10 {
@@ -163,13 +163,13 @@ mod test_rtx_timer {
     use crate::association::RtxTimerId;
     use crate::error::Result;
 
-	use deno_net::SystemTime;
+    use deno_net::Instant;
     use tokio::sync::mpsc;
 
     struct TestTimerObserver {
         ncbs: Arc<AtomicU32>,
         timer_id: RtxTimerId,
-        done_tx: Option<mpsc::Sender<SystemTime>>,
+        done_tx: Option<mpsc::Sender<Instant>>,
         max_rtos: usize,
     }
 
@@ -195,7 +195,7 @@ mod test_rtx_timer {
             assert_eq!(self.timer_id, timer_id, "unexpected timer ID: {}", timer_id);
             if (self.max_rtos > 0 && n_rtos == self.max_rtos) || self.max_rtos == usize::MAX {
                 if let Some(done) = &self.done_tx {
-                    let elapsed = SystemTime::now();
+                    let elapsed = Instant::now();
                     let _ = done.send(elapsed).await;
                 }
             }
@@ -205,7 +205,7 @@ mod test_rtx_timer {
             if self.max_rtos == 0 {
                 if let Some(done) = &self.done_tx {
                     assert_eq!(self.timer_id, timer_id, "unexpted timer ID: {}", timer_id);
-                    let elapsed = SystemTime::now();
+                    let elapsed = Instant::now();
                     //t.Logf("onRtxFailure: elapsed=%.03f\n", elapsed)
                     let _ = done.send(elapsed).await;
                 }
@@ -215,7 +215,7 @@ mod test_rtx_timer {
         }
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_callback_interval() -> Result<()> {
         let timer_id = RtxTimerId::T1Init;
         let ncbs = Arc::new(AtomicU32::new(0));
@@ -242,7 +242,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_last_start_wins() -> Result<()> {
         let timer_id = RtxTimerId::T3RTX;
         let ncbs = Arc::new(AtomicU32::new(0));
@@ -270,7 +270,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_stop_right_after_start() -> Result<()> {
         let timer_id = RtxTimerId::T3RTX;
         let ncbs = Arc::new(AtomicU32::new(0));
@@ -295,7 +295,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_start_stop_then_start() -> Result<()> {
         let timer_id = RtxTimerId::T1Cookie;
         let ncbs = Arc::new(AtomicU32::new(0));
@@ -324,7 +324,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_start_and_stop_in_atight_loop() -> Result<()> {
         let timer_id = RtxTimerId::T2Shutdown;
         let ncbs = Arc::new(AtomicU32::new(0));
@@ -348,7 +348,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local", start_paused = true)]
     async fn test_rtx_timer_should_stop_after_rtx_failure() -> Result<()> {
         let (done_tx, mut done_rx) = mpsc::channel(1);
 
@@ -361,7 +361,7 @@ mod test_rtx_timer {
             ..Default::default()
         }));
 
-        let since = SystemTime::now();
+        let since = Instant::now();
         let rt = RtxTimer::new(Arc::downgrade(&obs), timer_id, PATH_MAX_RETRANS);
 
         // RTO(msec) Total(msec)
@@ -383,7 +383,7 @@ mod test_rtx_timer {
         assert_eq!(5, ncbs.load(Ordering::SeqCst), "should be called 5 times");
 
         if let Some(elapsed) = elapsed {
-            let diff = elapsed.duration_since(since).unwrap();
+            let diff = elapsed.duration_since(since);
             assert!(
                 diff > Duration::from_millis(600),
                 "must have taken more than 600 msec"
@@ -397,7 +397,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_should_not_stop_if_max_retrans_is_zero() -> Result<()> {
         let (done_tx, mut done_rx) = mpsc::channel(1);
 
@@ -412,7 +412,7 @@ mod test_rtx_timer {
             ..Default::default()
         }));
 
-        let since = SystemTime::now();
+        let since = Instant::now();
         let rt = RtxTimer::new(Arc::downgrade(&obs), timer_id, 0);
 
         // RTO(msec) Total(msec)
@@ -434,7 +434,7 @@ mod test_rtx_timer {
         assert_eq!(6, ncbs.load(Ordering::SeqCst), "should be called 6 times");
 
         if let Some(elapsed) = elapsed {
-            let diff = elapsed.duration_since(since).unwrap();
+            let diff = elapsed.duration_since(since);
             assert!(
                 diff > Duration::from_millis(600),
                 "must have taken more than 600 msec"
@@ -450,7 +450,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_stop_timer_that_is_not_running_is_noop() -> Result<()> {
         let (done_tx, mut done_rx) = mpsc::channel(1);
 
@@ -478,7 +478,7 @@ mod test_rtx_timer {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_rtx_timer_closed_timer_wont_start() -> Result<()> {
         let timer_id = RtxTimerId::Reconfig;
         let ncbs = Arc::new(AtomicU32::new(0));
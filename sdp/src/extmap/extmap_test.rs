@@ -77,3 +77,35 @@ fn test_transport_cc_extmap() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_encrypted_extmap() -> Result<()> {
+    let line =
+        "extmap:1 urn:ietf:params:rtp-hdrext:encrypt urn:ietf:params:rtp-hdrext:ssrc-audio-level vad=on";
+    let mut reader = BufReader::new(line.as_bytes());
+    let e = ExtMap::unmarshal(&mut reader)?;
+
+    assert!(e.is_encrypted());
+    assert_eq!(
+        e.extension_uri()?,
+        Some(Url::parse(AUDIO_LEVEL_URI)?),
+        "encrypted extmap must give the URI of the extension"
+    );
+    assert_eq!(e.marshal(), line);
+
+    let e = ExtMap::encrypted(2, Direction::SendRecv, &Url::parse(SDES_MID_URI)?)?;
+    assert_eq!(
+        e.marshal(),
+        "extmap:2/sendrecv urn:ietf:params:rtp-hdrext:encrypt urn:ietf:params:rtp-hdrext:sdes:mid"
+    );
+
+    let plain = ExtMap {
+        value: 3,
+        uri: Some(Url::parse(SDES_MID_URI)?),
+        ..Default::default()
+    };
+    assert!(!plain.is_encrypted());
+    assert_eq!(plain.extension_uri()?, plain.uri);
+
+    Ok(())
+}
@@ -22,6 +22,9 @@ pub const SDES_MID_URI: &str = "urn:ietf:params:rtp-hdrext:sdes:mid";
 pub const SDES_RTP_STREAM_ID_URI: &str = "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id";
 pub const AUDIO_LEVEL_URI: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
 pub const VIDEO_ORIENTATION_URI: &str = "urn:3gpp:video-orientation";
+/// ENCRYPT_URI marks an extmap whose extension is encrypted, the extension's own URI
+/// follows it. https://tools.ietf.org/html/rfc6904#section-3
+pub const ENCRYPT_URI: &str = "urn:ietf:params:rtp-hdrext:encrypt";
 
 /// ExtMap represents the activation of a single RTP header extension
 #[derive(Debug, Clone, Default)]
@@ -96,8 +99,8 @@ impl ExtMap {
 
         let uri = Some(Url::parse(fields[1])?);
 
-        let ext_attr = if fields.len() == 3 {
-            Some(fields[2].to_owned())
+        let ext_attr = if fields.len() >= 3 {
+            Some(fields[2..].join(" "))
         } else {
             None
         };
@@ -110,6 +113,40 @@ impl ExtMap {
         })
     }
 
+    /// encrypted creates an ExtMap for the encrypted form of the extension with uri.
+    pub fn encrypted(value: isize, direction: Direction, uri: &Url) -> Result<Self> {
+        Ok(ExtMap {
+            value,
+            direction,
+            uri: Some(Url::parse(ENCRYPT_URI)?),
+            ext_attr: Some(uri.to_string()),
+        })
+    }
+
+    /// is_encrypted tells if the extension is encrypted as of RFC 6904.
+    pub fn is_encrypted(&self) -> bool {
+        self.uri
+            .as_ref()
+            .map_or(false, |uri| uri.as_str() == ENCRYPT_URI)
+    }
+
+    /// extension_uri returns the URI of the extension, which for an encrypted
+    /// extension is the first of its attributes.
+    pub fn extension_uri(&self) -> Result<Option<Url>> {
+        if !self.is_encrypted() {
+            return Ok(self.uri.clone());
+        }
+
+        match self
+            .ext_attr
+            .as_ref()
+            .and_then(|ext_attr| ext_attr.split_whitespace().next())
+        {
+            Some(uri) => Ok(Some(Url::parse(uri)?)),
+            None => Ok(None),
+        }
+    }
+
     /// marshal creates a string from an ExtMap
     pub fn marshal(&self) -> String {
         "extmap:".to_string() + self.to_string().as_str()
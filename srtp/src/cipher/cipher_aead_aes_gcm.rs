@@ -1,64 +1,75 @@
 use aes_gcm::{
-    aead::{generic_array::GenericArray, Aead, NewAead, Payload},
-    Aes128Gcm, Nonce,
+    aead::{consts::U12, generic_array::GenericArray, Aead, NewAead, Payload},
+    Nonce,
 };
 use byteorder::{BigEndian, ByteOrder};
 use bytes::{Bytes, BytesMut};
+use std::ops::Range;
 
-use super::Cipher;
+use super::cipher_aes_cm_hmac_sha1::aes_cm_keystream;
+use super::{gaps, gather, scatter, Cipher};
 use crate::{
     error::{Error, Result},
     key_derivation::*,
 };
-use util::marshal::*;
 
 pub const CIPHER_AEAD_AES_GCM_AUTH_TAG_LEN: usize = 16;
 
 const RTCP_ENCRYPTION_FLAG: u8 = 0x80;
 
-/// AEAD Cipher based on AES.
-pub(crate) struct CipherAeadAesGcm {
-    srtp_cipher: aes_gcm::Aes128Gcm,
-    srtcp_cipher: aes_gcm::Aes128Gcm,
+/// AEAD Cipher based on AES, A is aes_gcm::Aes128Gcm or aes_gcm::Aes256Gcm.
+pub(crate) struct CipherAeadAesGcm<A> {
+    srtp_cipher: A,
+    srtcp_cipher: A,
     srtp_session_salt: Vec<u8>,
     srtcp_session_salt: Vec<u8>,
+    srtp_header_key: Vec<u8>,
+    srtp_header_salt: Vec<u8>,
 }
 
-impl Cipher for CipherAeadAesGcm {
+impl<A: Aead<NonceSize = U12> + NewAead> Cipher for CipherAeadAesGcm<A> {
     fn auth_tag_len(&self) -> usize {
         CIPHER_AEAD_AES_GCM_AUTH_TAG_LEN
     }
 
+    fn rtcp_auth_tag_len(&self) -> usize {
+        CIPHER_AEAD_AES_GCM_AUTH_TAG_LEN
+    }
+
+    /// Everything outside of ranges is the AAD, which for cryptex is the fixed header
+    /// and the header extension header together.
+    /// https://www.rfc-editor.org/rfc/rfc9335#section-5.3
     fn encrypt_rtp(
         &mut self,
-        payload: &[u8],
+        plaintext: &[u8],
+        ranges: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes> {
-        // Grow the given buffer to fit the output.
-        let mut writer =
-            BytesMut::with_capacity(header.marshal_size() + payload.len() + self.auth_tag_len());
-
-        let data = header.marshal()?;
-        writer.extend(data);
-
         let nonce = self.rtp_initialization_vector(header, roc);
+        let msg = gather(plaintext, ranges);
 
         let encrypted = self.srtp_cipher.encrypt(
             Nonce::from_slice(&nonce),
             Payload {
-                msg: payload,
-                aad: &writer,
+                msg: &msg,
+                aad: &gather(plaintext, &gaps(ranges, plaintext.len())),
             },
         )?;
 
-        writer.extend(encrypted);
+        // Grow the given buffer to fit the output.
+        let mut writer = BytesMut::with_capacity(plaintext.len() + self.auth_tag_len());
+        writer.extend_from_slice(plaintext);
+        scatter(&mut writer, ranges, &encrypted[..msg.len()]);
+        writer.extend_from_slice(&encrypted[msg.len()..]);
+
         Ok(writer.freeze())
     }
 
     fn decrypt_rtp(
         &mut self,
         ciphertext: &[u8],
+        ranges: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes> {
@@ -67,22 +78,41 @@ impl Cipher for CipherAeadAesGcm {
         }
 
         let nonce = self.rtp_initialization_vector(header, roc);
-        let payload_offset = header.marshal_size();
+        let tag_offset = ciphertext.len() - self.auth_tag_len();
+        let mut msg = gather(ciphertext, ranges);
+        msg.extend_from_slice(&ciphertext[tag_offset..]);
         let decrypted_msg: Vec<u8> = self.srtp_cipher.decrypt(
             Nonce::from_slice(&nonce),
             Payload {
-                msg: &ciphertext[payload_offset..],
-                aad: &ciphertext[..payload_offset],
+                msg: &msg,
+                aad: &gather(ciphertext, &gaps(ranges, tag_offset)),
             },
         )?;
 
-        let mut writer = BytesMut::with_capacity(payload_offset + decrypted_msg.len());
-        writer.extend_from_slice(&ciphertext[..payload_offset]);
-        writer.extend(decrypted_msg);
+        let mut writer = BytesMut::with_capacity(tag_offset);
+        writer.extend_from_slice(&ciphertext[..tag_offset]);
+        scatter(&mut writer, ranges, &decrypted_msg);
 
         Ok(writer.freeze())
     }
 
+    /// AEAD profiles encrypt header extensions with AES-CM too, using the header
+    /// encryption key and salt derived from the master key.
+    fn rtp_header_extension_keystream(
+        &self,
+        header: &rtp::header::Header,
+        roc: u32,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let counter = generate_counter(
+            header.sequence_number,
+            roc,
+            header.ssrc,
+            &self.srtp_header_salt,
+        )?;
+        Ok(aes_cm_keystream(&self.srtp_header_key, &counter, len))
+    }
+
     fn encrypt_rtcp(&mut self, decrypted: &[u8], srtcp_index: usize, ssrc: u32) -> Result<Bytes> {
         let iv = self.rtcp_initialization_vector(srtcp_index, ssrc);
         let aad = self.rtcp_additional_authenticated_data(decrypted, srtcp_index);
@@ -134,9 +164,9 @@ impl Cipher for CipherAeadAesGcm {
     }
 }
 
-impl CipherAeadAesGcm {
+impl<A: Aead<NonceSize = U12> + NewAead> CipherAeadAesGcm<A> {
     /// Create a new AEAD instance.
-    pub(crate) fn new(master_key: &[u8], master_salt: &[u8]) -> Result<CipherAeadAesGcm> {
+    pub(crate) fn new(master_key: &[u8], master_salt: &[u8]) -> Result<CipherAeadAesGcm<A>> {
         let srtp_session_key = aes_cm_key_derivation(
             LABEL_SRTP_ENCRYPTION,
             master_key,
@@ -147,7 +177,7 @@ impl CipherAeadAesGcm {
 
         let srtp_block = GenericArray::from_slice(&srtp_session_key);
 
-        let srtp_cipher = Aes128Gcm::new(srtp_block);
+        let srtp_cipher = A::new(srtp_block);
 
         let srtcp_session_key = aes_cm_key_derivation(
             LABEL_SRTCP_ENCRYPTION,
@@ -159,14 +189,14 @@ impl CipherAeadAesGcm {
 
         let srtcp_block = GenericArray::from_slice(&srtcp_session_key);
 
-        let srtcp_cipher = Aes128Gcm::new(srtcp_block);
+        let srtcp_cipher = A::new(srtcp_block);
 
         let srtp_session_salt = aes_cm_key_derivation(
             LABEL_SRTP_SALT,
             master_key,
             master_salt,
             0,
-            master_key.len(),
+            master_salt.len(),
         )?;
 
         let srtcp_session_salt = aes_cm_key_derivation(
@@ -174,14 +204,32 @@ impl CipherAeadAesGcm {
             master_key,
             master_salt,
             0,
+            master_salt.len(),
+        )?;
+
+        let srtp_header_key = aes_cm_key_derivation(
+            LABEL_SRTP_HEADER_ENCRYPTION,
+            master_key,
+            master_salt,
+            0,
             master_key.len(),
         )?;
 
+        let srtp_header_salt = aes_cm_key_derivation(
+            LABEL_SRTP_HEADER_SALT,
+            master_key,
+            master_salt,
+            0,
+            master_salt.len(),
+        )?;
+
         Ok(CipherAeadAesGcm {
             srtp_cipher,
             srtcp_cipher,
             srtp_session_salt,
             srtcp_session_salt,
+            srtp_header_key,
+            srtp_header_salt,
         })
     }
 
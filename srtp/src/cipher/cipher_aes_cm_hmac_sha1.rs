@@ -1,27 +1,65 @@
-use super::Cipher;
+use super::{gather, scatter, Cipher};
 use crate::error::Result;
 use crate::{error::Error, key_derivation::*, protection_profile::*};
-use util::marshal::*;
 
 use aes::cipher::generic_array::GenericArray;
 use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
 use bytes::{BufMut, Bytes, BytesMut};
-use ctr::cipher::{NewCipher, StreamCipher, StreamCipherSeek};
+use ctr::cipher::{NewCipher, StreamCipher};
 use hmac::{Hmac, Mac, NewMac};
 use sha1::Sha1;
 use std::io::BufWriter;
+use std::ops::Range;
 use subtle::ConstantTimeEq;
 
 type HmacSha1 = Hmac<Sha1>;
 type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+type Aes256Ctr = ctr::Ctr128BE<aes::Aes256>;
 
 pub const CIPHER_AES_CM_HMAC_SHA1AUTH_TAG_LEN: usize = 10;
 
+/// Applies the AES-CM keystream for counter to buf, with AES-128 or AES-256 picked by the
+/// length of the session key.
+fn apply_aes_cm_keystream(session_key: &[u8], counter: &[u8], buf: &mut [u8]) {
+    let nonce = GenericArray::from_slice(counter);
+    if session_key.len() == 32 {
+        Aes256Ctr::new(GenericArray::from_slice(session_key), nonce).apply_keystream(buf);
+    } else {
+        Aes128Ctr::new(GenericArray::from_slice(session_key), nonce).apply_keystream(buf);
+    }
+}
+
+/// Applies the AES-CM keystream to the ranges of buf, as if they were one contiguous buffer.
+fn apply_aes_cm_keystream_to_ranges(
+    session_key: &[u8],
+    counter: &[u8],
+    buf: &mut [u8],
+    ranges: &[Range<usize>],
+) {
+    if let [range] = ranges {
+        apply_aes_cm_keystream(session_key, counter, &mut buf[range.clone()]);
+    } else {
+        let mut stream = gather(buf, ranges);
+        apply_aes_cm_keystream(session_key, counter, &mut stream);
+        scatter(buf, ranges, &stream);
+    }
+}
+
+/// Returns len bytes of the AES-CM keystream for counter.
+pub(crate) fn aes_cm_keystream(session_key: &[u8], counter: &[u8], len: usize) -> Vec<u8> {
+    let mut keystream = vec![0u8; len];
+    apply_aes_cm_keystream(session_key, counter, &mut keystream);
+    keystream
+}
+
 pub(crate) struct CipherAesCmHmacSha1 {
+    profile: ProtectionProfile,
     srtp_session_key: Vec<u8>,
     srtp_session_salt: Vec<u8>,
     srtp_session_auth: HmacSha1,
     //srtp_session_auth_tag: Vec<u8>,
+    srtp_header_key: Vec<u8>,
+    srtp_header_salt: Vec<u8>,
     srtcp_session_key: Vec<u8>,
     srtcp_session_salt: Vec<u8>,
     srtcp_session_auth: HmacSha1,
@@ -29,7 +67,7 @@ pub(crate) struct CipherAesCmHmacSha1 {
 }
 
 impl CipherAesCmHmacSha1 {
-    pub fn new(master_key: &[u8], master_salt: &[u8]) -> Result<Self> {
+    pub fn new(profile: ProtectionProfile, master_key: &[u8], master_salt: &[u8]) -> Result<Self> {
         let srtp_session_key = aes_cm_key_derivation(
             LABEL_SRTP_ENCRYPTION,
             master_key,
@@ -60,7 +98,22 @@ impl CipherAesCmHmacSha1 {
             master_salt.len(),
         )?;
 
-        let auth_key_len = ProtectionProfile::Aes128CmHmacSha1_80.auth_key_len();
+        let srtp_header_key = aes_cm_key_derivation(
+            LABEL_SRTP_HEADER_ENCRYPTION,
+            master_key,
+            master_salt,
+            0,
+            master_key.len(),
+        )?;
+        let srtp_header_salt = aes_cm_key_derivation(
+            LABEL_SRTP_HEADER_SALT,
+            master_key,
+            master_salt,
+            0,
+            master_salt.len(),
+        )?;
+
+        let auth_key_len = profile.auth_key_len();
 
         let srtp_session_auth_tag = aes_cm_key_derivation(
             LABEL_SRTP_AUTHENTICATION_TAG,
@@ -83,10 +136,13 @@ impl CipherAesCmHmacSha1 {
             .map_err(|e| Error::Other(e.to_string()))?;
 
         Ok(CipherAesCmHmacSha1 {
+            profile,
             srtp_session_key,
             srtp_session_salt,
             srtp_session_auth,
             //srtp_session_auth_tag,
+            srtp_header_key,
+            srtp_header_salt,
             srtcp_session_key,
             srtcp_session_salt,
             srtcp_session_auth,
@@ -149,36 +205,36 @@ impl CipherAesCmHmacSha1 {
         let code_bytes = result.into_bytes();
 
         // Truncate the hash to the first AUTH_TAG_SIZE bytes.
-        code_bytes[0..self.auth_tag_len()].to_vec()
+        code_bytes[0..self.rtcp_auth_tag_len()].to_vec()
     }
 }
 
 impl Cipher for CipherAesCmHmacSha1 {
     fn auth_tag_len(&self) -> usize {
-        CIPHER_AES_CM_HMAC_SHA1AUTH_TAG_LEN
+        self.profile.auth_tag_len()
+    }
+
+    fn rtcp_auth_tag_len(&self) -> usize {
+        self.profile.rtcp_auth_tag_len()
     }
 
     fn get_rtcp_index(&self, input: &[u8]) -> usize {
-        let tail_offset = input.len() - (self.auth_tag_len() + SRTCP_INDEX_SIZE);
+        let tail_offset = input.len() - (self.rtcp_auth_tag_len() + SRTCP_INDEX_SIZE);
         (BigEndian::read_u32(&input[tail_offset..tail_offset + SRTCP_INDEX_SIZE]) & !(1 << 31))
             as usize
     }
 
     fn encrypt_rtp(
         &mut self,
-        payload: &[u8],
+        plaintext: &[u8],
+        ranges: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes> {
-        let mut writer =
-            BytesMut::with_capacity(header.marshal_size() + payload.len() + self.auth_tag_len());
+        let mut writer = BytesMut::with_capacity(plaintext.len() + self.auth_tag_len());
 
-        // Copy the header unencrypted.
-        let data = header.marshal()?;
-        writer.extend(data);
-
-        // Write the plaintext header to the destination buffer.
-        writer.extend_from_slice(payload);
+        // Write the plaintext packet to the destination buffer.
+        writer.extend_from_slice(plaintext);
 
         // Encrypt the payload
         let counter = generate_counter(
@@ -187,11 +243,7 @@ impl Cipher for CipherAesCmHmacSha1 {
             header.ssrc,
             &self.srtp_session_salt,
         )?;
-        let key = GenericArray::from_slice(&self.srtp_session_key);
-        let nonce = GenericArray::from_slice(&counter);
-        let mut stream = Aes128Ctr::new(key, nonce);
-        let payload_offset = header.marshal_size();
-        stream.apply_keystream(&mut writer[payload_offset..]);
+        apply_aes_cm_keystream_to_ranges(&self.srtp_session_key, &counter, &mut writer, ranges);
 
         // Generate the auth tag.
         let auth_tag = self.generate_srtp_auth_tag(&writer, roc)?;
@@ -203,6 +255,7 @@ impl Cipher for CipherAesCmHmacSha1 {
     fn decrypt_rtp(
         &mut self,
         encrypted: &[u8],
+        ranges: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes> {
@@ -235,20 +288,29 @@ impl Cipher for CipherAesCmHmacSha1 {
             header.ssrc,
             &self.srtp_session_salt,
         )?;
-
-        let key = GenericArray::from_slice(&self.srtp_session_key);
-        let nonce = GenericArray::from_slice(&counter);
-        let mut stream = Aes128Ctr::new(key, nonce);
-        let payload_offset = header.marshal_size();
-        stream.seek(0);
-        stream.apply_keystream(&mut writer[payload_offset..]);
+        apply_aes_cm_keystream_to_ranges(&self.srtp_session_key, &counter, &mut writer, ranges);
 
         Ok(writer.freeze())
     }
 
+    fn rtp_header_extension_keystream(
+        &self,
+        header: &rtp::header::Header,
+        roc: u32,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let counter = generate_counter(
+            header.sequence_number,
+            roc,
+            header.ssrc,
+            &self.srtp_header_salt,
+        )?;
+        Ok(aes_cm_keystream(&self.srtp_header_key, &counter, len))
+    }
+
     fn encrypt_rtcp(&mut self, decrypted: &[u8], srtcp_index: usize, ssrc: u32) -> Result<Bytes> {
         let mut writer =
-            BytesMut::with_capacity(decrypted.len() + SRTCP_INDEX_SIZE + self.auth_tag_len());
+            BytesMut::with_capacity(decrypted.len() + SRTCP_INDEX_SIZE + self.rtcp_auth_tag_len());
 
         // Write the decrypted to the destination buffer.
         writer.extend_from_slice(decrypted);
@@ -261,11 +323,9 @@ impl Cipher for CipherAesCmHmacSha1 {
             &self.srtcp_session_salt,
         )?;
 
-        let key = GenericArray::from_slice(&self.srtcp_session_key);
-        let nonce = GenericArray::from_slice(&counter);
-        let mut stream = Aes128Ctr::new(key, nonce);
-
-        stream.apply_keystream(
+        apply_aes_cm_keystream(
+            &self.srtcp_session_key,
+            &counter,
             &mut writer[rtcp::header::HEADER_LENGTH + rtcp::header::SSRC_LENGTH..],
         );
 
@@ -280,14 +340,14 @@ impl Cipher for CipherAesCmHmacSha1 {
     }
 
     fn decrypt_rtcp(&mut self, encrypted: &[u8], srtcp_index: usize, ssrc: u32) -> Result<Bytes> {
-        if encrypted.len() < self.auth_tag_len() + SRTCP_INDEX_SIZE {
+        if encrypted.len() < self.rtcp_auth_tag_len() + SRTCP_INDEX_SIZE {
             return Err(Error::SrtcpTooSmall(
                 encrypted.len(),
-                self.auth_tag_len() + SRTCP_INDEX_SIZE,
+                self.rtcp_auth_tag_len() + SRTCP_INDEX_SIZE,
             ));
         }
 
-        let tail_offset = encrypted.len() - (self.auth_tag_len() + SRTCP_INDEX_SIZE);
+        let tail_offset = encrypted.len() - (self.rtcp_auth_tag_len() + SRTCP_INDEX_SIZE);
 
         let mut writer = BytesMut::with_capacity(tail_offset);
 
@@ -299,8 +359,8 @@ impl Cipher for CipherAesCmHmacSha1 {
         }
 
         // Split the auth tag and the cipher text into two parts.
-        let actual_tag = &encrypted[encrypted.len() - self.auth_tag_len()..];
-        let cipher_text = &encrypted[..encrypted.len() - self.auth_tag_len()];
+        let actual_tag = &encrypted[encrypted.len() - self.rtcp_auth_tag_len()..];
+        let cipher_text = &encrypted[..encrypted.len() - self.rtcp_auth_tag_len()];
 
         // Generate the auth tag we expect to see from the ciphertext.
         let expected_tag = self.generate_srtcp_auth_tag(cipher_text);
@@ -318,12 +378,9 @@ impl Cipher for CipherAesCmHmacSha1 {
             &self.srtcp_session_salt,
         )?;
 
-        let key = GenericArray::from_slice(&self.srtcp_session_key);
-        let nonce = GenericArray::from_slice(&counter);
-        let mut stream = Aes128Ctr::new(key, nonce);
-
-        stream.seek(0);
-        stream.apply_keystream(
+        apply_aes_cm_keystream(
+            &self.srtcp_session_key,
+            &counter,
             &mut writer[rtcp::header::HEADER_LENGTH + rtcp::header::SSRC_LENGTH..],
         );
 
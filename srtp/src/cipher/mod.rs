@@ -2,6 +2,7 @@ pub mod cipher_aead_aes_gcm;
 pub mod cipher_aes_cm_hmac_sha1;
 
 use bytes::Bytes;
+use std::ops::Range;
 
 use crate::error::Result;
 
@@ -34,28 +35,77 @@ pub(crate) trait Cipher {
     /// Get authenticated tag length.
     fn auth_tag_len(&self) -> usize;
 
+    /// Get authenticated tag length for RTCP, which is longer than the RTP one for the _32 profile.
+    fn rtcp_auth_tag_len(&self) -> usize;
+
     /// Retrieved RTCP index.
     fn get_rtcp_index(&self, input: &[u8]) -> usize;
 
-    /// Encrypt RTP payload.
+    /// Encrypt RTP packet. The bytes in encrypted are encrypted as one contiguous stream,
+    /// normally that is just the payload, the rest of the packet is only authenticated.
     fn encrypt_rtp(
         &mut self,
-        payload: &[u8],
+        packet: &[u8],
+        encrypted: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes>;
 
-    /// Decrypt RTP payload.
+    /// Decrypt RTP packet. The encrypted ranges all lie before the auth tag.
     fn decrypt_rtp(
         &mut self,
-        payload: &[u8],
+        packet: &[u8],
+        encrypted: &[Range<usize>],
         header: &rtp::header::Header,
         roc: u32,
     ) -> Result<Bytes>;
 
+    /// Keystream of len bytes to encrypt the header extensions of an RTP packet with.
+    /// https://tools.ietf.org/html/rfc6904#section-4.1
+    fn rtp_header_extension_keystream(
+        &self,
+        header: &rtp::header::Header,
+        roc: u32,
+        len: usize,
+    ) -> Result<Vec<u8>>;
+
     /// Encrypt RTCP payload.
     fn encrypt_rtcp(&mut self, payload: &[u8], srtcp_index: usize, ssrc: u32) -> Result<Bytes>;
 
     /// Decrypt RTCP payload.
     fn decrypt_rtcp(&mut self, payload: &[u8], srtcp_index: usize, ssrc: u32) -> Result<Bytes>;
 }
+
+/// gather copies the ranges of buf into one buffer.
+pub(crate) fn gather(buf: &[u8], ranges: &[Range<usize>]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(ranges.iter().map(|r| r.len()).sum());
+    for r in ranges {
+        out.extend_from_slice(&buf[r.clone()]);
+    }
+    out
+}
+
+/// scatter copies data back into the ranges of buf, undoing gather.
+pub(crate) fn scatter(buf: &mut [u8], ranges: &[Range<usize>], data: &[u8]) {
+    let mut offset = 0;
+    for r in ranges {
+        buf[r.clone()].copy_from_slice(&data[offset..offset + r.len()]);
+        offset += r.len();
+    }
+}
+
+/// gaps returns the parts of buf[..end] that are not in ranges, which must be sorted.
+pub(crate) fn gaps(ranges: &[Range<usize>], end: usize) -> Vec<Range<usize>> {
+    let mut out = vec![];
+    let mut pos = 0;
+    for r in ranges {
+        if r.start > pos {
+            out.push(pos..r.start);
+        }
+        pos = r.end;
+    }
+    if pos < end {
+        out.push(pos..end);
+    }
+    out
+}
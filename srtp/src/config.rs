@@ -11,6 +11,10 @@ pub struct SessionKeys {
     pub local_master_salt: Vec<u8>,
     pub remote_master_key: Vec<u8>,
     pub remote_master_salt: Vec<u8>,
+    /// MKIs tagging the master keys above, empty when packets carry no MKI.
+    /// https://tools.ietf.org/html/rfc3711#section-3.1
+    pub local_mki: Vec<u8>,
+    pub remote_mki: Vec<u8>,
 }
 
 /// Config is used to configure a session.
@@ -30,6 +34,12 @@ pub struct Config {
 
     pub local_rtcp_options: Option<ContextOption>,
     pub remote_rtcp_options: Option<ContextOption>,
+
+    /// IDs of the RTP header extensions that are encrypted, https://tools.ietf.org/html/rfc6904
+    pub encrypted_header_extensions: Vec<u8>,
+    /// cryptex encrypts the CSRCs and header extensions of RTP packets,
+    /// https://www.rfc-editor.org/rfc/rfc9335
+    pub cryptex: bool,
 }
 
 impl Config {
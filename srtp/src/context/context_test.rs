@@ -1,5 +1,6 @@
 use super::*;
 use crate::key_derivation::*;
+use util::marshal::*;
 
 use bytes::Bytes;
 use lazy_static::lazy_static;
@@ -305,3 +306,229 @@ fn test_decrypt_rtcp() {
 
     assert_eq!(gotten_decrypted_rtcp_packet, *DECRYPTED_RTCP_PACKET)
 }
+
+#[test]
+fn test_protection_profiles_round_trip() -> Result<()> {
+    let profiles = vec![
+        ProtectionProfile::Aes128CmHmacSha1_80,
+        ProtectionProfile::Aes128CmHmacSha1_32,
+        ProtectionProfile::Aes256CmHmacSha1_80,
+        ProtectionProfile::AeadAes128Gcm,
+        ProtectionProfile::AeadAes256Gcm,
+    ];
+
+    for profile in profiles {
+        let master_key = vec![0x11; profile.key_len()];
+        let master_salt = vec![0x22; profile.salt_len()];
+        let mut encrypt_ctx = Context::new(&master_key, &master_salt, profile, None, None)?;
+        let mut decrypt_ctx = Context::new(&master_key, &master_salt, profile, None, None)?;
+
+        let encrypted_rtp = encrypt_ctx.encrypt_rtp(&DECRYPTED_RTP_PACKET)?;
+        assert_eq!(
+            encrypted_rtp.len(),
+            DECRYPTED_RTP_PACKET.len() + profile.auth_tag_len(),
+            "{:?}: wrong SRTP length",
+            profile
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtp(&encrypted_rtp)?,
+            *DECRYPTED_RTP_PACKET,
+            "{:?}: SRTP round trip",
+            profile
+        );
+
+        let encrypted_rtcp = encrypt_ctx.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+        assert_eq!(
+            encrypted_rtcp.len(),
+            DECRYPTED_RTCP_PACKET.len() + SRTCP_INDEX_SIZE + profile.rtcp_auth_tag_len(),
+            "{:?}: wrong SRTCP length",
+            profile
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtcp(&encrypted_rtcp)?,
+            *DECRYPTED_RTCP_PACKET,
+            "{:?}: SRTCP round trip",
+            profile
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_aes_256_key_len() -> Result<()> {
+    for profile in &[
+        ProtectionProfile::Aes256CmHmacSha1_80,
+        ProtectionProfile::AeadAes256Gcm,
+    ] {
+        let result = Context::new(&[0; 16], &vec![0; profile.salt_len()], *profile, None, None);
+        assert_eq!(
+            result.err(),
+            Some(Error::SrtpMasterKeyLength(32, 16)),
+            "{:?} accepted a 128-bit master key",
+            profile
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mki_round_trip() -> Result<()> {
+    let mki = vec![0x01, 0x02, 0x03, 0x04];
+    let profiles = vec![
+        ProtectionProfile::Aes128CmHmacSha1_80,
+        ProtectionProfile::Aes128CmHmacSha1_32,
+        ProtectionProfile::Aes256CmHmacSha1_80,
+        ProtectionProfile::AeadAes128Gcm,
+        ProtectionProfile::AeadAes256Gcm,
+    ];
+
+    for profile in profiles {
+        let master_key = vec![0x11; profile.key_len()];
+        let master_salt = vec![0x22; profile.salt_len()];
+        let mut plain_ctx = Context::new(&master_key, &master_salt, profile, None, None)?;
+        let mut encrypt_ctx =
+            Context::new_with_mki(&master_key, &master_salt, &mki, profile, None, None)?;
+        let mut decrypt_ctx =
+            Context::new_with_mki(&master_key, &master_salt, &mki, profile, None, None)?;
+
+        // The MKI goes right before the auth tag, AEAD packets end with it.
+        let (rtp_tag_len, rtcp_tag_len) = match profile {
+            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => (0, 0),
+            _ => (profile.auth_tag_len(), profile.rtcp_auth_tag_len()),
+        };
+
+        let without_mki = plain_ctx.encrypt_rtp(&DECRYPTED_RTP_PACKET)?;
+        let with_mki = encrypt_ctx.encrypt_rtp(&DECRYPTED_RTP_PACKET)?;
+        let mki_offset = without_mki.len() - rtp_tag_len;
+        assert_eq!(
+            with_mki.len(),
+            without_mki.len() + mki.len(),
+            "{:?}: wrong SRTP length",
+            profile
+        );
+        assert_eq!(&with_mki[..mki_offset], &without_mki[..mki_offset]);
+        assert_eq!(&with_mki[mki_offset..mki_offset + mki.len()], &mki[..]);
+        assert_eq!(
+            &with_mki[mki_offset + mki.len()..],
+            &without_mki[mki_offset..]
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtp(&with_mki)?,
+            *DECRYPTED_RTP_PACKET,
+            "{:?}: SRTP round trip",
+            profile
+        );
+
+        let without_mki = plain_ctx.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+        let with_mki = encrypt_ctx.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+        let mki_offset = without_mki.len() - rtcp_tag_len;
+        assert_eq!(&with_mki[..mki_offset], &without_mki[..mki_offset]);
+        assert_eq!(&with_mki[mki_offset..mki_offset + mki.len()], &mki[..]);
+        assert_eq!(
+            &with_mki[mki_offset + mki.len()..],
+            &without_mki[mki_offset..]
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtcp(&with_mki)?,
+            *DECRYPTED_RTCP_PACKET,
+            "{:?}: SRTCP round trip",
+            profile
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mki_key_rotation() -> Result<()> {
+    let profile = ProtectionProfile::Aes128CmHmacSha1_80;
+    let key_1 = vec![0x11; profile.key_len()];
+    let key_2 = vec![0x33; profile.key_len()];
+    let salt = vec![0x22; profile.salt_len()];
+
+    let mut encrypt_ctx = Context::new_with_mki(&key_1, &salt, &[1], profile, None, None)?;
+    let mut decrypt_ctx = Context::new_with_mki(&key_1, &salt, &[1], profile, None, None)?;
+
+    let old_packet = encrypt_ctx.encrypt_rtp(&DECRYPTED_RTP_PACKET)?;
+
+    encrypt_ctx.add_cipher_for_mki(&[2], &key_2, &salt)?;
+    encrypt_ctx.set_send_mki(&[2])?;
+    assert_eq!(encrypt_ctx.send_mki(), &[2]);
+
+    let mut header = rtp::header::Header::unmarshal(&mut &DECRYPTED_RTP_PACKET[..])?;
+    header.sequence_number += 1;
+    let mut next = header.marshal()?.to_vec();
+    next.extend_from_slice(&DECRYPTED_RTP_PACKET[header.marshal_size()..]);
+    let new_packet = encrypt_ctx.encrypt_rtp(&next)?;
+
+    assert_eq!(
+        decrypt_ctx.decrypt_rtp(&new_packet).err(),
+        Some(Error::ErrMkiNotFound),
+        "packet of an unknown MKI must be rejected"
+    );
+
+    decrypt_ctx.add_cipher_for_mki(&[2], &key_2, &salt)?;
+    assert_eq!(decrypt_ctx.decrypt_rtp(&new_packet)?, next);
+    // Packets still in flight with the old key decrypt as well.
+    assert_eq!(decrypt_ctx.decrypt_rtp(&old_packet)?, *DECRYPTED_RTP_PACKET);
+
+    encrypt_ctx.remove_mki(&[1])?;
+    assert_eq!(encrypt_ctx.ciphers.len(), 1);
+
+    // The receiver drops the key it was created with once the sender moved on.
+    decrypt_ctx.remove_decryption_mki(&[1])?;
+    assert_eq!(
+        decrypt_ctx.decrypt_rtp(&old_packet).err(),
+        Some(Error::ErrMkiNotFound)
+    );
+    let mut header = rtp::header::Header::unmarshal(&mut &next[..])?;
+    header.sequence_number += 1;
+    let mut last = header.marshal()?.to_vec();
+    last.extend_from_slice(&next[header.marshal_size()..]);
+    let last_packet = encrypt_ctx.encrypt_rtp(&last)?;
+    assert_eq!(decrypt_ctx.decrypt_rtp(&last_packet)?, last);
+
+    Ok(())
+}
+
+#[test]
+fn test_mki_errors() -> Result<()> {
+    let profile = ProtectionProfile::Aes128CmHmacSha1_80;
+    let key = vec![0x11; profile.key_len()];
+    let salt = vec![0x22; profile.salt_len()];
+
+    let mut no_mki_ctx = Context::new(&key, &salt, profile, None, None)?;
+    assert_eq!(
+        no_mki_ctx.add_cipher_for_mki(&[1], &key, &salt),
+        Err(Error::ErrMkiNotEnabled)
+    );
+
+    let mut ctx = Context::new_with_mki(&key, &salt, &[1, 1], profile, None, None)?;
+    assert_eq!(
+        ctx.add_cipher_for_mki(&[2], &key, &salt),
+        Err(Error::MkiLength(2, 1))
+    );
+    assert_eq!(
+        ctx.add_cipher_for_mki(&[1, 1], &key, &salt),
+        Err(Error::ErrMkiAlreadyInUse)
+    );
+    assert_eq!(
+        ctx.add_cipher_for_mki(&[2, 2], &key[1..], &salt),
+        Err(Error::SrtpMasterKeyLength(16, 15))
+    );
+    assert_eq!(ctx.set_send_mki(&[2, 2]), Err(Error::ErrMkiNotFound));
+    assert_eq!(ctx.remove_mki(&[1, 1]), Err(Error::ErrMkiInUse));
+    assert_eq!(ctx.remove_mki(&[2, 2]), Err(Error::ErrMkiNotFound));
+    assert_eq!(
+        ctx.remove_decryption_mki(&[2, 2]),
+        Err(Error::ErrMkiNotFound)
+    );
+    assert_eq!(
+        ctx.remove_decryption_mki(&[1, 1]),
+        Err(Error::ErrMkiLastKey)
+    );
+
+    Ok(())
+}
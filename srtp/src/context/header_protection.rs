@@ -0,0 +1,243 @@
+use super::*;
+use crate::cipher::Cipher;
+
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{Bytes, BytesMut};
+use rtp::header::*;
+use std::ops::Range;
+
+/// Header extension profiles of cryptex packets, in place of the one-byte and two-byte profiles.
+/// https://www.rfc-editor.org/rfc/rfc9335#section-5.1
+pub const CRYPTEX_PROFILE_ONE_BYTE: u16 = 0xC0DE;
+pub const CRYPTEX_PROFILE_TWO_BYTE: u16 = 0xC2DE;
+
+const EXTENSION_HEADER_LENGTH: usize = 4;
+
+/// HeaderProtection decides which parts of an RTP header are encrypted along with the payload.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct HeaderProtection {
+    /// IDs of the header extensions encrypted as in https://tools.ietf.org/html/rfc6904
+    pub(crate) encrypted_extensions: Vec<u8>,
+    /// cryptex encrypts the CSRCs and all header extensions,
+    /// https://www.rfc-editor.org/rfc/rfc9335
+    pub(crate) cryptex: bool,
+}
+
+impl HeaderProtection {
+    /// protect encrypts the header extensions of a plaintext packet as configured and returns
+    /// the ranges of the packet the cipher has to encrypt. With cryptex the packet gets the
+    /// cryptex extension profile, and an empty extension if it only has CSRCs.
+    pub(crate) fn protect(
+        &self,
+        packet: &mut Vec<u8>,
+        header: &rtp::header::Header,
+        roc: u32,
+        cipher: &dyn Cipher,
+    ) -> Result<Vec<Range<usize>>> {
+        let csrc_end = csrc_end(packet);
+        let extension_end = extension_end(packet, packet.len())?;
+
+        if self.cryptex && (extension_end.is_some() || csrc_end > CSRC_OFFSET) {
+            if extension_end.is_some() {
+                let profile = match BigEndian::read_u16(&packet[csrc_end..]) {
+                    EXTENSION_PROFILE_ONE_BYTE => CRYPTEX_PROFILE_ONE_BYTE,
+                    EXTENSION_PROFILE_TWO_BYTE => CRYPTEX_PROFILE_TWO_BYTE,
+                    profile => return Err(Error::CryptexExtensionProfile(profile)),
+                };
+                BigEndian::write_u16(&mut packet[csrc_end..], profile);
+            } else {
+                // Without header extensions an empty one tells the receiver the CSRCs are encrypted.
+                let mut empty = [0u8; EXTENSION_HEADER_LENGTH];
+                BigEndian::write_u16(&mut empty, CRYPTEX_PROFILE_ONE_BYTE);
+                packet[0] |= 1 << EXTENSION_SHIFT;
+                packet.splice(csrc_end..csrc_end, empty.iter().copied());
+            }
+
+            return Ok(cryptex_ranges(csrc_end, packet.len()));
+        }
+
+        if let Some(extension_end) = extension_end {
+            self.apply_extension_keystream(
+                &mut packet[csrc_end..extension_end],
+                header,
+                roc,
+                cipher,
+            )?;
+            Ok(vec![extension_end..packet.len()])
+        } else {
+            Ok(vec![csrc_end..packet.len()])
+        }
+    }
+
+    /// encrypted_ranges returns the ranges of a received packet that the cipher has to decrypt,
+    /// body_end is where the auth tag starts.
+    pub(crate) fn encrypted_ranges(
+        &self,
+        packet: &[u8],
+        body_end: usize,
+    ) -> Result<Vec<Range<usize>>> {
+        let csrc_end = csrc_end(packet);
+
+        match extension_end(packet, body_end)? {
+            Some(extension_end) => {
+                if is_cryptex(BigEndian::read_u16(&packet[csrc_end..])) {
+                    if !self.cryptex {
+                        return Err(Error::ErrCryptexNotEnabled);
+                    }
+                    Ok(cryptex_ranges(csrc_end, body_end))
+                } else {
+                    Ok(vec![extension_end..body_end])
+                }
+            }
+            None => Ok(vec![csrc_end..body_end]),
+        }
+    }
+
+    /// unprotect decrypts the header extensions of a packet the cipher has decrypted,
+    /// and gives cryptex packets their regular extension profile back.
+    pub(crate) fn unprotect(
+        &self,
+        decrypted: Bytes,
+        header: &rtp::header::Header,
+        roc: u32,
+        cipher: &dyn Cipher,
+    ) -> Result<Bytes> {
+        let csrc_end = csrc_end(&decrypted);
+        let extension_end = match extension_end(&decrypted, decrypted.len())? {
+            Some(extension_end) => extension_end,
+            None => return Ok(decrypted),
+        };
+
+        let profile = match BigEndian::read_u16(&decrypted[csrc_end..]) {
+            CRYPTEX_PROFILE_ONE_BYTE => EXTENSION_PROFILE_ONE_BYTE,
+            CRYPTEX_PROFILE_TWO_BYTE => EXTENSION_PROFILE_TWO_BYTE,
+            _ if self.encrypted_extensions.is_empty() => return Ok(decrypted),
+            _ => {
+                let mut writer = BytesMut::from(&decrypted[..]);
+                self.apply_extension_keystream(
+                    &mut writer[csrc_end..extension_end],
+                    header,
+                    roc,
+                    cipher,
+                )?;
+                return Ok(writer.freeze());
+            }
+        };
+
+        let mut writer = BytesMut::from(&decrypted[..]);
+        BigEndian::write_u16(&mut writer[csrc_end..], profile);
+        Ok(writer.freeze())
+    }
+
+    /// Encrypts, or decrypts, the data of the header extensions in encrypted_extensions.
+    /// The keystream covers the extension data as a whole, so each element uses the part
+    /// at its own offset. https://tools.ietf.org/html/rfc6904#section-4.1
+    fn apply_extension_keystream(
+        &self,
+        extension: &mut [u8],
+        header: &rtp::header::Header,
+        roc: u32,
+        cipher: &dyn Cipher,
+    ) -> Result<()> {
+        if self.encrypted_extensions.is_empty() {
+            return Ok(());
+        }
+
+        let profile = BigEndian::read_u16(extension);
+        let data = &mut extension[EXTENSION_HEADER_LENGTH..];
+        let elements = encrypted_elements(profile, data, &self.encrypted_extensions)?;
+        if elements.is_empty() {
+            return Ok(());
+        }
+
+        let keystream = cipher.rtp_header_extension_keystream(header, roc, data.len())?;
+        for element in elements {
+            for i in element {
+                data[i] ^= keystream[i];
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_cryptex(profile: u16) -> bool {
+    profile == CRYPTEX_PROFILE_ONE_BYTE || profile == CRYPTEX_PROFILE_TWO_BYTE
+}
+
+fn csrc_end(packet: &[u8]) -> usize {
+    CSRC_OFFSET + (packet[0] & CC_MASK) as usize * CSRC_LENGTH
+}
+
+/// Returns where the header extension of packet ends, if it has one. end limits how far it may go.
+fn extension_end(packet: &[u8], end: usize) -> Result<Option<usize>> {
+    if (packet[0] >> EXTENSION_SHIFT) & EXTENSION_MASK == 0 {
+        return Ok(None);
+    }
+
+    let extension_start = csrc_end(packet);
+    if extension_start + EXTENSION_HEADER_LENGTH > end {
+        return Err(Error::ErrHeaderExtensionTooShort);
+    }
+    let extension_len = BigEndian::read_u16(&packet[extension_start + 2..]) as usize * 4;
+    let extension_end = extension_start + EXTENSION_HEADER_LENGTH + extension_len;
+    if extension_end > end {
+        return Err(Error::ErrHeaderExtensionTooShort);
+    }
+
+    Ok(Some(extension_end))
+}
+
+/// With cryptex the CSRCs, the header extension data and the payload are encrypted as one,
+/// skipping over the header extension header.
+fn cryptex_ranges(csrc_end: usize, end: usize) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    if csrc_end > CSRC_OFFSET {
+        ranges.push(CSRC_OFFSET..csrc_end);
+    }
+    ranges.push(csrc_end + EXTENSION_HEADER_LENGTH..end);
+    ranges
+}
+
+/// Returns the ranges of data holding the values of the header extensions in ids.
+fn encrypted_elements(profile: u16, data: &[u8], ids: &[u8]) -> Result<Vec<Range<usize>>> {
+    let mut elements = vec![];
+    let mut i = 0;
+
+    while i < data.len() {
+        // Padding
+        if data[i] == 0 {
+            i += 1;
+            continue;
+        }
+
+        let (id, start, len) = match profile {
+            EXTENSION_PROFILE_ONE_BYTE => {
+                let id = data[i] >> 4;
+                if id == EXTENSION_ID_RESERVED {
+                    break;
+                }
+                (id, i + 1, (data[i] & 0xF) as usize + 1)
+            }
+            EXTENSION_PROFILE_TWO_BYTE => {
+                if i + 1 >= data.len() {
+                    return Err(Error::ErrHeaderExtensionTooShort);
+                }
+                (data[i], i + 2, data[i + 1] as usize)
+            }
+            // Other profiles aren't made of elements with IDs.
+            _ => break,
+        };
+
+        let end = start + len;
+        if end > data.len() {
+            return Err(Error::ErrHeaderExtensionTooShort);
+        }
+        if ids.contains(&id) {
+            elements.push(start..end);
+        }
+        i = end;
+    }
+
+    Ok(elements)
+}
@@ -0,0 +1,216 @@
+use super::*;
+use util::marshal::*;
+
+use bytes::Bytes;
+
+const PROFILES: &[ProtectionProfile] = &[
+    ProtectionProfile::Aes128CmHmacSha1_80,
+    ProtectionProfile::Aes128CmHmacSha1_32,
+    ProtectionProfile::Aes256CmHmacSha1_80,
+    ProtectionProfile::AeadAes128Gcm,
+    ProtectionProfile::AeadAes256Gcm,
+];
+
+fn contexts(profile: ProtectionProfile) -> Result<(Context, Context)> {
+    let master_key = vec![0x11; profile.key_len()];
+    let master_salt = vec![0x22; profile.salt_len()];
+    Ok((
+        Context::new(&master_key, &master_salt, profile, None, None)?,
+        Context::new(&master_key, &master_salt, profile, None, None)?,
+    ))
+}
+
+fn packet(csrc: Vec<u32>, extensions: &[(u8, &'static [u8])]) -> Result<Bytes> {
+    let mut pkt = rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            sequence_number: 5000,
+            ssrc: 0xcafebabe,
+            csrc,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef, 0x01, 0x02]),
+    };
+    for (id, payload) in extensions {
+        pkt.header
+            .set_extension(*id, Bytes::from_static(payload))
+            .map_err(|err| Error::Other(err.to_string()))?;
+    }
+    Ok(pkt.marshal()?)
+}
+
+fn extension_payload(raw: &[u8], id: u8) -> Result<Option<Bytes>> {
+    let header = rtp::header::Header::unmarshal(&mut &raw[..])?;
+    Ok(header.get_extension(id))
+}
+
+#[test]
+fn test_encrypted_header_extensions() -> Result<()> {
+    for profile in PROFILES {
+        let (mut encrypt_ctx, mut decrypt_ctx) = contexts(*profile)?;
+        encrypt_ctx.set_encrypted_header_extensions(&[1]);
+        decrypt_ctx.set_encrypted_header_extensions(&[1]);
+
+        let decrypted = packet(vec![], &[(1, &[0x7f]), (2, &[0x01, 0x02, 0x03])])?;
+        let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+
+        assert_ne!(
+            extension_payload(&encrypted, 1)?,
+            Some(Bytes::from_static(&[0x7f])),
+            "{:?}: extension 1 must be encrypted",
+            profile
+        );
+        assert_eq!(
+            extension_payload(&encrypted, 2)?,
+            Some(Bytes::from_static(&[0x01, 0x02, 0x03])),
+            "{:?}: extension 2 must stay in clear",
+            profile
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtp(&encrypted)?,
+            decrypted,
+            "{:?}: round trip",
+            profile
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_encrypted_header_extensions_two_byte() -> Result<()> {
+    let (mut encrypt_ctx, mut decrypt_ctx) = contexts(ProtectionProfile::Aes128CmHmacSha1_80)?;
+    encrypt_ctx.set_encrypted_header_extensions(&[20]);
+    decrypt_ctx.set_encrypted_header_extensions(&[20]);
+
+    let long: &'static [u8] = &[0xab; 20];
+    let decrypted = packet(vec![], &[(20, long), (21, &[0x01])])?;
+    let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+
+    assert_ne!(
+        extension_payload(&encrypted, 20)?,
+        Some(Bytes::from_static(long))
+    );
+    assert_eq!(
+        extension_payload(&encrypted, 21)?,
+        Some(Bytes::from_static(&[0x01]))
+    );
+    assert_eq!(decrypt_ctx.decrypt_rtp(&encrypted)?, decrypted);
+
+    Ok(())
+}
+
+#[test]
+fn test_encrypted_header_extensions_not_negotiated() -> Result<()> {
+    let (mut encrypt_ctx, mut decrypt_ctx) = contexts(ProtectionProfile::Aes128CmHmacSha1_80)?;
+    encrypt_ctx.set_encrypted_header_extensions(&[1]);
+
+    let decrypted = packet(vec![], &[(1, &[0x7f])])?;
+    let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+
+    // The packet still authenticates, but the extension stays encrypted.
+    let received = decrypt_ctx.decrypt_rtp(&encrypted)?;
+    assert_eq!(received.len(), decrypted.len());
+    assert_ne!(
+        extension_payload(&received, 1)?,
+        Some(Bytes::from_static(&[0x7f]))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cryptex() -> Result<()> {
+    for profile in PROFILES {
+        let (mut encrypt_ctx, mut decrypt_ctx) = contexts(*profile)?;
+        encrypt_ctx.set_cryptex(true);
+        decrypt_ctx.set_cryptex(true);
+
+        let decrypted = packet(vec![0x01020304, 0x05060708], &[(1, &[0x7f])])?;
+        let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+
+        let csrc_end = rtp::header::CSRC_OFFSET + 2 * rtp::header::CSRC_LENGTH;
+        assert_eq!(
+            &encrypted[..rtp::header::CSRC_OFFSET],
+            &decrypted[..rtp::header::CSRC_OFFSET]
+        );
+        assert_ne!(
+            &encrypted[rtp::header::CSRC_OFFSET..csrc_end],
+            &decrypted[rtp::header::CSRC_OFFSET..csrc_end],
+            "{:?}: CSRCs must be encrypted",
+            profile
+        );
+        assert_eq!(
+            &encrypted[csrc_end..csrc_end + 4],
+            &[0xC0, 0xDE, 0x00, 0x01],
+            "{:?}: wrong cryptex extension header",
+            profile
+        );
+        assert_ne!(
+            &encrypted[csrc_end + 4..csrc_end + 8],
+            &decrypted[csrc_end + 4..csrc_end + 8],
+            "{:?}: extension data must be encrypted",
+            profile
+        );
+        assert_eq!(
+            decrypt_ctx.decrypt_rtp(&encrypted)?,
+            decrypted,
+            "{:?}: round trip",
+            profile
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cryptex_csrc_only() -> Result<()> {
+    let (mut encrypt_ctx, mut decrypt_ctx) = contexts(ProtectionProfile::AeadAes128Gcm)?;
+    encrypt_ctx.set_cryptex(true);
+    decrypt_ctx.set_cryptex(true);
+
+    let decrypted = packet(vec![0x01020304], &[])?;
+    let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+
+    // An empty header extension is added to flag cryptex.
+    assert_eq!(encrypted[0] & 0x10, 0x10);
+    assert_eq!(&encrypted[16..20], &[0xC0, 0xDE, 0x00, 0x00]);
+
+    let received = decrypt_ctx.decrypt_rtp(&encrypted)?;
+    let mut expected = packet(vec![0x01020304], &[])?.to_vec();
+    expected[0] |= 0x10;
+    expected.splice(16..16, [0xBE, 0xDE, 0x00, 0x00].iter().copied());
+    assert_eq!(&received[..], &expected[..]);
+
+    Ok(())
+}
+
+#[test]
+fn test_cryptex_not_negotiated() -> Result<()> {
+    let (mut encrypt_ctx, mut decrypt_ctx) = contexts(ProtectionProfile::Aes128CmHmacSha1_80)?;
+    encrypt_ctx.set_cryptex(true);
+
+    let encrypted = encrypt_ctx.encrypt_rtp(&packet(vec![], &[(1, &[0x7f])])?)?;
+    assert_eq!(
+        decrypt_ctx.decrypt_rtp(&encrypted),
+        Err(Error::ErrCryptexNotEnabled)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cryptex_without_header_extensions() -> Result<()> {
+    let (mut encrypt_ctx, mut decrypt_ctx) = contexts(ProtectionProfile::Aes128CmHmacSha1_80)?;
+    encrypt_ctx.set_cryptex(true);
+    decrypt_ctx.set_cryptex(true);
+
+    // Nothing to hide in the header, so the packet looks like a regular one.
+    let decrypted = packet(vec![], &[])?;
+    let (mut plain_ctx, _) = contexts(ProtectionProfile::Aes128CmHmacSha1_80)?;
+    let encrypted = encrypt_ctx.encrypt_rtp(&decrypted)?;
+    assert_eq!(encrypted, plain_ctx.encrypt_rtp(&decrypted)?);
+    assert_eq!(decrypt_ctx.decrypt_rtp(&encrypted)?, decrypted);
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod context_test;
 #[cfg(test)]
+mod header_protection_test;
+#[cfg(test)]
 mod srtcp_test;
 #[cfg(test)]
 mod srtp_test;
@@ -11,9 +13,14 @@ use crate::{
     option::*, protection_profile::*,
 };
 
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use header_protection::HeaderProtection;
+use bytes::{Bytes, BytesMut};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use util::replay_detector::*;
 
+pub mod header_protection;
 pub mod srtcp;
 pub mod srtp;
 
@@ -99,8 +106,19 @@ impl SrtpSsrcState {
 /// Context represents a SRTP cryptographic context
 /// Context can only be used for one-way operations
 /// it must either used ONLY for encryption or ONLY for decryption
+///
+/// A Context created with an MKI (Master Key Identifier) can hold several master keys,
+/// each tagged by its own MKI. Packets are encrypted with the key of the send MKI, which
+/// is carried in every packet so the receiver can pick the matching key.
+/// https://tools.ietf.org/html/rfc3711#section-3.1
 pub struct Context {
-    cipher: Box<dyn Cipher>,
+    profile: ProtectionProfile,
+    // One cipher per master key, by MKI. Without MKI there is just one, under the empty MKI.
+    ciphers: HashMap<Vec<u8>, Box<dyn Cipher>>,
+    // Length of every MKI of the context, 0 without MKI.
+    mki_len: usize,
+    send_mki: Vec<u8>,
+    header_protection: HeaderProtection,
 
     srtp_ssrc_states: HashMap<u32, SrtpSsrcState>,
     srtcp_ssrc_states: HashMap<u32, SrtcpSsrcState>,
@@ -118,24 +136,29 @@ impl Context {
         srtp_ctx_opt: Option<ContextOption>,
         srtcp_ctx_opt: Option<ContextOption>,
     ) -> Result<Context> {
-        let key_len = profile.key_len();
-        let salt_len = profile.salt_len();
-
-        if master_key.len() != key_len {
-            return Err(Error::SrtpMasterKeyLength(key_len, master_key.len()));
-        } else if master_salt.len() != salt_len {
-            return Err(Error::SrtpSaltLength(salt_len, master_salt.len()));
-        }
-
-        let cipher: Box<dyn Cipher> = match profile {
-            ProtectionProfile::Aes128CmHmacSha1_80 => {
-                Box::new(CipherAesCmHmacSha1::new(master_key, master_salt)?)
-            }
+        Context::new_with_mki(
+            master_key,
+            master_salt,
+            &[],
+            profile,
+            srtp_ctx_opt,
+            srtcp_ctx_opt,
+        )
+    }
 
-            ProtectionProfile::AeadAes128Gcm => {
-                Box::new(CipherAeadAesGcm::new(master_key, master_salt)?)
-            }
-        };
+    /// new_with_mki creates a new SRTP Context whose packets carry an MKI, starting with
+    /// master_key as the key for mki. An empty mki is the same as Context::new.
+    pub fn new_with_mki(
+        master_key: &[u8],
+        master_salt: &[u8],
+        mki: &[u8],
+        profile: ProtectionProfile,
+        srtp_ctx_opt: Option<ContextOption>,
+        srtcp_ctx_opt: Option<ContextOption>,
+    ) -> Result<Context> {
+        let cipher = Context::new_cipher(master_key, master_salt, profile)?;
+        let mut ciphers = HashMap::new();
+        ciphers.insert(mki.to_vec(), cipher);
 
         let srtp_ctx_opt = if let Some(ctx_opt) = srtp_ctx_opt {
             ctx_opt
@@ -150,7 +173,11 @@ impl Context {
         };
 
         Ok(Context {
-            cipher,
+            profile,
+            ciphers,
+            mki_len: mki.len(),
+            send_mki: mki.to_vec(),
+            header_protection: HeaderProtection::default(),
             srtp_ssrc_states: HashMap::new(),
             srtcp_ssrc_states: HashMap::new(),
             new_srtp_replay_detector: srtp_ctx_opt,
@@ -158,6 +185,172 @@ impl Context {
         })
     }
 
+    fn new_cipher(
+        master_key: &[u8],
+        master_salt: &[u8],
+        profile: ProtectionProfile,
+    ) -> Result<Box<dyn Cipher>> {
+        let key_len = profile.key_len();
+        let salt_len = profile.salt_len();
+
+        if master_key.len() != key_len {
+            return Err(Error::SrtpMasterKeyLength(key_len, master_key.len()));
+        } else if master_salt.len() != salt_len {
+            return Err(Error::SrtpSaltLength(salt_len, master_salt.len()));
+        }
+
+        let cipher: Box<dyn Cipher> = match profile {
+            ProtectionProfile::Aes128CmHmacSha1_80
+            | ProtectionProfile::Aes128CmHmacSha1_32
+            | ProtectionProfile::Aes256CmHmacSha1_80 => {
+                Box::new(CipherAesCmHmacSha1::new(profile, master_key, master_salt)?)
+            }
+
+            ProtectionProfile::AeadAes128Gcm => {
+                Box::new(CipherAeadAesGcm::<Aes128Gcm>::new(master_key, master_salt)?)
+            }
+            ProtectionProfile::AeadAes256Gcm => {
+                Box::new(CipherAeadAesGcm::<Aes256Gcm>::new(master_key, master_salt)?)
+            }
+        };
+
+        Ok(cipher)
+    }
+
+    /// add_cipher_for_mki adds a master key, used for packets carrying mki.
+    /// The context must have been created with an MKI of the same length.
+    pub fn add_cipher_for_mki(
+        &mut self,
+        mki: &[u8],
+        master_key: &[u8],
+        master_salt: &[u8],
+    ) -> Result<()> {
+        if self.mki_len == 0 {
+            return Err(Error::ErrMkiNotEnabled);
+        } else if mki.len() != self.mki_len {
+            return Err(Error::MkiLength(self.mki_len, mki.len()));
+        } else if self.ciphers.contains_key(mki) {
+            return Err(Error::ErrMkiAlreadyInUse);
+        }
+
+        let cipher = Context::new_cipher(master_key, master_salt, self.profile)?;
+        self.ciphers.insert(mki.to_vec(), cipher);
+
+        Ok(())
+    }
+
+    /// set_send_mki switches encryption to the master key of mki.
+    pub fn set_send_mki(&mut self, mki: &[u8]) -> Result<()> {
+        if !self.ciphers.contains_key(mki) {
+            return Err(Error::ErrMkiNotFound);
+        }
+        self.send_mki = mki.to_vec();
+
+        Ok(())
+    }
+
+    /// remove_mki removes the master key of mki, packets carrying it fail to decrypt
+    /// afterwards. The key used for sending can't be removed.
+    pub fn remove_mki(&mut self, mki: &[u8]) -> Result<()> {
+        if mki == self.send_mki.as_slice() {
+            return Err(Error::ErrMkiInUse);
+        }
+        if self.ciphers.remove(mki).is_none() {
+            return Err(Error::ErrMkiNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// remove_decryption_mki removes the master key of mki from a context only used for
+    /// decryption. Any key can go, including the one the context was created with, as long
+    /// as another one is left.
+    pub fn remove_decryption_mki(&mut self, mki: &[u8]) -> Result<()> {
+        if !self.ciphers.contains_key(mki) {
+            return Err(Error::ErrMkiNotFound);
+        } else if self.ciphers.len() == 1 {
+            return Err(Error::ErrMkiLastKey);
+        }
+        self.ciphers.remove(mki);
+
+        Ok(())
+    }
+
+    /// set_encrypted_header_extensions sets the IDs of the RTP header extensions that are
+    /// encrypted. https://tools.ietf.org/html/rfc6904
+    pub fn set_encrypted_header_extensions(&mut self, ids: &[u8]) {
+        self.header_protection.encrypted_extensions = ids.to_vec();
+    }
+
+    /// set_cryptex turns on cryptex, encrypting the CSRCs and all header extensions of sent
+    /// packets and accepting received packets protected that way.
+    /// https://www.rfc-editor.org/rfc/rfc9335
+    pub fn set_cryptex(&mut self, cryptex: bool) {
+        self.header_protection.cryptex = cryptex;
+    }
+
+    /// send_mki returns the MKI of the master key used for sending.
+    pub fn send_mki(&self) -> &[u8] {
+        &self.send_mki
+    }
+
+    fn send_cipher(&mut self) -> Result<&mut Box<dyn Cipher>> {
+        self.ciphers
+            .get_mut(&self.send_mki)
+            .ok_or(Error::ErrMkiNotFound)
+    }
+
+    fn cipher_for_mki(&mut self, mki: &[u8]) -> Result<&mut Box<dyn Cipher>> {
+        self.ciphers.get_mut(mki).ok_or(Error::ErrMkiNotFound)
+    }
+
+    /// The MKI sits right before the authentication tag, which AEAD profiles don't have
+    /// at the end since it is part of their ciphertext.
+    /// https://tools.ietf.org/html/rfc7714#section-8.1
+    fn auth_tag_len_after_mki(&self, is_rtp: bool) -> usize {
+        match self.profile {
+            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => 0,
+            _ if is_rtp => self.profile.auth_tag_len(),
+            _ => self.profile.rtcp_auth_tag_len(),
+        }
+    }
+
+    /// insert_mki puts the send MKI into an encrypted packet.
+    fn insert_mki(&self, packet: Bytes, is_rtp: bool) -> Bytes {
+        if self.send_mki.is_empty() {
+            return packet;
+        }
+
+        let mki_offset = packet.len() - self.auth_tag_len_after_mki(is_rtp);
+        let mut writer = BytesMut::with_capacity(packet.len() + self.send_mki.len());
+        writer.extend_from_slice(&packet[..mki_offset]);
+        writer.extend_from_slice(&self.send_mki);
+        writer.extend_from_slice(&packet[mki_offset..]);
+        writer.freeze()
+    }
+
+    /// split_mki takes the MKI out of an encrypted packet, returning the packet without it
+    /// and the MKI.
+    fn split_mki<'a>(&self, packet: &'a [u8], is_rtp: bool) -> Result<(Cow<'a, [u8]>, &'a [u8])> {
+        let mki_len = self.mki_len;
+        if mki_len == 0 {
+            return Ok((Cow::Borrowed(packet), &[]));
+        }
+
+        let tail_len = mki_len + self.auth_tag_len_after_mki(is_rtp);
+        if packet.len() < tail_len {
+            return Err(Error::ErrTooShortMki);
+        }
+
+        let mki_offset = packet.len() - tail_len;
+        let mut stripped = packet[..mki_offset].to_vec();
+        stripped.extend_from_slice(&packet[mki_offset + mki_len..]);
+        Ok((
+            Cow::Owned(stripped),
+            &packet[mki_offset..mki_offset + mki_len],
+        ))
+    }
+
     fn get_srtp_ssrc_state(&mut self, ssrc: u32) -> Option<&mut SrtpSsrcState> {
         let s = SrtpSsrcState {
             ssrc,
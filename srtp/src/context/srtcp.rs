@@ -10,7 +10,8 @@ impl Context {
         let mut buf = encrypted;
         rtcp::header::Header::unmarshal(&mut buf)?;
 
-        let index = self.cipher.get_rtcp_index(encrypted);
+        let (encrypted, mki) = self.split_mki(encrypted, false)?;
+        let index = self.cipher_for_mki(mki)?.get_rtcp_index(&encrypted);
         let ssrc = u32::from_be_bytes([encrypted[4], encrypted[5], encrypted[6], encrypted[7]]);
 
         {
@@ -25,7 +26,9 @@ impl Context {
             }
         }
 
-        let dst = self.cipher.decrypt_rtcp(encrypted, index, ssrc)?;
+        let dst = self
+            .cipher_for_mki(mki)?
+            .decrypt_rtcp(&encrypted, index, ssrc)?;
 
         {
             if let Some(state) = self.get_srtcp_ssrc_state(ssrc) {
@@ -59,6 +62,7 @@ impl Context {
             }
         }
 
-        self.cipher.encrypt_rtcp(decrypted, index, ssrc)
+        let dst = self.send_cipher()?.encrypt_rtcp(decrypted, index, ssrc)?;
+        Ok(self.insert_mki(dst, false))
     }
 }
@@ -28,7 +28,19 @@ impl Context {
             }
         }
 
-        let dst = self.cipher.decrypt_rtp(encrypted, header, roc)?;
+        let (encrypted, mki) = self.split_mki(encrypted, true)?;
+        let cipher = self.ciphers.get_mut(mki).ok_or(Error::ErrMkiNotFound)?;
+        let auth_tag_len = cipher.auth_tag_len();
+        if encrypted.len() < auth_tag_len {
+            return Err(Error::SrtpTooSmall(encrypted.len(), auth_tag_len));
+        }
+        let ranges = self
+            .header_protection
+            .encrypted_ranges(&encrypted, encrypted.len() - auth_tag_len)?;
+        let dst = cipher.decrypt_rtp(&encrypted, &ranges, header, roc)?;
+        let dst = self
+            .header_protection
+            .unprotect(dst, header, roc, cipher.as_ref())?;
         {
             if let Some(state) = self.get_srtp_ssrc_state(header.ssrc) {
                 if let Some(replay_detector) = &mut state.replay_detector {
@@ -62,9 +74,18 @@ impl Context {
             }
         }
 
-        let dst = self
-            .cipher
-            .encrypt_rtp(&plaintext[header.marshal_size()..], header, roc)?;
+        let mut packet = header.marshal()?.to_vec();
+        packet.extend_from_slice(&plaintext[header.marshal_size()..]);
+
+        let cipher = self
+            .ciphers
+            .get_mut(&self.send_mki)
+            .ok_or(Error::ErrMkiNotFound)?;
+        let ranges = self
+            .header_protection
+            .protect(&mut packet, header, roc, cipher.as_ref())?;
+        let dst = cipher.encrypt_rtp(&packet, &ranges, header, roc)?;
+        let dst = self.insert_mki(dst, true);
 
         {
             if let Some(state) = self.get_srtp_ssrc_state(header.ssrc) {
@@ -39,6 +39,22 @@ pub enum Error {
     ErrStreamAlreadyInited,
     #[error("failed to cast child")]
     ErrFailedTypeAssertion,
+    #[error("MKI is not enabled for this context")]
+    ErrMkiNotEnabled,
+    #[error("MKI already has a master key")]
+    ErrMkiAlreadyInUse,
+    #[error("no master key for MKI")]
+    ErrMkiNotFound,
+    #[error("master key of MKI is used for sending")]
+    ErrMkiInUse,
+    #[error("the last master key can't be removed")]
+    ErrMkiLastKey,
+    #[error("packet is too short to carry the MKI")]
+    ErrTooShortMki,
+    #[error("RTP header extension runs past the end of the packet")]
+    ErrHeaderExtensionTooShort,
+    #[error("got a cryptex packet, but cryptex is not enabled")]
+    ErrCryptexNotEnabled,
 
     #[error("index_over_kdr > 0 is not supported yet")]
     UnsupportedIndexOverKdr,
@@ -46,6 +62,12 @@ pub enum Error {
     SrtpMasterKeyLength(usize, usize),
     #[error("SRTP Salt must be len {0}, got {1}")]
     SrtpSaltLength(usize, usize),
+    #[error("SRTP Master Key of len {0} is not supported")]
+    UnsupportedMasterKeyLength(usize),
+    #[error("MKI must be len {0}, got {1}")]
+    MkiLength(usize, usize),
+    #[error("cryptex can't protect header extension profile {0:#06x}")]
+    CryptexExtensionProfile(u16),
     #[error("SyntaxError: {0}")]
     ExtMapParse(String),
     #[error("ssrc {0} not exist in srtp_ssrc_state")]
@@ -1,9 +1,14 @@
 /// ProtectionProfile specifies Cipher and AuthTag details, similar to TLS cipher suite
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ProtectionProfile {
     Aes128CmHmacSha1_80 = 0x0001,
+    Aes128CmHmacSha1_32 = 0x0002,
+    /// AES_256_CM_HMAC_SHA1_80 of RFC 6188. It has no DTLS-SRTP profile assigned, so it is only
+    /// usable with keys exchanged by other means such as SDES.
+    Aes256CmHmacSha1_80 = 0x0003,
     AeadAes128Gcm = 0x0007,
+    AeadAes256Gcm = 0x0008,
 }
 
 impl Default for ProtectionProfile {
@@ -15,28 +20,47 @@ impl Default for ProtectionProfile {
 impl ProtectionProfile {
     pub(crate) fn key_len(&self) -> usize {
         match *self {
-            ProtectionProfile::Aes128CmHmacSha1_80 | ProtectionProfile::AeadAes128Gcm => 16,
+            ProtectionProfile::Aes128CmHmacSha1_80
+            | ProtectionProfile::Aes128CmHmacSha1_32
+            | ProtectionProfile::AeadAes128Gcm => 16,
+            ProtectionProfile::Aes256CmHmacSha1_80 | ProtectionProfile::AeadAes256Gcm => 32,
         }
     }
 
     pub(crate) fn salt_len(&self) -> usize {
         match *self {
-            ProtectionProfile::Aes128CmHmacSha1_80 => 14,
-            ProtectionProfile::AeadAes128Gcm => 12,
+            ProtectionProfile::Aes128CmHmacSha1_80
+            | ProtectionProfile::Aes128CmHmacSha1_32
+            | ProtectionProfile::Aes256CmHmacSha1_80 => 14,
+            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => 12,
         }
     }
 
     pub(crate) fn auth_tag_len(&self) -> usize {
         match *self {
-            ProtectionProfile::Aes128CmHmacSha1_80 => 10, //CIPHER_AES_CM_HMAC_SHA1AUTH_TAG_LEN,
-            ProtectionProfile::AeadAes128Gcm => 16,       //CIPHER_AEAD_AES_GCM_AUTH_TAG_LEN,
+            //CIPHER_AES_CM_HMAC_SHA1AUTH_TAG_LEN,
+            ProtectionProfile::Aes128CmHmacSha1_80 | ProtectionProfile::Aes256CmHmacSha1_80 => 10,
+            ProtectionProfile::Aes128CmHmacSha1_32 => 4,
+            //CIPHER_AEAD_AES_GCM_AUTH_TAG_LEN,
+            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => 16,
+        }
+    }
+
+    /// SRTCP keeps the 80-bit tag even for the _32 profile
+    /// https://tools.ietf.org/html/rfc5764#section-4.1.2
+    pub(crate) fn rtcp_auth_tag_len(&self) -> usize {
+        match *self {
+            ProtectionProfile::Aes128CmHmacSha1_32 => 10,
+            _ => self.auth_tag_len(),
         }
     }
 
     pub(crate) fn auth_key_len(&self) -> usize {
         match *self {
-            ProtectionProfile::Aes128CmHmacSha1_80 => 20,
-            ProtectionProfile::AeadAes128Gcm => 0,
+            ProtectionProfile::Aes128CmHmacSha1_80
+            | ProtectionProfile::Aes128CmHmacSha1_32
+            | ProtectionProfile::Aes256CmHmacSha1_80 => 20,
+            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => 0,
         }
     }
 }
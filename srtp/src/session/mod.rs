@@ -29,6 +29,7 @@ const DEFAULT_SESSION_SRTCP_REPLAY_PROTECTION_WINDOW: usize = 64;
 /// instead of making everyone re-implement
 pub struct Session {
     local_context: Arc<Mutex<Context>>,
+    remote_context: Arc<Mutex<Context>>,
     streams_map: Arc<Mutex<HashMap<u32, Arc<Stream>>>>,
     new_stream_rx: Arc<Mutex<mpsc::Receiver<Arc<Stream>>>>,
     close_stream_tx: mpsc::Sender<u32>,
@@ -43,17 +44,19 @@ impl Session {
         config: Config,
         is_rtp: bool,
     ) -> Result<Self> {
-        let local_context = Context::new(
+        let mut local_context = Context::new_with_mki(
             &config.keys.local_master_key,
             &config.keys.local_master_salt,
+            &config.keys.local_mki,
             config.profile,
             config.local_rtp_options,
             config.local_rtcp_options,
         )?;
 
-        let mut remote_context = Context::new(
+        let mut remote_context = Context::new_with_mki(
             &config.keys.remote_master_key,
             &config.keys.remote_master_salt,
+            &config.keys.remote_mki,
             config.profile,
             if config.remote_rtp_options.is_none() {
                 Some(srtp_replay_protection(
@@ -71,6 +74,11 @@ impl Session {
             },
         )?;
 
+        for context in [&mut local_context, &mut remote_context] {
+            context.set_encrypted_header_extensions(&config.encrypted_header_extensions);
+            context.set_cryptex(config.cryptex);
+        }
+
         let streams_map = Arc::new(Mutex::new(HashMap::new()));
         let (mut new_stream_tx, new_stream_rx) = mpsc::channel(8);
         let (close_stream_tx, mut close_stream_rx) = mpsc::channel(8);
@@ -78,9 +86,11 @@ impl Session {
         let udp_tx = Arc::clone(&conn);
         let udp_rx = Arc::clone(&conn);
         let cloned_streams_map = Arc::clone(&streams_map);
+        let remote_context = Arc::new(Mutex::new(remote_context));
+        let cloned_remote_context = Arc::clone(&remote_context);
         let cloned_close_stream_tx = close_stream_tx.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; 8192];
 
             loop {
@@ -90,7 +100,7 @@ impl Session {
                     &cloned_streams_map,
                     &cloned_close_stream_tx,
                     &mut new_stream_tx,
-                    &mut remote_context,
+                    &cloned_remote_context,
                     is_rtp,
                 );
                 let close_stream = close_stream_rx.recv();
@@ -111,6 +121,7 @@ impl Session {
 
         Ok(Session {
             local_context: Arc::new(Mutex::new(local_context)),
+            remote_context,
             streams_map,
             new_stream_rx: Arc::new(Mutex::new(new_stream_rx)),
             close_stream_tx,
@@ -131,7 +142,7 @@ impl Session {
         streams_map: &Arc<Mutex<HashMap<u32, Arc<Stream>>>>,
         close_stream_tx: &mpsc::Sender<u32>,
         new_stream_tx: &mut mpsc::Sender<Arc<Stream>>,
-        remote_context: &mut Context,
+        remote_context: &Arc<Mutex<Context>>,
         is_rtp: bool,
     ) -> Result<()> {
         let n = udp_rx.recv(buf).await?;
@@ -139,10 +150,13 @@ impl Session {
             return Err(Error::SessionEof);
         }
 
-        let decrypted = if is_rtp {
-            remote_context.decrypt_rtp(&buf[0..n])?
-        } else {
-            remote_context.decrypt_rtcp(&buf[0..n])?
+        let decrypted = {
+            let mut remote_context = remote_context.lock().await;
+            if is_rtp {
+                remote_context.decrypt_rtp(&buf[0..n])?
+            } else {
+                remote_context.decrypt_rtcp(&buf[0..n])?
+            }
         };
 
         let mut buf = &decrypted[..];
@@ -258,6 +272,62 @@ impl Session {
         let raw = pkt.marshal()?;
         self.write(&raw, false).await
     }
+
+    /// add_local_key adds a master key for mki to the keys packets can be sent with.
+    /// The session must have been created with a local MKI.
+    pub async fn add_local_key(
+        &self,
+        mki: &[u8],
+        master_key: &[u8],
+        master_salt: &[u8],
+    ) -> Result<()> {
+        let mut local_context = self.local_context.lock().await;
+        local_context.add_cipher_for_mki(mki, master_key, master_salt)
+    }
+
+    /// set_local_mki makes the session send with the master key of mki.
+    pub async fn set_local_mki(&self, mki: &[u8]) -> Result<()> {
+        let mut local_context = self.local_context.lock().await;
+        local_context.set_send_mki(mki)
+    }
+
+    /// rotate_local_key adds a master key for mki and sends with it from now on.
+    pub async fn rotate_local_key(
+        &self,
+        mki: &[u8],
+        master_key: &[u8],
+        master_salt: &[u8],
+    ) -> Result<()> {
+        let mut local_context = self.local_context.lock().await;
+        local_context.add_cipher_for_mki(mki, master_key, master_salt)?;
+        local_context.set_send_mki(mki)
+    }
+
+    /// remove_local_key drops the master key of mki, which must not be the one sent with.
+    pub async fn remove_local_key(&self, mki: &[u8]) -> Result<()> {
+        let mut local_context = self.local_context.lock().await;
+        local_context.remove_mki(mki)
+    }
+
+    /// add_remote_key accepts packets from the remote carrying mki, decrypting them with
+    /// master_key. The session must have been created with a remote MKI.
+    pub async fn add_remote_key(
+        &self,
+        mki: &[u8],
+        master_key: &[u8],
+        master_salt: &[u8],
+    ) -> Result<()> {
+        let mut remote_context = self.remote_context.lock().await;
+        remote_context.add_cipher_for_mki(mki, master_key, master_salt)
+    }
+
+    /// remove_remote_key stops accepting packets from the remote carrying mki.
+    /// Any remote key can be removed, including the one the session was created with,
+    /// as long as another one is left.
+    pub async fn remove_remote_key(&self, mki: &[u8]) -> Result<()> {
+        let mut remote_context = self.remote_context.lock().await;
+        remote_context.remove_decryption_mki(mki)
+    }
 }
 
 /// create a list of Destination SSRCs
@@ -29,6 +29,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -36,6 +37,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
 
         local_rtcp_options: None,
         remote_rtcp_options: None,
+        ..Default::default()
     };
 
     let cb = Config {
@@ -55,6 +57,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -62,6 +65,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
 
         local_rtcp_options: None,
         remote_rtcp_options: None,
+        ..Default::default()
     };
 
     let sa = Session::new(Arc::new(ua), ca, false).await?;
@@ -72,7 +76,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
 
 const TEST_SSRC: u32 = 5000;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtcp_accept() -> Result<()> {
     let (sa, sb) = build_session_srtcp_pair().await?;
 
@@ -110,7 +114,7 @@ async fn test_session_srtcp_accept() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtcp_listen() -> Result<()> {
     let (sa, sb) = build_session_srtcp_pair().await?;
 
@@ -166,7 +170,7 @@ async fn get_sender_ssrc(read_stream: &Arc<Stream>) -> Result<u32> {
     Ok(pli.sender_ssrc)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtcp_replay_protection() -> Result<()> {
     let (sa, sb) = build_session_srtcp_pair().await?;
 
@@ -197,7 +201,7 @@ async fn test_session_srtcp_replay_protection() -> Result<()> {
     let cloned_received_ssrc = Arc::clone(&received_ssrc);
     let count = expected_ssrc.len();
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut i = 0;
         while i < count {
             match get_sender_ssrc(&read_stream).await {
@@ -3,11 +3,9 @@ use crate::error::Result;
 use crate::protection_profile::*;
 
 use bytes::{Bytes, BytesMut};
+use deno_net::DatagramConn as UdpSocket;
 use std::{collections::HashMap, sync::Arc};
-use tokio::{
-    net::UdpSocket,
-    sync::{mpsc, Mutex},
-};
+use tokio::sync::{mpsc, Mutex};
 
 async fn build_session_srtp_pair() -> Result<(Session, Session)> {
     let ua = UdpSocket::bind("127.0.0.1:0").await?;
@@ -33,6 +31,7 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -40,6 +39,7 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
 
         local_rtcp_options: None,
         remote_rtcp_options: None,
+        ..Default::default()
     };
 
     let cb = Config {
@@ -59,6 +59,7 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -66,6 +67,7 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
 
         local_rtcp_options: None,
         remote_rtcp_options: None,
+        ..Default::default()
     };
 
     let sa = Session::new(Arc::new(ua), ca, true).await?;
@@ -77,7 +79,7 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
 const TEST_SSRC: u32 = 5000;
 const RTP_HEADER_SIZE: usize = 12;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtp_accept() -> Result<()> {
     let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
     let mut read_buffer = BytesMut::with_capacity(RTP_HEADER_SIZE + test_payload.len());
@@ -117,7 +119,7 @@ async fn test_session_srtp_accept() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtp_listen() -> Result<()> {
     let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
     let mut read_buffer = BytesMut::with_capacity(RTP_HEADER_SIZE + test_payload.len());
@@ -152,7 +154,7 @@ async fn test_session_srtp_listen() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtp_multi_ssrc() -> Result<()> {
     let ssrcs = vec![5000, 5001, 5002];
     let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
@@ -224,7 +226,7 @@ async fn payload_srtp(
     Ok(hdr.sequence_number)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_session_srtp_replay_protection() -> Result<()> {
     let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
 
@@ -268,7 +270,7 @@ async fn test_session_srtp_replay_protection() -> Result<()> {
     let cloned_received_sequence_number = Arc::clone(&received_sequence_number);
     let count = expected_sequence_number.len();
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut i = 0;
         while i < count {
             match payload_srtp(&read_stream, RTP_HEADER_SIZE, &test_payload).await {
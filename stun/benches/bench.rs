@@ -21,7 +21,7 @@ use stun::message::{
 use stun::textattrs::{Nonce, Realm, Software, Username};
 use stun::uattrs::UnknownAttributes;
 use stun::xoraddr::{xor_bytes, XorMappedAddress};
-use tokio::time::Instant;
+use deno_net::Instant;
 
 // AGENT_COLLECT_CAP is initial capacity for Agent.Collect slices,
 // sufficient to make function zero-alloc in most cases.
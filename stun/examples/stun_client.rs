@@ -7,8 +7,9 @@ use stun::Error;
 use clap::{App, Arg};
 use std::sync::Arc;
 use deno_net::DatagramConn as UdpSocket;
+use util::Conn;
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     let mut app = App::new("STUN Client")
         .version("0.1.0")
@@ -43,7 +44,11 @@ async fn main() -> Result<(), Error> {
     println!("Local address: {}", conn.local_addr()?);
 
     println!("Connecting to: {}", server);
-    conn.connect(server).await?;
+    let server_addr = deno_net::lookup_host(server)
+        .await?
+        .next()
+        .ok_or_else(|| Error::Other(format!("{} did not resolve", server)))?;
+    conn.connect(server_addr).await?;
 
     let mut client = ClientBuilder::new().with_conn(Arc::new(conn)).build()?;
 
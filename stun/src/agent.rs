@@ -9,7 +9,7 @@ use rand::Rng;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tokio::time::Instant;
+use deno_net::Instant;
 
 /// Handler handles state changes of transaction.
 /// Handler is called on transaction state change.
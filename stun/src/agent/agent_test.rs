@@ -4,7 +4,7 @@ use crate::error::*;
 use std::ops::Add;
 use std::time::Duration;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_process_in_transaction() -> Result<()> {
     let mut m = Message::new();
     let (handler_tx, mut handler_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -30,7 +30,7 @@ async fn test_agent_process_in_transaction() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_process() -> Result<()> {
     let mut m = Message::new();
     let (handler_tx, mut handler_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -119,7 +119,7 @@ fn test_agent_start() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_agent_stop() -> Result<()> {
     let (handler_tx, mut handler_rx) = tokio::sync::mpsc::unbounded_channel();
     let mut a = Agent::new(Some(Arc::new(handler_tx)));
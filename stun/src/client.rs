@@ -11,8 +11,9 @@ use std::collections::HashMap;
 use std::io::BufReader;
 use std::ops::Add;
 use std::sync::Arc;
+use deno_net::{Instant, Ticker};
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::{self, Duration, Instant};
 
 const DEFAULT_TIMEOUT_RATE: Duration = Duration::from_millis(5);
 const DEFAULT_RTO: Duration = Duration::from_millis(300);
@@ -45,8 +46,8 @@ impl Collector for TickerCollector {
         let (close_tx, mut close_rx) = mpsc::channel(1);
         self.close_tx = Some(close_tx);
 
-        wasm_bindgen_futures::spawn_local(async move {
-            let mut interval = time::interval(rate);
+        deno_net::spawn(async move {
+            let mut interval = deno_net::interval(rate);
 
             loop {
                 tokio::select! {
@@ -282,7 +283,7 @@ impl Client {
         mut t: HashMap<TransactionId, ClientTransaction>,
         max_attempts: u32,
     ) {
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             while let Some(event) = handler_rx.recv().await {
                 match event.event_type {
                     EventType::Close => {
@@ -408,7 +409,7 @@ impl Client {
         );
 
         let agent = Agent::new(Some(handler_tx));
-        wasm_bindgen_futures::spawn_local(async move { Agent::run(agent, client_agent_rx).await });
+        deno_net::spawn(async move { Agent::run(agent, client_agent_rx).await });
 
         if self.settings.collector.is_none() {
             self.settings.collector = Some(Box::new(TickerCollector::default()));
@@ -418,7 +419,7 @@ impl Client {
         }
 
         let conn_rx = Arc::clone(&conn);
-        wasm_bindgen_futures::spawn_local(
+        deno_net::spawn(
             async move { Client::read_until_closed(close_rx, conn_rx, client_agent_tx).await },
         );
 
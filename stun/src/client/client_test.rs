@@ -1,12 +1,15 @@
 use super::*;
 
 #[test]
-fn ensure_client_settings_is_send() {
-    let client = ClientSettings::default();
-
-    ensure_send(client);
+fn test_client_settings_default() {
+    // The stack is single threaded, so the settings are no longer required to be Send.
+    let settings = ClientSettings::default();
+
+    assert_eq!(settings.buffer_size, DEFAULT_MAX_BUFFER_SIZE);
+    assert_eq!(settings.max_attempts, DEFAULT_MAX_ATTEMPTS);
+    assert!(!settings.closed);
+    assert!(settings.collector.is_none());
+    assert!(settings.c.is_none());
 }
 
-fn ensure_send<T: Send>(_: T) {}
-
 //TODO: add more client tests
@@ -6,8 +6,9 @@ use crate::checks::*;
 use crate::error::*;
 use crate::message::*;
 
+use hmac::{Hmac, Mac};
 use md5::{Digest, Md5};
-use ring::hmac;
+use sha1::Sha1;
 use std::fmt;
 
 // separator for credentials.
@@ -23,8 +24,9 @@ pub(crate) const CREDENTIALS_SEP: &str = ":";
 pub struct MessageIntegrity(pub Vec<u8>);
 
 fn new_hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
-    let mac = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
-    hmac::sign(&mac, message).as_ref().to_vec()
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC takes keys of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
 }
 
 impl fmt::Display for MessageIntegrity {
@@ -9,7 +9,7 @@ use util::Conn;
 
 // RUST_LOG=trace cargo run --color=always --package turn --example turn_client_udp -- --host 0.0.0.0 --user user=pass --ping
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::init();
 
@@ -117,7 +117,7 @@ async fn main() -> Result<(), Error> {
 
 async fn do_ping_test(
     client: &Client,
-    relay_conn: impl Conn + std::marker::Send + std::marker::Sync + 'static,
+    relay_conn: impl Conn + 'static,
 ) -> Result<(), Error> {
     // Send BindingRequest to learn our external IP
     let mapped_addr = client.send_binding_request().await?;
@@ -137,7 +137,7 @@ async fn do_ping_test(
     let pinger_conn_rx = Arc::clone(&pinger_conn_tx);
 
     // Start read-loop on pingerConn
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             let (n, from) = match pinger_conn_rx.recv_from(&mut buf).await {
@@ -159,7 +159,7 @@ async fn do_ping_test(
     });
 
     // Start read-loop on relay_conn
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             let (n, from) = match relay_conn.recv_from(&mut buf).await {
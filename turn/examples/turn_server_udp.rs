@@ -12,6 +12,7 @@ use deno_net::DatagramConn as UdpSocket;
 use tokio::signal;
 use std::time::Duration;
 use util::vnet::net::*;
+use util::Conn;
 
 struct MyAuthHandler {
     cred_map: HashMap<String, Vec<u8>>,
@@ -41,7 +42,7 @@ impl AuthHandler for MyAuthHandler {
 
 // RUST_LOG=trace cargo run --color=always --package turn --example turn_server_udp -- --public-ip 0.0.0.0 --users user=pass
 
-#[tokio::main]
+#[tokio::main(flavor = "local")]
 async fn main() -> Result<(), Error> {
     env_logger::init();
 
@@ -156,7 +156,7 @@ impl Manager {
         let reservations = Arc::clone(&self.reservations);
         let reservation_token2 = reservation_token.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let sleep = deno_net::sleep(Duration::from_secs(30));
             tokio::pin!(sleep);
             tokio::select! {
@@ -39,7 +39,7 @@ fn random_five_tuple() -> FiveTuple {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_packet_handler() -> Result<()> {
     //env_logger::init();
 
@@ -51,7 +51,7 @@ async fn test_packet_handler() -> Result<()> {
     let src_addr = client_listener.local_addr()?;
     let (data_ch_tx, mut data_ch_rx) = mpsc::channel(1);
     // client listener read data
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buffer = vec![0u8; RTP_MTU];
         loop {
             let n = match client_listener.recv_from(&mut buffer).await {
@@ -162,7 +162,7 @@ async fn test_packet_handler() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_create_allocation_duplicate_five_tuple() -> Result<()> {
     //env_logger::init();
 
@@ -197,7 +197,7 @@ async fn test_create_allocation_duplicate_five_tuple() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_delete_allocation() -> Result<()> {
     //env_logger::init();
 
@@ -234,7 +234,7 @@ async fn test_delete_allocation() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_allocation_timeout() -> Result<()> {
     //env_logger::init();
 
@@ -287,7 +287,7 @@ async fn test_allocation_timeout() -> Result<()> {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_manager_close() -> Result<()> {
     // env_logger::init();
 
@@ -336,7 +336,7 @@ async fn test_manager_close() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_delete_allocation_by_username() -> Result<()> {
     let turn_socket: Arc<dyn Conn> = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
 
@@ -436,7 +436,7 @@ async fn create_client(username: String, server_port: u16) -> Result<Client> {
 }
 
 #[cfg(feature = "metrics")]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_get_allocations_info() -> Result<()> {
     let (server, server_port) = create_server().await?;
 
@@ -488,7 +488,7 @@ async fn test_get_allocations_info() -> Result<()> {
 }
 
 #[cfg(feature = "metrics")]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_get_allocations_info_bytes_count() -> Result<()> {
     let (server, server_port) = create_server().await?;
 
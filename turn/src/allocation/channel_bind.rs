@@ -6,7 +6,8 @@ use crate::proto::channum::*;
 
 use std::sync::{atomic::AtomicBool, atomic::Ordering, Arc};
 use tokio::sync::Mutex;
-use tokio::time::{Duration, Instant};
+use deno_net::Instant;
+use std::time::Duration;
 
 // ChannelBind represents a TURN Channel
 // https://tools.ietf.org/html/rfc5766#section-2.5
@@ -39,7 +40,7 @@ impl ChannelBind {
         let number = self.number;
         let timer_expired = Arc::clone(&self.timer_expired);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let timer = deno_net::sleep(lifetime);
             tokio::pin!(timer);
             let mut done = false;
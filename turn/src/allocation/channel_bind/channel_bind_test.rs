@@ -27,7 +27,7 @@ async fn create_channel_bind(lifetime: Duration) -> Result<Allocation> {
     Ok(a)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_channel_bind() -> Result<()> {
     let a = create_channel_bind(Duration::from_millis(20)).await?;
 
@@ -22,10 +22,9 @@ use std::{
     net::SocketAddr,
     sync::{atomic::AtomicBool, atomic::Ordering, Arc},
 };
-use tokio::{
-    sync::{mpsc, Mutex},
-    time::{Duration, Instant},
-};
+use deno_net::Instant;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 
 const RTP_MTU: usize = 1500;
 
@@ -236,7 +235,7 @@ impl Allocation {
 
         log::trace!("allocation with {} closed!", self.five_tuple);
 
-        let _ = self.turn_socket.close().await;
+        // The turn socket is the server's listening socket, shared by every allocation on it.
         let _ = self.relay_socket.close().await;
 
         Ok(())
@@ -250,7 +249,7 @@ impl Allocation {
         let five_tuple = self.five_tuple;
         let timer_expired = Arc::clone(&self.timer_expired);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let timer = deno_net::sleep(lifetime);
             tokio::pin!(timer);
             let mut done = false;
@@ -321,7 +320,7 @@ impl Allocation {
         let channel_bindings = Arc::clone(&self.channel_bindings);
         let permissions = Arc::clone(&self.permissions);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buffer = vec![0u8; RTP_MTU];
 
             loop {
@@ -2,7 +2,8 @@ use super::*;
 
 use std::sync::{atomic::AtomicBool, atomic::Ordering, Arc};
 use tokio::sync::Mutex;
-use tokio::time::{Duration, Instant};
+use deno_net::Instant;
+use std::time::Duration;
 
 pub(crate) const PERMISSION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
@@ -35,7 +36,7 @@ impl Permission {
         let addr = self.addr;
         let timer_expired = Arc::clone(&self.timer_expired);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let timer = deno_net::sleep(lifetime);
             tokio::pin!(timer);
             let mut done = false;
@@ -36,7 +36,7 @@ fn test_generate_auth_key() -> Result<()> {
 }
 
 #[cfg(target_family = "unix")]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_new_long_term_auth_handler() -> Result<()> {
     use crate::client::*;
     use crate::relay::relay_static::*;
@@ -47,6 +47,7 @@ async fn test_new_long_term_auth_handler() -> Result<()> {
     use std::sync::Arc;
     use deno_net::DatagramConn as UdpSocket;
     use util::vnet::net::*;
+    use util::Conn;
 
     //env_logger::init();
 
@@ -7,8 +7,9 @@ use std::net::SocketAddr;
 use std::time::Duration;
 use deno_net::{SystemTime, UNIX_EPOCH};
 
+use hmac::{Hmac, Mac};
 use md5::{Digest, Md5};
-use ring::hmac;
+use sha1::Sha1;
 
 pub trait AuthHandler {
     fn auth_handle(&self, username: &str, realm: &str, src_addr: SocketAddr) -> Result<Vec<u8>>;
@@ -26,11 +27,10 @@ pub fn generate_long_term_credentials(
 }
 
 fn long_term_credentials(username: &str, shared_secret: &str) -> String {
-    let mac = hmac::Key::new(
-        hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
-        shared_secret.as_bytes(),
-    );
-    let password = hmac::sign(&mac, username.as_bytes()).as_ref().to_vec();
+    let mut mac = Hmac::<Sha1>::new_from_slice(shared_secret.as_bytes())
+        .expect("HMAC takes keys of any size");
+    mac.update(username.as_bytes());
+    let password = mac.finalize().into_bytes().to_vec();
     base64::encode(&password)
 }
 
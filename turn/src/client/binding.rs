@@ -4,7 +4,7 @@ mod binding_test;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 
-use tokio::time::Instant;
+use deno_net::Instant;
 
 //  Chanel number:
 //    0x4000 through 0x7FFF: These values are the allowed channel
@@ -51,7 +51,7 @@ async fn create_listening_test_client_with_stun_serv() -> Result<Client> {
     Ok(c)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_with_stun_send_binding_request() -> Result<()> {
     //env_logger::init();
 
@@ -70,7 +70,7 @@ async fn test_client_with_stun_send_binding_request() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_with_stun_send_binding_request_to_parallel() -> Result<()> {
     env_logger::init();
 
@@ -82,7 +82,7 @@ async fn test_client_with_stun_send_binding_request_to_parallel() -> Result<()>
 
     let to = lookup_host(true, "stun1.l.google.com:19302").await?;
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         drop(stared_tx);
         if let Ok(resp) = c2.send_binding_request_to(&to.to_string()).await {
             log::debug!("mapped-addr: {}", resp);
@@ -102,7 +102,7 @@ async fn test_client_with_stun_send_binding_request_to_parallel() -> Result<()>
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_with_stun_send_binding_request_to_timeout() -> Result<()> {
     //env_logger::init();
 
@@ -128,7 +128,7 @@ impl AuthHandler for TestAuthHandler {
 // Create an allocation, and then delete all nonces
 // The subsequent Write on the allocation will cause a CreatePermission
 // which will be forced to handle a stale nonce response
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_client_nonce_expiration() -> Result<()> {
     // env_logger::init();
 
@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod client_test;
+#[cfg(test)]
+mod stun_conn_test;
 
 pub mod binding;
 pub mod periodic_timer;
 pub mod permission;
 pub mod relay_conn;
+pub mod stun_conn;
 pub mod transaction;
 
 use crate::error::*;
@@ -221,7 +224,7 @@ impl ClientInternal {
         let read_ch_tx = Arc::clone(&self.read_ch_tx);
         let binding_mgr = Arc::clone(&self.binding_mgr);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; MAX_DATA_BUFFER_SIZE];
             loop {
                 //TODO: gracefully exit loop
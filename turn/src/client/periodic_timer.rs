@@ -45,7 +45,7 @@ impl PeriodicTimer {
     }
 
     // Start starts the timer.
-    pub async fn start<T: 'static + PeriodicTimerTimeoutHandler + std::marker::Send>(
+    pub async fn start<T: 'static + PeriodicTimerTimeoutHandler>(
         &self,
         timeout_handler: Arc<Mutex<T>>,
     ) -> bool {
@@ -61,7 +61,7 @@ impl PeriodicTimer {
         let interval = self.interval;
         let id = self.id;
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             loop {
                 let timer = deno_net::sleep(interval);
                 tokio::pin!(timer);
@@ -10,7 +10,7 @@ impl PeriodicTimerTimeoutHandler for DummyPeriodicTimerTimeoutHandler {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_periodic_timer() -> Result<()> {
     let timer_id = TimerIdRefresh::Perms;
     let rt = PeriodicTimer::new(timer_id, Duration::from_millis(50));
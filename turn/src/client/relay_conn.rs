@@ -23,7 +23,8 @@ use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
-use tokio::time::{Duration, Instant};
+use deno_net::Instant;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
@@ -261,7 +262,7 @@ impl<T: RelayConnObserver> RelayConnInternal<T> {
                             b.set_state(BindingState::Request);
                         }
                     }
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         let result = RelayConnInternal::bind(
                             rc_obs,
                             bind_addr,
@@ -325,7 +326,7 @@ impl<T: RelayConnObserver> RelayConnInternal<T> {
                         b.set_state(BindingState::Refresh);
                     }
                 }
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let result =
                         RelayConnInternal::bind(rc_obs, bind_addr, bind_number, nonce, integrity)
                             .await;
@@ -37,7 +37,7 @@ impl RelayConnObserver for DummyRelayConnObserver {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_relay_conn() -> Result<()> {
     let obs = DummyRelayConnObserver {
         turn_server_addr: String::new(),
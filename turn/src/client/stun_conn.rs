@@ -0,0 +1,153 @@
+use crate::proto::chandata::ChannelData;
+
+use async_trait::async_trait;
+use deno_net::StreamSocket;
+use std::io;
+use std::net::SocketAddr;
+use tokio::sync::Mutex;
+use util::{Conn, Error, Result};
+
+/// Size of the part of both STUN and ChannelData headers that holds the length.
+const FRAME_HEADER_LEN: usize = 4;
+const STUN_HEADER_LEN: usize = 20;
+
+/// StunConn carries STUN messages and ChannelData messages over a stream, for TURN over TCP
+/// and TLS. Both are delimited by their own length fields, ChannelData padded to a multiple
+/// of 4 bytes, see [RFC 8656](https://www.rfc-editor.org/rfc/rfc8656#section-12.5).
+pub struct StunConn {
+    conn: deno_net::Conn,
+    // Messages have to go out and come in whole, so concurrent senders and
+    // receivers take turns on the stream.
+    read_lock: Mutex<()>,
+    write_lock: Mutex<()>,
+}
+
+impl StunConn {
+    pub fn new(conn: deno_net::Conn) -> Self {
+        StunConn {
+            conn,
+            read_lock: Mutex::new(()),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// dial opens a TCP connection to the TURN server at raddr.
+    pub async fn dial(raddr: SocketAddr) -> Result<Self> {
+        Ok(StunConn::new(deno_net::Conn::connect(raddr).await?))
+    }
+
+    /// dial_tls opens a TLS connection to the TURN server at raddr, whose certificate has to
+    /// be valid for hostname. Only the deno backend supports TLS.
+    pub async fn dial_tls(raddr: SocketAddr, hostname: &str) -> Result<Self> {
+        Ok(StunConn::new(
+            deno_net::Conn::connect_tls(raddr, hostname).await?,
+        ))
+    }
+
+    /// read_full fills buf from the stream, returning false if the stream ended first.
+    async fn read_full(&self, buf: &mut [u8]) -> Result<bool> {
+        let mut total = 0;
+        while total < buf.len() {
+            let n = self.conn.read(&mut buf[total..]).await?;
+            if n == 0 {
+                return Ok(false);
+            }
+            total += n;
+        }
+        Ok(true)
+    }
+}
+
+/// frame_len returns the length of the message that starts with header.
+fn frame_len(header: &[u8; FRAME_HEADER_LEN]) -> Result<usize> {
+    let len = u16::from_be_bytes([header[2], header[3]]) as usize;
+    match header[0] >> 6 {
+        // STUN messages start with two zero bits, their length excludes the header.
+        0b00 => Ok(STUN_HEADER_LEN + len),
+        // ChannelData starts with 0b01 as channel numbers are 0x4000 through 0x7FFF.
+        0b01 => Ok(FRAME_HEADER_LEN + (len + 3) / 4 * 4),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "neither a STUN nor a ChannelData message",
+        )
+        .into()),
+    }
+}
+
+#[async_trait(?Send)]
+impl Conn for StunConn {
+    async fn connect(&self, addr: SocketAddr) -> Result<()> {
+        if Conn::remote_addr(self) == Some(addr) {
+            Ok(())
+        } else {
+            Err(Error::ErrAlreadyConnected)
+        }
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        let _read_lock = self.read_lock.lock().await;
+
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        if !self.read_full(&mut header).await? {
+            return Err(Error::ErrBufferClosed);
+        }
+        let len = frame_len(&header)?;
+
+        if len > buf.len() {
+            // Drain the message so the next read starts on a message boundary.
+            let mut discard = vec![0u8; len - FRAME_HEADER_LEN];
+            if !self.read_full(&mut discard).await? {
+                return Err(Error::ErrBufferClosed);
+            }
+            return Err(Error::ErrBufferShort);
+        }
+
+        buf[..FRAME_HEADER_LEN].copy_from_slice(&header);
+        if !self.read_full(&mut buf[FRAME_HEADER_LEN..len]).await? {
+            return Err(Error::ErrBufferClosed);
+        }
+        Ok(len)
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let n = self.recv(buf).await?;
+        let raddr = Conn::remote_addr(self).ok_or(Error::ErrNoRemAddr)?;
+        Ok((n, raddr))
+    }
+
+    async fn send(&self, buf: &[u8]) -> Result<usize> {
+        // Messages are framed by their own headers, so they go out as they are. The
+        // ChannelData encoder always pads, as it has to over streams.
+        if !ChannelData::is_channel_data(buf) && !stun::message::is_message(buf) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only STUN and ChannelData messages can be sent over a stream",
+            )
+            .into());
+        }
+
+        let _write_lock = self.write_lock.lock().await;
+        self.conn.write_all(buf).await?;
+        Ok(buf.len())
+    }
+
+    async fn send_to(&self, buf: &[u8], target: SocketAddr) -> Result<usize> {
+        if Conn::remote_addr(self) != Some(target) {
+            return Err(Error::ErrTcpPeerMismatch);
+        }
+        self.send(buf).await
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        StreamSocket::local_addr(&self.conn).map_err(|_| Error::ErrLocAddr)
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        StreamSocket::remote_addr(&self.conn).ok()
+    }
+
+    async fn close(&self) -> Result<()> {
+        StreamSocket::close(&self.conn);
+        Ok(())
+    }
+}
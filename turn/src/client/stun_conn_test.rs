@@ -0,0 +1,74 @@
+use super::stun_conn::*;
+use super::*;
+use crate::proto::channum::ChannelNumber;
+
+use deno_net::StreamListener;
+
+#[tokio::test(flavor = "local")]
+async fn test_stun_conn_framing() -> Result<()> {
+    let listener = deno_net::Listener::bind("127.0.0.1:0").await?;
+    let laddr = StreamListener::local_addr(&listener)?;
+
+    let (dialed, accepted) = tokio::join!(StunConn::dial(laddr), listener.accept());
+    let dialed = dialed?;
+    let (accepted, raddr) = accepted?;
+    let accepted = StunConn::new(accepted);
+
+    let mut msg = Message::new();
+    msg.build(&[
+        Box::new(TransactionId::new()),
+        Box::new(BINDING_REQUEST),
+        Box::new(Software::new(ATTR_SOFTWARE, "TEST SOFTWARE".to_owned())),
+    ])?;
+    let mut ch_data = ChannelData {
+        data: vec![1, 2, 3, 4, 5],
+        number: ChannelNumber(0x4000),
+        ..Default::default()
+    };
+    ch_data.encode();
+
+    // Messages come out whole and in order, however the stream splits them.
+    dialed.send(&msg.raw).await?;
+    dialed.send_to(&ch_data.raw, laddr).await?;
+
+    let mut buf = vec![0u8; 1500];
+    let (n, from) = accepted.recv_from(&mut buf).await?;
+    assert_eq!(&buf[..n], &msg.raw[..]);
+    assert_eq!(from, raddr);
+    let n = accepted.recv(&mut buf).await?;
+    assert_eq!(n, 12, "ChannelData should keep its padding");
+    let mut received = ChannelData {
+        raw: buf[..n].to_vec(),
+        ..Default::default()
+    };
+    received.decode()?;
+    assert_eq!(received, ch_data);
+
+    // Anything else can't be framed.
+    let result = dialed.send(&[0xff; 8]).await;
+    assert!(result.is_err(), "expected error, but got ok");
+
+    dialed.close().await?;
+    accepted.close().await?;
+    StreamListener::close(&listener);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_stun_conn_dial_tls_unsupported_natively() -> Result<()> {
+    let listener = deno_net::Listener::bind("127.0.0.1:0").await?;
+    let laddr = StreamListener::local_addr(&listener)?;
+
+    // The native backend has no TLS stack, it has to say so rather than dial in the clear.
+    let result = StunConn::dial_tls(laddr, "localhost").await;
+    match result {
+        Err(util::Error::Io(err)) => assert_eq!(err.0.kind(), std::io::ErrorKind::Unsupported),
+        Err(err) => panic!("expected Unsupported, but got {}", err),
+        Ok(_) => panic!("expected error, but got ok"),
+    }
+
+    StreamListener::close(&listener);
+
+    Ok(())
+}
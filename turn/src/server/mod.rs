@@ -20,8 +20,9 @@ use tokio::{
         broadcast::{self, error::RecvError},
         mpsc, oneshot, Mutex,
     },
-    time::{Duration, Instant},
 };
+use deno_net::Instant;
+use std::time::Duration;
 use util::Conn;
 
 const INBOUND_MTU: usize = 1500;
@@ -64,7 +65,7 @@ impl Server {
                 relay_addr_generator: p.relay_addr_generator,
             }));
 
-            wasm_bindgen_futures::spawn_local(Server::read_loop(
+            deno_net::spawn(Server::read_loop(
                 conn,
                 allocation_manager,
                 nonces,
@@ -152,7 +153,7 @@ impl Server {
 
         let (mut close_tx, mut close_rx) = oneshot::channel::<()>();
 
-        wasm_bindgen_futures::spawn_local({
+        deno_net::spawn({
             let allocation_manager = Arc::clone(&allocation_manager);
 
             async move {
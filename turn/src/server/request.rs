@@ -35,9 +35,9 @@ use std::net::SocketAddr;
 #[cfg(feature = "metrics")]
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use deno_net::SystemTime;
+use deno_net::{Instant, SystemTime};
+use std::time::Duration;
 use tokio::sync::Mutex;
-use tokio::time::{Duration, Instant};
 
 use md5::{Digest, Md5};
 
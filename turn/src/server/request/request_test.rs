@@ -1,16 +1,13 @@
 use super::*;
 use crate::relay::relay_none::*;
 
-use std::{net::IpAddr, str::FromStr};
-use tokio::{
-    net::UdpSocket,
-    time::{Duration, Instant},
-};
+use deno_net::{DatagramConn as UdpSocket, Instant};
+use std::{net::IpAddr, str::FromStr, time::Duration};
 use util::vnet::net::*;
 
 const STATIC_KEY: &str = "ABC";
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_allocation_lifetime_parsing() -> Result<()> {
     let lifetime = Lifetime(Duration::from_secs(5));
 
@@ -34,7 +31,7 @@ async fn test_allocation_lifetime_parsing() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_allocation_lifetime_overflow() -> Result<()> {
     let lifetime = Lifetime(MAXIMUM_ALLOCATION_LIFETIME * 2);
 
@@ -58,7 +55,7 @@ impl AuthHandler for TestAuthHandler {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_allocation_lifetime_deletion_zero_lifetime() -> Result<()> {
     //env_logger::init();
 
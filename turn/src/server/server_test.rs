@@ -38,7 +38,7 @@ impl AuthHandler for TestAuthHandler {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_server_simple() -> Result<()> {
     // here, it should use static port, like "0.0.0.0:3478",
     // but, due to different test environment, let's fake it by using "0.0.0.0:0"
@@ -215,7 +215,7 @@ async fn build_vnet() -> Result<VNet> {
     })
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_server_vnet_send_binding_request() -> Result<()> {
     let v = build_vnet().await?;
 
@@ -252,7 +252,7 @@ async fn test_server_vnet_send_binding_request() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_server_vnet_echo_via_relay() -> Result<()> {
     let v = build_vnet().await?;
 
@@ -284,7 +284,7 @@ async fn test_server_vnet_echo_via_relay() -> Result<()> {
 
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         let mut n;
         let mut from;
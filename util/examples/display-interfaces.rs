@@ -1,8 +1,7 @@
 use std::error::Error;
-use webrtc_util::ifaces::ifaces;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let interfaces = ifaces()?;
+    let interfaces = deno_net::network_interfaces()?;
     for (index, interface) in interfaces.iter().enumerate() {
         println!("{} {:?}", index, interface);
     }
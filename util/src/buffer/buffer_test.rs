@@ -5,7 +5,7 @@ use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 use tokio_test::assert_ok;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer() {
     let buffer = Buffer::new(0, 0);
     let mut packet: Vec<u8> = vec![0; 4];
@@ -113,24 +113,24 @@ async fn test_wraparound(grow: bool) {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_wraparound() {
     test_wraparound(false).await;
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_wraparound_grow() {
     test_wraparound(true).await;
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_async() {
     let buffer = Buffer::new(0, 0);
 
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
 
     let buffer2 = buffer.clone();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut packet: Vec<u8> = vec![0; 4];
 
         let n = assert_ok!(buffer2.read(&mut packet, None).await);
@@ -160,7 +160,7 @@ async fn test_buffer_async() {
     done_rx.recv().await;
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_limit_count() {
     let buffer = Buffer::new(2, 0);
 
@@ -218,7 +218,7 @@ async fn test_buffer_limit_count() {
     buffer.close().await;
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_limit_size() {
     let buffer = Buffer::new(0, 11);
 
@@ -286,7 +286,7 @@ async fn test_buffer_limit_size() {
     buffer.close().await;
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_limit_sizes() {
     let sizes = vec![
         128 * 1024,
@@ -331,7 +331,7 @@ async fn test_buffer_limit_sizes() {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_buffer_misc() {
     let buffer = Buffer::new(0, 0);
 
@@ -9,7 +9,7 @@ use tokio::sync::mpsc;
 const MSG1: Bytes = Bytes::from_static(b"ADC");
 const MSG2: Bytes = Bytes::from_static(b"DEFG");
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_normal() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -18,7 +18,7 @@ async fn test_bridge_normal() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn1.recv(&mut buf).await?;
         let _ = tx.send(n).await;
@@ -33,7 +33,7 @@ async fn test_bridge_normal() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_drop_1st_packet_from_conn0() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -44,7 +44,7 @@ async fn test_bridge_drop_1st_packet_from_conn0() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn1.recv(&mut buf).await?;
         let _ = tx.send(n).await;
@@ -60,7 +60,7 @@ async fn test_bridge_drop_1st_packet_from_conn0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_drop_2nd_packet_from_conn0() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -71,7 +71,7 @@ async fn test_bridge_drop_2nd_packet_from_conn0() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn1.recv(&mut buf).await?;
         let _ = tx.send(n).await;
@@ -87,7 +87,7 @@ async fn test_bridge_drop_2nd_packet_from_conn0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_drop_1st_packet_from_conn1() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -98,7 +98,7 @@ async fn test_bridge_drop_1st_packet_from_conn1() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn0.recv(&mut buf).await?;
         let _ = tx.send(n).await;
@@ -114,7 +114,7 @@ async fn test_bridge_drop_1st_packet_from_conn1() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_drop_2nd_packet_from_conn1() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -125,7 +125,7 @@ async fn test_bridge_drop_2nd_packet_from_conn1() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn0.recv(&mut buf).await?;
         let _ = tx.send(n).await;
@@ -141,7 +141,7 @@ async fn test_bridge_drop_2nd_packet_from_conn1() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_reorder_packets_from_conn0() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -152,7 +152,7 @@ async fn test_bridge_reorder_packets_from_conn0() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn1.recv(&mut buf).await?;
         assert_eq!(n, MSG2.len(), "unexpected length");
@@ -172,7 +172,7 @@ async fn test_bridge_reorder_packets_from_conn0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_reorder_packets_from_conn1() -> Result<()> {
     let (br, conn0, conn1) = Bridge::new(0, None, None);
 
@@ -183,7 +183,7 @@ async fn test_bridge_reorder_packets_from_conn1() -> Result<()> {
 
     let (tx, mut rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 256];
         let n = conn0.recv(&mut buf).await?;
         assert_eq!(n, MSG2.len(), "unexpected length");
@@ -203,7 +203,7 @@ async fn test_bridge_reorder_packets_from_conn1() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_inverse_error() -> Result<()> {
     let mut q = VecDeque::new();
     q.push_back(MSG1);
@@ -211,7 +211,7 @@ async fn test_bridge_inverse_error() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_bridge_drop_next_n_packets() -> Result<()> {
     for id in 0..2 {
         let (br, conn0, conn1) = Bridge::new(0, None, None);
@@ -222,7 +222,7 @@ async fn test_bridge_drop_next_n_packets() -> Result<()> {
 
         let (tx, mut rx) = mpsc::channel(5);
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let mut buf = vec![0u8; 256];
             for _ in 0..2u8 {
                 let n = dst_conn.recv(&mut buf).await?;
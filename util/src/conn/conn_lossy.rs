@@ -0,0 +1,150 @@
+use super::*;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{Error, ErrorKind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Mutex;
+
+/// LossyConfig describes how a LossyConn mistreats the datagrams sent through it.
+/// Chances are percentages, from 0 to 100.
+#[derive(Clone, Debug, Default)]
+pub struct LossyConfig {
+    /// loss_chance is the chance a datagram is dropped.
+    pub loss_chance: u8,
+    /// duplicate_chance is the chance a datagram is delivered twice.
+    pub duplicate_chance: u8,
+    /// reorder_chance is the chance a datagram is held back and delivered after the next one.
+    pub reorder_chance: u8,
+    /// mtu is the largest datagram the path carries, anything bigger is dropped.
+    /// 0 means there is no limit.
+    pub mtu: usize,
+    /// seed seeds the random decisions, so the same datagrams are always treated the same.
+    pub seed: u64,
+}
+
+/// LossyStats counts what a LossyConn did to the datagrams sent through it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LossyStats {
+    pub sent: usize,
+    pub dropped: usize,
+    pub oversized: usize,
+    pub duplicated: usize,
+    pub reordered: usize,
+}
+
+/// LossyConn wraps a datagram Conn and drops, duplicates and reorders what is sent through
+/// it, to test how protocols above cope with a bad network. Receiving is left untouched.
+pub struct LossyConn {
+    conn: Arc<dyn Conn>,
+    config: LossyConfig,
+    rng: Mutex<StdRng>,
+    // A datagram held back to be delivered after the next one.
+    held: Mutex<Option<Vec<u8>>>,
+
+    sent: AtomicUsize,
+    dropped: AtomicUsize,
+    oversized: AtomicUsize,
+    duplicated: AtomicUsize,
+    reordered: AtomicUsize,
+}
+
+impl LossyConn {
+    pub fn new(conn: Arc<dyn Conn>, config: LossyConfig) -> Self {
+        LossyConn {
+            conn,
+            rng: Mutex::new(StdRng::seed_from_u64(config.seed)),
+            config,
+            held: Mutex::new(None),
+            sent: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+            oversized: AtomicUsize::new(0),
+            duplicated: AtomicUsize::new(0),
+            reordered: AtomicUsize::new(0),
+        }
+    }
+
+    /// stats returns what has been done to the datagrams sent so far.
+    pub fn stats(&self) -> LossyStats {
+        LossyStats {
+            sent: self.sent.load(Ordering::SeqCst),
+            dropped: self.dropped.load(Ordering::SeqCst),
+            oversized: self.oversized.load(Ordering::SeqCst),
+            duplicated: self.duplicated.load(Ordering::SeqCst),
+            reordered: self.reordered.load(Ordering::SeqCst),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Conn for LossyConn {
+    async fn connect(&self, addr: SocketAddr) -> Result<()> {
+        self.conn.connect(addr).await
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        self.conn.recv(buf).await
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        self.conn.recv_from(buf).await
+    }
+
+    async fn send(&self, buf: &[u8]) -> Result<usize> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+
+        if self.config.mtu != 0 && buf.len() > self.config.mtu {
+            self.oversized.fetch_add(1, Ordering::SeqCst);
+            return Ok(buf.len());
+        }
+
+        // Draw every chance for every datagram, so one setting doesn't shift the others.
+        let (lose, duplicate, reorder) = {
+            let mut rng = self.rng.lock().await;
+            (
+                rng.gen_range(0..100) < self.config.loss_chance,
+                rng.gen_range(0..100) < self.config.duplicate_chance,
+                rng.gen_range(0..100) < self.config.reorder_chance,
+            )
+        };
+
+        if lose {
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+            return Ok(buf.len());
+        }
+
+        let mut held = self.held.lock().await;
+        if reorder && held.is_none() {
+            self.reordered.fetch_add(1, Ordering::SeqCst);
+            *held = Some(buf.to_vec());
+            return Ok(buf.len());
+        }
+
+        self.conn.send(buf).await?;
+        if duplicate {
+            self.duplicated.fetch_add(1, Ordering::SeqCst);
+            self.conn.send(buf).await?;
+        }
+        if let Some(held) = held.take() {
+            self.conn.send(&held).await?;
+        }
+
+        Ok(buf.len())
+    }
+
+    async fn send_to(&self, _buf: &[u8], _target: SocketAddr) -> Result<usize> {
+        Err(Error::new(ErrorKind::Other, "Not applicable").into())
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        self.conn.local_addr()
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.conn.remote_addr()
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.conn.close().await
+    }
+}
@@ -0,0 +1,126 @@
+use super::conn_lossy::*;
+use super::conn_pipe::*;
+use super::*;
+
+const END: &[u8] = b"end";
+
+// deliver sends one single byte datagram per id through a LossyConn over a pipe, and returns
+// the ids that came out the other end, in order.
+async fn deliver(config: LossyConfig, ids: &[u8]) -> Result<(Vec<u8>, LossyStats)> {
+    let (ca, cb) = pipe();
+    let ca: Arc<dyn Conn> = Arc::new(ca);
+    let lossy = LossyConn::new(Arc::clone(&ca), config);
+
+    let mut received = vec![];
+    let mut buf = vec![0u8; 16];
+    for id in ids {
+        lossy.send(&[*id]).await?;
+        // Drain as we go, the pipe only buffers a few datagrams.
+        while let Ok(Ok(n)) =
+            deno_net::timeout(std::time::Duration::from_millis(1), cb.recv(&mut buf)).await
+        {
+            received.push(buf[..n].to_vec());
+        }
+    }
+    ca.send(END).await?;
+    loop {
+        let n = cb.recv(&mut buf).await?;
+        if &buf[..n] == END {
+            break;
+        }
+        received.push(buf[..n].to_vec());
+    }
+
+    Ok((received.into_iter().map(|d| d[0]).collect(), lossy.stats()))
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_passthrough() -> Result<()> {
+    let (received, stats) = deliver(LossyConfig::default(), &[1, 2, 3]).await?;
+    assert_eq!(received, vec![1, 2, 3]);
+    assert_eq!(
+        stats,
+        LossyStats {
+            sent: 3,
+            ..Default::default()
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_loss() -> Result<()> {
+    let config = LossyConfig {
+        loss_chance: 100,
+        ..Default::default()
+    };
+    let (received, stats) = deliver(config, &[1, 2, 3]).await?;
+    assert!(received.is_empty());
+    assert_eq!(stats.dropped, 3);
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_duplicate() -> Result<()> {
+    let config = LossyConfig {
+        duplicate_chance: 100,
+        ..Default::default()
+    };
+    let (received, stats) = deliver(config, &[1, 2]).await?;
+    assert_eq!(received, vec![1, 1, 2, 2]);
+    assert_eq!(stats.duplicated, 2);
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_reorder() -> Result<()> {
+    let config = LossyConfig {
+        reorder_chance: 100,
+        ..Default::default()
+    };
+    // Every other datagram is held back behind the next one, the last one stays held.
+    let (received, stats) = deliver(config, &[1, 2, 3, 4, 5]).await?;
+    assert_eq!(received, vec![2, 1, 4, 3]);
+    assert_eq!(stats.reordered, 3);
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_mtu() -> Result<()> {
+    let (ca, cb) = pipe();
+    let lossy = LossyConn::new(
+        Arc::new(ca),
+        LossyConfig {
+            mtu: 4,
+            ..Default::default()
+        },
+    );
+
+    lossy.send(b"too long").await?;
+    lossy.send(b"fits").await?;
+
+    let mut buf = vec![0u8; 16];
+    let n = cb.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"fits");
+    assert_eq!(lossy.stats().oversized, 1);
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_lossy_conn_seed() -> Result<()> {
+    let config = LossyConfig {
+        loss_chance: 50,
+        seed: 7,
+        ..Default::default()
+    };
+    let ids: Vec<u8> = (0..32).collect();
+
+    let (first, _) = deliver(config.clone(), &ids).await?;
+    let (second, _) = deliver(config, &ids).await?;
+    assert_eq!(
+        first, second,
+        "the same seed should drop the same datagrams"
+    );
+    assert!(!first.is_empty() && first.len() < ids.len());
+    Ok(())
+}
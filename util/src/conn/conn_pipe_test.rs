@@ -1,7 +1,7 @@
 use super::conn_pipe::*;
 use super::*;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_pipe() -> Result<()> {
     let (c1, c2) = pipe();
     let mut b1 = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
@@ -0,0 +1,159 @@
+use super::*;
+use crate::error::Error;
+
+use deno_net::{StreamListener, StreamSocket};
+use tokio::sync::Mutex;
+
+/// Size of the length prefix in front of every packet.
+const FRAME_HEADER_LEN: usize = 2;
+
+/// TcpConn carries packets over a TCP stream, each one prefixed with its length
+/// as a 16-bit big-endian integer, as described in
+/// [RFC 4571](https://www.rfc-editor.org/rfc/rfc4571#section-2).
+pub struct TcpConn {
+    conn: deno_net::Conn,
+    // Frames have to go out and come in whole, so concurrent senders and
+    // receivers take turns on the stream.
+    read_lock: Mutex<()>,
+    write_lock: Mutex<()>,
+}
+
+impl TcpConn {
+    pub fn new(conn: deno_net::Conn) -> Self {
+        TcpConn {
+            conn,
+            read_lock: Mutex::new(()),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// dial opens a TCP connection to raddr.
+    pub async fn dial<A: ToSocketAddrs>(raddr: A) -> Result<Self> {
+        Ok(TcpConn::new(deno_net::Conn::connect(raddr).await?))
+    }
+
+    /// read_full fills buf from the stream, returning false if the stream ended first.
+    async fn read_full(&self, buf: &mut [u8]) -> Result<bool> {
+        let mut total = 0;
+        while total < buf.len() {
+            let n = self.conn.read(&mut buf[total..]).await?;
+            if n == 0 {
+                return Ok(false);
+            }
+            total += n;
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait(?Send)]
+impl Conn for TcpConn {
+    async fn connect(&self, addr: SocketAddr) -> Result<()> {
+        if Conn::remote_addr(self) == Some(addr) {
+            Ok(())
+        } else {
+            Err(Error::ErrAlreadyConnected)
+        }
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        let _read_lock = self.read_lock.lock().await;
+
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        if !self.read_full(&mut header).await? {
+            return Err(Error::ErrBufferClosed);
+        }
+        let len = u16::from_be_bytes(header) as usize;
+
+        if len > buf.len() {
+            // Drain the packet so the next read starts on a frame boundary.
+            let mut discard = vec![0u8; len];
+            if !self.read_full(&mut discard).await? {
+                return Err(Error::ErrBufferClosed);
+            }
+            return Err(Error::ErrBufferShort);
+        }
+
+        if !self.read_full(&mut buf[..len]).await? {
+            return Err(Error::ErrBufferClosed);
+        }
+        Ok(len)
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let n = self.recv(buf).await?;
+        let raddr = Conn::remote_addr(self).ok_or(Error::ErrNoRemAddr)?;
+        Ok((n, raddr))
+    }
+
+    async fn send(&self, buf: &[u8]) -> Result<usize> {
+        if buf.len() > u16::MAX as usize {
+            return Err(Error::ErrPacketTooBig);
+        }
+
+        // Write the header and packet together so a failed write can't leave
+        // half a frame behind.
+        let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + buf.len());
+        frame.extend_from_slice(&(buf.len() as u16).to_be_bytes());
+        frame.extend_from_slice(buf);
+
+        let _write_lock = self.write_lock.lock().await;
+        self.conn.write_all(&frame).await?;
+        Ok(buf.len())
+    }
+
+    async fn send_to(&self, buf: &[u8], target: SocketAddr) -> Result<usize> {
+        if Conn::remote_addr(self) != Some(target) {
+            return Err(Error::ErrTcpPeerMismatch);
+        }
+        self.send(buf).await
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        StreamSocket::local_addr(&self.conn).map_err(|_| Error::ErrLocAddr)
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        StreamSocket::remote_addr(&self.conn).ok()
+    }
+
+    async fn close(&self) -> Result<()> {
+        StreamSocket::close(&self.conn);
+        Ok(())
+    }
+}
+
+/// TcpListener accepts TCP connections and hands them out as framed [`TcpConn`]s.
+pub struct TcpListener {
+    listener: deno_net::Listener,
+}
+
+impl TcpListener {
+    /// bind listens for TCP connections on laddr.
+    pub async fn bind<A: ToSocketAddrs>(laddr: A) -> Result<Self> {
+        Ok(TcpListener {
+            listener: deno_net::Listener::bind(laddr).await?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl Listener for TcpListener {
+    /// accept waits for and returns the next connection to the listener.
+    async fn accept(&self) -> Result<(Arc<dyn Conn>, SocketAddr)> {
+        let (conn, raddr) = self.listener.accept().await?;
+        Ok((Arc::new(TcpConn::new(conn)), raddr))
+    }
+
+    /// close closes the listener.
+    /// Any blocked accept operations will be unblocked and return errors.
+    async fn close(&self) -> Result<()> {
+        StreamListener::close(&self.listener);
+        Ok(())
+    }
+
+    /// addr returns the listener's network address.
+    async fn addr(&self) -> Result<SocketAddr> {
+        Ok(StreamListener::local_addr(&self.listener)?)
+    }
+}
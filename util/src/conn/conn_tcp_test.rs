@@ -0,0 +1,76 @@
+use super::conn_tcp::*;
+use super::*;
+use crate::error::Error;
+
+#[tokio::test(flavor = "local")]
+async fn test_tcp_conn_framing() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let laddr = listener.addr().await?;
+
+    let (dialed, accepted) = tokio::join!(TcpConn::dial(laddr), listener.accept());
+    let dialed = dialed?;
+    let (accepted, raddr) = accepted?;
+    assert_eq!(Some(raddr), dialed.local_addr().ok());
+
+    // Packets come out whole and in order, however the stream splits them.
+    dialed.send(&[1, 2, 3]).await?;
+    dialed.send_to(&[4, 5, 6, 7, 8], laddr).await?;
+    dialed.send(&[]).await?;
+
+    let mut buf = vec![0u8; 16];
+    let n = accepted.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], &[1, 2, 3]);
+    let (n, from) = accepted.recv_from(&mut buf).await?;
+    assert_eq!(&buf[..n], &[4, 5, 6, 7, 8]);
+    assert_eq!(from, raddr);
+    let n = accepted.recv(&mut buf).await?;
+    assert_eq!(n, 0);
+
+    // A packet that doesn't fit is dropped without losing the frame boundary.
+    accepted.send(&[9; 32]).await?;
+    accepted.send(&[10]).await?;
+    let result = dialed.recv(&mut buf).await;
+    assert_eq!(result, Err(Error::ErrBufferShort));
+    let n = dialed.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], &[10]);
+
+    dialed.close().await?;
+    accepted.close().await?;
+    listener.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_tcp_conn_send_to_other_peer() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let laddr = listener.addr().await?;
+
+    let (dialed, accepted) = tokio::join!(TcpConn::dial(laddr), listener.accept());
+    let dialed = dialed?;
+    let _accepted = accepted?;
+
+    let other: SocketAddr = "127.0.0.1:9".parse()?;
+    let result = dialed.send_to(&[1], other).await;
+    assert_eq!(result, Err(Error::ErrTcpPeerMismatch));
+    let result = dialed.connect(other).await;
+    assert_eq!(result, Err(Error::ErrAlreadyConnected));
+    dialed.connect(laddr).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_tcp_conn_send_too_big() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let laddr = listener.addr().await?;
+
+    let (dialed, accepted) = tokio::join!(TcpConn::dial(laddr), listener.accept());
+    let dialed = dialed?;
+    let _accepted = accepted?;
+
+    let result = dialed.send(&vec![0u8; u16::MAX as usize + 1]).await;
+    assert_eq!(result, Err(Error::ErrPacketTooBig));
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_conn_lookup_host() -> Result<()> {
     let stun_serv_addr = "stun1.l.google.com:19302";
 
@@ -2,8 +2,10 @@ use super::*;
 use crate::error::Error;
 use crate::Buffer;
 
+use crate::sync::{Mutex as SyncMutex, RwLock};
+
 use core::sync::atomic::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::AtomicBool;
@@ -16,8 +18,20 @@ const DEFAULT_LISTEN_BACKLOG: usize = 128; // same as Linux default
 pub type AcceptFilterFn =
     Box<dyn (Fn(&[u8]) -> Pin<Box<dyn Future<Output = bool> + 'static>>)>;
 
+pub type DatagramRouterFn = Box<dyn Fn(&[u8]) -> Option<Vec<u8>>>;
+
+pub type ConnectionIdentifierFn = Arc<dyn Fn(&[u8]) -> Option<Vec<u8>>>;
+
 type AcceptDoneCh = (mpsc::Receiver<Arc<UdpConn>>, watch::Receiver<()>);
 
+/// ConnMap finds the conn a datagram belongs to, by the identifier it carries or else by
+/// the address it comes from.
+#[derive(Default)]
+struct ConnMap {
+    by_addr: HashMap<String, Arc<UdpConn>>,
+    by_id: HashMap<Vec<u8>, Arc<UdpConn>>,
+}
+
 /// listener is used in the [DTLS](https://github.com/webrtc-rs/dtls) and
 /// [SCTP](https://github.com/webrtc-rs/sctp) transport to provide a connection-oriented
 /// listener over a UDP.
@@ -27,7 +41,7 @@ struct ListenerImpl {
     accept_ch_tx: Arc<Mutex<Option<mpsc::Sender<Arc<UdpConn>>>>>,
     done_ch_tx: Arc<Mutex<Option<watch::Sender<()>>>>,
     ch_rx: Arc<Mutex<AcceptDoneCh>>,
-    conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
+    conns: Arc<Mutex<ConnMap>>,
 }
 
 #[async_trait(?Send)]
@@ -39,7 +53,7 @@ impl Listener for ListenerImpl {
         tokio::select! {
             c = accept_ch_rx.recv() =>{
                 if let Some(c) = c{
-                    let raddr = c.raddr;
+                    let raddr = *c.raddr.read();
                     Ok((c, raddr))
                 }else{
                     Err(Error::ErrClosedListenerAcceptCh)
@@ -87,6 +101,15 @@ pub struct ListenConfig {
     /// AcceptFilter determines whether the new conn should be made for
     /// the incoming packet. If not set, any packet creates new conn.
     pub accept_filter: Option<AcceptFilterFn>,
+
+    /// DatagramRouter extracts an identifier, such as a DTLS connection id, from an incoming
+    /// packet. A packet whose identifier was seen by ConnectionIdentifier goes to that conn
+    /// whichever address it comes from. If not set, packets are routed by address only.
+    pub datagram_router: Option<DatagramRouterFn>,
+
+    /// ConnectionIdentifier extracts the identifier a conn will be known by from a packet
+    /// the conn sends. If not set, conns are only known by their remote address.
+    pub connection_identifier: Option<ConnectionIdentifierFn>,
 }
 
 pub async fn listen<A: ToSocketAddrs>(laddr: A) -> Result<impl Listener> {
@@ -110,20 +133,24 @@ impl ListenConfig {
             accept_ch_tx: Arc::new(Mutex::new(Some(accept_ch_tx))),
             done_ch_tx: Arc::new(Mutex::new(Some(done_ch_tx))),
             ch_rx: Arc::new(Mutex::new((accept_ch_rx, done_ch_rx.clone()))),
-            conns: Arc::new(Mutex::new(HashMap::new())),
+            conns: Arc::new(Mutex::new(ConnMap::default())),
         };
 
         let pconn = Arc::clone(&l.pconn);
         let accepting = Arc::clone(&l.accepting);
         let accept_filter = self.accept_filter.take();
+        let datagram_router = self.datagram_router.take();
+        let connection_identifier = self.connection_identifier.take();
         let accept_ch_tx = Arc::clone(&l.accept_ch_tx);
         let conns = Arc::clone(&l.conns);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             ListenConfig::read_loop(
                 done_ch_rx,
                 pconn,
                 accepting,
                 accept_filter,
+                datagram_router,
+                connection_identifier,
                 accept_ch_tx,
                 conns,
             )
@@ -137,13 +164,16 @@ impl ListenConfig {
     /// 1. Dispatching incoming packets to the correct Conn.
     ///    It can therefore not be ended until all Conns are closed.
     /// 2. Creating a new Conn when receiving from a new remote.
+    #[allow(clippy::too_many_arguments)]
     async fn read_loop(
         mut done_ch_rx: watch::Receiver<()>,
         pconn: Arc<dyn Conn>,
         accepting: Arc<AtomicBool>,
         accept_filter: Option<AcceptFilterFn>,
+        datagram_router: Option<DatagramRouterFn>,
+        connection_identifier: Option<ConnectionIdentifierFn>,
         accept_ch_tx: Arc<Mutex<Option<mpsc::Sender<Arc<UdpConn>>>>>,
-        conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
+        conns: Arc<Mutex<ConnMap>>,
     ) {
         let mut buf = vec![0u8; RECEIVE_MTU];
 
@@ -159,6 +189,8 @@ impl ListenConfig {
                                 &pconn,
                                 &accepting,
                                 &accept_filter,
+                                &datagram_router,
+                                &connection_identifier,
                                 &accept_ch_tx,
                                 &conns,
                                 raddr,
@@ -171,7 +203,7 @@ impl ListenConfig {
                             };
 
                             if let Some(conn) = udp_conn {
-                                let _ = conn.buffer.write(&buf[..n]).await;
+                                conn.deliver(&buf[..n], raddr).await;
                             }
                         }
                         Err(err) => {
@@ -184,18 +216,25 @@ impl ListenConfig {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_udp_conn(
         pconn: &Arc<dyn Conn>,
         accepting: &Arc<AtomicBool>,
         accept_filter: &Option<AcceptFilterFn>,
+        datagram_router: &Option<DatagramRouterFn>,
+        connection_identifier: &Option<ConnectionIdentifierFn>,
         accept_ch_tx: &Arc<Mutex<Option<mpsc::Sender<Arc<UdpConn>>>>>,
-        conns: &Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
+        conns: &Arc<Mutex<ConnMap>>,
         raddr: SocketAddr,
         buf: &[u8],
     ) -> Result<Option<Arc<UdpConn>>> {
         {
             let m = conns.lock().await;
-            if let Some(conn) = m.get(raddr.to_string().as_str()) {
+            let routed = datagram_router
+                .as_ref()
+                .and_then(|route| route(buf))
+                .and_then(|id| m.by_id.get(&id));
+            if let Some(conn) = routed.or_else(|| m.by_addr.get(raddr.to_string().as_str())) {
                 return Ok(Some(conn.clone()));
             }
         }
@@ -210,7 +249,12 @@ impl ListenConfig {
             }
         }
 
-        let udp_conn = Arc::new(UdpConn::new(Arc::clone(pconn), Arc::clone(conns), raddr));
+        let udp_conn = Arc::new(UdpConn::new(
+            Arc::clone(pconn),
+            Arc::clone(conns),
+            raddr,
+            connection_identifier.clone(),
+        ));
         {
             let accept_ch = accept_ch_tx.lock().await;
             if let Some(tx) = &*accept_ch {
@@ -224,7 +268,7 @@ impl ListenConfig {
 
         {
             let mut m = conns.lock().await;
-            m.insert(raddr.to_string(), Arc::clone(&udp_conn));
+            m.by_addr.insert(raddr.to_string(), Arc::clone(&udp_conn));
         }
 
         Ok(Some(udp_conn))
@@ -234,43 +278,101 @@ impl ListenConfig {
 /// UdpConn augments a connection-oriented connection over a UdpSocket
 pub struct UdpConn {
     pconn: Arc<dyn Conn>,
-    conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
-    raddr: SocketAddr,
+    conns: Arc<Mutex<ConnMap>>,
+    raddr: RwLock<SocketAddr>,
+    connection_identifier: Option<ConnectionIdentifierFn>,
+    ids: SyncMutex<Vec<Vec<u8>>>,
+    // sources holds the address each buffered packet came from, in the same order.
+    sources: SyncMutex<VecDeque<SocketAddr>>,
     buffer: Buffer,
 }
 
 impl UdpConn {
     fn new(
         pconn: Arc<dyn Conn>,
-        conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
+        conns: Arc<Mutex<ConnMap>>,
         raddr: SocketAddr,
+        connection_identifier: Option<ConnectionIdentifierFn>,
     ) -> Self {
         UdpConn {
             pconn,
             conns,
-            raddr,
+            raddr: RwLock::new(raddr),
+            connection_identifier,
+            ids: SyncMutex::new(vec![]),
+            sources: SyncMutex::new(VecDeque::new()),
             buffer: Buffer::new(0, 0),
         }
     }
+
+    async fn deliver(&self, packet: &[u8], source: SocketAddr) {
+        self.sources.lock().push_back(source);
+        if self.buffer.write(packet).await.is_err() {
+            self.sources.lock().pop_back();
+        }
+    }
+
+    async fn read(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let n = self.buffer.read(buf, None).await?;
+        let source = self.sources.lock().pop_front();
+        Ok((n, source.unwrap_or_else(|| *self.raddr.read())))
+    }
+
+    fn is(&self, conn: &Arc<UdpConn>) -> bool {
+        std::ptr::eq(Arc::as_ptr(conn), self)
+    }
+
+    /// identify registers the identifier found in an outgoing packet, if there is one.
+    async fn identify(&self, packet: &[u8]) {
+        let id = match self.connection_identifier.as_ref().and_then(|f| f(packet)) {
+            Some(id) => id,
+            None => return,
+        };
+        if self.ids.lock().contains(&id) {
+            return;
+        }
+
+        let mut conns = self.conns.lock().await;
+        // Only a conn the listener still routes to may claim an identifier.
+        let conn = match conns.by_addr.get(self.raddr.read().to_string().as_str()) {
+            Some(conn) if self.is(conn) => Arc::clone(conn),
+            _ => return,
+        };
+        self.ids.lock().push(id.clone());
+        conns.by_id.insert(id, conn);
+    }
 }
 
 #[async_trait(?Send)]
 impl Conn for UdpConn {
+    /// connect moves the conn to a new remote address, e.g. once the peer proved it moved.
+    /// The socket is shared with the other conns of the listener, so it stays unconnected.
     async fn connect(&self, addr: SocketAddr) -> Result<()> {
-        self.pconn.connect(addr).await
+        let mut conns = self.conns.lock().await;
+        let old = std::mem::replace(&mut *self.raddr.write(), addr).to_string();
+        let conn = match conns.by_addr.get(old.as_str()) {
+            Some(conn) if self.is(conn) => conns.by_addr.remove(old.as_str()),
+            _ => None,
+        };
+        if let Some(conn) = conn {
+            conns.by_addr.insert(addr.to_string(), conn);
+        }
+        Ok(())
     }
 
     async fn recv(&self, buf: &mut [u8]) -> Result<usize> {
-        Ok(self.buffer.read(buf, None).await?)
+        let (n, _) = self.read(buf).await?;
+        Ok(n)
     }
 
     async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
-        let n = self.buffer.read(buf, None).await?;
-        Ok((n, self.raddr))
+        self.read(buf).await
     }
 
     async fn send(&self, buf: &[u8]) -> Result<usize> {
-        self.pconn.send_to(buf, self.raddr).await
+        self.identify(buf).await;
+        let raddr = *self.raddr.read();
+        self.pconn.send_to(buf, raddr).await
     }
 
     async fn send_to(&self, buf: &[u8], target: SocketAddr) -> Result<usize> {
@@ -282,12 +384,15 @@ impl Conn for UdpConn {
     }
 
     fn remote_addr(&self) -> Option<SocketAddr> {
-        Some(self.raddr)
+        Some(*self.raddr.read())
     }
 
     async fn close(&self) -> Result<()> {
         let mut conns = self.conns.lock().await;
-        conns.remove(self.raddr.to_string().as_str());
+        conns.by_addr.remove(self.raddr.read().to_string().as_str());
+        for id in self.ids.lock().drain(..) {
+            conns.by_id.remove(&id);
+        }
         Ok(())
     }
 }
@@ -50,7 +50,7 @@ async fn pipe() -> Result<(
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_listener_close_timeout() -> Result<()> {
     let (listener, ca, _) = pipe().await?;
 
@@ -62,7 +62,7 @@ async fn test_listener_close_timeout() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_listener_close_unaccepted() -> Result<()> {
     const BACKLOG: usize = 2;
 
@@ -89,7 +89,7 @@ async fn test_listener_close_unaccepted() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_listener_accept_filter() -> Result<()> {
     let tests = vec![("CreateConn", &[0xAA], true), ("Discarded", &[0x00], false)];
 
@@ -117,7 +117,7 @@ async fn test_listener_accept_filter() -> Result<()> {
         let (ch_accepted_tx, mut ch_accepted_rx) = mpsc::channel::<()>(1);
         let mut ch_accepted_tx = Some(ch_accepted_tx);
         let listener2 = Arc::clone(&listener);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let (c, _raddr) = match listener2.accept().await {
                 Ok((c, raddr)) => (c, raddr),
                 Err(err) => {
@@ -154,7 +154,7 @@ async fn test_listener_accept_filter() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_listener_concurrent() -> Result<()> {
     const BACKLOG: usize = 2;
 
@@ -195,7 +195,7 @@ async fn test_listener_concurrent() -> Result<()> {
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
     let mut done_tx = Some(done_tx);
     let listener2 = Arc::clone(&listener);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         match listener2.accept().await {
             Ok((conn, _raddr)) => {
                 conn.close().await?;
@@ -218,3 +218,60 @@ async fn test_listener_concurrent() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "local")]
+async fn test_listener_datagram_router() -> Result<()> {
+    // The first byte of a packet identifies the conn it belongs to.
+    let listener = ListenConfig {
+        datagram_router: Some(Box::new(|pkt: &[u8]| pkt.first().map(|b| vec![*b]))),
+        connection_identifier: Some(Arc::new(|pkt: &[u8]| pkt.first().map(|b| vec![*b]))),
+        ..Default::default()
+    }
+    .listen("127.0.0.1:0")
+    .await?;
+    let laddr = listener.addr().await?;
+
+    let d_conn = UdpSocket::bind("127.0.0.1:0").await?;
+    d_conn.connect(laddr).await?;
+    d_conn.send(b"Ahello").await?;
+
+    let (l_conn, raddr) = listener.accept().await?;
+    assert_eq!(raddr, d_conn.local_addr()?);
+    let mut buf = vec![0u8; 64];
+    let n = l_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Ahello");
+
+    // Sending a packet makes the conn known by the identifier it carries.
+    l_conn.send(b"Aack").await?;
+    let n = d_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Aack");
+
+    // The peer moves: its packets still reach the conn, reporting the new address.
+    let moved = UdpSocket::bind("127.0.0.1:0").await?;
+    moved.connect(laddr).await?;
+    moved.send(b"Amoved").await?;
+    let (n, from) = l_conn.recv_from(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Amoved");
+    assert_eq!(from, moved.local_addr()?);
+    assert_eq!(
+        l_conn.remote_addr(),
+        Some(raddr),
+        "a routed packet must not move the conn by itself"
+    );
+
+    l_conn.connect(from).await?;
+    assert_eq!(l_conn.remote_addr(), Some(from));
+    l_conn.send(b"Aback").await?;
+    let n = moved.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Aback");
+
+    // Packets from the new address without the identifier are routed by address now.
+    moved.send(b"Bplain").await?;
+    let n = l_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"Bplain");
+
+    l_conn.close().await?;
+    listener.close().await?;
+
+    Ok(())
+}
@@ -1,14 +1,21 @@
 pub mod conn_bridge;
 pub mod conn_disconnected_packet;
+#[cfg(any(test, feature = "lossy"))]
+pub mod conn_lossy;
 pub mod conn_pipe;
+pub mod conn_tcp;
 pub mod conn_udp;
 pub mod conn_udp_listener;
 
 #[cfg(test)]
 mod conn_bridge_test;
 #[cfg(test)]
+mod conn_lossy_test;
+#[cfg(test)]
 mod conn_pipe_test;
 #[cfg(test)]
+mod conn_tcp_test;
+#[cfg(test)]
 mod conn_test;
 
 //TODO: remove this conditional test
@@ -39,6 +39,10 @@ pub enum Error {
     ErrAlreadyClosed,
     #[error("no remAddr defined")]
     ErrNoRemAddr,
+    #[error("already connected")]
+    ErrAlreadyConnected,
+    #[error("tcp: target is not the connected peer")]
+    ErrTcpPeerMismatch,
     #[error("address already in use")]
     ErrAddressAlreadyInUse,
     #[error("no such UDPConn")]
@@ -65,8 +65,8 @@ pub mod buffer;
 #[cfg(feature = "conn")]
 pub mod conn;
 
-// #[cfg(feature = "vnet")]
-// pub mod vnet;
+#[cfg(feature = "vnet")]
+pub mod vnet;
 
 #[cfg(feature = "marshal")]
 pub mod marshal;
@@ -7,7 +7,7 @@ use std::str::FromStr;
 
 const DEMO_IP: &str = "1.2.3.4";
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_chunk_queue() -> Result<()> {
     let c: Box<dyn Chunk> = Box::new(ChunkUdp::new(
         SocketAddr::from_str("192.188.0.2:1234")?,
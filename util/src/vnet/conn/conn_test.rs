@@ -34,7 +34,7 @@ impl ConnObserver for DummyObserver {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_send_to_recv_from() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -73,7 +73,7 @@ async fn test_udp_conn_send_to_recv_from() -> Result<()> {
     let (rcvd_ch_tx, mut rcvd_ch_rx) = mpsc::channel(1);
     let (done_ch_tx, mut done_ch_rx) = mpsc::channel::<()>(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
 
         loop {
@@ -128,7 +128,7 @@ async fn test_udp_conn_send_to_recv_from() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_send_recv() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -167,7 +167,7 @@ async fn test_udp_conn_send_recv() -> Result<()> {
     let (rcvd_ch_tx, mut rcvd_ch_rx) = mpsc::channel(1);
     let (done_ch_tx, mut done_ch_rx) = mpsc::channel::<()>(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
 
         loop {
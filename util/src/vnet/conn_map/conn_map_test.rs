@@ -22,7 +22,7 @@ impl ConnObserver for DummyObserver {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_insert_remove() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -61,7 +61,7 @@ async fn test_udp_conn_map_insert_remove() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_insert_0_remove() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -100,7 +100,7 @@ async fn test_udp_conn_map_insert_0_remove() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_find_0() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -129,7 +129,7 @@ async fn test_udp_conn_map_find_0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_insert_many_ips_with_same_port() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -176,7 +176,7 @@ async fn test_udp_conn_map_insert_many_ips_with_same_port() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_already_inuse_when_insert_0() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -201,7 +201,7 @@ async fn test_udp_conn_map_already_inuse_when_insert_0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_already_inuse_when_insert_a_specified_ip() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -226,7 +226,7 @@ async fn test_udp_conn_map_already_inuse_when_insert_a_specified_ip() -> Result<
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_already_inuse_when_insert_same_specified_ip() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -251,7 +251,7 @@ async fn test_udp_conn_map_already_inuse_when_insert_same_specified_ip() -> Resu
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_find_failure_1() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -273,7 +273,7 @@ async fn test_udp_conn_map_find_failure_1() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_find_failure_2() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
@@ -295,7 +295,7 @@ async fn test_udp_conn_map_find_failure_2() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_udp_conn_map_insert_two_on_same_port_then_remove() -> Result<()> {
     let conn_map = UdpConnMap::new();
 
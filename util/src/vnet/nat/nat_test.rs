@@ -37,7 +37,7 @@ fn test_nat_type_default() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_behavior_full_cone_nat() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -98,7 +98,7 @@ async fn test_nat_mapping_behavior_full_cone_nat() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_behavior_addr_restricted_cone_nat() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -177,7 +177,7 @@ async fn test_nat_mapping_behavior_addr_restricted_cone_nat() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_behavior_port_restricted_cone_nat() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -256,7 +256,7 @@ async fn test_nat_mapping_behavior_port_restricted_cone_nat() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_behavior_symmetric_nat_addr_dependent_mapping() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -308,7 +308,7 @@ async fn test_nat_mapping_behavior_symmetric_nat_addr_dependent_mapping() -> Res
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_behavior_symmetric_nat_port_dependent_mapping() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -360,7 +360,7 @@ async fn test_nat_mapping_behavior_symmetric_nat_port_dependent_mapping() -> Res
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_timeout_refresh_on_outbound() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -425,7 +425,7 @@ async fn test_nat_mapping_timeout_refresh_on_outbound() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat_mapping_timeout_outbound_detects_timeout() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -469,7 +469,7 @@ async fn test_nat_mapping_timeout_outbound_detects_timeout() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat1to1_bahavior_one_mapping() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -515,7 +515,7 @@ async fn test_nat1to1_bahavior_one_mapping() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat1to1_bahavior_more_mapping() -> Result<()> {
     let nat = NetworkAddressTranslator::new(NatConfig {
         nat_type: NatType {
@@ -582,7 +582,7 @@ async fn test_nat1to1_bahavior_more_mapping() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_nat1to1_bahavior_failure() -> Result<()> {
     // 1:1 NAT requires more than one mapping
     let result = NetworkAddressTranslator::new(NatConfig {
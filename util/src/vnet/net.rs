@@ -7,11 +7,10 @@ use crate::error::*;
 use crate::vnet::chunk::Chunk;
 use crate::vnet::conn::{ConnObserver, UdpConn};
 use crate::vnet::router::*;
-use crate::{conn, ifaces, Conn};
+use crate::{conn, Conn};
 
 use async_trait::async_trait;
 use ipnet::IpNet;
-use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -443,29 +442,30 @@ impl Net {
 
             Net::VNet(Arc::new(Mutex::new(vnet)))
         } else {
-            let interfaces = match ifaces::ifaces() {
+            let interfaces = match deno_net::network_interfaces() {
                 Ok(ifs) => ifs,
-                Err(_) => vec![],
+                Err(err) => {
+                    log::warn!("failed to enumerate network interfaces: {}", err);
+                    vec![]
+                }
             };
 
-            let mut m: HashMap<String, Vec<IpNet>> = HashMap::new();
+            // Keep the order the host reports the interfaces in.
+            let mut ifs: Vec<Interface> = vec![];
             for iface in interfaces {
-                if let Some(addrs) = m.get_mut(&iface.name) {
-                    if let Some(addr) = iface.addr {
-                        if let Ok(inet) = Interface::convert(addr, iface.mask) {
-                            addrs.push(inet);
-                        }
-                    }
-                } else if let Some(addr) = iface.addr {
-                    if let Ok(inet) = Interface::convert(addr, iface.mask) {
-                        m.insert(iface.name, vec![inet]);
-                    }
-                }
-            }
+                let inet = match Interface::convert(
+                    SocketAddr::new(iface.addr, 0),
+                    iface.netmask.map(|mask| SocketAddr::new(mask, 0)),
+                ) {
+                    Ok(inet) => inet,
+                    Err(_) => continue,
+                };
 
-            let mut ifs = vec![];
-            for (name, addrs) in m.into_iter() {
-                ifs.push(Interface::new(name, addrs));
+                if let Some(ifc) = ifs.iter_mut().find(|ifc| ifc.name == iface.name) {
+                    ifc.add_addr(inet);
+                } else {
+                    ifs.push(Interface::new(iface.name, vec![inet]));
+                }
             }
 
             Net::Ifs(ifs)
@@ -546,6 +546,7 @@ impl Net {
                     Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0).into()
                 };
                 let local_addr = SocketAddr::new(any_ip, 0);
+                let remote_addr = conn::lookup_host(use_ipv4, remote_addr).await?;
 
                 let conn = UdpSocket::bind(local_addr).await?;
                 conn.connect(remote_addr).await?;
@@ -21,7 +21,7 @@ impl ConnObserver for DummyObserver {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_interfaces() -> Result<()> {
     let nw = Net::new(None);
     assert!(!nw.is_virtual(), "should be false");
@@ -38,7 +38,7 @@ async fn test_net_native_interfaces() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_resolve_addr() -> Result<()> {
     let nw = Net::new(None);
     assert!(!nw.is_virtual(), "should be false");
@@ -53,7 +53,7 @@ async fn test_net_native_resolve_addr() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_bind() -> Result<()> {
     let nw = Net::new(None);
     assert!(!nw.is_virtual(), "should be false");
@@ -70,7 +70,7 @@ async fn test_net_native_bind() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_dail() -> Result<()> {
     let nw = Net::new(None);
     assert!(!nw.is_virtual(), "should be false");
@@ -88,7 +88,7 @@ async fn test_net_native_dail() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_loopback() -> Result<()> {
     let nw = Net::new(None);
     assert!(!nw.is_virtual(), "should be false");
@@ -114,16 +114,14 @@ async fn test_net_native_loopback() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_native_unexpected_operations() -> Result<()> {
     let mut lo_name = String::new();
-    let ifcs = ifaces::ifaces()?;
+    let ifcs = deno_net::network_interfaces()?;
     for ifc in &ifcs {
-        if let Some(addr) = ifc.addr {
-            if addr.ip().is_loopback() {
-                lo_name = ifc.name.clone();
-                break;
-            }
+        if ifc.addr.is_loopback() {
+            lo_name = ifc.name.clone();
+            break;
         }
     }
 
@@ -147,7 +145,7 @@ async fn test_net_native_unexpected_operations() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_interfaces() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -174,7 +172,7 @@ async fn test_net_virtual_interfaces() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_interface_by_name() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -206,7 +204,7 @@ async fn test_net_virtual_interface_by_name() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_has_ipaddr() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -240,7 +238,7 @@ async fn test_net_virtual_has_ipaddr() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_get_all_ipaddrs() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -269,7 +267,7 @@ async fn test_net_virtual_get_all_ipaddrs() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_assign_port() -> Result<()> {
     let mut nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -326,7 +324,7 @@ async fn test_net_virtual_assign_port() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_determine_source_ip() -> Result<()> {
     let mut nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -386,7 +384,7 @@ async fn test_net_virtual_determine_source_ip() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_resolve_addr() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -408,7 +406,7 @@ async fn test_net_virtual_resolve_addr() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_loopback1() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -434,7 +432,7 @@ async fn test_net_virtual_loopback1() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_bind_specific_port() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -452,7 +450,7 @@ async fn test_net_virtual_bind_specific_port() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_dail_lo0() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
     assert!(nw.is_virtual(), "should be true");
@@ -470,7 +468,7 @@ async fn test_net_virtual_dail_lo0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_dail_eth0() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_string(),
@@ -502,7 +500,7 @@ async fn test_net_virtual_dail_eth0() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_resolver() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_string(),
@@ -530,7 +528,7 @@ async fn test_net_virtual_resolver() -> Result<()> {
     }
 
     let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let (conn, raddr) = {
             let raddr = nw.resolve_addr(true, "test.webrtc.rs:1234").await?;
             (nw.dail(true, "test.webrtc.rs:1234").await?, raddr)
@@ -561,7 +559,7 @@ async fn test_net_virtual_resolver() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_loopback2() -> Result<()> {
     let nw = Net::new(Some(NetConfig::default()));
 
@@ -585,7 +583,7 @@ async fn test_net_virtual_loopback2() -> Result<()> {
     let (close_ch_tx, mut close_ch_rx) = mpsc::channel::<bool>(1);
     let conn_rx = Arc::clone(&conn);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             tokio::select! {
@@ -641,7 +639,7 @@ async fn get_ipaddr(nic: &Arc<Mutex<dyn Nic>>) -> Result<IpAddr> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_end2end() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -709,7 +707,7 @@ async fn test_net_virtual_end2end() -> Result<()> {
     let mut close_ch_rx2 = close_ch_tx.subscribe();
 
     // conn1
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             log::debug!("conn1: wait for a message..");
@@ -737,7 +735,7 @@ async fn test_net_virtual_end2end() -> Result<()> {
     });
 
     // conn2
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             log::debug!("conn2: wait for a message..");
@@ -784,7 +782,7 @@ async fn test_net_virtual_end2end() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_net_virtual_two_ips_on_a_nic() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -841,7 +839,7 @@ async fn test_net_virtual_two_ips_on_a_nic() -> Result<()> {
     let mut close_ch_rx2 = close_ch_tx.subscribe();
 
     // conn1
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             log::debug!("conn1: wait for a message..");
@@ -869,7 +867,7 @@ async fn test_net_virtual_two_ips_on_a_nic() -> Result<()> {
     });
 
     // conn2
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let mut buf = vec![0u8; 1500];
         loop {
             log::debug!("conn2: wait for a message..");
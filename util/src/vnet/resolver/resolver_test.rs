@@ -2,7 +2,7 @@ use super::*;
 
 const DEMO_IP: &str = "1.2.3.4";
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_resolver_standalone() -> Result<()> {
     let mut r = Resolver::new();
 
@@ -33,7 +33,7 @@ async fn test_resolver_standalone() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_resolver_cascaded() -> Result<()> {
     let mut r0 = Resolver::new();
 
@@ -314,7 +314,7 @@ impl Router {
         let name = self.name.clone();
         let ipv4net = self.ipv4net;
 
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             while let Ok(d) = Router::process_chunks(
                 &name,
                 ipv4net,
@@ -458,8 +458,9 @@ impl Router {
             d = Duration::from_secs(0);
 
             if let Some(c) = queue.peek().await {
-                // check timestamp to find if the chunk is due
-                if c.get_timestamp().duration_since(cut_off).is_ok() {
+                // check timestamp to find if the chunk is due. The clock only has millisecond
+                // resolution, so a chunk stamped right at cut_off is due.
+                if c.get_timestamp() > cut_off {
                     // There is one or more chunk in the queue but none of them are due.
                     // Calculate the next sleep duration here.
                     let next_expire = c.get_timestamp().add(min_delay);
@@ -490,9 +491,11 @@ impl Router {
                 // check if the destination is in our subnet
                 if ipv4net.contains(&dst_ip) {
                     // search for the destination NIC
-                    if let Some(nic) = ri.nics.get(&dst_ip.to_string()) {
+                    if let Some(nic) = ri.nics.get(&dst_ip.to_string()).cloned() {
                         // found the NIC, forward the chunk to the NIC.
-                        // call to NIC must unlock mutex
+                        // call to NIC must unlock mutex, a net sending through this router
+                        // holds its own lock while it waits for ours
+                        drop(ri);
                         let ni = nic.lock().await;
                         ni.on_inbound_chunk(c).await;
                     } else {
@@ -502,10 +505,11 @@ impl Router {
                 } else {
                     // the destination is outside of this subnet
                     // is this WAN?
-                    if let Some(parent) = &ri.parent {
+                    if let Some(parent) = ri.parent.clone() {
                         // Pass it to the parent via NAT
                         if let Some(to_parent) = ri.nat.translate_outbound(&*c).await? {
                             // call to parent router mutex unlock mutex
+                            drop(ri);
                             let p = parent.lock().await;
                             p.push(to_parent).await;
                         }
@@ -130,7 +130,7 @@ fn test_router_standalone_cidr_parsing() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_standalone_assign_ip_address() -> Result<()> {
     let r = Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_string(),
@@ -155,7 +155,7 @@ async fn test_router_standalone_assign_ip_address() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_standalone_add_net() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_string(),
@@ -186,7 +186,7 @@ async fn test_router_standalone_add_net() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_standalone_routing() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_string(),
@@ -258,7 +258,7 @@ async fn test_router_standalone_routing() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_standalone_add_chunk_filter() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -460,7 +460,7 @@ async fn delay_sub_test(title: String, min_delay: Duration, max_jitter: Duration
 
 //use std::io::Write;
 #[cfg(target_os = "linux")]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_delay() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -501,7 +501,7 @@ async fn test_router_delay() -> Result<()> {
 
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_one_child() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -689,7 +689,7 @@ fn test_router_static_ips_static_ip_local_ip_mapping() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_static_ips_1to1_nat() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "0.0.0.0/0".to_owned(),
@@ -749,7 +749,7 @@ async fn test_router_static_ips_1to1_nat() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_failures_stop() -> Result<()> {
     let mut r = Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_owned(),
@@ -762,7 +762,7 @@ async fn test_router_failures_stop() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_failures_add_net() -> Result<()> {
     let wan = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_owned(),
@@ -786,7 +786,7 @@ async fn test_router_failures_add_net() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_router_failures_add_router() -> Result<()> {
     let r1 = Arc::new(Mutex::new(Router::new(RouterConfig {
         cidr: "1.2.3.0/24".to_owned(),
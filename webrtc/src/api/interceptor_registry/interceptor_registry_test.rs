@@ -17,7 +17,7 @@ use interceptor::{Attributes, Interceptor, RTPWriter, RTPWriterFn};
 // * Assert an extension can be set on an outbound packet
 // * Assert an extension can be read on an outbound packet
 // * Assert that attributes set by an interceptor are returned to the Reader
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_peer_connection_interceptor() -> Result<()> {
     let create_pc = || async {
         let mut m = MediaEngine::default();
@@ -5,7 +5,7 @@ use crate::peer_connection::configuration::RTCConfiguration;
 use regex::Regex;
 use std::io::Cursor;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_opus_case() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -25,7 +25,7 @@ async fn test_opus_case() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_video_case() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -49,7 +49,7 @@ async fn test_video_case() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_media_engine_remote_description() -> Result<()> {
     let must_parse = |raw: &str| -> Result<SessionDescription> {
         let mut reader = Cursor::new(raw.as_bytes());
@@ -501,7 +501,7 @@ a=fmtp:97 apt=96
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_media_engine_header_extension_direction() -> Result<()> {
     let register_codec = |m: &mut MediaEngine| -> Result<()> {
         m.register_codec(
@@ -600,7 +600,7 @@ async fn test_media_engine_header_extension_direction() -> Result<()> {
 }
 
 /// If a user attempts to register a codec twice we should just discard duplicate calls
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_media_engine_double_register() -> Result<()> {
     let mut m = MediaEngine::default();
 
@@ -655,7 +655,7 @@ async fn validate(m: &MediaEngine) -> Result<()> {
 }
 
 /// The cloned MediaEngine instance should be able to update negotiated header extensions.
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_update_header_extenstion_to_cloned_media_engine() -> Result<()> {
     let mut m = MediaEngine::default();
 
@@ -688,7 +688,7 @@ async fn test_update_header_extenstion_to_cloned_media_engine() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_extension_id_collision() -> Result<()> {
     let must_parse = |raw: &str| -> Result<SessionDescription> {
         let mut reader = Cursor::new(raw.as_bytes());
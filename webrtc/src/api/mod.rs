@@ -24,9 +24,9 @@ use crate::rtp_transceiver::rtp_receiver::RTCRtpReceiver;
 use crate::rtp_transceiver::rtp_sender::RTCRtpSender;
 use crate::sctp_transport::RTCSctpTransport;
 use crate::track::track_local::TrackLocal;
+use dtls::signature_hash_algorithm::SignatureAlgorithm;
 use interceptor::{registry::Registry, Interceptor};
 
-use rcgen::KeyPair;
 use std::sync::Arc;
 use deno_net::SystemTime;
 
@@ -91,8 +91,7 @@ impl API {
                     .map_err(|_| Error::ErrCertificateExpired)?;
             }
         } else {
-            let kp = KeyPair::generate(&rcgen::PKCS_ECDSA_P256_SHA256)?;
-            let cert = RTCCertificate::from_key_pair(kp)?;
+            let cert = RTCCertificate::generate_self_signed(SignatureAlgorithm::Ecdsa)?;
             certificates = vec![cert];
         };
 
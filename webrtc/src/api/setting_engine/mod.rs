@@ -3,10 +3,13 @@ mod setting_engine_test;
 
 use crate::dtls_transport::dtls_role::DTLSRole;
 use crate::ice_transport::ice_candidate_type::RTCIceCandidateType;
+use dtls::config::{ConnectionIdGenerator, KeyLogCallback};
+use dtls::crypto::provider::CryptoProvider;
 use dtls::extension::extension_use_srtp::SrtpProtectionProfile;
 use ice::agent::agent_config::{InterfaceFilterFn, IpFilterFn};
 use ice::mdns::MulticastDnsMode;
 use ice::network_type::NetworkType;
+use ice::tcp_mux::TCPMux;
 use ice::udp_network::UDPNetwork;
 
 use crate::error::{Error, Result};
@@ -69,13 +72,19 @@ pub struct SettingEngine {
     pub(crate) disable_srtcp_replay_protection: bool,
     pub(crate) vnet: Option<Arc<Net>>,
     //BufferFactory                             :func(packetType packetio.BufferPacketType, ssrc uint32) io.ReadWriteCloser,
-    //iceTCPMux                                 :ice.TCPMux,?
+    pub(crate) ice_tcp_mux: Option<Arc<dyn TCPMux>>,
+    pub(crate) disable_active_tcp: bool,
     //iceProxyDialer                            :proxy.Dialer,?
     pub(crate) udp_network: UDPNetwork,
     pub(crate) disable_media_engine_copy: bool,
     pub(crate) srtp_protection_profiles: Vec<SrtpProtectionProfile>,
+    pub(crate) srtp_header_extension_encryption: bool,
+    pub(crate) srtp_cryptex: bool,
     pub(crate) receive_mtu: usize,
     pub(crate) mid_generator: Option<Arc<dyn Fn(isize) -> String>>,
+    pub(crate) crypto_provider: Option<Arc<dyn CryptoProvider>>,
+    pub(crate) dtls_connection_id_generator: Option<ConnectionIdGenerator>,
+    pub(crate) dtls_key_log: Option<KeyLogCallback>,
 }
 
 impl SettingEngine {
@@ -96,10 +105,24 @@ impl SettingEngine {
 
     /// set_srtp_protection_profiles allows the user to override the default srtp Protection Profiles
     /// The default srtp protection profiles are provided by the function `defaultSrtpProtectionProfiles`
+    /// To require 256-bit keys, pass only Srtp_Aead_Aes_256_Gcm.
     pub fn set_srtp_protection_profiles(&mut self, profiles: Vec<SrtpProtectionProfile>) {
         self.srtp_protection_profiles = profiles
     }
 
+    /// set_srtp_header_extension_encryption offers to encrypt the RTP header extensions
+    /// (RFC 6904) and accepts the remote's offer to do so. Only the extensions both
+    /// sides negotiated with the encrypt URI are encrypted.
+    pub fn set_srtp_header_extension_encryption(&mut self, enabled: bool) {
+        self.srtp_header_extension_encryption = enabled;
+    }
+
+    /// set_srtp_cryptex offers cryptex (RFC 9335), which encrypts the CSRCs and all RTP
+    /// header extensions, and accepts the remote's offer of it.
+    pub fn set_srtp_cryptex(&mut self, enabled: bool) {
+        self.srtp_cryptex = enabled;
+    }
+
     /// set_ice_timeouts sets the behavior around ICE Timeouts
     /// * disconnected_timeout is the duration without network activity before a Agent is considered disconnected. Default is 5 Seconds
     /// * failed_timeout is the duration without network activity before a Agent is considered failed after disconnected. Default is 25 Seconds
@@ -281,11 +304,17 @@ impl SettingEngine {
         self.sdp_media_level_fingerprints = sdp_media_level_fingerprints;
     }
 
-    // SetICETCPMux enables ICE-TCP when set to a non-nil value. Make sure that
-    // NetworkTypeTCP4 or NetworkTypeTCP6 is enabled as well.
-    //pub fn SetICETCPMux(&mut self, tcpMux ice.TCPMux) {
-    //    self.iceTCPMux = tcpMux
-    //}
+    /// set_ice_tcp_mux enables ICE-TCP passive candidates served by the given TCPMux. Make sure that
+    /// NetworkType::Tcp4 or NetworkType::Tcp6 is enabled as well.
+    pub fn set_ice_tcp_mux(&mut self, tcp_mux: Arc<dyn TCPMux>) {
+        self.ice_tcp_mux = Some(tcp_mux);
+    }
+
+    /// set_disable_active_tcp stops the ICE agent from gathering active ICE-TCP candidates
+    /// and dialing the remote passive ones.
+    pub fn set_disable_active_tcp(&mut self, is_disabled: bool) {
+        self.disable_active_tcp = is_disabled;
+    }
 
     // SetICEProxyDialer sets the proxy dialer interface based on golang.org/x/net/proxy.
     //pub fn SetICEProxyDialer(&mut self, d proxy.Dialer) {
@@ -318,4 +347,23 @@ impl SettingEngine {
     pub fn set_mid_generator(&mut self, f: impl Fn(isize) -> String + 'static) {
         self.mid_generator = Some(Arc::new(f));
     }
+
+    /// set_crypto_provider overrides the backend DTLS uses for signing, key exchange, record
+    /// protection and hashing. Leave it unset for the pure Rust provider.
+    pub fn set_crypto_provider(&mut self, provider: Arc<dyn CryptoProvider>) {
+        self.crypto_provider = Some(provider);
+    }
+
+    /// set_dtls_connection_id_generator enables DTLS connection ids (RFC 9146), so the DTLS
+    /// association survives the remote's address changing, e.g. after a NAT rebinding.
+    /// Use dtls::config::random_cid_generator for a sensible default.
+    pub fn set_dtls_connection_id_generator(&mut self, generator: ConnectionIdGenerator) {
+        self.dtls_connection_id_generator = Some(generator);
+    }
+
+    /// set_dtls_key_log makes DTLS hand every master secret to `key_log` as an SSLKEYLOGFILE
+    /// line, so captures of the peer connection can be decrypted in Wireshark. Debugging only.
+    pub fn set_dtls_key_log(&mut self, key_log: KeyLogCallback) {
+        self.dtls_key_log = Some(key_log);
+    }
 }
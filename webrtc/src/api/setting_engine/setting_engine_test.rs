@@ -124,33 +124,26 @@ fn test_set_replay_protection() -> Result<()> {
     Ok(())
 }
 
-/*TODO:#[test] fn test_setting_engine_set_ice_tcp_mux() ->Result<()> {
+#[tokio::test(flavor = "local")]
+async fn test_setting_engine_set_ice_tcp_mux() -> Result<()> {
+    let listener = util::conn::conn_tcp::TcpListener::bind("127.0.0.1:0").await?;
+    let tcp_mux = ice::tcp_mux::TCPMuxDefault::new(ice::tcp_mux::TCPMuxParams::new(listener));
 
-    listener, err := net.ListenTCP("tcp", &net.TCPAddr{})
-    if err != nil {
-        panic(err)
-    }
-
-    defer func() {
-        _ = listener.Close()
-    }()
-
-    tcpMux := NewICETCPMux(nil, listener, 8)
-
-    defer func() {
-        _ = tcpMux.Close()
-    }()
+    let mut s = SettingEngine::default();
+    s.set_ice_tcp_mux(tcp_mux.clone());
 
-     let mut s = SettingEngine::default();
-    settingEngine.SetICETCPMux(tcpMux)
+    let ice_tcp_mux = s.ice_tcp_mux.clone().unwrap();
+    assert!(Arc::ptr_eq(
+        &(tcp_mux.clone() as Arc<dyn ice::tcp_mux::TCPMux>),
+        &ice_tcp_mux
+    ));
 
-    assert.Equal(t, tcpMux, settingEngine.iceTCPMux)
+    ice::tcp_mux::TCPMux::close(tcp_mux.as_ref()).await?;
 
     Ok(())
 }
-*/
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_setting_engine_set_disable_media_engine_copy() -> Result<()> {
     //"Copy"
     {
@@ -61,14 +61,14 @@ async fn close_reliability_param_test(
 }
 
 /*
-TODO: #[tokio::test] async fnBenchmarkDataChannelSend2(b *testing.B)  { benchmarkDataChannelSend(b, 2) }
-#[tokio::test] async fnBenchmarkDataChannelSend4(b *testing.B)  { benchmarkDataChannelSend(b, 4) }
-#[tokio::test] async fnBenchmarkDataChannelSend8(b *testing.B)  { benchmarkDataChannelSend(b, 8) }
-#[tokio::test] async fnBenchmarkDataChannelSend16(b *testing.B) { benchmarkDataChannelSend(b, 16) }
-#[tokio::test] async fnBenchmarkDataChannelSend32(b *testing.B) { benchmarkDataChannelSend(b, 32) }
+TODO: #[tokio::test(flavor = "local")] async fnBenchmarkDataChannelSend2(b *testing.B)  { benchmarkDataChannelSend(b, 2) }
+#[tokio::test(flavor = "local")] async fnBenchmarkDataChannelSend4(b *testing.B)  { benchmarkDataChannelSend(b, 4) }
+#[tokio::test(flavor = "local")] async fnBenchmarkDataChannelSend8(b *testing.B)  { benchmarkDataChannelSend(b, 8) }
+#[tokio::test(flavor = "local")] async fnBenchmarkDataChannelSend16(b *testing.B) { benchmarkDataChannelSend(b, 16) }
+#[tokio::test(flavor = "local")] async fnBenchmarkDataChannelSend32(b *testing.B) { benchmarkDataChannelSend(b, 32) }
 
 // See https://github.com/pion/webrtc/issues/1516
-#[tokio::test] async fnbenchmarkDataChannelSend(b *testing.B, numChannels int) {
+#[tokio::test(flavor = "local")] async fnbenchmarkDataChannelSend(b *testing.B, numChannels int) {
     offerPC, answerPC, err := newPair()
     if err != nil {
         b.Fatalf("Failed to create a PC pair for testing")
@@ -108,7 +108,7 @@ TODO: #[tokio::test] async fnBenchmarkDataChannelSend2(b *testing.B)  { benchmar
 }
 */
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_open() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -150,7 +150,7 @@ async fn test_data_channel_open() -> Result<()> {
                     }));
                     d.on_message(Box::new(move |_: DataChannelMessage| {
                         let done_tx3 = Arc::clone(&done_tx2);
-                        wasm_bindgen_futures::spawn_local(async move {
+                        deno_net::spawn(async move {
                             // Wait a little bit to ensure all messages are processed.
                             deno_net::sleep(Duration::from_millis(100)).await;
                             let _ = done_tx3.send(()).await;
@@ -183,7 +183,7 @@ async fn test_data_channel_open() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_send_before_signaling() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -241,7 +241,7 @@ async fn test_data_channel_send_before_signaling() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_send_after_connected() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -324,7 +324,7 @@ async fn test_data_channel_send_after_connected() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_close() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -353,7 +353,7 @@ async fn test_data_channel_close() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_parameters_max_packet_life_time_exchange() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -410,7 +410,7 @@ async fn test_data_channel_parameters_max_packet_life_time_exchange() -> Result<
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_parameters_max_retransmits_exchange() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -454,7 +454,7 @@ async fn test_data_channel_parameters_max_retransmits_exchange() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_parameters_protocol_exchange() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -502,7 +502,7 @@ async fn test_data_channel_parameters_protocol_exchange() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_parameters_negotiated_exchange() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -566,7 +566,7 @@ async fn test_data_channel_parameters_negotiated_exchange() -> Result<()> {
     }));
 
     let done_tx = Arc::new(Mutex::new(Some(done_tx)));
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         loop {
             if seen_answer_message.load(Ordering::SeqCst)
                 && seen_offer_message.load(Ordering::SeqCst)
@@ -599,7 +599,7 @@ async fn test_data_channel_parameters_negotiated_exchange() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_event_handlers() -> Result<()> {
     let api = APIBuilder::new().build();
 
@@ -647,7 +647,7 @@ async fn test_data_channel_event_handlers() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_messages_are_ordered() -> Result<()> {
     let api = APIBuilder::new().build();
 
@@ -680,7 +680,7 @@ async fn test_data_channel_messages_are_ordered() -> Result<()> {
         })
     }));
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         for j in 1..=m {
             let buf = j.to_be_bytes().to_vec();
 
@@ -732,7 +732,7 @@ async fn test_data_channel_messages_are_ordered() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_parameters_go() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -815,7 +815,7 @@ async fn test_data_channel_parameters_go() -> Result<()> {
 //use log::LevelFilter;
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_buffered_amount_set_before_open() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -859,7 +859,7 @@ async fn test_data_channel_buffered_amount_set_before_open() -> Result<()> {
                 let n = n_packets_received2.fetch_add(1, Ordering::SeqCst);
                 if n == 9 {
                     let done_tx3 = Arc::clone(&done_tx2);
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         deno_net::sleep(Duration::from_millis(10)).await;
                         let mut done = done_tx3.lock().await;
                         done.take();
@@ -920,7 +920,7 @@ async fn test_data_channel_buffered_amount_set_before_open() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_buffered_amount_set_after_open() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -949,7 +949,7 @@ async fn test_data_channel_buffered_amount_set_after_open() -> Result<()> {
                 let n = n_packets_received2.fetch_add(1, Ordering::SeqCst);
                 if n == 9 {
                     let done_tx3 = Arc::clone(&done_tx2);
-                    wasm_bindgen_futures::spawn_local(async move {
+                    deno_net::spawn(async move {
                         deno_net::sleep(Duration::from_millis(10)).await;
                         let mut done = done_tx3.lock().await;
                         done.take();
@@ -1008,7 +1008,7 @@ async fn test_data_channel_buffered_amount_set_after_open() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_eof_detach() -> Result<()> {
     let label: &str = "test-channel";
     let test_data: &'static str = "this is some test data";
@@ -1055,7 +1055,7 @@ async fn test_eof_detach() -> Result<()> {
     }));
 
     let w = wg.worker();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let _d = w;
 
         log::debug!("Waiting for OnDataChannel");
@@ -1095,7 +1095,7 @@ async fn test_eof_detach() -> Result<()> {
     let dc = attached.detach().await?;
 
     let w = wg.worker();
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let _d = w;
         log::debug!("Sending ping...");
         dc.write(&Bytes::from_static(test_data.as_bytes())).await?;
@@ -1118,7 +1118,7 @@ async fn test_eof_detach() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_eof_no_detach() -> Result<()> {
     let label: &str = "test-channel";
     let test_data: &'static [u8] = b"this is some test data";
@@ -1214,7 +1214,7 @@ async fn test_eof_no_detach() -> Result<()> {
 
 // Assert that a Session Description that doesn't follow
 // draft-ietf-mmusic-sctp-sdp is still accepted
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_non_standard_session_description() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -1395,11 +1395,11 @@ async fn signal_ortc_pair(stack_a: Arc<TestOrtcStack>, stack_b: Arc<TestOrtcStac
     let (a_tx, mut a_rx) = mpsc::channel(1);
     let (b_tx, mut b_rx) = mpsc::channel(1);
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let _ = a_tx.send(stack_b.set_signal(&sig_a, false).await).await;
     });
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         let _ = b_tx.send(stack_a.set_signal(&sig_b, true).await).await;
     });
 
@@ -1417,7 +1417,7 @@ async fn signal_ortc_pair(stack_a: Arc<TestOrtcStack>, stack_b: Arc<TestOrtcStac
     flatten_errs(close_errs)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_data_channel_ortc_e2e() -> Result<()> {
     let api = APIBuilder::new().build();
 
@@ -222,7 +222,7 @@ impl RTCDataChannel {
 
         let detach_data_channels = self.setting_engine.detach.data_channels;
         let detach_called = Arc::clone(&self.detach_called);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             if let Some(f) = on_open_handler {
                 f().await;
 
@@ -276,7 +276,7 @@ impl RTCDataChannel {
             let on_close_handler = Arc::clone(&self.on_close_handler);
             let on_error_handler = Arc::clone(&self.on_error_handler);
             let notify_rx = self.notify_tx.clone();
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 RTCDataChannel::read_loop(
                     notify_rx,
                     dc,
@@ -317,7 +317,7 @@ impl RTCDataChannel {
                             ready_state.store(RTCDataChannelState::Closed as u8, Ordering::SeqCst);
 
                             let on_close_handler2 = Arc::clone(&on_close_handler);
-                            wasm_bindgen_futures::spawn_local(async move {
+                            deno_net::spawn(async move {
                                 if let Some(handler) = &*on_close_handler2.load() {
                                     let mut f = handler.lock().await;
                                     f().await;
@@ -331,7 +331,7 @@ impl RTCDataChannel {
                             ready_state.store(RTCDataChannelState::Closed as u8, Ordering::SeqCst);
 
                             let on_error_handler2 = Arc::clone(&on_error_handler);
-                            wasm_bindgen_futures::spawn_local(async move {
+                            deno_net::spawn(async move {
                                 if let Some(handler) = &*on_error_handler2.load() {
                                     let mut f = handler.lock().await;
                                     f(err.into()).await;
@@ -339,7 +339,7 @@ impl RTCDataChannel {
                             });
 
                             let on_close_handler2 = Arc::clone(&on_close_handler);
-                            wasm_bindgen_futures::spawn_local(async move {
+                            deno_net::spawn(async move {
                                 if let Some(handler) = &*on_close_handler2.load() {
                                     let mut f = handler.lock().await;
                                     f().await;
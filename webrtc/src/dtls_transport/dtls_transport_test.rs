@@ -18,7 +18,7 @@ use waitgroup::WaitGroup;
 //use std::io::Write;
 
 // An invalid fingerprint MUST cause PeerConnectionState to go to PeerConnectionStateFailed
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_invalid_fingerprint_causes_failed() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -166,7 +166,7 @@ async fn run_test(r: DTLSRole) -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_peer_connection_dtls_role_setting_engine_server() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -186,7 +186,7 @@ async fn test_peer_connection_dtls_role_setting_engine_server() -> Result<()> {
     run_test(DTLSRole::Server).await
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_peer_connection_dtls_role_setting_engine_client() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -30,6 +30,7 @@ use crate::ice_transport::RTCIceTransport;
 use crate::mux::endpoint::Endpoint;
 use crate::mux::mux_func::{match_dtls, match_srtcp, match_srtp, MatchFunc};
 use crate::peer_connection::certificate::RTCCertificate;
+use crate::peer_connection::sdp::SrtpHeaderProtection;
 use crate::rtp_transceiver::SSRC;
 use crate::stats::stats_collector::StatsCollector;
 
@@ -43,6 +44,7 @@ pub mod dtls_transport_state;
 
 pub(crate) fn default_srtp_protection_profiles() -> Vec<SrtpProtectionProfile> {
     vec![
+        SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm,
         SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm,
         SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80,
     ]
@@ -68,6 +70,8 @@ pub struct RTCDtlsTransport {
     pub(crate) remote_certificate: Mutex<Bytes>,
     pub(crate) state: AtomicU8, //DTLSTransportState,
     pub(crate) srtp_protection_profile: Mutex<ProtectionProfile>,
+    pub(crate) srtp_encrypted_header_extensions: Mutex<Vec<u8>>,
+    pub(crate) srtp_cryptex: AtomicBool,
     pub(crate) on_state_change_handler: ArcSwapOption<Mutex<OnDTLSTransportStateChangeHdlrFn>>,
     pub(crate) conn: Mutex<Option<Arc<DTLSConn>>>,
 
@@ -173,6 +177,31 @@ impl RTCDtlsTransport {
         remote_certificate.clone()
     }
 
+    /// set_srtp_header_protection keeps the parts of the remote's header protection
+    /// the SettingEngine allows, they are used once SRTP starts.
+    pub(crate) async fn set_srtp_header_protection(&self, remote: &SrtpHeaderProtection) {
+        let encrypted_header_extensions = if self.setting_engine.srtp_header_extension_encryption
+        {
+            remote
+                .encrypted_extensions
+                .values()
+                .map(|id| *id as u8)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        {
+            let mut srtp_encrypted_header_extensions =
+                self.srtp_encrypted_header_extensions.lock().await;
+            *srtp_encrypted_header_extensions = encrypted_header_extensions;
+        }
+        self.srtp_cryptex.store(
+            self.setting_engine.srtp_cryptex && remote.cryptex,
+            Ordering::SeqCst,
+        );
+    }
+
     pub(crate) async fn start_srtp(&self) -> Result<()> {
         let profile = {
             let srtp_protection_profile = self.srtp_protection_profile.lock().await;
@@ -181,6 +210,12 @@ impl RTCDtlsTransport {
 
         let mut srtp_config = srtp::config::Config {
             profile,
+            encrypted_header_extensions: {
+                let srtp_encrypted_header_extensions =
+                    self.srtp_encrypted_header_extensions.lock().await;
+                srtp_encrypted_header_extensions.clone()
+            },
+            cryptex: self.srtp_cryptex.load(Ordering::SeqCst),
             ..Default::default()
         };
 
@@ -358,6 +393,9 @@ impl RTCDtlsTransport {
                 },
                 client_auth: ClientAuthType::RequireAnyClientCert,
                 insecure_skip_verify: true,
+                crypto_provider: self.setting_engine.crypto_provider.clone(),
+                connection_id_generator: self.setting_engine.dtls_connection_id_generator.clone(),
+                key_log: self.setting_engine.dtls_key_log.clone(),
                 ..Default::default()
             },
         ))
@@ -416,6 +454,12 @@ impl RTCDtlsTransport {
                 dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80 => {
                     srtp::protection_profile::ProtectionProfile::Aes128CmHmacSha1_80
                 }
+                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_32 => {
+                    srtp::protection_profile::ProtectionProfile::Aes128CmHmacSha1_32
+                }
+                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm => {
+                    srtp::protection_profile::ProtectionProfile::AeadAes256Gcm
+                }
                 _ => {
                     if let Err(err) = dtls_conn.close().await {
                         log::error!("{}", err);
@@ -1,3 +1,4 @@
+#[cfg(not(target_arch = "wasm32"))]
 use rcgen::RcgenError;
 use std::future::Future;
 use std::num::ParseIntError;
@@ -399,6 +400,7 @@ pub enum Error {
 
     #[error("utf-8 error: {0}")]
     Utf8(#[from] FromUtf8Error),
+    #[cfg(not(target_arch = "wasm32"))]
     #[error("{0}")]
     RcGen(#[from] RcgenError),
     #[error("mpsc send: {0}")]
@@ -136,7 +136,8 @@ impl RTCIceGatherer {
                 .clone(),
             local_ufrag: self.setting_engine.candidates.username_fragment.clone(),
             local_pwd: self.setting_engine.candidates.password.clone(),
-            //TODO: TCPMux:                 self.setting_engine.iceTCPMux,
+            tcp_mux: self.setting_engine.ice_tcp_mux.clone(),
+            disable_active_tcp: self.setting_engine.disable_active_tcp,
             //TODO: ProxyDialer:            self.setting_engine.iceProxyDialer,
             ..Default::default()
         };
@@ -329,7 +330,7 @@ mod test {
     use crate::ice_transport::ice_server::RTCIceServer;
     use tokio::sync::mpsc;
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_new_ice_gatherer_success() -> Result<()> {
         let opts = RTCIceGatherOptions {
             ice_servers: vec![RTCIceServer {
@@ -379,7 +380,7 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_ice_gather_mdns_candidate_gathering() -> Result<()> {
         let mut s = SettingEngine::default();
         s.set_ice_multicast_dns_mode(ice::mdns::MulticastDnsMode::QueryAndGather);
@@ -11,7 +11,7 @@ use std::sync::atomic::AtomicU32;
 use std::time::Duration;
 use waitgroup::WaitGroup;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_ice_transport_on_selected_candidate_pair_change() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -57,7 +57,7 @@ async fn test_ice_transport_on_selected_candidate_pair_change() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_ice_transport_get_selected_candidate_pair() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -0,0 +1,255 @@
+use super::event_handler::EventHandler;
+use super::{event, to_js_error};
+use crate::data_channel::data_channel_state::RTCDataChannelState;
+use crate::data_channel::RTCDataChannel;
+
+use bytes::Bytes;
+use js_sys::{ArrayBuffer, Function, Reflect, Uint8Array};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+#[derive(Default)]
+struct DataChannelShared {
+    label: String,
+    inner: RefCell<Option<Arc<RTCDataChannel>>>,
+    /// set when creating the channel failed or it was closed before it existed
+    closed: Cell<bool>,
+
+    onopen: EventHandler<Function>,
+    onmessage: EventHandler<Function>,
+    onclose: EventHandler<Function>,
+    onerror: EventHandler<Function>,
+}
+
+/// DataChannel is the JS `RTCDataChannel`. createDataChannel hands it out before the
+/// underlying channel exists, so it starts out pending and is attached once created.
+#[wasm_bindgen(js_name = RTCDataChannel)]
+pub struct DataChannel {
+    shared: Rc<DataChannelShared>,
+}
+
+impl DataChannel {
+    pub(crate) fn pending(label: &str) -> Self {
+        DataChannel {
+            shared: Rc::new(DataChannelShared {
+                label: label.to_owned(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub(crate) fn from_inner(dc: Arc<RTCDataChannel>) -> Self {
+        let c = DataChannel::pending(dc.label());
+        c.attach(dc);
+        c
+    }
+
+    pub(crate) fn clone_ref(&self) -> Self {
+        DataChannel {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+
+    /// attach binds the JS object to the created channel and forwards its events.
+    pub(crate) fn attach(&self, dc: Arc<RTCDataChannel>) {
+        let weak = Rc::downgrade(&self.shared);
+        dc.on_open(Box::new(move || {
+            if let Some(shared) = weak.upgrade() {
+                shared.onopen.dispatch(&event("open", &[]));
+            }
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(&self.shared);
+        dc.on_message(Box::new(move |msg| {
+            if let Some(shared) = weak.upgrade() {
+                let data = if msg.is_string {
+                    JsValue::from_str(&String::from_utf8_lossy(&msg.data))
+                } else {
+                    Uint8Array::from(&msg.data[..]).buffer().into()
+                };
+                shared
+                    .onmessage
+                    .dispatch(&event("message", &[("data", data)]));
+            }
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(&self.shared);
+        dc.on_close(Box::new(move || {
+            if let Some(shared) = weak.upgrade() {
+                shared.onclose.dispatch(&event("close", &[]));
+            }
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(&self.shared);
+        dc.on_error(Box::new(move |err| {
+            if let Some(shared) = weak.upgrade() {
+                dispatch_error(&shared, err);
+            }
+            Box::pin(async {})
+        }));
+
+        if self.shared.closed.get() {
+            deno_net::spawn(async move {
+                let _ = dc.close().await;
+            });
+        } else {
+            *self.shared.inner.borrow_mut() = Some(dc);
+        }
+    }
+
+    /// fail marks a pending channel as closed because it could not be created.
+    pub(crate) fn fail(&self, err: impl std::fmt::Display) {
+        self.shared.closed.set(true);
+        dispatch_error(&self.shared, err);
+        self.shared.onclose.dispatch(&event("close", &[]));
+    }
+
+    fn inner(&self) -> Option<Arc<RTCDataChannel>> {
+        self.shared.inner.borrow().clone()
+    }
+}
+
+fn dispatch_error(shared: &DataChannelShared, err: impl std::fmt::Display) {
+    shared
+        .onerror
+        .dispatch(&event("error", &[("error", to_js_error(err))]));
+}
+
+fn spawn_send(weak: Weak<DataChannelShared>, dc: Arc<RTCDataChannel>, data: Result<String, Bytes>) {
+    deno_net::spawn(async move {
+        let result = match data {
+            Ok(text) => dc.send_text(text).await,
+            Err(bytes) => dc.send(&bytes).await,
+        };
+        if let (Err(err), Some(shared)) = (result, weak.upgrade()) {
+            dispatch_error(&shared, err);
+        }
+    });
+}
+
+/// bytes_of copies an ArrayBuffer or ArrayBuffer view into Bytes.
+fn bytes_of(data: &JsValue) -> Result<Bytes, JsValue> {
+    let view = if data.is_instance_of::<ArrayBuffer>() {
+        Uint8Array::new(data)
+    } else if ArrayBuffer::is_view(data) {
+        let buffer = Reflect::get(data, &"buffer".into())?;
+        let offset = Reflect::get(data, &"byteOffset".into())?
+            .as_f64()
+            .unwrap_or(0.0);
+        let length = Reflect::get(data, &"byteLength".into())?
+            .as_f64()
+            .unwrap_or(0.0);
+        Uint8Array::new_with_byte_offset_and_length(&buffer, offset as u32, length as u32)
+    } else {
+        return Err(js_sys::TypeError::new(
+            "data must be a string, ArrayBuffer or ArrayBufferView",
+        )
+        .into());
+    };
+    Ok(Bytes::from(view.to_vec()))
+}
+
+#[wasm_bindgen(js_class = RTCDataChannel)]
+impl DataChannel {
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> String {
+        self.shared.label.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> Option<u16> {
+        self.inner().map(|dc| dc.id())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn protocol(&self) -> String {
+        self.inner()
+            .map(|dc| dc.protocol().to_owned())
+            .unwrap_or_default()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ordered(&self) -> bool {
+        self.inner().map(|dc| dc.ordered()).unwrap_or(true)
+    }
+
+    #[wasm_bindgen(getter = readyState)]
+    pub fn ready_state(&self) -> String {
+        if self.shared.closed.get() {
+            return RTCDataChannelState::Closed.to_string();
+        }
+        self.inner()
+            .map(|dc| dc.ready_state())
+            .unwrap_or(RTCDataChannelState::Connecting)
+            .to_string()
+    }
+
+    /// send queues `data` (a string, ArrayBuffer or ArrayBuffer view) on the channel. Send
+    /// failures are reported through onerror.
+    pub fn send(&self, data: JsValue) -> Result<(), JsValue> {
+        let dc = match self.inner() {
+            Some(dc) if dc.ready_state() == RTCDataChannelState::Open => dc,
+            _ => return Err(to_js_error("RTCDataChannel.readyState is not 'open'")),
+        };
+        let data = match data.as_string() {
+            Some(text) => Ok(text),
+            None => Err(bytes_of(&data)?),
+        };
+        spawn_send(Rc::downgrade(&self.shared), dc, data);
+        Ok(())
+    }
+
+    pub fn close(&self) {
+        match self.inner() {
+            Some(dc) => deno_net::spawn(async move {
+                let _ = dc.close().await;
+            }),
+            None => self.shared.closed.set(true),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onopen(&self) -> Option<js_sys::Function> {
+        self.shared.onopen.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onopen(&self, f: Option<js_sys::Function>) {
+        self.shared.onopen.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onmessage(&self) -> Option<js_sys::Function> {
+        self.shared.onmessage.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onmessage(&self, f: Option<js_sys::Function>) {
+        self.shared.onmessage.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onclose(&self) -> Option<js_sys::Function> {
+        self.shared.onclose.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onclose(&self, f: Option<js_sys::Function>) {
+        self.shared.onclose.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onerror(&self) -> Option<js_sys::Function> {
+        self.shared.onerror.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onerror(&self, f: Option<js_sys::Function>) {
+        self.shared.onerror.set(f);
+    }
+}
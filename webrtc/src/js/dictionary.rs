@@ -0,0 +1,126 @@
+use crate::data_channel::data_channel_init::RTCDataChannelInit;
+use crate::ice_transport::ice_candidate::RTCIceCandidateInit;
+use crate::ice_transport::ice_server::RTCIceServer;
+use crate::peer_connection::configuration::RTCConfiguration;
+use crate::peer_connection::offer_answer_options::RTCOfferOptions;
+use crate::peer_connection::sdp::sdp_type::RTCSdpType;
+use crate::peer_connection::signaling_state::RTCSignalingState;
+
+use serde::Deserialize;
+
+/// RTCConfiguration dictionary as passed from JS.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Configuration {
+    #[serde(default)]
+    ice_servers: Vec<IceServer>,
+    ice_transport_policy: Option<String>,
+    bundle_policy: Option<String>,
+    rtcp_mux_policy: Option<String>,
+    #[serde(default)]
+    ice_candidate_pool_size: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IceServer {
+    urls: Urls,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    credential: String,
+    credential_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Urls {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<Configuration> for RTCConfiguration {
+    fn from(c: Configuration) -> Self {
+        RTCConfiguration {
+            ice_servers: c
+                .ice_servers
+                .into_iter()
+                .map(|s| RTCIceServer {
+                    urls: match s.urls {
+                        Urls::One(url) => vec![url],
+                        Urls::Many(urls) => urls,
+                    },
+                    username: s.username,
+                    credential: s.credential,
+                    credential_type: s.credential_type.as_deref().unwrap_or("password").into(),
+                })
+                .collect(),
+            ice_transport_policy: c.ice_transport_policy.as_deref().unwrap_or("all").into(),
+            bundle_policy: c.bundle_policy.as_deref().unwrap_or("balanced").into(),
+            rtcp_mux_policy: c.rtcp_mux_policy.as_deref().unwrap_or("require").into(),
+            ice_candidate_pool_size: c.ice_candidate_pool_size,
+            ..Default::default()
+        }
+    }
+}
+
+/// RTCOfferOptions dictionary as passed from JS.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OfferOptions {
+    #[serde(default)]
+    ice_restart: bool,
+    #[serde(default)]
+    voice_activity_detection: bool,
+}
+
+impl From<OfferOptions> for RTCOfferOptions {
+    fn from(o: OfferOptions) -> Self {
+        RTCOfferOptions {
+            ice_restart: o.ice_restart,
+            voice_activity_detection: o.voice_activity_detection,
+        }
+    }
+}
+
+/// RTCDataChannelInit dictionary as passed from JS.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataChannelInit {
+    ordered: Option<bool>,
+    max_packet_life_time: Option<u16>,
+    max_retransmits: Option<u16>,
+    protocol: Option<String>,
+    #[serde(default)]
+    negotiated: bool,
+    id: Option<u16>,
+}
+
+impl From<DataChannelInit> for RTCDataChannelInit {
+    fn from(c: DataChannelInit) -> Self {
+        RTCDataChannelInit {
+            ordered: c.ordered,
+            max_packet_life_time: c.max_packet_life_time,
+            max_retransmits: c.max_retransmits,
+            protocol: c.protocol,
+            negotiated: if c.negotiated { c.id } else { None },
+        }
+    }
+}
+
+/// remote_candidate returns the candidate addIceCandidate has to add. A missing candidate or
+/// an empty candidate string signals the end of candidates, which needs no action.
+pub(crate) fn remote_candidate(
+    candidate: Option<RTCIceCandidateInit>,
+) -> Option<RTCIceCandidateInit> {
+    candidate.filter(|c| !c.candidate.is_empty())
+}
+
+/// implicit_description_type is the type of description setLocalDescription creates when it
+/// is called without one.
+pub(crate) fn implicit_description_type(state: RTCSignalingState) -> RTCSdpType {
+    match state {
+        RTCSignalingState::HaveRemoteOffer => RTCSdpType::Answer,
+        _ => RTCSdpType::Offer,
+    }
+}
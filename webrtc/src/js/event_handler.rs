@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+use std::fmt;
+
+/// A settable `on<event>` attribute. The function is looked up when the event fires, so it can
+/// be assigned before or after the underlying object exists.
+pub(crate) struct EventHandler<F>(RefCell<Option<F>>);
+
+impl<F> Default for EventHandler<F> {
+    fn default() -> Self {
+        EventHandler(RefCell::new(None))
+    }
+}
+
+impl<F: Clone> EventHandler<F> {
+    pub(crate) fn get(&self) -> Option<F> {
+        self.0.borrow().clone()
+    }
+
+    pub(crate) fn set(&self, f: Option<F>) {
+        *self.0.borrow_mut() = f;
+    }
+
+    /// dispatch_with calls the handler, if any, through `call`. Errors thrown by the handler
+    /// are logged rather than propagated into the stack.
+    pub(crate) fn dispatch_with<E: fmt::Debug>(&self, call: impl FnOnce(&F) -> Result<(), E>) {
+        // clone first, the handler may reassign itself
+        let f = self.get();
+        if let Some(f) = f {
+            if let Err(err) = call(&f) {
+                log::warn!("event handler threw: {:?}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::dictionary::*;
+use super::event_handler::EventHandler;
+use super::operations::OperationsChain;
+use crate::data_channel::data_channel_init::RTCDataChannelInit;
+use crate::ice_transport::ice_candidate::RTCIceCandidateInit;
+use crate::ice_transport::ice_credential_type::RTCIceCredentialType;
+use crate::peer_connection::configuration::RTCConfiguration;
+use crate::peer_connection::offer_answer_options::RTCOfferOptions;
+use crate::peer_connection::policy::bundle_policy::RTCBundlePolicy;
+use crate::peer_connection::policy::ice_transport_policy::RTCIceTransportPolicy;
+use crate::peer_connection::policy::rtcp_mux_policy::RTCRtcpMuxPolicy;
+use crate::peer_connection::sdp::sdp_type::RTCSdpType;
+use crate::peer_connection::sdp::session_description::RTCSessionDescription;
+use crate::peer_connection::signaling_state::RTCSignalingState;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[test]
+fn test_configuration() {
+    let config: Configuration = serde_json::from_str(
+        r#"{
+            "iceServers": [
+                {"urls": "stun:stun.l.google.com:19302"},
+                {
+                    "urls": ["turn:turn.example.org:3478", "turns:turn.example.org:5349"],
+                    "username": "user",
+                    "credential": "secret",
+                    "credentialType": "oauth"
+                }
+            ],
+            "iceTransportPolicy": "relay",
+            "bundlePolicy": "max-bundle",
+            "rtcpMuxPolicy": "negotiate",
+            "iceCandidatePoolSize": 4
+        }"#,
+    )
+    .unwrap();
+    let config = RTCConfiguration::from(config);
+
+    assert_eq!(config.ice_servers.len(), 2);
+    assert_eq!(
+        config.ice_servers[0].urls,
+        vec!["stun:stun.l.google.com:19302"]
+    );
+    assert_eq!(config.ice_servers[0].username, "");
+    assert_eq!(
+        config.ice_servers[0].credential_type,
+        RTCIceCredentialType::Password
+    );
+    assert_eq!(
+        config.ice_servers[1].urls,
+        vec!["turn:turn.example.org:3478", "turns:turn.example.org:5349"]
+    );
+    assert_eq!(config.ice_servers[1].username, "user");
+    assert_eq!(config.ice_servers[1].credential, "secret");
+    assert_eq!(
+        config.ice_servers[1].credential_type,
+        RTCIceCredentialType::Oauth
+    );
+    assert_eq!(config.ice_transport_policy, RTCIceTransportPolicy::Relay);
+    assert_eq!(config.bundle_policy, RTCBundlePolicy::MaxBundle);
+    assert_eq!(config.rtcp_mux_policy, RTCRtcpMuxPolicy::Negotiate);
+    assert_eq!(config.ice_candidate_pool_size, 4);
+}
+
+#[test]
+fn test_configuration_defaults() {
+    let config = RTCConfiguration::from(serde_json::from_str::<Configuration>("{}").unwrap());
+    assert!(config.ice_servers.is_empty());
+    assert_eq!(config.ice_transport_policy, RTCIceTransportPolicy::All);
+    assert_eq!(config.bundle_policy, RTCBundlePolicy::Balanced);
+    assert_eq!(config.rtcp_mux_policy, RTCRtcpMuxPolicy::Require);
+    assert_eq!(config.ice_candidate_pool_size, 0);
+
+    // An ICE server needs urls.
+    assert!(serde_json::from_str::<Configuration>(r#"{"iceServers": [{}]}"#).is_err());
+}
+
+#[test]
+fn test_offer_options() {
+    let options: OfferOptions = serde_json::from_str(r#"{"iceRestart": true}"#).unwrap();
+    assert_eq!(
+        RTCOfferOptions::from(options),
+        RTCOfferOptions {
+            ice_restart: true,
+            voice_activity_detection: false,
+        }
+    );
+}
+
+#[test]
+fn test_data_channel_init() {
+    let init: DataChannelInit = serde_json::from_str(
+        r#"{"ordered": false, "maxRetransmits": 3, "protocol": "chat", "id": 5}"#,
+    )
+    .unwrap();
+    let init = RTCDataChannelInit::from(init);
+    assert_eq!(init.ordered, Some(false));
+    assert_eq!(init.max_packet_life_time, None);
+    assert_eq!(init.max_retransmits, Some(3));
+    assert_eq!(init.protocol.as_deref(), Some("chat"));
+    // The id is only used for negotiated channels.
+    assert_eq!(init.negotiated, None);
+
+    let init: DataChannelInit = serde_json::from_str(r#"{"negotiated": true, "id": 5}"#).unwrap();
+    assert_eq!(RTCDataChannelInit::from(init).negotiated, Some(5));
+}
+
+#[test]
+fn test_session_description_init() {
+    let desc: RTCSessionDescription =
+        serde_json::from_str(r#"{"type": "answer", "sdp": "v=0\r\n"}"#).unwrap();
+    assert_eq!(desc.sdp_type, RTCSdpType::Answer);
+    assert_eq!(desc.sdp, "v=0\r\n");
+}
+
+#[test]
+fn test_remote_candidate() {
+    let candidate: RTCIceCandidateInit = serde_json::from_str(
+        r#"{
+            "candidate": "candidate:1 1 udp 2130706431 192.168.1.2 50000 typ host",
+            "sdpMid": "0",
+            "sdpMLineIndex": 0,
+            "usernameFragment": "abcd"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(candidate.sdp_mid.as_deref(), Some("0"));
+    assert_eq!(candidate.sdp_mline_index, Some(0));
+    assert_eq!(candidate.username_fragment.as_deref(), Some("abcd"));
+    assert_eq!(remote_candidate(Some(candidate.clone())), Some(candidate));
+
+    // End of candidates.
+    let empty: RTCIceCandidateInit = serde_json::from_str(r#"{"candidate": ""}"#).unwrap();
+    assert_eq!(remote_candidate(Some(empty)), None);
+    assert_eq!(remote_candidate(None), None);
+}
+
+#[test]
+fn test_implicit_description_type() {
+    let tests = [
+        (RTCSignalingState::Stable, RTCSdpType::Offer),
+        (RTCSignalingState::HaveLocalOffer, RTCSdpType::Offer),
+        (RTCSignalingState::HaveRemoteOffer, RTCSdpType::Answer),
+    ];
+    for (state, expected) in tests {
+        assert_eq!(implicit_description_type(state), expected, "{}", state);
+    }
+}
+
+type Handler = Rc<dyn Fn() -> Result<(), &'static str>>;
+
+#[test]
+fn test_event_handler() {
+    let handler: Rc<EventHandler<Handler>> = Rc::new(EventHandler::default());
+    let calls = Rc::new(Cell::new(0));
+    let dispatch = |handler: &EventHandler<Handler>| handler.dispatch_with(|f| f());
+
+    // No handler set.
+    dispatch(&handler);
+
+    // A handler that throws, and then clears itself from within the dispatch.
+    let (h, c) = (Rc::downgrade(&handler), Rc::clone(&calls));
+    handler.set(Some(Rc::new(move || {
+        c.set(c.get() + 1);
+        h.upgrade().unwrap().set(None);
+        Err("thrown")
+    })));
+    assert!(handler.get().is_some());
+    dispatch(&handler);
+    assert_eq!(calls.get(), 1);
+    assert!(handler.get().is_none());
+    dispatch(&handler);
+    assert_eq!(calls.get(), 1);
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_operations_chain() {
+    let chain = OperationsChain::default();
+    let log = Rc::new(RefCell::new(vec![]));
+
+    let mut done = vec![];
+    for (i, &delay) in [30u64, 0, 10].iter().enumerate() {
+        let log = Rc::clone(&log);
+        let op = chain.enqueue(async move {
+            log.borrow_mut().push(format!("start {}", i));
+            deno_net::sleep(Duration::from_millis(delay)).await;
+            log.borrow_mut().push(format!("end {}", i));
+            if i == 0 {
+                Err(i)
+            } else {
+                Ok(i)
+            }
+        });
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        deno_net::spawn(async move {
+            let _ = tx.send(op.await);
+        });
+        done.push(rx);
+    }
+
+    let mut results = vec![];
+    for rx in done {
+        results.push(rx.await.unwrap());
+    }
+    // The failed first operation doesn't stop the ones behind it.
+    assert_eq!(results, vec![Err(0), Ok(1), Ok(2)]);
+    assert_eq!(
+        *log.borrow(),
+        vec!["start 0", "end 0", "start 1", "end 1", "start 2", "end 2"]
+    );
+}
+
+#[tokio::test(flavor = "local")]
+async fn test_operations_chain_dropped() {
+    let chain = OperationsChain::default();
+    // Dropping an operation settles it.
+    drop(chain.enqueue(async {}));
+    assert_eq!(chain.enqueue(async { 1 }).await, 1);
+}
@@ -0,0 +1,76 @@
+//! JavaScript bindings for the wasm build.
+//!
+//! The classes exported here follow the shape of the W3C WebRTC API closely enough that Deno
+//! code can use them like the browser globals:
+//!
+//! ```js
+//! import { RTCPeerConnection } from "./webrtc.js";
+//!
+//! const pc = new RTCPeerConnection({ iceServers: [{ urls: "stun:stun.l.google.com:19302" }] });
+//! pc.onicecandidate = ({ candidate }) => signal({ candidate });
+//! const dc = pc.createDataChannel("chat");
+//! dc.onmessage = ({ data }) => console.log(data);
+//! await pc.setLocalDescription(await pc.createOffer());
+//! ```
+//!
+//! Dictionaries (descriptions, candidates, configuration) cross the boundary as plain objects,
+//! and events are plain objects carrying a `type` plus the W3C event fields.
+//!
+//! The bindings themselves only build for wasm; the dictionaries, event handlers and the
+//! operations chain they are built on are plain Rust and tested natively.
+
+#[cfg(test)]
+mod js_test;
+
+#[cfg(all(target_arch = "wasm32", not(feature = "native")))]
+pub mod data_channel;
+pub(crate) mod dictionary;
+pub(crate) mod event_handler;
+pub(crate) mod operations;
+#[cfg(all(target_arch = "wasm32", not(feature = "native")))]
+pub mod peer_connection;
+
+use event_handler::EventHandler;
+
+use std::fmt;
+
+use js_sys::{Function, Object, Reflect};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+impl EventHandler<Function> {
+    /// dispatch calls the handler, if any, with `event`.
+    pub(crate) fn dispatch(&self, event: &JsValue) {
+        self.dispatch_with(|f| f.call1(&JsValue::UNDEFINED, event).map(|_| ()));
+    }
+}
+
+/// event builds an event object of the given type with extra fields.
+pub(crate) fn event(typ: &str, fields: &[(&str, JsValue)]) -> JsValue {
+    let ev = Object::new();
+    let _ = Reflect::set(&ev, &"type".into(), &typ.into());
+    for (k, v) in fields {
+        let _ = Reflect::set(&ev, &(*k).into(), v);
+    }
+    ev.into()
+}
+
+/// to_js_error converts a Rust error into a JS `Error` for rejecting promises.
+pub(crate) fn to_js_error(err: impl fmt::Display) -> JsValue {
+    js_sys::Error::new(&err.to_string()).into()
+}
+
+/// from_js reads a JS dictionary into its serde counterpart.
+pub(crate) fn from_js<T: DeserializeOwned>(value: &JsValue) -> Result<T, JsValue> {
+    let json = js_sys::JSON::stringify(value)?
+        .as_string()
+        .ok_or_else(|| to_js_error("expected an object"))?;
+    serde_json::from_str(&json).map_err(to_js_error)
+}
+
+/// to_js writes a serde value out as a plain JS object.
+pub(crate) fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    let json = serde_json::to_string(value).map_err(to_js_error)?;
+    js_sys::JSON::parse(&json)
+}
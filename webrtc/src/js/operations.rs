@@ -0,0 +1,30 @@
+use std::cell::RefCell;
+use std::future::Future;
+use tokio::sync::oneshot;
+
+/// OperationsChain runs the operations enqueued on it one at a time, in order, see
+/// <https://w3c.github.io/webrtc-pc/#dfn-operations-chain>
+#[derive(Default)]
+pub(crate) struct OperationsChain {
+    /// settles once the last enqueued operation has
+    tail: RefCell<Option<oneshot::Receiver<()>>>,
+}
+
+impl OperationsChain {
+    /// enqueue returns `op`, set to run once every previously enqueued operation has settled,
+    /// so that e.g. a createDataChannel followed by createOffer sees the channel. The returned
+    /// future has to be driven for the operations behind it to run, dropping it settles it.
+    pub(crate) fn enqueue<T>(&self, op: impl Future<Output = T>) -> impl Future<Output = T> {
+        let (settled_tx, settled_rx) = oneshot::channel();
+        let prev = self.tail.replace(Some(settled_rx));
+        async move {
+            if let Some(prev) = prev {
+                // a failed operation doesn't stop the ones queued behind it
+                let _ = prev.await;
+            }
+            let result = op.await;
+            let _ = settled_tx.send(());
+            result
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use super::data_channel::DataChannel;
+use super::dictionary::{
+    implicit_description_type, remote_candidate, Configuration, DataChannelInit, OfferOptions,
+};
+use super::event_handler::EventHandler;
+use super::operations::OperationsChain;
+use super::{event, from_js, to_js, to_js_error};
+use crate::api::interceptor_registry::register_default_interceptors;
+use crate::api::media_engine::MediaEngine;
+use crate::api::{APIBuilder, API};
+use crate::data_channel::data_channel_init::RTCDataChannelInit;
+use crate::error::Result;
+use crate::ice_transport::ice_candidate::RTCIceCandidateInit;
+use crate::ice_transport::ice_connection_state::RTCIceConnectionState;
+use crate::ice_transport::ice_gathering_state::RTCIceGatheringState;
+use crate::peer_connection::configuration::RTCConfiguration;
+use crate::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use crate::peer_connection::sdp::sdp_type::RTCSdpType;
+use crate::peer_connection::sdp::session_description::RTCSessionDescription;
+use crate::peer_connection::signaling_state::RTCSignalingState;
+use crate::peer_connection::RTCPeerConnection;
+
+use interceptor::registry::Registry;
+use js_sys::{Function, Promise};
+use std::future::Future;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+/// optional reads an optional dictionary argument, treating undefined and null as absent.
+fn optional<T: serde::de::DeserializeOwned>(
+    value: &JsValue,
+) -> std::result::Result<Option<T>, JsValue> {
+    if value.is_undefined() || value.is_null() {
+        Ok(None)
+    } else {
+        from_js(value).map(Some)
+    }
+}
+
+struct PeerConnectionShared {
+    api: API,
+    configuration: RTCConfiguration,
+    inner: OnceCell<Arc<RTCPeerConnection>>,
+    chain: OperationsChain,
+
+    onicecandidate: EventHandler<Function>,
+    ondatachannel: EventHandler<Function>,
+    onnegotiationneeded: EventHandler<Function>,
+    onsignalingstatechange: EventHandler<Function>,
+    oniceconnectionstatechange: EventHandler<Function>,
+    onicegatheringstatechange: EventHandler<Function>,
+    onconnectionstatechange: EventHandler<Function>,
+}
+
+impl PeerConnectionShared {
+    /// connection returns the peer connection, creating it on first use. Creation is async
+    /// so it can't happen in the JS constructor.
+    async fn connection(self: &Rc<Self>) -> Result<Arc<RTCPeerConnection>> {
+        let pc = self
+            .inner
+            .get_or_try_init(|| async {
+                let pc = Arc::new(
+                    self.api
+                        .new_peer_connection(self.configuration.clone())
+                        .await?,
+                );
+                self.forward_events(&pc);
+                Result::<_>::Ok(pc)
+            })
+            .await?;
+        Ok(Arc::clone(pc))
+    }
+
+    fn forward_events(self: &Rc<Self>, pc: &RTCPeerConnection) {
+        let weak = Rc::downgrade(self);
+        pc.on_ice_candidate(Box::new(move |candidate| {
+            if let Some(shared) = weak.upgrade() {
+                let candidate = match candidate.map(|c| c.to_json()) {
+                    Some(Ok(init)) => to_js(&init).unwrap_or(JsValue::NULL),
+                    Some(Err(err)) => {
+                        log::warn!("failed to serialize local candidate: {}", err);
+                        return Box::pin(async {});
+                    }
+                    // end of candidates
+                    None => JsValue::NULL,
+                };
+                shared
+                    .onicecandidate
+                    .dispatch(&event("icecandidate", &[("candidate", candidate)]));
+            }
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_data_channel(Box::new(move |dc| {
+            if let Some(shared) = weak.upgrade() {
+                let channel = DataChannel::from_inner(dc);
+                shared
+                    .ondatachannel
+                    .dispatch(&event("datachannel", &[("channel", channel.into())]));
+            }
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_negotiation_needed(Box::new(move || {
+            dispatch(&weak, |s| &s.onnegotiationneeded, "negotiationneeded");
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_signaling_state_change(Box::new(move |_| {
+            dispatch(&weak, |s| &s.onsignalingstatechange, "signalingstatechange");
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_ice_connection_state_change(Box::new(move |_| {
+            dispatch(
+                &weak,
+                |s| &s.oniceconnectionstatechange,
+                "iceconnectionstatechange",
+            );
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_ice_gathering_state_change(Box::new(move |_| {
+            dispatch(
+                &weak,
+                |s| &s.onicegatheringstatechange,
+                "icegatheringstatechange",
+            );
+            Box::pin(async {})
+        }));
+
+        let weak = Rc::downgrade(self);
+        pc.on_peer_connection_state_change(Box::new(move |_| {
+            dispatch(
+                &weak,
+                |s| &s.onconnectionstatechange,
+                "connectionstatechange",
+            );
+            Box::pin(async {})
+        }));
+    }
+}
+
+fn dispatch(
+    weak: &Weak<PeerConnectionShared>,
+    handler: impl Fn(&PeerConnectionShared) -> &EventHandler<Function>,
+    typ: &str,
+) {
+    if let Some(shared) = weak.upgrade() {
+        handler(&shared).dispatch(&event(typ, &[]));
+    }
+}
+
+/// PeerConnection is the JS `RTCPeerConnection`.
+#[wasm_bindgen(js_name = RTCPeerConnection)]
+pub struct PeerConnection {
+    shared: Rc<PeerConnectionShared>,
+}
+
+impl PeerConnection {
+    /// enqueue runs `op` on the operations chain.
+    fn enqueue<F>(&self, op: F) -> Promise
+    where
+        F: Future<Output = std::result::Result<JsValue, JsValue>> + 'static,
+    {
+        future_to_promise(self.shared.chain.enqueue(op))
+    }
+}
+
+#[wasm_bindgen(js_class = RTCPeerConnection)]
+impl PeerConnection {
+    #[wasm_bindgen(constructor)]
+    pub fn new(configuration: JsValue) -> std::result::Result<PeerConnection, JsValue> {
+        let configuration: RTCConfiguration = optional::<Configuration>(&configuration)?
+            .unwrap_or_default()
+            .into();
+        for server in &configuration.ice_servers {
+            server.validate().map_err(to_js_error)?;
+        }
+
+        let mut media_engine = MediaEngine::default();
+        media_engine
+            .register_default_codecs()
+            .map_err(to_js_error)?;
+        let registry = register_default_interceptors(Registry::new(), &mut media_engine)
+            .map_err(to_js_error)?;
+        let builder = APIBuilder::new()
+            .with_media_engine(media_engine)
+            .with_interceptor_registry(registry);
+        #[cfg(all(feature = "webcrypto", target_arch = "wasm32"))]
+        let builder = {
+            let mut setting_engine = crate::api::setting_engine::SettingEngine::default();
+            setting_engine
+                .set_crypto_provider(std::sync::Arc::new(dtls::crypto::provider::WebCrypto));
+            builder.with_setting_engine(setting_engine)
+        };
+        let api = builder.build();
+
+        Ok(PeerConnection {
+            shared: Rc::new(PeerConnectionShared {
+                api,
+                configuration,
+                inner: OnceCell::new(),
+                chain: OperationsChain::default(),
+                onicecandidate: EventHandler::default(),
+                ondatachannel: EventHandler::default(),
+                onnegotiationneeded: EventHandler::default(),
+                onsignalingstatechange: EventHandler::default(),
+                oniceconnectionstatechange: EventHandler::default(),
+                onicegatheringstatechange: EventHandler::default(),
+                onconnectionstatechange: EventHandler::default(),
+            }),
+        })
+    }
+
+    #[wasm_bindgen(js_name = createOffer)]
+    pub fn create_offer(&self, options: JsValue) -> Promise {
+        let shared = Rc::clone(&self.shared);
+        self.enqueue(async move {
+            let options = optional::<OfferOptions>(&options)?.map(Into::into);
+            let pc = shared.connection().await.map_err(to_js_error)?;
+            let offer = pc.create_offer(options).await.map_err(to_js_error)?;
+            to_js(&offer)
+        })
+    }
+
+    #[wasm_bindgen(js_name = createAnswer)]
+    pub fn create_answer(&self) -> Promise {
+        let shared = Rc::clone(&self.shared);
+        self.enqueue(async move {
+            let pc = shared.connection().await.map_err(to_js_error)?;
+            let answer = pc.create_answer(None).await.map_err(to_js_error)?;
+            to_js(&answer)
+        })
+    }
+
+    /// setLocalDescription applies `description`, or without one creates the offer or answer
+    /// the signaling state calls for.
+    #[wasm_bindgen(js_name = setLocalDescription)]
+    pub fn set_local_description(&self, description: JsValue) -> Promise {
+        let shared = Rc::clone(&self.shared);
+        self.enqueue(async move {
+            let description = optional::<RTCSessionDescription>(&description)?;
+            let pc = shared.connection().await.map_err(to_js_error)?;
+            let description = match description {
+                Some(d) => d,
+                None => match implicit_description_type(pc.signaling_state()) {
+                    RTCSdpType::Answer => pc.create_answer(None).await,
+                    _ => pc.create_offer(None).await,
+                }
+                .map_err(to_js_error)?,
+            };
+            pc.set_local_description(description)
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    #[wasm_bindgen(js_name = setRemoteDescription)]
+    pub fn set_remote_description(&self, description: JsValue) -> Promise {
+        let shared = Rc::clone(&self.shared);
+        self.enqueue(async move {
+            let description: RTCSessionDescription = from_js(&description)?;
+            let pc = shared.connection().await.map_err(to_js_error)?;
+            pc.set_remote_description(description)
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// addIceCandidate adds a remote candidate, see remote_candidate for the end of candidates.
+    #[wasm_bindgen(js_name = addIceCandidate)]
+    pub fn add_ice_candidate(&self, candidate: JsValue) -> Promise {
+        let shared = Rc::clone(&self.shared);
+        self.enqueue(async move {
+            let candidate = match remote_candidate(optional::<RTCIceCandidateInit>(&candidate)?) {
+                Some(c) => c,
+                None => return Ok(JsValue::UNDEFINED),
+            };
+            let pc = shared.connection().await.map_err(to_js_error)?;
+            pc.add_ice_candidate(candidate).await.map_err(to_js_error)?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// createDataChannel returns the channel right away, it opens once negotiation completes.
+    #[wasm_bindgen(js_name = createDataChannel)]
+    pub fn create_data_channel(
+        &self,
+        label: String,
+        init: JsValue,
+    ) -> std::result::Result<DataChannel, JsValue> {
+        let options: Option<RTCDataChannelInit> =
+            optional::<DataChannelInit>(&init)?.map(Into::into);
+
+        let channel = DataChannel::pending(&label);
+        let pending = channel.clone_ref();
+        let shared = Rc::clone(&self.shared);
+        // never rejects, creation errors are reported on the channel
+        let _ = self.enqueue(async move {
+            let result = match shared.connection().await {
+                Ok(pc) => pc.create_data_channel(&label, options).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(dc) => pending.attach(dc),
+                Err(err) => pending.fail(err),
+            }
+            Ok(JsValue::UNDEFINED)
+        });
+        Ok(channel)
+    }
+
+    pub fn close(&self) {
+        if let Some(pc) = self.shared.inner.get() {
+            let pc = Arc::clone(pc);
+            deno_net::spawn(async move {
+                if let Err(err) = pc.close().await {
+                    log::warn!("failed to close peer connection: {}", err);
+                }
+            });
+        }
+    }
+
+    #[wasm_bindgen(getter = signalingState)]
+    pub fn signaling_state(&self) -> String {
+        match self.shared.inner.get() {
+            Some(pc) => pc.signaling_state(),
+            None => RTCSignalingState::Stable,
+        }
+        .to_string()
+    }
+
+    #[wasm_bindgen(getter = iceGatheringState)]
+    pub fn ice_gathering_state(&self) -> String {
+        match self.shared.inner.get() {
+            Some(pc) => pc.ice_gathering_state(),
+            None => RTCIceGatheringState::New,
+        }
+        .to_string()
+    }
+
+    #[wasm_bindgen(getter = iceConnectionState)]
+    pub fn ice_connection_state(&self) -> String {
+        match self.shared.inner.get() {
+            Some(pc) => pc.ice_connection_state(),
+            None => RTCIceConnectionState::New,
+        }
+        .to_string()
+    }
+
+    #[wasm_bindgen(getter = connectionState)]
+    pub fn connection_state(&self) -> String {
+        match self.shared.inner.get() {
+            Some(pc) => pc.connection_state(),
+            None => RTCPeerConnectionState::New,
+        }
+        .to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onicecandidate(&self) -> Option<js_sys::Function> {
+        self.shared.onicecandidate.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onicecandidate(&self, f: Option<js_sys::Function>) {
+        self.shared.onicecandidate.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ondatachannel(&self) -> Option<js_sys::Function> {
+        self.shared.ondatachannel.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_ondatachannel(&self, f: Option<js_sys::Function>) {
+        self.shared.ondatachannel.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onnegotiationneeded(&self) -> Option<js_sys::Function> {
+        self.shared.onnegotiationneeded.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onnegotiationneeded(&self, f: Option<js_sys::Function>) {
+        self.shared.onnegotiationneeded.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onsignalingstatechange(&self) -> Option<js_sys::Function> {
+        self.shared.onsignalingstatechange.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onsignalingstatechange(&self, f: Option<js_sys::Function>) {
+        self.shared.onsignalingstatechange.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn oniceconnectionstatechange(&self) -> Option<js_sys::Function> {
+        self.shared.oniceconnectionstatechange.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_oniceconnectionstatechange(&self, f: Option<js_sys::Function>) {
+        self.shared.oniceconnectionstatechange.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onicegatheringstatechange(&self) -> Option<js_sys::Function> {
+        self.shared.onicegatheringstatechange.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onicegatheringstatechange(&self, f: Option<js_sys::Function>) {
+        self.shared.onicegatheringstatechange.set(f);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn onconnectionstatechange(&self) -> Option<js_sys::Function> {
+        self.shared.onconnectionstatechange.get()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_onconnectionstatechange(&self, f: Option<js_sys::Function>) {
+        self.shared.onconnectionstatechange.set(f);
+    }
+}
@@ -22,6 +22,8 @@ pub mod data_channel;
 pub mod dtls_transport;
 pub mod error;
 pub mod ice_transport;
+#[cfg(any(test, all(target_arch = "wasm32", not(feature = "native"))))]
+pub mod js;
 pub mod mux;
 pub mod peer_connection;
 pub mod rtp_transceiver;
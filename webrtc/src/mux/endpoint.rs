@@ -45,8 +45,13 @@ impl Conn for Endpoint {
             Err(err) => Err(io::Error::new(io::ErrorKind::Other, err.to_string()).into()),
         }
     }
-    async fn recv_from(&self, _buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
-        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    /// reads a packet like recv, reporting the remote address of the underlying conn
+    async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        let raddr = self
+            .remote_addr()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Not applicable"))?;
+        let n = self.recv(buf).await?;
+        Ok((n, raddr))
     }
 
     /// writes bytes to the underlying conn
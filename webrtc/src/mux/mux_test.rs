@@ -24,7 +24,7 @@ async fn pipe_memory() -> (Arc<Endpoint>, impl Conn) {
     (e, cb)
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_no_endpoints() -> crate::error::Result<()> {
     // In memory pipe
     let (ca, _) = pipe();
@@ -93,7 +93,7 @@ impl Conn for MuxErrorConn {
     }
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_non_fatal_read() -> Result<()> {
     let expected_data = b"expected_data".to_vec();
 
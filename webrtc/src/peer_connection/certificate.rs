@@ -1,14 +1,19 @@
-use dtls::crypto::{CryptoPrivateKey, CryptoPrivateKeyKind};
+use dtls::crypto::provider::CryptoProvider;
+#[cfg(not(target_arch = "wasm32"))]
+use dtls::crypto::CryptoPrivateKey;
+use dtls::signature_hash_algorithm::SignatureAlgorithm;
+#[cfg(not(target_arch = "wasm32"))]
 use rcgen::{CertificateParams, KeyPair};
-use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, RsaKeyPair};
 use sha2::{Digest, Sha256};
 
+use deno_net::{SystemTime, UNIX_EPOCH};
 use std::ops::Add;
 use std::time::Duration;
-use deno_net::{SystemTime, UNIX_EPOCH};
 
 use crate::dtls_transport::dtls_fingerprint::RTCDtlsFingerprint;
-use crate::error::{Error, Result};
+#[cfg(any(not(target_arch = "wasm32"), feature = "pem"))]
+use crate::error::Error;
+use crate::error::Result;
 use crate::peer_connection::math_rand_alpha;
 use crate::stats::stats_collector::StatsCollector;
 use crate::stats::{CertificateStats, StatsReportType};
@@ -38,55 +43,27 @@ impl RTCCertificate {
     /// Generates a new certificate from the given parameters.
     ///
     /// See [`rcgen::Certificate::from_params`].
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_params(params: CertificateParams) -> Result<Self> {
         let not_after = params.not_after;
         let x509_cert = rcgen::Certificate::from_params(params)?;
 
         let key_pair = x509_cert.get_key_pair();
-        let serialized_der = key_pair.serialize_der();
-
-        let private_key = if key_pair.is_compatible(&rcgen::PKCS_ED25519) {
-            CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ed25519(
-                    Ed25519KeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::new(e.to_string()))?,
-                ),
-                serialized_der,
-            }
-        } else if key_pair.is_compatible(&rcgen::PKCS_ECDSA_P256_SHA256) {
-            CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Ecdsa256(
-                    EcdsaKeyPair::from_pkcs8(
-                        &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
-                        &serialized_der,
-                    )
-                    .map_err(|e| Error::new(e.to_string()))?,
-                ),
-                serialized_der,
-            }
-        } else if key_pair.is_compatible(&rcgen::PKCS_RSA_SHA256) {
-            CryptoPrivateKey {
-                kind: CryptoPrivateKeyKind::Rsa256(
-                    RsaKeyPair::from_pkcs8(&serialized_der)
-                        .map_err(|e| Error::new(e.to_string()))?,
-                ),
-                serialized_der,
-            }
-        } else {
-            return Err(Error::new("Unsupported key_pair".to_owned()));
-        };
+
+        let private_key = CryptoPrivateKey::from_key_pair(key_pair)?;
 
         let expires = if cfg!(target_arch = "arm") {
             // Workaround for issue overflow when adding duration to instant on armv7
             // https://github.com/webrtc-rs/examples/issues/5 https://github.com/chronotope/chrono/issues/343
             SystemTime::now().add(Duration::from_secs(172800)) //60*60*48 or 2 days
         } else {
-            not_after.into()
+            let secs = not_after.unix_timestamp().max(0) as u64;
+            UNIX_EPOCH.add(Duration::from_secs(secs))
         };
 
         Ok(Self {
             dtls_certificate: dtls::crypto::Certificate {
-                certificate: vec![rustls::Certificate(x509_cert.serialize_der()?)],
+                certificate: vec![x509_cert.serialize_der()?],
                 private_key,
             },
             expires,
@@ -95,6 +72,7 @@ impl RTCCertificate {
     }
 
     /// Generates a new certificate with default [`CertificateParams`] using the given keypair.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_key_pair(key_pair: KeyPair) -> Result<Self> {
         let mut params = CertificateParams::new(vec![math_rand_alpha(16)]);
 
@@ -112,6 +90,40 @@ impl RTCCertificate {
         RTCCertificate::from_params(params)
     }
 
+    /// Generates a new self-signed certificate, valid for 30 days, with a key from
+    /// [`RustCrypto`](dtls::crypto::provider::RustCrypto).
+    pub fn generate_self_signed(signature: SignatureAlgorithm) -> Result<Self> {
+        let expires = SystemTime::now().add(Duration::from_secs(60 * 60 * 24 * 30));
+        let dtls_certificate = dtls::crypto::Certificate::generate_self_signed_expiring(
+            vec![math_rand_alpha(16)],
+            signature,
+            expires,
+        )?;
+
+        Ok(RTCCertificate::from_existing(dtls_certificate, expires))
+    }
+
+    /// Generates a new self-signed certificate, valid for 30 days, entirely through the given
+    /// [`CryptoProvider`].
+    ///
+    /// Unlike [`from_key_pair`](Self::from_key_pair) this doesn't go through rcgen, so it works
+    /// with any provider, including `crypto.subtle` on wasm.
+    pub async fn generate(
+        provider: &dyn CryptoProvider,
+        signature: SignatureAlgorithm,
+    ) -> Result<Self> {
+        let expires = SystemTime::now().add(Duration::from_secs(60 * 60 * 24 * 30));
+        let dtls_certificate = dtls::crypto::Certificate::generate_self_signed_with_provider(
+            provider,
+            vec![math_rand_alpha(16)],
+            signature,
+            expires,
+        )
+        .await?;
+
+        Ok(RTCCertificate::from_existing(dtls_certificate, expires))
+    }
+
     /// Parses a certificate from the ASCII PEM format.
     #[cfg(feature = "pem")]
     pub fn from_pem(pem_str: &str) -> Result<Self> {
@@ -191,7 +203,7 @@ impl RTCCertificate {
 
         for c in &self.dtls_certificate.certificate {
             let mut h = Sha256::new();
-            h.update(c.as_ref());
+            h.update(c);
             let hashed = h.finalize();
             let values: Vec<String> = hashed.iter().map(|x| format! {"{:02x}", x}).collect();
 
@@ -278,6 +290,29 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_generate_self_signed_certificate() -> Result<()> {
+        for signature in [SignatureAlgorithm::Ecdsa, SignatureAlgorithm::Ed25519] {
+            let cert = RTCCertificate::generate_self_signed(signature)?;
+            assert!(cert.expires.duration_since(SystemTime::now()).is_ok());
+            assert_eq!(cert.get_fingerprints().len(), 1);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "local")]
+    async fn test_generate_certificate_with_provider() -> Result<()> {
+        let provider = dtls::crypto::provider::default_provider();
+        for signature in [SignatureAlgorithm::Ecdsa, SignatureAlgorithm::Ed25519] {
+            let cert = RTCCertificate::generate(provider.as_ref(), signature).await?;
+            assert!(cert.expires.duration_since(SystemTime::now()).is_ok());
+            assert_eq!(cert.get_fingerprints().len(), 1);
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "pem")]
     #[test]
     fn test_certificate_serialize_pem_and_from_pem() -> Result<()> {
@@ -74,10 +74,10 @@ use ::sdp::description::session::*;
 use ::sdp::util::ConnectionRole;
 use arc_swap::ArcSwapOption;
 use async_trait::async_trait;
+use dtls::signature_hash_algorithm::SignatureAlgorithm;
 use interceptor::{stats, Attributes, Interceptor, RTCPWriter};
 use peer_connection_internal::*;
 use rand::{thread_rng, Rng};
-use rcgen::KeyPair;
 use srtp::stream::Stream;
 use std::future::Future;
 use std::pin::Pin;
@@ -277,8 +277,7 @@ impl RTCPeerConnection {
                     .map_err(|_| Error::ErrCertificateExpired)?;
             }
         } else {
-            let kp = KeyPair::generate(&rcgen::PKCS_ECDSA_P256_SHA256)?;
-            let cert = RTCCertificate::from_key_pair(kp)?;
+            let cert = RTCCertificate::generate_self_signed(SignatureAlgorithm::Ecdsa)?;
             configuration.certificates = vec![cert];
         };
 
@@ -594,7 +593,7 @@ impl RTCPeerConnection {
         log::debug!("got new track: {:?}", t);
 
         if t.is_some() {
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 if let Some(handler) = &*on_track_handler.load() {
                     let mut f = handler.lock().await;
                     f(t, r).await;
@@ -1326,6 +1325,11 @@ impl RTCPeerConnection {
                 .update_from_remote_description(parsed)
                 .await?;
 
+            self.internal
+                .dtls_transport
+                .set_srtp_header_protection(&srtp_header_protection_from_description(parsed)?)
+                .await;
+
             let mut local_transceivers = self.get_transceivers().await;
             let remote_description = self.remote_description().await;
             let we_offer = desc.sdp_type == RTCSdpType::Answer;
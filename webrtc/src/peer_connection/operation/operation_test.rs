@@ -3,7 +3,7 @@ use crate::error::Result;
 use super::*;
 use tokio::sync::Mutex;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_operations_enqueue() -> Result<()> {
     let ops = Operations::new();
     for _ in 0..100 {
@@ -38,7 +38,7 @@ async fn test_operations_enqueue() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_operations_done() -> Result<()> {
     let ops = Operations::new();
     ops.done().await;
@@ -1,4 +1,4 @@
-use tokio::time::Instant;
+use deno_net::Instant;
 
 use super::*;
 use crate::rtp_transceiver::create_stream_info;
@@ -236,7 +236,7 @@ impl PeerConnectionInternal {
         let dtls_transport = Arc::clone(&self.dtls_transport);
         let is_closed = Arc::clone(&self.is_closed);
         let pci = Arc::clone(self);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             let simulcast_routine_count = Arc::new(AtomicU64::new(0));
             loop {
                 let srtp_session = match dtls_transport.get_srtp_session().await {
@@ -273,7 +273,7 @@ impl PeerConnectionInternal {
                 let dtls_transport2 = Arc::clone(&dtls_transport);
                 let simulcast_routine_count2 = Arc::clone(&simulcast_routine_count);
                 let pci2 = Arc::clone(&pci);
-                wasm_bindgen_futures::spawn_local(async move {
+                deno_net::spawn(async move {
                     let ssrc = stream.get_ssrc();
 
                     dtls_transport2
@@ -294,7 +294,7 @@ impl PeerConnectionInternal {
         });
 
         let dtls_transport = Arc::clone(&self.dtls_transport);
-        wasm_bindgen_futures::spawn_local(async move {
+        deno_net::spawn(async move {
             loop {
                 let srtcp_session = match dtls_transport.get_srtcp_session().await {
                     Some(s) => s,
@@ -713,6 +713,9 @@ impl PeerConnectionInternal {
             is_icelite: self.setting_engine.candidates.ice_lite,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: self.ice_gathering_state(),
+            encrypt_header_extensions: self.setting_engine.srtp_header_extension_encryption,
+            cryptex: self.setting_engine.srtp_cryptex,
+            remote_srtp_header_protection: None,
         };
         populate_sdp(
             d,
@@ -828,11 +831,23 @@ impl PeerConnectionInternal {
             return Err(Error::ErrNonCertificate);
         };
 
+        // Answers take the remote offer into account, re-offers state our own preferences.
+        let remote_srtp_header_protection =
+            match remote_description.as_ref().and_then(|r| r.parsed.as_ref()) {
+                Some(parsed) if !include_unmatched => {
+                    Some(srtp_header_protection_from_description(parsed)?)
+                }
+                _ => None,
+            };
+
         let params = PopulateSdpParams {
             media_description_fingerprint: self.setting_engine.sdp_media_level_fingerprints,
             is_icelite: self.setting_engine.candidates.ice_lite,
             connection_role,
             ice_gathering_state: self.ice_gathering_state(),
+            encrypt_header_extensions: self.setting_engine.srtp_header_extension_encryption,
+            cryptex: self.setting_engine.srtp_cryptex,
+            remote_srtp_header_protection,
         };
         populate_sdp(
             d,
@@ -1086,7 +1101,7 @@ impl PeerConnectionInternal {
 
             let receiver2 = Arc::clone(&receiver);
             let on_track_handler2 = Arc::clone(&on_track_handler);
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 if let Some(track) = receiver2.track().await {
                     let mut b = vec![0u8; receive_mtu];
                     let n = match track.peek(&mut b).await {
@@ -258,7 +258,7 @@ pub(crate) async fn until_connection_state(
     }));
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_get_stats() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -312,7 +312,7 @@ async fn test_get_stats() -> Result<()> {
                 None => return result,
             };
 
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 while let Ok((pkt, _)) = track.read_rtp().await {
                     dbg!(&pkt);
                     let last = pkt.payload[pkt.payload.len() - 1];
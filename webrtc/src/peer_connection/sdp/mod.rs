@@ -399,6 +399,9 @@ pub(crate) struct AddTransceiverSdpParams {
     dtls_role: ConnectionRole,
     ice_gathering_state: RTCIceGatheringState,
     offered_direction: Option<RTCRtpTransceiverDirection>,
+    encrypt_header_extensions: bool,
+    cryptex: bool,
+    remote_srtp_header_protection: Option<SrtpHeaderProtection>,
 }
 
 pub(crate) async fn add_transceiver_sdp(
@@ -507,13 +510,29 @@ pub(crate) async fn add_transceiver_sdp(
         .await;
     for rtp_extension in &parameters.header_extensions {
         let ext_url = Url::parse(rtp_extension.uri.as_str())?;
-        media = media.with_extmap(sdp::extmap::ExtMap {
-            value: rtp_extension.id,
-            uri: Some(ext_url),
-            ..Default::default()
+        // When answering, only encrypt what the offer asked to.
+        let encrypt = params.encrypt_header_extensions
+            && params
+                .remote_srtp_header_protection
+                .as_ref()
+                .map_or(true, |remote| {
+                    remote.encrypted_extensions.contains_key(&rtp_extension.uri)
+                });
+        media = media.with_extmap(if encrypt {
+            ExtMap::encrypted(rtp_extension.id, Default::default(), &ext_url)?
+        } else {
+            ExtMap {
+                value: rtp_extension.id,
+                uri: Some(ext_url),
+                ..Default::default()
+            }
         });
     }
 
+    if params.cryptex {
+        media = media.with_property_attribute(ATTR_KEY_CRYPTEX.to_owned());
+    }
+
     if !media_section.rid_map.is_empty() {
         let mut recv_rids: Vec<String> = vec![];
 
@@ -647,6 +666,10 @@ pub(crate) struct PopulateSdpParams {
     pub(crate) is_icelite: bool,
     pub(crate) connection_role: ConnectionRole,
     pub(crate) ice_gathering_state: RTCIceGatheringState,
+    pub(crate) encrypt_header_extensions: bool,
+    pub(crate) cryptex: bool,
+    /// What the offer says about encrypting RTP headers, None when we are the offerer.
+    pub(crate) remote_srtp_header_protection: Option<SrtpHeaderProtection>,
 }
 
 /// populate_sdp serializes a PeerConnections state into an SDP
@@ -698,6 +721,13 @@ pub(crate) async fn populate_sdp(
                 dtls_role: params.connection_role,
                 ice_gathering_state: params.ice_gathering_state,
                 offered_direction: m.offered_direction,
+                encrypt_header_extensions: params.encrypt_header_extensions,
+                cryptex: params.cryptex
+                    && params
+                        .remote_srtp_header_protection
+                        .as_ref()
+                        .map_or(true, |remote| remote.cryptex),
+                remote_srtp_header_protection: params.remote_srtp_header_protection.clone(),
             };
             let (d1, should_add_id) = add_transceiver_sdp(
                 d,
@@ -948,7 +978,7 @@ pub(crate) fn rtp_extensions_from_media_description(
             let mut reader = BufReader::new(a_str.as_bytes());
             let e = ExtMap::unmarshal(&mut reader)?;
 
-            if let Some(uri) = e.uri {
+            if let Some(uri) = e.extension_uri()? {
                 out.insert(uri.to_string(), e.value);
             }
         }
@@ -957,6 +987,58 @@ pub(crate) fn rtp_extensions_from_media_description(
     Ok(out)
 }
 
+/// SrtpHeaderProtection is what a SessionDescription says about encrypting RTP headers.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(crate) struct SrtpHeaderProtection {
+    /// URIs and IDs of the header extensions to encrypt, https://tools.ietf.org/html/rfc6904
+    pub(crate) encrypted_extensions: HashMap<String, isize>,
+    /// cryptex, https://www.rfc-editor.org/rfc/rfc9335
+    pub(crate) cryptex: bool,
+}
+
+/// srtp_header_protection_from_description collects the encrypted header extensions of all
+/// media sections. cryptex counts when it is set for the session or for every RTP media section,
+/// since bundled media share one SRTP session.
+pub(crate) fn srtp_header_protection_from_description(
+    desc: &SessionDescription,
+) -> Result<SrtpHeaderProtection> {
+    let mut header_protection = SrtpHeaderProtection {
+        cryptex: desc.attributes.iter().any(|a| a.key == ATTR_KEY_CRYPTEX),
+        ..Default::default()
+    };
+    let mut media_cryptex = None;
+
+    for m in &desc.media_descriptions {
+        if m.media_name.media == MEDIA_SECTION_APPLICATION {
+            continue;
+        }
+
+        for a in &m.attributes {
+            if a.key == ATTR_KEY_EXT_MAP {
+                let a_str = a.to_string();
+                let mut reader = BufReader::new(a_str.as_bytes());
+                let e = ExtMap::unmarshal(&mut reader)?;
+
+                if !e.is_encrypted() {
+                    continue;
+                }
+                if let Some(uri) = e.extension_uri()? {
+                    header_protection
+                        .encrypted_extensions
+                        .insert(uri.to_string(), e.value);
+                }
+            }
+        }
+
+        let cryptex = m.attribute(ATTR_KEY_CRYPTEX).is_some();
+        media_cryptex = Some(media_cryptex.unwrap_or(true) && cryptex);
+    }
+
+    header_protection.cryptex |= media_cryptex.unwrap_or(false);
+
+    Ok(header_protection)
+}
+
 /// update_sdp_origin saves sdp.Origin in PeerConnection when creating 1st local SDP;
 /// for subsequent calling, it updates Origin for SessionDescription from saved one
 /// and increments session version by one.
@@ -101,7 +101,7 @@ fn test_extract_fingerprint() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_extract_ice_details() -> Result<()> {
     const DEFAULT_UFRAG: &str = "DEFAULT_PWD";
     const DEFAULT_PWD: &str = "DEFAULT_UFRAG";
@@ -542,6 +542,9 @@ async fn fingerprint_test(
         is_icelite: false,
         connection_role: ConnectionRole::Active,
         ice_gathering_state: RTCIceGatheringState::New,
+        encrypt_header_extensions: false,
+        cryptex: false,
+        remote_srtp_header_protection: None,
     };
 
     let s = populate_sdp(
@@ -565,7 +568,7 @@ async fn fingerprint_test(
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_media_description_fingerprints() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -653,7 +656,7 @@ async fn test_media_description_fingerprints() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_populate_sdp() -> Result<()> {
     //"Rid"
     {
@@ -690,6 +693,9 @@ async fn test_populate_sdp() -> Result<()> {
             is_icelite: se.candidates.ice_lite,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
+            encrypt_header_extensions: false,
+            cryptex: false,
+            remote_srtp_header_protection: None,
         };
         let offer_sdp = populate_sdp(
             d,
@@ -771,6 +777,9 @@ async fn test_populate_sdp() -> Result<()> {
             is_icelite: se.candidates.ice_lite,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
+            encrypt_header_extensions: false,
+            cryptex: false,
+            remote_srtp_header_protection: None,
         };
         let offer_sdp = populate_sdp(
             d,
@@ -807,7 +816,7 @@ async fn test_populate_sdp() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_populate_sdp_reject() -> Result<()> {
     let se = SettingEngine::default();
     let mut me = MediaEngine::default();
@@ -874,6 +883,9 @@ async fn test_populate_sdp_reject() -> Result<()> {
         is_icelite: se.candidates.ice_lite,
         connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
         ice_gathering_state: RTCIceGatheringState::Complete,
+        encrypt_header_extensions: false,
+        cryptex: false,
+        remote_srtp_header_protection: None,
     };
     let offer_sdp = populate_sdp(
         d,
@@ -1060,3 +1072,68 @@ fn test_rtp_extensions_from_media_description() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_srtp_header_protection_from_description() -> Result<()> {
+    let media = |kind: &str, attributes: Vec<Attribute>| MediaDescription {
+        media_name: MediaName {
+            media: kind.to_owned(),
+            formats: vec!["111".to_owned()],
+            ..Default::default()
+        },
+        attributes,
+        ..Default::default()
+    };
+    let cryptex = || Attribute {
+        key: ATTR_KEY_CRYPTEX.to_owned(),
+        value: None,
+    };
+
+    let desc = SessionDescription {
+        media_descriptions: vec![
+            media(
+                "audio",
+                vec![
+                    Attribute {
+                        key: "extmap".to_owned(),
+                        value: Some(format!(
+                            "1 {} {}",
+                            sdp::extmap::ENCRYPT_URI,
+                            sdp::extmap::AUDIO_LEVEL_URI
+                        )),
+                    },
+                    Attribute {
+                        key: "extmap".to_owned(),
+                        value: Some("3 ".to_owned() + sdp::extmap::SDES_MID_URI),
+                    },
+                    cryptex(),
+                ],
+            ),
+            media("video", vec![cryptex()]),
+            media(MEDIA_SECTION_APPLICATION, vec![]),
+        ],
+        ..Default::default()
+    };
+
+    let header_protection = srtp_header_protection_from_description(&desc)?;
+    assert_eq!(header_protection.encrypted_extensions.len(), 1);
+    assert_eq!(
+        header_protection.encrypted_extensions[sdp::extmap::AUDIO_LEVEL_URI],
+        1
+    );
+    assert!(header_protection.cryptex);
+
+    // The encrypted extension keeps its own URI for the media engine.
+    let extensions = rtp_extensions_from_media_description(&desc.media_descriptions[0])?;
+    assert_eq!(extensions[sdp::extmap::AUDIO_LEVEL_URI], 1);
+
+    // cryptex has to cover every RTP media section.
+    let mut desc = desc;
+    desc.media_descriptions[1].attributes.clear();
+    assert!(!srtp_header_protection_from_description(&desc)?.cryptex);
+
+    desc.attributes.push(cryptex());
+    assert!(srtp_header_protection_from_description(&desc)?.cryptex);
+
+    Ok(())
+}
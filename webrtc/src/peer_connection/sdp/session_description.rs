@@ -140,7 +140,7 @@ mod test {
         }
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_session_description_answer() -> Result<()> {
         let mut m = MediaEngine::default();
         m.register_default_codecs()?;
@@ -165,7 +165,7 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_session_description_offer() -> Result<()> {
         let mut m = MediaEngine::default();
         m.register_default_codecs()?;
@@ -184,7 +184,7 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_session_description_pranswer() -> Result<()> {
         let mut m = MediaEngine::default();
         m.register_default_codecs()?;
@@ -207,7 +207,7 @@ mod test {
         Ok(())
     }
 
-    #[tokio::test]
+    #[tokio::test(flavor = "local")]
     async fn test_session_description_unmarshal() -> Result<()> {
         let mut m = MediaEngine::default();
         m.register_default_codecs()?;
@@ -51,7 +51,7 @@ lazy_static! {
 //use log::LevelFilter;
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_set_rtp_parameters() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -164,7 +164,7 @@ async fn test_set_rtp_parameters() -> Result<()> {
 
 // Assert that SetReadDeadline works as expected
 // This test uses VNet since we must have zero loss
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_receiver_set_read_deadline() -> Result<()> {
     let (mut sender, mut receiver, wan) = create_vnet_pair().await?;
 
@@ -17,7 +17,7 @@ use std::sync::atomic::AtomicU64;
 use std::time::Duration;
 use waitgroup::WaitGroup;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_sender_replace_track() -> Result<()> {
     let mut s = SettingEngine::default();
     s.disable_srtp_replay_protection(true);
@@ -94,7 +94,7 @@ async fn test_rtp_sender_replace_track() -> Result<()> {
     signal_pair(&mut sender, &mut receiver).await?;
 
     // Block Until packet with 0xAA has been seen
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         send_video_until_done(
             seen_packet_a_rx,
             vec![track_a],
@@ -111,7 +111,7 @@ async fn test_rtp_sender_replace_track() -> Result<()> {
         .await?;
 
     // Block Until packet with 0xBB has been seen
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         send_video_until_done(
             seen_packet_b_rx,
             vec![track_b],
@@ -125,7 +125,7 @@ async fn test_rtp_sender_replace_track() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_sender_get_parameters() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -152,7 +152,7 @@ async fn test_rtp_sender_get_parameters() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_sender_set_read_deadline() -> Result<()> {
     let (mut sender, mut receiver, wan) = create_vnet_pair().await?;
 
@@ -199,7 +199,7 @@ async fn test_rtp_sender_set_read_deadline() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_sender_replace_track_invalid_track_kind_change() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -242,7 +242,7 @@ async fn test_rtp_sender_replace_track_invalid_track_kind_change() -> Result<()>
         },
     ));
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         send_video_until_done(
             seen_packet_rx,
             vec![track_a],
@@ -262,7 +262,7 @@ async fn test_rtp_sender_replace_track_invalid_track_kind_change() -> Result<()>
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_sender_replace_track_invalid_codec_change() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -326,7 +326,7 @@ async fn test_rtp_sender_replace_track_invalid_codec_change() -> Result<()> {
         },
     ));
 
-    wasm_bindgen_futures::spawn_local(async move {
+    deno_net::spawn(async move {
         send_video_until_done(
             seen_packet_rx,
             vec![track_a],
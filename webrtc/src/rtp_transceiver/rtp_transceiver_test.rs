@@ -6,7 +6,7 @@ use crate::api::APIBuilder;
 use crate::peer_connection::configuration::RTCConfiguration;
 use crate::peer_connection::peer_connection_test::{close_pair_now, create_vnet_pair};
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_transceiver_set_codec_preferences() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -127,7 +127,7 @@ async fn test_rtp_transceiver_set_codec_preferences() -> Result<()> {
 }
 
 // Assert that SetCodecPreferences properly filters codecs and PayloadTypes are respected
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_transceiver_set_codec_preferences_payload_type() -> Result<()> {
     let test_codec = RTCRtpCodecParameters {
         capability: RTCRtpCodecCapability {
@@ -199,7 +199,7 @@ async fn test_rtp_transceiver_set_codec_preferences_payload_type() -> Result<()>
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_transceiver_direction_change() -> Result<()> {
     let (offer_pc, answer_pc, _) = create_vnet_pair().await?;
 
@@ -240,7 +240,7 @@ async fn test_rtp_transceiver_direction_change() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_transceiver_set_direction_causing_negotiation() -> Result<()> {
     let (offer_pc, answer_pc, _) = create_vnet_pair().await?;
 
@@ -299,7 +299,7 @@ async fn test_rtp_transceiver_set_direction_causing_negotiation() -> Result<()>
 }
 
 #[ignore]
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_rtp_transceiver_stopping() -> Result<()> {
     let (offer_pc, answer_pc, _) = create_vnet_pair().await?;
 
@@ -185,7 +185,7 @@ impl RTCSctpTransport {
                 data_channels_accepted: Arc::clone(&self.data_channels_accepted),
                 setting_engine: Arc::clone(&self.setting_engine),
             };
-            wasm_bindgen_futures::spawn_local(async move {
+            deno_net::spawn(async move {
                 RTCSctpTransport::accept_data_channels(param).await;
             });
 
@@ -1,7 +1,7 @@
 use super::*;
 use std::sync::atomic::AtomicU16;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_generate_data_channel_id() -> Result<()> {
     let sctp_transport_with_channels = |ids: &[u16]| -> RTCSctpTransport {
         let mut data_channels = vec![];
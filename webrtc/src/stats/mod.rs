@@ -15,8 +15,7 @@ use stats_collector::StatsCollector;
 use serde::{Serialize, Serializer};
 use std::collections::HashMap;
 use std::sync::Arc;
-use deno_net::SystemTime;
-use tokio::time::Instant;
+use deno_net::{Instant, SystemTime};
 
 mod serialize;
 pub mod stats_collector;
@@ -179,6 +178,8 @@ pub struct ICECandidatePairStats {
     pub first_request_timestamp: Instant,
     #[serde(with = "serialize::instant_to_epoch_seconds")]
     pub last_request_timestamp: Instant,
+    #[serde(with = "serialize::instant_to_epoch_seconds")]
+    pub last_response_timestamp: Instant,
     pub retransmissions_sent: u64,
 }
 
@@ -198,6 +199,7 @@ impl From<CandidatePairStats> for ICECandidatePairStats {
             last_packet_received_timestamp: stats.last_packet_received_timestamp,
             last_packet_sent_timestamp: stats.last_packet_sent_timestamp,
             last_request_timestamp: stats.last_request_timestamp,
+            last_response_timestamp: stats.last_response_timestamp,
             local_candidate_id: stats.local_candidate_id,
             nominated: stats.nominated,
             packets_received: stats.packets_received,
@@ -464,6 +466,7 @@ pub struct InboundRTPStats {
     // NB: `framesDecoded`, `frameWidth`, frameHeight`, `framesPerSecond`, `qpSum`,
     // `totalDecodeTime`, `totalInterFrameDelay`, and `totalSquaredInterFrameDelay` are all decoder
     // specific values and can't be produced since we aren't decoding.
+    #[serde(with = "serialize::system_time_to_epoch_seconds")]
     pub last_packet_received_timestamp: Option<SystemTime>,
     pub header_bytes_received: u64,
     // TODO: `packetsDiscarded`. This value only makes sense if we have jitter buffer, which we
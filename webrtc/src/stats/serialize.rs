@@ -1,13 +1,12 @@
 pub mod instant_to_epoch_seconds {
-    // Serializes a `tokio::time::Instant` to an approximation of epoch time in the form
+    // Serializes a `deno_net::Instant` to an approximation of epoch time in the form
     // of an `f64` where the integer portion is seconds and the decimal portion is milliseconds.
     // For instance, `Monday, May 30, 2022 10:45:26.456 PM UTC` converts to `1653950726.456`.
     //
     // Note that an `Instant` is not connected to real world time, so this conversion is
     // approximate.
     use serde::{Serialize, Serializer};
-	use deno_net::{SystemTime, UNIX_EPOCH};
-    use tokio::time::Instant;
+	use deno_net::{Instant, SystemTime, UNIX_EPOCH};
 
     pub fn serialize<S>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -25,3 +24,22 @@ pub mod instant_to_epoch_seconds {
         epoch_ms.serialize(serializer)
     }
 }
+
+pub mod system_time_to_epoch_seconds {
+    // Serializes an optional `deno_net::SystemTime` in the same epoch seconds form as
+    // `instant_to_epoch_seconds`.
+    use serde::{Serialize, Serializer};
+    use deno_net::{SystemTime, UNIX_EPOCH};
+
+    pub fn serialize<S>(time: &Option<SystemTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let epoch_ms = time.map(|time| {
+            let epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+            epoch.as_millis() as f64 / 1000.0
+        });
+
+        epoch_ms.serialize(serializer)
+    }
+}
@@ -147,7 +147,7 @@ impl TrackLocalStaticSample {
     /// use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
     /// use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
     ///
-    /// #[tokio::main]
+    /// #[tokio::main(flavor = "local")]
     /// async fn main() {
     ///     let track = TrackLocalStaticSample::new(
     ///        RTCRtpCodecCapability {
@@ -12,7 +12,7 @@ use tokio::sync::{mpsc, Mutex};
 
 // If a remote doesn't support a Codec used by a `TrackLocalStatic`
 // an error should be returned to the user
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_track_local_static_no_codec_intersection() -> Result<()> {
     let track: Arc<dyn TrackLocal> = Arc::new(TrackLocalStaticSample::new(
         RTCRtpCodecCapability {
@@ -121,7 +121,7 @@ async fn test_track_local_static_no_codec_intersection() -> Result<()> {
 }
 
 // Assert that Bind/Unbind happens when expected
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_track_local_static_closed() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
@@ -179,7 +179,7 @@ async fn test_track_local_static_closed() -> Result<()> {
 //use log::LevelFilter;
 //use std::io::Write;
 
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_track_local_static_payload_type() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -291,7 +291,7 @@ async fn test_track_local_static_payload_type() -> Result<()> {
 
 // Assert that writing to a Track doesn't modify the input
 // Even though we can pass a pointer we shouldn't modify the incoming value
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_track_local_static_mutate_input() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {
@@ -354,7 +354,7 @@ async fn test_track_local_static_mutate_input() -> Result<()> {
 
 // Assert that writing to a Track that has Binded (but not connected)
 // does not block
-#[tokio::test]
+#[tokio::test(flavor = "local")]
 async fn test_track_local_static_binding_non_blocking() -> Result<()> {
     /*env_logger::Builder::new()
     .format(|buf, record| {